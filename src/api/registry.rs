@@ -398,6 +398,9 @@ impl<'runtime> ObjRef<'runtime> for crate::ufo::objects::Layer {
                     .glyphs()
                     .iter()
                     .map(|(k, v)| {
+                        if let Err(err) = crate::prelude::Glyph::load(v) {
+                            eprintln!("{err}");
+                        }
                         (
                             k.clone(),
                             PyUuid(runtime.register_obj(v.borrow().metadata.upcast_ref())),
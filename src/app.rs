@@ -238,6 +238,11 @@ impl ApplicationImpl for ApplicationInner {
         self.window.set_resizable(true);
     }
 
+    fn shutdown(&self, app: &Self::Type) {
+        self.runtime.project.borrow().close();
+        self.parent_shutdown(app);
+    }
+
     /// `gio::Application` is bit special. It does not get initialized
     /// when `new` is called and the object created, but rather
     /// once the `startup` signal is emitted and the `gio::Application::startup`
@@ -412,42 +417,12 @@ impl ApplicationInner {
         let import_glyphs = gtk::gio::SimpleAction::new("project.import.glyphs", None);
 
         import_glyphs.connect_activate(glib::clone!(@weak window => move |_, _| {
-            #[cfg(feature = "python")]
-            {
-                crate::ufo::import::glyphsapp::import_action_cb(window);
-            }
-            #[cfg(not(feature = "python"))]
-            {
-                // [ref:needs_user_doc] Add compilation instructions and/or url to docs.
-                let dialog = crate::utils::widgets::new_simple_error_dialog(
-                    None,
-                    "This application build doesn't include python support. <i>Glyphs</i> import is performed with the <tt>glyphsLib</tt> python3 library.\n\nCompile or install the app with <tt>python</tt> Cargo feature enabled.",
-                    None,
-                    &window,
-                );
-                dialog.run();
-                dialog.emit_close();
-            }
+            crate::ufo::import::glyphsapp::import_action_cb(window);
         }));
         let import_ufo2 = gtk::gio::SimpleAction::new("project.import.ufo2", None);
 
         import_ufo2.connect_activate(glib::clone!(@weak window => move |_, _| {
-            #[cfg(feature = "python")]
-            {
-                crate::ufo::import::ufo2::import_action_cb(window);
-            }
-            #[cfg(not(feature = "python"))]
-            {
-                // [ref:needs_user_doc] Add compilation instructions and/or url to docs.
-                let dialog = crate::utils::widgets::new_simple_error_dialog(
-                    None,
-                    "This application build doesn't include python support. <i>UFOv2</i> import is performed with the <tt>fontTools</tt> python3 library.\n\nCompile or install the app with <tt>python</tt> Cargo feature enabled.",
-                    None,
-                    &window,
-                );
-                dialog.run();
-                dialog.emit_close();
-            }
+            crate::ufo::import::ufo2::import_action_cb(window);
         }));
         let open = gtk::gio::SimpleAction::new("project.open", None);
         open.connect_activate(glib::clone!(@weak window => move |_, _| {
@@ -465,6 +440,26 @@ impl ApplicationInner {
             window.emit_by_name::<()>("open-project", &[&path]);
             dialog.hide();
         }));
+        let open_ufoz = gtk::gio::SimpleAction::new("project.open.ufoz", None);
+        open_ufoz.connect_activate(glib::clone!(@weak window => move |_, _| {
+            let filter = gtk::FileFilter::new();
+            filter.add_pattern(&format!("*.{}", crate::ufo::ufoz::EXTENSION));
+            filter.set_name(Some("Zipped UFO (.ufoz)"));
+            let dialog = gtk::FileChooserNative::new(
+                Some("Open .ufoz archive..."),
+                Some(&window),
+                gtk::FileChooserAction::Open,
+                None,
+                None
+            );
+            dialog.add_filter(&filter);
+            crate::return_if_not_ok_or_accept!(dialog.run());
+
+            let Some(f) = dialog.filename() else { return; };
+            let Some(path) = f.to_str() else { return; };
+            window.emit_by_name::<()>("open-project", &[&path]);
+            dialog.hide();
+        }));
         let open_path =
             gtk::gio::SimpleAction::new("project.open_path", Some(glib::VariantTy::STRING));
         open_path.connect_activate(glib::clone!(@weak window => move |_, path| {
@@ -586,6 +581,73 @@ impl ApplicationInner {
                 dialog.emit_close();
             }
         }));
+        let project_export_ufoz = gtk::gio::SimpleAction::new("project.export.ufoz", None);
+        project_export_ufoz.connect_activate(
+            glib::clone!(@weak self.window as window, @weak obj as app => move |_, _| {
+                crate::ufo::ufoz::export_action_cb(
+                    window.upcast(),
+                    app.runtime.project.borrow().clone(),
+                );
+            }),
+        );
+        let project_add_extrema = gtk::gio::SimpleAction::new("project.add-extrema", None);
+        project_add_extrema.connect_activate(glib::clone!(@weak obj as app => move |_, _| {
+            let glyphs = app.runtime.project.borrow().default_layer.glyphs().clone();
+            let actions = glyphs
+                .values()
+                .filter_map(|glyph| Glyph::add_extrema_action(glyph, None))
+                .collect::<Vec<Action>>();
+            if actions.is_empty() {
+                return;
+            }
+            let mut action = Action::group(
+                EventStamp {
+                    t: std::any::TypeId::of::<Project>(),
+                    property: "add-extrema",
+                    id: Box::new([]),
+                },
+                actions,
+            );
+            (action.redo)();
+            app.undo_db.borrow().event(action);
+        }));
+        let project_correct_direction =
+            gtk::gio::SimpleAction::new("project.correct-direction", Some(glib::VariantTy::STRING));
+        project_correct_direction.connect_activate(
+            glib::clone!(@weak obj as app => move |_, name| {
+                use glib::FromVariant;
+                let Some(convention) = name
+                    .and_then(String::from_variant)
+                    .and_then(|s| DirectionConvention::from_name(&s))
+                else {
+                    return;
+                };
+                let glyphs = app.runtime.project.borrow().default_layer.glyphs().clone();
+                let actions = glyphs
+                    .values()
+                    .filter_map(|glyph| Glyph::correct_direction_action(glyph, convention))
+                    .collect::<Vec<Action>>();
+                if actions.is_empty() {
+                    return;
+                }
+                let mut action = Action::group(
+                    EventStamp {
+                        t: std::any::TypeId::of::<Project>(),
+                        property: "correct-direction",
+                        id: Box::new([]),
+                    },
+                    actions,
+                );
+                (action.redo)();
+                app.undo_db.borrow().event(action);
+            }),
+        );
+        let project_text_preview = gtk::gio::SimpleAction::new("project.text-preview", None);
+        project_text_preview.connect_activate(
+            glib::clone!(@weak self.window as window => move |_, _| {
+                window.open_text_preview();
+            }),
+        );
         let bug_report = gtk::gio::SimpleAction::new("bug_report", None);
         let app = application.clone();
         bug_report.connect_activate(move |_, _| {
@@ -605,6 +667,10 @@ impl ApplicationInner {
         application.add_action(&project_properties);
         application.add_action(&project_save);
         application.add_action(&project_export);
+        application.add_action(&project_export_ufoz);
+        application.add_action(&project_add_extrema);
+        application.add_action(&project_correct_direction);
+        application.add_action(&project_text_preview);
         application.add_action(&import_glyphs);
         application.add_action(&import_ufo2);
         application.add_action(&settings);
@@ -613,6 +679,7 @@ impl ApplicationInner {
         application.add_action(&bug_report);
         application.add_action(&open_path);
         application.add_action(&open);
+        application.add_action(&open_ufoz);
         application.add_action(&new_project);
         application.add_action(&undo);
         application.add_action(&redo);
@@ -632,7 +699,7 @@ impl ApplicationInner {
             .filter(|i| {
                 i.last_application().map(|a| a == "gerb").unwrap_or(false)
                     && i.mime_type()
-                        .map(|a| a == "inode/directory")
+                        .map(|a| a == "inode/directory" || a == crate::ufo::ufoz::MIME_TYPE)
                         .unwrap_or(false)
                     && i.uri_display()
                         .map(|a| Path::new(&a).exists())
@@ -646,6 +713,7 @@ impl ApplicationInner {
             let import_menu = gio::Menu::new();
             file_menu.append(Some("_New"), Some("app.project.new"));
             file_menu.append(Some("_Open"), Some("app.project.open"));
+            file_menu.append(Some("Open .ufoz archive"), Some("app.project.open.ufoz"));
             if !items.is_empty() {
                 let recent_menu = gio::Menu::new();
                 for i in items.into_iter().take(10) {
@@ -672,6 +740,7 @@ impl ApplicationInner {
             );
             file_menu.append_submenu(Some("_Import"), &import_menu);
             file_menu.append(Some("_Export"), Some("app.project.export"));
+            file_menu.append(Some("Export as .ufoz"), Some("app.project.export.ufoz"));
             let project_section = gio::Menu::new();
             project_section.append(Some("_Properties"), Some("app.project.properties"));
             #[cfg(feature = "python")]
@@ -690,6 +759,29 @@ impl ApplicationInner {
             undo_section.append(Some("_Undo"), Some("app.undo"));
             undo_section.append(Some("_Redo"), Some("app.redo"));
             edit_menu.append_section(Some("Action history"), &undo_section);
+            let font_section = gio::Menu::new();
+            font_section.append(
+                Some("Add extreme points to all glyphs"),
+                Some("app.project.add-extrema"),
+            );
+            for (label, convention) in [
+                (
+                    "Correct path direction of all glyphs (PostScript)",
+                    DirectionConvention::PostScript,
+                ),
+                (
+                    "Correct path direction of all glyphs (TrueType)",
+                    DirectionConvention::TrueType,
+                ),
+            ] {
+                let item = gio::MenuItem::new(Some(label), None);
+                item.set_action_and_target_value(
+                    Some("app.project.correct-direction"),
+                    Some(&convention.name().to_variant()),
+                );
+                font_section.append_item(&item);
+            }
+            edit_menu.append_section(Some("Font"), &font_section);
             menu_bar.append_submenu(Some("_Edit"), &edit_menu);
         }
 
@@ -697,6 +789,7 @@ impl ApplicationInner {
             let win_menu = gio::Menu::new();
             win_menu.append(Some("_Next tab"), Some("win.next_tab"));
             win_menu.append(Some("_Previous tab"), Some("win.prev_tab"));
+            win_menu.append(Some("_Text preview"), Some("app.project.text-preview"));
             let theme_menu = gio::Menu::new();
             for (label, theme) in [
                 ("System default", "system-default"),
@@ -57,6 +57,8 @@ pub struct SettingsInner {
     pub file: Rc<RefCell<Option<(PathBuf, BufWriter<File>)>>>,
     pub document: Rc<RefCell<Document>>,
     pub show_prerelease_warning: Cell<bool>,
+    /// Strings offered in the text preview, e.g. spacing control strings like `HHxHH`.
+    pub text_preview_strings: RefCell<Vec<String>>,
 }
 
 #[glib::object_subclass]
@@ -75,6 +77,10 @@ impl ObjectImpl for SettingsInner {
         self.guideline_width.set(Self::GUIDELINE_WIDTH_INIT_VAL);
         self.warp_cursor.set(Self::WARP_CURSOR_INIT_VAL);
         self.show_prerelease_warning.set(true);
+        *self.text_preview_strings.borrow_mut() = Self::TEXT_PREVIEW_STRINGS_INIT_VAL
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
     }
 
     fn properties() -> &'static [glib::ParamSpec] {
@@ -130,6 +136,13 @@ impl ObjectImpl for SettingsInner {
                         types::MarkColor::None as i32,
                         glib::ParamFlags::READWRITE | UI_EDITABLE,
                     ),
+                    glib::ParamSpecString::new(
+                        Settings::TEXT_PREVIEW_STRINGS,
+                        Settings::TEXT_PREVIEW_STRINGS,
+                        "Strings offered in the text preview, one per line.",
+                        None,
+                        glib::ParamFlags::READWRITE,
+                    ),
                 ]
             });
         PROPERTIES.as_ref()
@@ -143,6 +156,9 @@ impl ObjectImpl for SettingsInner {
             Settings::WARP_CURSOR => self.warp_cursor.get().to_value(),
             Settings::SHOW_PRERELEASE_WARNING => self.show_prerelease_warning.get().to_value(),
             Settings::MARK_COLOR => self.mark_color.get().to_value(),
+            Settings::TEXT_PREVIEW_STRINGS => {
+                self.text_preview_strings.borrow().join("\n").to_value()
+            }
             _ => unimplemented!("{}", pspec.name()),
         }
     }
@@ -179,6 +195,17 @@ impl ObjectImpl for SettingsInner {
                 self.mark_color.set(value.get().unwrap());
                 self.save_settings().unwrap();
             }
+            Settings::TEXT_PREVIEW_STRINGS => {
+                *self.text_preview_strings.borrow_mut() = value
+                    .get::<Option<String>>()
+                    .unwrap()
+                    .unwrap_or_default()
+                    .lines()
+                    .filter(|l| !l.is_empty())
+                    .map(str::to_string)
+                    .collect();
+                self.save_settings().unwrap();
+            }
             _ => unimplemented!("{}", pspec.name()),
         }
     }
@@ -188,6 +215,8 @@ impl SettingsInner {
     pub const HANDLE_SIZE_INIT_VAL: f64 = 5.0;
     pub const LINE_WIDTH_INIT_VAL: f64 = 0.85;
     pub const GUIDELINE_WIDTH_INIT_VAL: f64 = 1.0;
+    pub const TEXT_PREVIEW_STRINGS_INIT_VAL: &'static [&'static str] =
+        &["HHxHH", "HOHxHOH", "OOxOO", "nnxnn", "nonxnon", "ooxoo"];
     pub const WARP_CURSOR_INIT_VAL: bool = false;
 
     pub fn get_config_file() -> Result<PathBuf, Box<dyn std::error::Error>> {
@@ -272,6 +301,13 @@ impl SettingsInner {
             document[Settings::MARK_COLOR] = toml_value(self.mark_color.get().name());
             document[Settings::SHOW_PRERELEASE_WARNING] =
                 toml_value(self.show_prerelease_warning.get());
+            document[Settings::TEXT_PREVIEW_STRINGS] = toml_value(
+                self.text_preview_strings
+                    .borrow()
+                    .iter()
+                    .map(String::as_str)
+                    .collect::<toml_edit::Array>(),
+            );
             file.rewind()?;
             file.get_mut().set_len(0)?;
             file.write_all(document.to_string().as_bytes())?;
@@ -449,6 +485,19 @@ impl SettingsInner {
                 save = true;
             }
         }
+        /* string lists */
+        if let Some(v) = document
+            .get(Settings::TEXT_PREVIEW_STRINGS)
+            .and_then(TomlItem::as_array)
+        {
+            *self.text_preview_strings.borrow_mut() = v
+                .iter()
+                .filter_map(|s| s.as_str())
+                .map(str::to_string)
+                .collect();
+        } else {
+            save = true;
+        }
         drop(document);
         if save {
             self.save_settings()?;
@@ -615,6 +664,7 @@ impl Settings {
     pub const WARP_CURSOR: &'static str = "warp-cursor";
     pub const MARK_COLOR: &'static str = "mark-color";
     pub const SHOW_PRERELEASE_WARNING: &'static str = "show-prerelease-warning";
+    pub const TEXT_PREVIEW_STRINGS: &'static str = "text-preview-strings";
 
     pub fn new() -> Self {
         glib::Object::new::<Self>(&[]).unwrap()
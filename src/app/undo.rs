@@ -35,7 +35,7 @@ pub struct Event {
     pub action: Action,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 #[repr(C)]
 pub struct EventStamp {
     pub t: std::any::TypeId,
@@ -58,6 +58,28 @@ pub struct Action {
     pub undo: Box<dyn FnMut()>,
 }
 
+impl Action {
+    /// Combine `actions` into a single undo step. Redo runs them in order and undo in reverse
+    /// order.
+    pub fn group(stamp: EventStamp, actions: Vec<Self>) -> Self {
+        let actions = std::rc::Rc::new(RefCell::new(actions));
+        Self {
+            stamp,
+            compress: false,
+            redo: Box::new(glib::clone!(@strong actions => move || {
+                for action in actions.borrow_mut().iter_mut() {
+                    (action.redo)();
+                }
+            })),
+            undo: Box::new(move || {
+                for action in actions.borrow_mut().iter_mut().rev() {
+                    (action.undo)();
+                }
+            }),
+        }
+    }
+}
+
 impl std::fmt::Debug for Action {
     fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
         fmt.debug_struct("Action")
@@ -78,6 +100,8 @@ pub struct UndoDatabaseInner {
     pub database: RefCell<Vec<Event>>,
     pub timestamp: RefCell<u64>,
     pub cursor: RefCell<usize>,
+    /// Set while [`UndoDatabase::undo`] or [`UndoDatabase::redo`] runs actions.
+    pub replaying: std::cell::Cell<bool>,
 }
 
 #[glib::object_subclass]
@@ -158,8 +182,40 @@ impl UndoDatabase {
         self.notify(Self::CAN_UNDO);
     }
 
+    /// Adds `action`, which has already been applied, to the latest event so that both are
+    /// undone and redone as a single step. If there is no latest event it is recorded as a new
+    /// one.
+    pub fn amend(&self, action: Action) {
+        {
+            let cursor = *self.imp().cursor.borrow();
+            let mut db = self.imp().database.borrow_mut();
+            if let Some(last) = cursor.checked_sub(1).and_then(|i| db.get_mut(i)) {
+                let placeholder = Action {
+                    stamp: last.action.stamp.clone(),
+                    compress: last.action.compress,
+                    redo: Box::new(|| {}),
+                    undo: Box::new(|| {}),
+                };
+                let previous = std::mem::replace(&mut last.action, placeholder);
+                let compress = previous.compress;
+                last.action = Action::group(previous.stamp.clone(), vec![previous, action]);
+                /* Keep it compressible with the events before it. */
+                last.action.compress = compress;
+                return;
+            }
+        }
+        self.event(action);
+    }
+
+    /// Whether an undo or redo is running actions. Changes they make are already part of the
+    /// history and shouldn't be recorded again.
+    pub fn is_replaying(&self) -> bool {
+        self.imp().replaying.get()
+    }
+
     pub fn undo(&self) {
         let mut did = false;
+        self.imp().replaying.set(true);
         {
             let mut cursor = self.imp().cursor.borrow_mut();
             let mut db = self.imp().database.borrow_mut();
@@ -179,6 +235,7 @@ impl UndoDatabase {
                 }
             }
         }
+        self.imp().replaying.set(false);
         if did {
             self.notify(Self::CAN_UNDO);
             self.notify(Self::CAN_REDO);
@@ -187,6 +244,7 @@ impl UndoDatabase {
 
     pub fn redo(&self) {
         let mut did = false;
+        self.imp().replaying.set(true);
         {
             let mut cursor = self.imp().cursor.borrow_mut();
             let mut db = self.imp().database.borrow_mut();
@@ -207,6 +265,7 @@ impl UndoDatabase {
                 }
             }
         }
+        self.imp().replaying.set(false);
         if did {
             self.notify(Self::CAN_UNDO);
             self.notify(Self::CAN_REDO);
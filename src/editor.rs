@@ -25,7 +25,9 @@ use once_cell::unsync::OnceCell;
 use std::collections::HashSet;
 
 use crate::app::settings::types::ShowMinimap;
-use crate::glyphs::{Contour, Glyph, GlyphDrawingOptions, GlyphPointIndex, Guideline};
+use crate::glyphs::{
+    Anchor, Component, Contour, Glyph, GlyphDrawingOptions, GlyphPointIndex, Guideline,
+};
 use crate::prelude::*;
 use crate::views::{
     canvas::{Layer, LayerBuilder},
@@ -33,7 +35,9 @@ use crate::views::{
 };
 
 mod layers;
+mod line;
 mod menu;
+mod metrics;
 mod settings;
 mod shortcuts;
 mod state;
@@ -49,7 +53,9 @@ type StatusBarMessage = u32;
 pub struct EditorInner {
     app: OnceCell<Application>,
     project: OnceCell<Project>,
-    glyph: OnceCell<Rc<RefCell<Glyph>>>,
+    glyph: RefCell<Option<Rc<RefCell<Glyph>>>>,
+    /// Handler of the edited glyph's `modified` notifications.
+    glyph_modified_handler: RefCell<Option<glib::SignalHandlerId>>,
     state: OnceCell<Rc<RefCell<State>>>,
     viewport: Canvas,
     statusbar_context_id: Cell<Option<u32>>,
@@ -65,11 +71,19 @@ pub struct EditorInner {
     show_glyph_guidelines: Cell<bool>,
     show_project_guidelines: Cell<bool>,
     show_metrics_guidelines: Cell<bool>,
+    show_missing_extrema: Cell<bool>,
+    show_curvature_comb: Cell<bool>,
+    curvature_comb_scale: Cell<f64>,
+    curvature_comb_density: Cell<u64>,
+    curvature_comb_selection: Cell<bool>,
     modifying_in_process: Cell<bool>,
     show_minimap: Cell<ShowMinimap>,
     settings: OnceCell<Settings>,
     menubar: gtk::MenuBar,
     preview: Cell<Option<StatusBarMessage>>,
+    mark_preview: Cell<Option<StatusBarMessage>>,
+    /// Index of the glyph shown attached to each anchor name by the mark preview.
+    mark_preview_choices: RefCell<IndexMap<String, usize>>,
     ctrl: OnceCell<gtk::EventControllerKey>,
     action_group: gio::SimpleActionGroup,
     lock: Cell<(Option<StatusBarMessage>, tools::constraints::Lock)>,
@@ -77,6 +91,20 @@ pub struct EditorInner {
     precision: Cell<(Option<StatusBarMessage>, tools::constraints::Precision)>,
     shortcuts: shortcuts::Shortcuts,
     shortcut_status: gtk::Box,
+    /// Outline shown while the simplify dialog is open.
+    simplify_preview: RefCell<Option<Vec<Contour>>>,
+    /// LSB, advance and RSB fields of the metrics bar.
+    metrics_fields: OnceCell<[gtk::SpinButton; 3]>,
+    /// Set while the metrics bar fields are refreshed from the glyph, so that the change isn't
+    /// applied back.
+    updating_metrics_bar: Cell<bool>,
+    /// Text of the editing line, see [`Editor::set_line_text`].
+    line_text: RefCell<String>,
+    /// Index of the edited glyph in the editing line.
+    line_active: Cell<usize>,
+    /// Editing line as last laid out. Cleared when the text or the edited glyph changes, and
+    /// laid out again when an advance width changed.
+    line_layout: RefCell<Option<Vec<crate::glyphs::PlacedGlyph>>>,
 }
 
 #[glib::object_subclass]
@@ -95,6 +123,10 @@ impl ObjectImpl for EditorInner {
         self.show_glyph_guidelines.set(true);
         self.show_project_guidelines.set(true);
         self.show_metrics_guidelines.set(true);
+        self.curvature_comb_scale
+            .set(settings::EditorSettingsInner::CURVATURE_COMB_SCALE_INIT_VAL);
+        self.curvature_comb_density
+            .set(settings::EditorSettingsInner::CURVATURE_COMB_DENSITY_INIT_VAL);
         self.statusbar_context_id.set(None);
         self.viewport.set_mouse(ViewPoint((0.0, 0.0).into()));
 
@@ -166,6 +198,94 @@ impl ObjectImpl for EditorInner {
                 }))))
                 .build(),
         );
+        self.viewport.add_layer(
+            LayerBuilder::new()
+                .set_name(Some("editing line"))
+                .set_active(true)
+                .set_hidden(false)
+                .set_callback(Some(Box::new(clone!(@weak obj => @default-return Inhibit(false), move |viewport: &Canvas, mut cr: ContextRef<'_, '_>| {
+                    layers::draw_line(viewport, cr.push(), obj)
+                }))))
+                .build(),
+        );
+        {
+            let layer = LayerBuilder::new()
+                .set_name(Some("extrema"))
+                .set_active(false)
+                .set_hidden(false)
+                .set_callback(Some(Box::new(clone!(@weak obj => @default-return Inhibit(false), move |viewport: &Canvas, mut cr: ContextRef<'_, '_>| {
+                    layers::draw_missing_extrema(viewport, cr.push(), obj)
+                }))))
+                .build();
+            obj.bind_property(Editor::SHOW_MISSING_EXTREMA, &layer, Layer::ACTIVE)
+                .flags(glib::BindingFlags::BIDIRECTIONAL | glib::BindingFlags::SYNC_CREATE)
+                .build();
+            self.viewport.add_layer(layer);
+        }
+        {
+            let layer = LayerBuilder::new()
+                .set_name(Some("curvature comb"))
+                .set_active(false)
+                .set_hidden(false)
+                .set_callback(Some(Box::new(clone!(@weak obj => @default-return Inhibit(false), move |viewport: &Canvas, mut cr: ContextRef<'_, '_>| {
+                    layers::draw_curvature_comb(viewport, cr.push(), obj)
+                }))))
+                .build();
+            obj.bind_property(Editor::SHOW_CURVATURE_COMB, &layer, Layer::ACTIVE)
+                .flags(glib::BindingFlags::BIDIRECTIONAL | glib::BindingFlags::SYNC_CREATE)
+                .build();
+            self.viewport.add_layer(layer);
+        }
+        self.viewport.add_layer(
+            LayerBuilder::new()
+                .set_name(Some("sidebearings"))
+                .set_active(true)
+                .set_hidden(false)
+                .set_callback(Some(Box::new(clone!(@weak obj => @default-return Inhibit(false), move |viewport: &Canvas, mut cr: ContextRef<'_, '_>| {
+                    layers::draw_sidebearings(viewport, cr.push(), obj)
+                }))))
+                .build(),
+        );
+        self.viewport.add_layer(
+            LayerBuilder::new()
+                .set_name(Some("mark preview"))
+                .set_active(true)
+                .set_hidden(true)
+                .set_callback(Some(Box::new(clone!(@weak obj => @default-return Inhibit(false), move |viewport: &Canvas, mut cr: ContextRef<'_, '_>| {
+                    layers::draw_mark_preview(viewport, cr.push(), obj)
+                }))))
+                .build(),
+        );
+        self.viewport.add_layer(
+            LayerBuilder::new()
+                .set_name(Some("component selection"))
+                .set_active(true)
+                .set_hidden(true)
+                .set_callback(Some(Box::new(clone!(@weak obj => @default-return Inhibit(false), move |viewport: &Canvas, mut cr: ContextRef<'_, '_>| {
+                    layers::draw_component_selection(viewport, cr.push(), obj)
+                }))))
+                .build(),
+        );
+        self.viewport.add_layer(
+            LayerBuilder::new()
+                .set_name(Some("anchors"))
+                .set_active(true)
+                .set_hidden(false)
+                .set_callback(Some(Box::new(clone!(@weak obj => @default-return Inhibit(false), move |viewport: &Canvas, mut cr: ContextRef<'_, '_>| {
+                    layers::draw_anchors(viewport, cr.push(), obj)
+                }))))
+                .build(),
+        );
+        self.viewport.add_layer(
+            LayerBuilder::new()
+                .set_name(Some("simplify preview"))
+                .set_active(true)
+                .set_hidden(true)
+                .set_callback(Some(Box::new(clone!(@weak obj => @default-return Inhibit(false), move |viewport: &Canvas, mut cr: ContextRef<'_, '_>| {
+                    layers::draw_simplify_preview(viewport, cr.push(), obj)
+                }))))
+                .build(),
+        );
         self.viewport.add_pre_layer(
             LayerBuilder::new()
                 .set_name(Some("guidelines"))
@@ -189,6 +309,16 @@ impl ObjectImpl for EditorInner {
             .add_overlay(Child::new(self.toolbar_box.clone()));
         self.overlay
             .add_overlay(Child::new(self.create_layer_widget()).expanded(false));
+        self.overlay
+            .add_overlay(Child::new(self.create_metrics_bar(obj)).expanded(false));
+        self.viewport.connect_draw(
+            clone!(@weak obj => @default-return Inhibit(false), move |_, _| {
+                // Outline edits don't notify anything, so refresh the fields whenever the
+                // canvas is redrawn.
+                obj.update_metrics_bar();
+                Inhibit(false)
+            }),
+        );
         obj.add(&self.overlay);
         obj.set_visible(true);
         obj.set_expand(true);
@@ -196,8 +326,8 @@ impl ObjectImpl for EditorInner {
     }
 
     fn properties() -> &'static [glib::ParamSpec] {
-        static PROPERTIES: once_cell::sync::Lazy<Vec<glib::ParamSpec>> =
-            once_cell::sync::Lazy::new(|| {
+        static PROPERTIES: once_cell::sync::Lazy<Vec<glib::ParamSpec>> = once_cell::sync::Lazy::new(
+            || {
                 vec![
                     glib::ParamSpecString::new(
                         Editor::TITLE,
@@ -220,6 +350,13 @@ impl ObjectImpl for EditorInner {
                         false,
                         glib::ParamFlags::READWRITE,
                     ),
+                    glib::ParamSpecBoolean::new(
+                        Editor::MARK_PREVIEW,
+                        Editor::MARK_PREVIEW,
+                        "Draw marks attached to the glyph's anchors, or bases attached to a mark's anchors.",
+                        false,
+                        glib::ParamFlags::READWRITE,
+                    ),
                     glib::ParamSpecBoolean::new(
                         Editor::IS_MENU_VISIBLE,
                         Editor::IS_MENU_VISIBLE,
@@ -260,6 +397,45 @@ impl ObjectImpl for EditorInner {
                         true,
                         glib::ParamFlags::READWRITE | UI_EDITABLE,
                     ),
+                    glib::ParamSpecBoolean::new(
+                        Editor::SHOW_MISSING_EXTREMA,
+                        Editor::SHOW_MISSING_EXTREMA,
+                        Editor::SHOW_MISSING_EXTREMA,
+                        false,
+                        glib::ParamFlags::READWRITE | UI_EDITABLE,
+                    ),
+                    glib::ParamSpecBoolean::new(
+                        Editor::SHOW_CURVATURE_COMB,
+                        Editor::SHOW_CURVATURE_COMB,
+                        Editor::SHOW_CURVATURE_COMB,
+                        false,
+                        glib::ParamFlags::READWRITE | UI_EDITABLE,
+                    ),
+                    glib::ParamSpecDouble::new(
+                        Editor::CURVATURE_COMB_SCALE,
+                        Editor::CURVATURE_COMB_SCALE,
+                        Editor::CURVATURE_COMB_SCALE,
+                        0.0,
+                        1_000_000.0,
+                        settings::EditorSettingsInner::CURVATURE_COMB_SCALE_INIT_VAL,
+                        glib::ParamFlags::READWRITE | UI_EDITABLE,
+                    ),
+                    glib::ParamSpecUInt64::new(
+                        Editor::CURVATURE_COMB_DENSITY,
+                        Editor::CURVATURE_COMB_DENSITY,
+                        Editor::CURVATURE_COMB_DENSITY,
+                        2,
+                        200,
+                        settings::EditorSettingsInner::CURVATURE_COMB_DENSITY_INIT_VAL,
+                        glib::ParamFlags::READWRITE | UI_EDITABLE,
+                    ),
+                    glib::ParamSpecBoolean::new(
+                        Editor::CURVATURE_COMB_SELECTION,
+                        Editor::CURVATURE_COMB_SELECTION,
+                        Editor::CURVATURE_COMB_SELECTION,
+                        false,
+                        glib::ParamFlags::READWRITE | UI_EDITABLE,
+                    ),
                     glib::ParamSpecBoolean::new(
                         Editor::MODIFYING_IN_PROCESS,
                         Editor::MODIFYING_IN_PROCESS,
@@ -324,7 +500,8 @@ impl ObjectImpl for EditorInner {
                         glib::ParamFlags::READWRITE,
                     ),
                 ]
-            });
+            },
+        );
         PROPERTIES.as_ref()
     }
 
@@ -343,6 +520,7 @@ impl ObjectImpl for EditorInner {
             }
             Editor::CLOSEABLE => true.to_value(),
             Editor::PREVIEW => self.preview.get().is_some().to_value(),
+            Editor::MARK_PREVIEW => self.mark_preview.get().is_some().to_value(),
             Editor::IS_MENU_VISIBLE => true.to_value(),
             Editor::UNITS_PER_EM => self.units_per_em.get().to_value(),
             Editor::X_HEIGHT => self.x_height.get().to_value(),
@@ -353,6 +531,11 @@ impl ObjectImpl for EditorInner {
             Editor::SHOW_GLYPH_GUIDELINES => self.show_glyph_guidelines.get().to_value(),
             Editor::SHOW_PROJECT_GUIDELINES => self.show_project_guidelines.get().to_value(),
             Editor::SHOW_METRICS_GUIDELINES => self.show_metrics_guidelines.get().to_value(),
+            Editor::SHOW_MISSING_EXTREMA => self.show_missing_extrema.get().to_value(),
+            Editor::SHOW_CURVATURE_COMB => self.show_curvature_comb.get().to_value(),
+            Editor::CURVATURE_COMB_SCALE => self.curvature_comb_scale.get().to_value(),
+            Editor::CURVATURE_COMB_DENSITY => self.curvature_comb_density.get().to_value(),
+            Editor::CURVATURE_COMB_SELECTION => self.curvature_comb_selection.get().to_value(),
             Editor::MODIFYING_IN_PROCESS => self.modifying_in_process.get().to_value(),
             Editor::SHOW_MINIMAP => self.show_minimap.get().to_value(),
             Editor::ACTIVE_TOOL => {
@@ -409,6 +592,26 @@ impl ObjectImpl for EditorInner {
             Editor::SHOW_METRICS_GUIDELINES => {
                 self.show_metrics_guidelines.set(value.get().unwrap());
             }
+            Editor::SHOW_MISSING_EXTREMA => {
+                self.show_missing_extrema.set(value.get().unwrap());
+                self.viewport.queue_draw();
+            }
+            Editor::SHOW_CURVATURE_COMB => {
+                self.show_curvature_comb.set(value.get().unwrap());
+                self.viewport.queue_draw();
+            }
+            Editor::CURVATURE_COMB_SCALE => {
+                self.curvature_comb_scale.set(value.get().unwrap());
+                self.viewport.queue_draw();
+            }
+            Editor::CURVATURE_COMB_DENSITY => {
+                self.curvature_comb_density.set(value.get().unwrap());
+                self.viewport.queue_draw();
+            }
+            Editor::CURVATURE_COMB_SELECTION => {
+                self.curvature_comb_selection.set(value.get().unwrap());
+                self.viewport.queue_draw();
+            }
             Editor::MODIFYING_IN_PROCESS => {
                 self.modifying_in_process.set(value.get().unwrap());
             }
@@ -431,6 +634,28 @@ impl ObjectImpl for EditorInner {
                 }
                 self.viewport.queue_draw();
             }
+            Editor::MARK_PREVIEW => {
+                let v: bool = value.get().unwrap();
+                if let Some(mid) = self.mark_preview.get() {
+                    if v {
+                        return;
+                    }
+                    self.pop_statusbar_message(Some(mid));
+                    self.mark_preview.set(None);
+                } else {
+                    if !v {
+                        return;
+                    }
+                    /* Anchors of other glyphs are only known once they are parsed. */
+                    let layer = self.glyph().borrow().metadata.layer().clone();
+                    if let Some(Err(err)) = layer.map(|l| l.load_all()) {
+                        eprintln!("{err}");
+                    }
+                    self.mark_preview
+                        .set(self.new_statusbar_message("Mark preview."));
+                }
+                self.viewport.queue_draw();
+            }
             Editor::LOCK => {
                 if let Some(v) = value
                     .get::<u32>()
@@ -506,8 +731,8 @@ impl EditorInner {
         self.project.get().unwrap()
     }
 
-    pub fn glyph(&self) -> &Rc<RefCell<Glyph>> {
-        self.glyph.get().unwrap()
+    pub fn glyph(&self) -> Rc<RefCell<Glyph>> {
+        self.glyph.borrow().clone().unwrap()
     }
 
     pub fn app_settings(&self) -> &Settings {
@@ -518,7 +743,7 @@ impl EditorInner {
         let statusbar = self.app().statusbar();
         if self.statusbar_context_id.get().is_none() {
             self.statusbar_context_id.set(Some(
-                statusbar.context_id(&format!("Editor-{:?}", &self.glyph.get().unwrap())),
+                statusbar.context_id(&format!("Editor-{:?}", self.glyph())),
             ));
         }
         if let Some(cid) = self.statusbar_context_id.get().as_ref() {
@@ -553,6 +778,7 @@ impl Editor {
     pub const IS_MENU_VISIBLE: &'static str = Workspace::IS_MENU_VISIBLE;
     pub const MENUBAR: &'static str = Workspace::MENUBAR;
     pub const PREVIEW: &'static str = "preview";
+    pub const MARK_PREVIEW: &'static str = "mark-preview";
     inherit_property!(
         FontInfo,
         ASCENDER,
@@ -570,12 +796,20 @@ impl Editor {
         LOCK_GUIDELINES,
         SHOW_GLYPH_GUIDELINES,
         SHOW_PROJECT_GUIDELINES,
-        SHOW_METRICS_GUIDELINES
+        SHOW_METRICS_GUIDELINES,
+        SHOW_MISSING_EXTREMA,
+        SHOW_CURVATURE_COMB,
+        CURVATURE_COMB_SCALE,
+        CURVATURE_COMB_DENSITY,
+        CURVATURE_COMB_SELECTION
     );
 
     pub fn new(app: Application, glyph: Rc<RefCell<Glyph>>) -> Self {
+        if let Err(err) = Glyph::load(&glyph) {
+            eprintln!("{err}");
+        }
         let ret: Self = glib::Object::new(&[]).unwrap();
-        ret.glyph.set(glyph.clone()).unwrap();
+        *ret.glyph.borrow_mut() = Some(glyph.clone());
         ret.app.set(app.clone()).unwrap();
         let project = app.runtime.project.borrow().clone();
         ret.connect_map(|self_| {
@@ -641,6 +875,29 @@ impl Editor {
         {
             let prop_action = gio::PropertyAction::new(Self::PREVIEW_ACTION, &ret, Self::PREVIEW);
             ret.action_group.add_action(&prop_action);
+            let prop_action =
+                gio::PropertyAction::new(Self::MARK_PREVIEW_ACTION, &ret, Self::MARK_PREVIEW);
+            ret.action_group.add_action(&prop_action);
+            for (action_name, forward) in [
+                (Self::MARK_PREVIEW_NEXT_ACTION, true),
+                (Self::MARK_PREVIEW_PREVIOUS_ACTION, false),
+            ] {
+                let action = gio::SimpleAction::new(action_name, None);
+                action.connect_activate(glib::clone!(@weak ret as obj => move |_, _| {
+                    obj.cycle_mark_preview(forward);
+                }));
+                ret.action_group.add_action(&action);
+            }
+            for (action_name, forward) in [
+                (Self::LINE_NEXT_ACTION, true),
+                (Self::LINE_PREVIOUS_ACTION, false),
+            ] {
+                let action = gio::SimpleAction::new(action_name, None);
+                action.connect_activate(glib::clone!(@weak ret as obj => move |_, _| {
+                    obj.cycle_line_glyph(forward);
+                }));
+                ret.action_group.add_action(&action);
+            }
         }
         for (zoom_action, tool_func) in [
             (
@@ -667,6 +924,23 @@ impl Editor {
                 ret.viewport.clone(),
             ))))
             .expect("Failed to create glyph state");
+        ret.connect_glyph_modified(&glyph);
+        ret.connect_notify_local(Some(Self::MODIFYING_IN_PROCESS), |ret, _| {
+            if ret.property::<bool>(Self::MODIFYING_IN_PROCESS) {
+                return;
+            }
+            // Tools don't mark the glyph as modified while editing, so glyphs with metric
+            // keys referring to it are updated once the edit is done, in its last undo step.
+            let (layer, name) = {
+                let glyph = ret.glyph();
+                let glyph = glyph.borrow();
+                let layer = glyph.metadata.layer().clone();
+                (layer, glyph.name().clone())
+            };
+            if let Some(action) = layer.and_then(|layer| layer.update_metric_dependents(&name)) {
+                ret.app().undo_db.borrow().amend(action);
+            }
+        });
         ret.project.set(project).unwrap();
         Tool::setup_toolbox(&ret, glyph);
         ret.setup_menu(&ret);
@@ -766,6 +1040,85 @@ impl Editor {
         self.state().borrow().add_undo_action(action);
     }
 
+    /// Glyphs the mark preview can attach to the anchor `name` of the edited glyph: marks with
+    /// a `_name` anchor for a base anchor, or glyphs with a `name` anchor and no mark anchors
+    /// for a mark anchor `_name`. Returns each glyph with the position of its matching anchor.
+    pub fn mark_preview_candidates(&self, name: &str) -> Vec<(Rc<RefCell<Glyph>>, Point)> {
+        let glyph = self.glyph();
+        let Some(layer) = glyph.borrow().metadata.layer().clone() else {
+            return vec![];
+        };
+        let (target, want_mark) = match name.strip_prefix('_') {
+            Some(base_name) => (base_name.to_string(), false),
+            None => (format!("_{name}"), true),
+        };
+        let glyphs = layer.glyphs();
+        glyphs
+            .values()
+            .filter(|g| !Rc::ptr_eq(g, &glyph))
+            .filter_map(|g| {
+                let b = g.borrow();
+                if !b.is_loaded() {
+                    return None;
+                }
+                let anchors = b.metadata.anchors.borrow();
+                if !want_mark && anchors.iter().any(Anchor::is_mark) {
+                    return None;
+                }
+                let position = anchors.iter().find(|a| a.name() == target)?.position();
+                Some((g.clone(), position))
+            })
+            .collect()
+    }
+
+    /// Shows the next, or previous, candidate of the mark preview for the selected anchors, or
+    /// for every anchor if none is selected.
+    pub fn cycle_mark_preview(&self, forward: bool) {
+        if !self.property::<bool>(Self::MARK_PREVIEW) {
+            return;
+        }
+        let names = {
+            let state = self.state().borrow();
+            if state.anchor_selection.is_empty() {
+                let glyph = state.glyph.borrow();
+                let anchors = glyph.metadata.anchors.borrow();
+                anchors.iter().map(|a| a.name()).collect::<Vec<String>>()
+            } else {
+                state.anchor_selection.iter().map(|a| a.name()).collect()
+            }
+        };
+        let mut msg = vec![];
+        for name in names {
+            let candidates = self.mark_preview_candidates(&name);
+            if candidates.is_empty() {
+                continue;
+            }
+            let mut choices = self.mark_preview_choices.borrow_mut();
+            let idx = choices.entry(name.clone()).or_insert(0);
+            *idx = if forward {
+                (*idx + 1) % candidates.len()
+            } else {
+                (*idx + candidates.len() - 1) % candidates.len()
+            };
+            msg.push(format!(
+                "{name}: {} ({}/{})",
+                candidates[*idx].0.borrow().name(),
+                *idx + 1,
+                candidates.len()
+            ));
+        }
+        if let Some(mid) = self.mark_preview.get() {
+            self.pop_statusbar_message(Some(mid));
+        }
+        self.mark_preview
+            .set(self.new_statusbar_message(&if msg.is_empty() {
+                "Mark preview: no glyphs to attach.".to_string()
+            } else {
+                format!("Mark preview: {}.", msg.join(", "))
+            }));
+        self.viewport.queue_draw();
+    }
+
     pub fn state(&self) -> &Rc<RefCell<State>> {
         self.state.get().unwrap()
     }
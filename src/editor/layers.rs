@@ -186,6 +186,356 @@ pub fn draw_glyph_layer(viewport: &Canvas, mut cr: ContextRef, obj: Editor) -> I
     Inhibit(false)
 }
 
+pub fn draw_missing_extrema(viewport: &Canvas, mut cr: ContextRef, obj: Editor) -> Inhibit {
+    if obj.property::<bool>(Editor::PREVIEW) {
+        return Inhibit(false);
+    }
+    let scale: f64 = viewport
+        .transformation
+        .property::<f64>(Transformation::SCALE);
+    let ppu: f64 = viewport
+        .transformation
+        .property::<f64>(Transformation::PIXELS_PER_UNIT);
+    let line_width = obj.app_settings().property::<f64>(Settings::LINE_WIDTH) / (scale * ppu);
+    let radius = 2.0 * obj.app_settings().property::<f64>(Settings::HANDLE_SIZE) / (scale * ppu);
+    let state = obj.state().borrow();
+    cr.transform(viewport.transformation.matrix());
+    cr.set_line_width(line_width);
+    cr.set_source_color_alpha(Color::from_hex("#ff6f00")); // [ref:hardcoded_color_value]
+    for p in state
+        .glyph
+        .borrow()
+        .contours
+        .iter()
+        .flat_map(Contour::missing_extrema)
+    {
+        /* Draw a cross so the marker stands out from on-curve handles. */
+        cr.move_to(p.x - radius, p.y - radius);
+        cr.line_to(p.x + radius, p.y + radius);
+        cr.move_to(p.x - radius, p.y + radius);
+        cr.line_to(p.x + radius, p.y - radius);
+        cr.stroke().unwrap();
+    }
+    Inhibit(false)
+}
+
+pub fn draw_curvature_comb(viewport: &Canvas, mut cr: ContextRef, obj: Editor) -> Inhibit {
+    if obj.property::<bool>(Editor::PREVIEW) {
+        return Inhibit(false);
+    }
+    let scale: f64 = viewport
+        .transformation
+        .property::<f64>(Transformation::SCALE);
+    let ppu: f64 = viewport
+        .transformation
+        .property::<f64>(Transformation::PIXELS_PER_UNIT);
+    let line_width = obj.app_settings().property::<f64>(Settings::LINE_WIDTH) / (scale * ppu);
+    let radius = 2.0 * obj.app_settings().property::<f64>(Settings::HANDLE_SIZE) / (scale * ppu);
+    let comb_scale = obj.property::<f64>(Editor::CURVATURE_COMB_SCALE);
+    let density = obj.property::<u64>(Editor::CURVATURE_COMB_DENSITY) as usize;
+    let state = obj.state().borrow();
+    let selected = obj
+        .property::<bool>(Editor::CURVATURE_COMB_SELECTION)
+        .then(|| {
+            state
+                .get_selection()
+                .iter()
+                .map(|idx| idx.contour_index)
+                .collect::<Vec<usize>>()
+        });
+    cr.transform(viewport.transformation.matrix());
+    cr.set_line_width(line_width);
+    let tip = |(position, normal, curvature): (Point, Point, f64)| {
+        position - (comb_scale * curvature) * normal
+    };
+    for (contour_index, contour) in state.glyph.borrow().contours.iter().enumerate() {
+        if selected
+            .as_ref()
+            .map_or(false, |idxs| !idxs.contains(&contour_index))
+        {
+            continue;
+        }
+        cr.set_source_color_alpha(Color::from_hex("#00897b").with_alpha(150)); // [ref:hardcoded_color_value]
+        for samples in contour.curvature_comb(density) {
+            /* Teeth, then the outline joining their tips. */
+            for &sample in &samples {
+                let (position, end) = (sample.0, tip(sample));
+                cr.move_to(position.x, position.y);
+                cr.line_to(end.x, end.y);
+            }
+            cr.stroke().unwrap();
+            for (i, &sample) in samples.iter().enumerate() {
+                let end = tip(sample);
+                if i == 0 {
+                    cr.move_to(end.x, end.y);
+                } else {
+                    cr.line_to(end.x, end.y);
+                }
+            }
+            cr.stroke().unwrap();
+        }
+        cr.set_source_color_alpha(Color::from_hex("#e53935")); // [ref:hardcoded_color_value]
+        for (position, normal, before, after) in contour.curvature_discontinuities() {
+            /* Join the two tips of the comb at the discontinuity. */
+            let (a, b) = (
+                tip((position, normal, before)),
+                tip((position, normal, after)),
+            );
+            cr.move_to(a.x, a.y);
+            cr.line_to(b.x, b.y);
+            cr.stroke().unwrap();
+            cr.arc(
+                position.x,
+                position.y,
+                radius,
+                0.0,
+                2.0 * std::f64::consts::PI,
+            );
+            cr.stroke().unwrap();
+        }
+    }
+    Inhibit(false)
+}
+
+pub fn draw_simplify_preview(viewport: &Canvas, mut cr: ContextRef, obj: Editor) -> Inhibit {
+    let preview = obj.imp().simplify_preview.borrow();
+    let Some(contours) = preview.as_ref() else {
+        return Inhibit(false);
+    };
+    let scale: f64 = viewport
+        .transformation
+        .property::<f64>(Transformation::SCALE);
+    let ppu: f64 = viewport
+        .transformation
+        .property::<f64>(Transformation::PIXELS_PER_UNIT);
+    let line_width = obj.app_settings().property::<f64>(Settings::LINE_WIDTH) / (scale * ppu);
+    let radius = obj.app_settings().property::<f64>(Settings::HANDLE_SIZE) / (scale * ppu);
+    cr.transform(viewport.transformation.matrix());
+    cr.set_line_width(2.0 * line_width);
+    cr.set_source_color_alpha(Color::from_hex("#1e88e5").with_alpha(200)); // [ref:hardcoded_color_value]
+    for contour in contours {
+        contour.append_path(&cr);
+        cr.stroke().unwrap();
+        /* Mark the on-curve points that remain. */
+        for curve in contour.curves().iter() {
+            let p = curve.points()[0].position;
+            cr.arc(p.x, p.y, radius, 0.0, 2.0 * std::f64::consts::PI);
+            cr.fill().unwrap();
+        }
+    }
+    Inhibit(false)
+}
+
+pub fn draw_line(viewport: &Canvas, mut cr: ContextRef, obj: Editor) -> Inhibit {
+    let (line, active) = obj.line();
+    if line.len() < 2 {
+        return Inhibit(false);
+    }
+    let preview = obj.property::<bool>(Editor::PREVIEW);
+    let units_per_em = obj.property::<f64>(Editor::UNITS_PER_EM);
+    let ascender = obj.property::<f64>(Editor::ASCENDER);
+    let scale: f64 = viewport
+        .transformation
+        .property::<f64>(Transformation::SCALE);
+    let ppu: f64 = viewport
+        .transformation
+        .property::<f64>(Transformation::PIXELS_PER_UNIT);
+    let line_width = obj.app_settings().property::<f64>(Settings::LINE_WIDTH) / (scale * ppu);
+    let fill = if preview {
+        Color::BLACK
+    } else {
+        Color::BLACK.with_alpha(160)
+    };
+    cr.transform(viewport.transformation.matrix());
+    for (i, p) in line.iter().enumerate() {
+        if i == active {
+            continue;
+        }
+        if let Some(ref glyph) = p.glyph {
+            glyph.borrow().draw(
+                cr.push(),
+                GlyphDrawingOptions {
+                    outline: (Color::new_alpha(0, 0, 0, 0), 0.0).into(),
+                    inner_fill: Some((fill, 0.0).into()),
+                    matrix: Matrix::new(1.0, 0.0, 0.0, 1.0, p.x, 0.0),
+                    units_per_em,
+                    ..Default::default()
+                },
+            );
+        } else if !preview {
+            /* Missing glyphs are drawn as a crossed box. */
+            let cr1 = cr.push();
+            cr1.set_source_color_alpha(fill);
+            cr1.set_line_width(line_width);
+            cr1.rectangle(p.x, 0.0, p.width, 0.7 * ascender);
+            cr1.stroke_preserve().unwrap();
+            cr1.move_to(p.x, 0.0);
+            cr1.line_to(p.x + p.width, 0.7 * ascender);
+            cr1.stroke().unwrap();
+        }
+    }
+    Inhibit(false)
+}
+
+pub fn draw_sidebearings(viewport: &Canvas, mut cr: ContextRef, obj: Editor) -> Inhibit {
+    if obj.property::<bool>(Editor::PREVIEW) {
+        return Inhibit(false);
+    }
+    let descender = obj.property::<f64>(Editor::DESCENDER).min(0.0);
+    let ascender = obj.property::<f64>(Editor::ASCENDER);
+    let line_width = obj.app_settings().property::<f64>(Settings::LINE_WIDTH);
+    let (line, active) = obj.line();
+    let mouse = viewport.get_mouse();
+    let ViewPoint(origin) = viewport.unit_to_view_point(UnitPoint((0.0, descender).into()));
+    /* Draw in view coordinates so that the lines and labels keep their size. */
+    cr.set_line_width(line_width);
+    let color = Color::from_hex("#00897b"); // [ref:hardcoded_color_value]
+    for (i, p) in line.iter().enumerate() {
+        let Some(ref glyph) = p.glyph else {
+            continue;
+        };
+        let sidebearings = glyph.borrow().sidebearings();
+        /* Lines of the other glyphs of the editing line are fainter. Labels go inside the
+         * glyph's advance so that they don't overlap with its neighbours'. */
+        cr.set_source_color_alpha(color.with_alpha(if i == active { 200 } else { 100 }));
+        for (x, left, label) in [
+            (p.x, true, sidebearings.map(|(lsb, _)| lsb)),
+            (p.x + p.width, false, sidebearings.map(|(_, rsb)| rsb)),
+        ] {
+            let ViewPoint(bottom) = viewport.unit_to_view_point(UnitPoint((x, descender).into()));
+            let ViewPoint(top) = viewport.unit_to_view_point(UnitPoint((x, ascender).into()));
+            let hovering =
+                (mouse.0.x - bottom.x).abs() <= 6.0 && (top.y..=bottom.y).contains(&mouse.0.y);
+            cr.set_line_width(if hovering { 2.0 } else { 1.0 } * line_width);
+            cr.move_to(bottom.x, bottom.y);
+            cr.line_to(top.x, top.y);
+            cr.stroke().unwrap();
+            if let Some(label) = label {
+                let label = format!("{}", label.round());
+                let extents = cr.text_extents(&label).unwrap();
+                let text_x = if left {
+                    bottom.x + 4.0
+                } else {
+                    bottom.x - extents.width - 4.0
+                };
+                cr.move_to(text_x, origin.y + extents.height + 4.0);
+                cr.show_text(&label).unwrap();
+            }
+        }
+    }
+    Inhibit(false)
+}
+
+pub fn draw_mark_preview(viewport: &Canvas, mut cr: ContextRef, obj: Editor) -> Inhibit {
+    if !obj.property::<bool>(Editor::MARK_PREVIEW) {
+        return Inhibit(false);
+    }
+    let preview = obj.property::<bool>(Editor::PREVIEW);
+    let units_per_em = obj.property::<f64>(Editor::UNITS_PER_EM);
+    let anchors = obj
+        .glyph()
+        .borrow()
+        .metadata
+        .anchors
+        .borrow()
+        .iter()
+        .map(|a| (a.name(), a.position()))
+        .collect::<Vec<(String, Point)>>();
+    let fill = if preview {
+        Color::BLACK
+    } else {
+        Color::from_hex("#1e88e5").with_alpha(120) // [ref:hardcoded_color_value]
+    };
+    cr.transform(viewport.transformation.matrix());
+    for (name, position) in anchors {
+        let candidates = obj.mark_preview_candidates(&name);
+        if candidates.is_empty() {
+            continue;
+        }
+        let idx = obj
+            .mark_preview_choices
+            .borrow()
+            .get(&name)
+            .copied()
+            .unwrap_or(0)
+            % candidates.len();
+        let (ref glyph, attach) = candidates[idx];
+        let offset = position - attach;
+        glyph.borrow().draw(
+            cr.push(),
+            GlyphDrawingOptions {
+                outline: (Color::new_alpha(0, 0, 0, 0), 0.0).into(),
+                inner_fill: Some((fill, 0.0).into()),
+                matrix: Matrix::new(1.0, 0.0, 0.0, 1.0, offset.x, offset.y),
+                units_per_em,
+                ..Default::default()
+            },
+        );
+    }
+    Inhibit(false)
+}
+
+pub fn draw_component_selection(viewport: &Canvas, mut cr: ContextRef, obj: Editor) -> Inhibit {
+    if obj.property::<bool>(Editor::PREVIEW) {
+        return Inhibit(false);
+    }
+    let state = obj.state().borrow();
+    let Some(component) = state
+        .component_selection
+        .and_then(|i| state.glyph.borrow().components.get(i).cloned())
+    else {
+        return Inhibit(false);
+    };
+    let scale: f64 = viewport
+        .transformation
+        .property::<f64>(Transformation::SCALE);
+    let ppu: f64 = viewport
+        .transformation
+        .property::<f64>(Transformation::PIXELS_PER_UNIT);
+    let line_width = obj.app_settings().property::<f64>(Settings::LINE_WIDTH) / (scale * ppu);
+    cr.transform(viewport.transformation.matrix());
+    cr.set_line_width(3.0 * line_width);
+    cr.set_source_color_alpha(Color::from_hex("#fb8c00").with_alpha(200)); // [ref:hardcoded_color_value]
+    for contour in component.decomposed() {
+        contour.append_path(&cr);
+        cr.stroke().unwrap();
+    }
+    Inhibit(false)
+}
+
+pub fn draw_anchors(viewport: &Canvas, mut cr: ContextRef, obj: Editor) -> Inhibit {
+    if obj.property::<bool>(Editor::PREVIEW) {
+        return Inhibit(false);
+    }
+    let line_width = obj.app_settings().property::<f64>(Settings::LINE_WIDTH);
+    let radius = 1.5 * obj.app_settings().property::<f64>(Settings::HANDLE_SIZE);
+    let state = obj.state().borrow();
+    let glyph = state.glyph.borrow();
+    cr.set_line_width(line_width);
+    for anchor in glyph.metadata.anchors.borrow().iter() {
+        /* Draw in view coordinates so that the marker and its label keep their size. */
+        let ViewPoint(p) = viewport.unit_to_view_point(UnitPoint(anchor.position()));
+        let color = if anchor.is_mark() {
+            Color::from_hex("#8e24aa") // [ref:hardcoded_color_value]
+        } else {
+            Color::from_hex("#c62828") // [ref:hardcoded_color_value]
+        };
+        cr.set_source_color(color);
+        cr.move_to(p.x, p.y - radius);
+        cr.line_to(p.x + radius, p.y);
+        cr.line_to(p.x, p.y + radius);
+        cr.line_to(p.x - radius, p.y);
+        cr.close_path();
+        if state.anchor_selection.contains(anchor) {
+            cr.fill_preserve().unwrap();
+        }
+        cr.stroke().unwrap();
+        cr.move_to(p.x + radius + 2.0, p.y - radius - 2.0);
+        cr.show_text(&anchor.name()).unwrap();
+    }
+    Inhibit(false)
+}
+
 pub fn draw_guidelines(viewport: &Canvas, mut cr: ContextRef, obj: Editor) -> Inhibit {
     let state = obj.state();
     let matrix = viewport.transformation.matrix();
@@ -0,0 +1,278 @@
+/*
+ * gerb
+ *
+ * Copyright 2022 - Manos Pitsidianakis
+ *
+ * This file is part of gerb.
+ *
+ * gerb is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * gerb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with gerb. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Editing line: the glyphs of a text string shown side by side around the edited glyph. Any
+//! of them can be made the edited glyph without leaving the view.
+
+use super::tools::ImageTool;
+use super::*;
+use crate::glyphs::{layout_text, PlacedGlyph, TextItem};
+use crate::ufo::objects::Layer;
+
+impl EditorInner {
+    /// Lays out the editing line with the current advance widths and kerning, and returns it
+    /// with the index of the edited glyph. Positions are relative to the edited glyph's origin.
+    pub fn line(&self) -> (Vec<PlacedGlyph>, usize) {
+        let glyph = self.glyph();
+        let mut line = self.layout_line();
+        let is_active = |p: &PlacedGlyph| p.glyph.as_ref().map_or(false, |g| Rc::ptr_eq(g, &glyph));
+        let active = Some(self.line_active.get())
+            .filter(|&i| line.get(i).map_or(false, is_active))
+            .or_else(|| line.iter().position(is_active));
+        let Some(active) = active else {
+            /* The text doesn't contain the edited glyph, so it is shown alone. */
+            let (name, width) = {
+                let glyph = glyph.borrow();
+                (glyph.name().clone(), glyph.width().unwrap_or(0.0))
+            };
+            return (
+                vec![PlacedGlyph {
+                    item: TextItem::Name(name),
+                    glyph: Some(glyph),
+                    x: 0.0,
+                    width,
+                    kerning: 0.0,
+                }],
+                0,
+            );
+        };
+        self.line_active.set(active);
+        let origin = line[active].x;
+        for p in line.iter_mut() {
+            p.x -= origin;
+        }
+        (line, active)
+    }
+
+    /// Index of the glyph of the editing line, other than the edited one, under the unit
+    /// coordinate `x`.
+    pub fn line_neighbour_at(&self, x: f64) -> Option<usize> {
+        let (line, active) = self.line();
+        line.iter()
+            .position(|p| p.glyph.is_some() && (p.x..(p.x + p.width)).contains(&x))
+            .filter(|&i| i != active)
+    }
+
+    fn layout_line(&self) -> Vec<PlacedGlyph> {
+        if let Some(line) = self.line_layout.borrow().as_ref().filter(|line| {
+            line.iter().all(|p| {
+                p.glyph
+                    .as_ref()
+                    .map_or(true, |g| g.borrow().width().unwrap_or(0.0) == p.width)
+            })
+        }) {
+            return line.clone();
+        }
+        let glyph = self.glyph();
+        let (name, layer) = {
+            let glyph = glyph.borrow();
+            (glyph.name().clone(), glyph.metadata.layer().clone())
+        };
+        let text = match self.line_text.borrow().as_str() {
+            "" => format!("/{name}"),
+            text => text.to_string(),
+        };
+        let project = self.project();
+        let layer = layer.unwrap_or_else(|| project.default_layer.clone());
+        if !layer.property::<bool>(Layer::LOADED) {
+            /* Codepoints and advance widths are only known once the glyphs are parsed. */
+            if let Err(err) = layer.load_all() {
+                eprintln!("{err}");
+            }
+        }
+        let kerning = |first: &str, second: &str| project.kerning_pair(first, second);
+        let line = layout_text(
+            &text,
+            &layer.glyphs(),
+            Some(&kerning),
+            self.units_per_em.get() / 2.0,
+        );
+        *self.line_layout.borrow_mut() = Some(line.clone());
+        line
+    }
+}
+
+impl Editor {
+    /// Shows the glyphs of `text`, e.g. `HHxHH` or `/a/a.sc`, around the edited glyph. If the
+    /// text doesn't contain the edited glyph, its first glyph is edited instead. An empty text
+    /// shows the edited glyph alone.
+    pub fn set_line_text(&self, text: &str) {
+        *self.line_text.borrow_mut() = text.to_string();
+        self.line_active.set(0);
+        self.line_layout.borrow_mut().take();
+        let line = self.layout_line();
+        let glyph = self.glyph();
+        if !line
+            .iter()
+            .filter_map(|p| p.glyph.as_ref())
+            .any(|g| Rc::ptr_eq(g, &glyph))
+        {
+            if let Some((index, first)) = line
+                .iter()
+                .enumerate()
+                .find_map(|(i, p)| Some((i, p.glyph.clone()?)))
+            {
+                self.set_glyph(first, index, 0.0);
+            }
+        }
+        self.viewport.queue_draw();
+    }
+
+    /// Makes the glyph at `index` of the editing line the edited glyph, keeping the line in
+    /// place on screen.
+    pub fn activate_line_glyph(&self, index: usize) {
+        if self.property::<bool>(Self::MODIFYING_IN_PROCESS) {
+            return;
+        }
+        let (line, active) = self.line();
+        if index == active {
+            return;
+        }
+        if let Some((glyph, x)) = line.get(index).and_then(|p| Some((p.glyph.clone()?, p.x))) {
+            self.set_glyph(glyph, index, x);
+        }
+    }
+
+    /// Activates the next (`forward`) or previous glyph of the editing line, skipping glyphs
+    /// the layer doesn't have.
+    pub fn cycle_line_glyph(&self, forward: bool) {
+        let (line, active) = self.line();
+        let next = if forward {
+            (active + 1..line.len()).find(|&i| line[i].glyph.is_some())
+        } else {
+            (0..active).rev().find(|&i| line[i].glyph.is_some())
+        };
+        if let Some(index) = next {
+            self.activate_line_glyph(index);
+        }
+    }
+
+    /// Edit `glyph`, placed `dx` units from the origin of the current one in the editing line.
+    fn set_glyph(&self, glyph: Rc<RefCell<Glyph>>, index: usize, dx: f64) {
+        if !self.app().window.load_glyph(&glyph) {
+            return;
+        }
+        let previous = self.glyph.borrow_mut().replace(glyph.clone());
+        if let (Some(previous), Some(handler)) = (previous, self.glyph_modified_handler.take()) {
+            previous.borrow().metadata.disconnect(handler);
+        }
+        self.connect_glyph_modified(&glyph);
+        self.line_active.set(index);
+        /* An empty text shows the edited glyph alone. */
+        if self.line_text.borrow().is_empty() {
+            self.line_layout.borrow_mut().take();
+        }
+        {
+            let mut state = self.state().borrow_mut();
+            state.glyph = glyph.clone();
+            state.anchor_selection.clear();
+            state.component_selection = None;
+            state.reload_contours();
+            if let Some(image) = state
+                .tools
+                .get(&ImageTool::static_type())
+                .and_then(|t| t.clone().downcast::<ImageTool>().ok())
+            {
+                image.set_glyph(&glyph);
+            }
+        }
+        self.hovering.set(None);
+        let t = &self.viewport.transformation;
+        let factor = t.property::<f64>(Transformation::SCALE)
+            * t.property::<f64>(Transformation::PIXELS_PER_UNIT);
+        t.move_camera_by_delta(ViewPoint((dx * factor, 0.0).into()));
+        t.set_property(
+            Transformation::CONTENT_WIDTH,
+            glyph
+                .borrow()
+                .width()
+                .unwrap_or_else(|| self.property::<f64>(Self::UNITS_PER_EM)),
+        );
+        self.update_metrics_bar();
+        self.notify(Self::TITLE);
+        self.viewport.queue_draw();
+    }
+
+    /// Reloads the points of the edited glyph when a command outside the editor's tools
+    /// replaces its contours.
+    pub(super) fn connect_glyph_modified(&self, glyph: &Rc<RefCell<Glyph>>) {
+        let handler = glyph.borrow().metadata.connect_notify_local(
+            Some(GlyphMetadata::MODIFIED),
+            clone!(@weak self as obj => move |metadata, _| {
+                if !metadata.modified() {
+                    return;
+                }
+                // Contours were replaced by a command outside the editor's tools, so point
+                // indices are stale. Reload once the command has released the glyph.
+                glib::idle_add_local(clone!(@weak obj => @default-return glib::Continue(false), move || {
+                    obj.state().borrow_mut().reload_contours();
+                    glib::Continue(false)
+                }));
+            }),
+        );
+        *self.glyph_modified_handler.borrow_mut() = Some(handler);
+    }
+
+    /// Open a dialog to set the text of the editing line.
+    pub fn line_text_dialog(&self) {
+        let dialog = gtk::Dialog::with_buttons(
+            Some("Editing line"),
+            Some(&self.app().window),
+            gtk::DialogFlags::MODAL | gtk::DialogFlags::DESTROY_WITH_PARENT,
+            &[
+                ("Cancel", gtk::ResponseType::Cancel),
+                ("Apply", gtk::ResponseType::Ok),
+            ],
+        );
+        let content_box: gtk::Box = dialog.content_area();
+        content_box.set_margin(5);
+        content_box.set_spacing(5);
+        let label = gtk::Label::new(Some(
+            "Glyphs to show around the edited glyph. Use /name for glyphs by name.",
+        ));
+        label.set_line_wrap(true);
+        let strings = gtk::ComboBoxText::with_entry();
+        for s in self.app_settings().text_preview_strings.borrow().iter() {
+            strings.append_text(s);
+        }
+        let Some(entry) = strings
+            .child()
+            .and_then(|c| c.downcast::<gtk::Entry>().ok())
+        else {
+            return;
+        };
+        entry.set_placeholder_text(Some("e.g. HHxHH or /a/a.sc/a"));
+        entry.set_text(&self.line_text.borrow());
+        entry.set_activates_default(true);
+        content_box.add(&label);
+        content_box.add(&strings);
+        dialog.set_default_response(gtk::ResponseType::Ok);
+        dialog.connect_response(
+            glib::clone!(@weak self as obj, @weak entry => move |dialog, response| {
+                if response == gtk::ResponseType::Ok {
+                    obj.set_line_text(&entry.text());
+                }
+                dialog.close();
+            }),
+        );
+        dialog.show_all();
+    }
+}
@@ -19,10 +19,11 @@
  * along with gerb. If not, see <http://www.gnu.org/licenses/>.
  */
 
-use super::{Editor, EditorInner};
-use crate::glyphs::Contour;
+use super::{Editor, EditorInner, SelectionModifier};
+use crate::glyphs::{Contour, Metric, MetricExpr};
 use crate::prelude::*;
 use crate::views::Canvas;
+use gtk::cairo::Matrix;
 use gtk::{gio, glib::subclass::prelude::*, prelude::*};
 
 fn new_accel_item(menu: &gio::Menu, app: &Application, label: &str, detailed_action_name: &str) {
@@ -46,10 +47,46 @@ impl EditorInner {
         {
             let glyph_menu = gio::Menu::new();
             new_accel_item(&glyph_menu, app, "Preview", "view.preview");
+            new_accel_item(&glyph_menu, app, "Preview marks", "view.mark-preview");
+            new_accel_item(
+                &glyph_menu,
+                app,
+                "Next mark preview",
+                "view.mark-preview.next",
+            );
+            new_accel_item(
+                &glyph_menu,
+                app,
+                "Previous mark preview",
+                "view.mark-preview.previous",
+            );
+            new_accel_item(&glyph_menu, app, "Editing line…", "glyph.editing-line");
+            new_accel_item(&glyph_menu, app, "Next glyph in line", "view.line.next");
+            new_accel_item(
+                &glyph_menu,
+                app,
+                "Previous glyph in line",
+                "view.line.previous",
+            );
             new_accel_item(&glyph_menu, app, "Save", "glyph.save");
             new_accel_item(&glyph_menu, app, "Properties", "glyph.properties");
             new_accel_item(&glyph_menu, app, "Inspect", "glyph.inspect");
             new_accel_item(&glyph_menu, app, "Export to SVG", "glyph.export.svg");
+            new_accel_item(&glyph_menu, app, "Add extreme points", "glyph.add-extrema");
+            new_accel_item(&glyph_menu, app, "Simplify…", "glyph.simplify");
+            new_accel_item(&glyph_menu, app, "Metric keys…", "glyph.metric-keys");
+            new_accel_item(
+                &glyph_menu,
+                app,
+                "Correct path direction (PostScript)",
+                "glyph.correct-direction::postscript",
+            );
+            new_accel_item(
+                &glyph_menu,
+                app,
+                "Correct path direction (TrueType)",
+                "glyph.correct-direction::truetype",
+            );
             {
                 let view_glyph_menu = gio::Menu::new();
                 new_accel_item(&view_glyph_menu, app, "Show grid", "glyph.show.grid");
@@ -61,9 +98,44 @@ impl EditorInner {
                     "Show total area",
                     "glyph.show.total-area",
                 );
+                new_accel_item(
+                    &view_glyph_menu,
+                    app,
+                    "Show missing extrema",
+                    "glyph.show.missing-extrema",
+                );
+                new_accel_item(
+                    &view_glyph_menu,
+                    app,
+                    "Show curvature comb",
+                    "glyph.show.curvature-comb",
+                );
+                new_accel_item(
+                    &view_glyph_menu,
+                    app,
+                    "Curvature comb options…",
+                    "glyph.curvature-comb-options",
+                );
                 glyph_menu.append_section(None, &view_glyph_menu);
             }
             menumodel.append_submenu(Some("_Glyph"), &glyph_menu);
+            let prop_action = gtk::gio::PropertyAction::new(
+                "show.missing-extrema",
+                obj,
+                Editor::SHOW_MISSING_EXTREMA,
+            );
+            action_group.add_action(&prop_action);
+            let prop_action = gtk::gio::PropertyAction::new(
+                "show.curvature-comb",
+                obj,
+                Editor::SHOW_CURVATURE_COMB,
+            );
+            action_group.add_action(&prop_action);
+            let comb_options = gtk::gio::SimpleAction::new("curvature-comb-options", None);
+            comb_options.connect_activate(glib::clone!(@weak obj => move |_, _| {
+                obj.curvature_comb_options();
+            }));
+            action_group.add_action(&comb_options);
             for (action_name, property) in [
                 ("show.grid", Canvas::SHOW_GRID),
                 ("show.inner-fill", Canvas::INNER_FILL),
@@ -75,6 +147,18 @@ impl EditorInner {
                 action_group.add_action(&prop_action);
             }
         }
+        {
+            let point_menu = gio::Menu::new();
+            for point_type in PointType::ALL {
+                new_accel_item(
+                    &point_menu,
+                    app,
+                    point_type.label(),
+                    &format!("view.{}::{}", Editor::POINT_TYPE_ACTION, point_type.name()),
+                );
+            }
+            menumodel.append_submenu(Some("_Point"), &point_menu);
+        }
         {
             let curve_menu = gio::Menu::new();
             new_accel_item(&curve_menu, app, "Properties", "glyph.curve.properties");
@@ -91,6 +175,13 @@ impl EditorInner {
             let contour_menu = gio::Menu::new();
             new_accel_item(&contour_menu, app, "Properties", "glyph.contour.properties");
             new_accel_item(&contour_menu, app, "Reverse", "glyph.contour.reverse");
+            new_accel_item(
+                &contour_menu,
+                app,
+                "Add extreme points",
+                "glyph.contour.add-extrema",
+            );
+            new_accel_item(&contour_menu, app, "Simplify…", "glyph.contour.simplify");
             menumodel.append_submenu(Some("_Contour"), &contour_menu);
         }
         {
@@ -164,6 +255,41 @@ impl EditorInner {
                 gtk::gio::PropertyAction::new("guideline.lock", obj, Editor::LOCK_GUIDELINES);
             action_group.add_action(&prop_action);
         }
+        {
+            let component_menu = gio::Menu::new();
+            new_accel_item(
+                &component_menu,
+                app,
+                "Properties",
+                "glyph.component.properties",
+            );
+            new_accel_item(
+                &component_menu,
+                app,
+                "Open base glyph",
+                "glyph.component.open-base",
+            );
+            new_accel_item(
+                &component_menu,
+                app,
+                "Decompose",
+                "glyph.component.decompose",
+            );
+            new_accel_item(
+                &component_menu,
+                app,
+                "Decompose all",
+                "glyph.component.decompose-all",
+            );
+            menumodel.append_submenu(Some("C_omponents"), &component_menu);
+        }
+        {
+            let anchor_menu = gio::Menu::new();
+            new_accel_item(&anchor_menu, app, "Add anchor", "glyph.anchor.add");
+            new_accel_item(&anchor_menu, app, "Properties", "glyph.anchor.properties");
+            new_accel_item(&anchor_menu, app, "Delete", "glyph.anchor.delete");
+            menumodel.append_submenu(Some("_Anchors"), &anchor_menu);
+        }
         {
             let layer_menu = gio::Menu::new();
             new_accel_item(&layer_menu, app, "Properties", "glyph.layer.properties");
@@ -197,6 +323,16 @@ impl EditorInner {
                 obj.make_debug_window();
             }));
             action_group.add_action(&inspect);
+            let editing_line = gtk::gio::SimpleAction::new("editing-line", None);
+            editing_line.connect_activate(glib::clone!(@weak obj => move |_, _| {
+                obj.line_text_dialog();
+            }));
+            action_group.add_action(&editing_line);
+            let metric_keys = gtk::gio::SimpleAction::new("metric-keys", None);
+            metric_keys.connect_activate(glib::clone!(@weak obj => move |_, _| {
+                obj.metric_keys();
+            }));
+            action_group.add_action(&metric_keys);
             let export_svg = gtk::gio::SimpleAction::new("export.svg", None);
             export_svg.connect_activate(clone!(@weak obj => move |_, _| {
                 let dialog = gtk::FileChooserDialog::builder()
@@ -227,6 +363,90 @@ impl EditorInner {
                 dialog.emit_close();
             }));
             action_group.add_action(&export_svg);
+            let add_extrema = gtk::gio::SimpleAction::new("add-extrema", None);
+            add_extrema.connect_activate(glib::clone!(@weak obj => move |_, _| {
+                obj.add_extrema(false);
+            }));
+            action_group.add_action(&add_extrema);
+            let contour_add_extrema = gtk::gio::SimpleAction::new("contour.add-extrema", None);
+            contour_add_extrema.connect_activate(glib::clone!(@weak obj => move |_, _| {
+                obj.add_extrema(true);
+            }));
+            action_group.add_action(&contour_add_extrema);
+            let simplify = gtk::gio::SimpleAction::new("simplify", None);
+            simplify.connect_activate(glib::clone!(@weak obj => move |_, _| {
+                obj.simplify(false);
+            }));
+            action_group.add_action(&simplify);
+            let contour_simplify = gtk::gio::SimpleAction::new("contour.simplify", None);
+            contour_simplify.connect_activate(glib::clone!(@weak obj => move |_, _| {
+                obj.simplify(true);
+            }));
+            action_group.add_action(&contour_simplify);
+            let correct_direction =
+                gtk::gio::SimpleAction::new("correct-direction", Some(glib::VariantTy::STRING));
+            correct_direction.connect_activate(glib::clone!(@weak obj => move |_, name| {
+                use glib::FromVariant;
+                if let Some(convention) = name
+                    .and_then(String::from_variant)
+                    .and_then(|s| DirectionConvention::from_name(&s))
+                {
+                    obj.correct_direction(convention);
+                }
+            }));
+            action_group.add_action(&correct_direction);
+            let contour_properties = gtk::gio::SimpleAction::new("contour.properties", None);
+            contour_properties.connect_activate(glib::clone!(@weak obj => move |_, _| {
+                obj.contour_properties();
+            }));
+            action_group.add_action(&contour_properties);
+            let component_properties = gtk::gio::SimpleAction::new("component.properties", None);
+            component_properties.connect_activate(glib::clone!(@weak obj => move |_, _| {
+                let index = obj.state().borrow().component_selection;
+                if let Some(index) = index {
+                    obj.component_properties(index);
+                }
+            }));
+            action_group.add_action(&component_properties);
+            let component_open_base = gtk::gio::SimpleAction::new("component.open-base", None);
+            component_open_base.connect_activate(glib::clone!(@weak obj => move |_, _| {
+                let index = obj.state().borrow().component_selection;
+                if let Some(index) = index {
+                    obj.open_component_base(index);
+                }
+            }));
+            action_group.add_action(&component_open_base);
+            let component_decompose = gtk::gio::SimpleAction::new("component.decompose", None);
+            component_decompose.connect_activate(glib::clone!(@weak obj => move |_, _| {
+                obj.decompose_components(true);
+            }));
+            action_group.add_action(&component_decompose);
+            let component_decompose_all =
+                gtk::gio::SimpleAction::new("component.decompose-all", None);
+            component_decompose_all.connect_activate(glib::clone!(@weak obj => move |_, _| {
+                obj.decompose_components(false);
+            }));
+            action_group.add_action(&component_decompose_all);
+            let anchor_add = gtk::gio::SimpleAction::new("anchor.add", None);
+            anchor_add.connect_activate(glib::clone!(@weak obj => move |_, _| {
+                obj.add_anchor();
+            }));
+            action_group.add_action(&anchor_add);
+            let anchor_properties = gtk::gio::SimpleAction::new("anchor.properties", None);
+            anchor_properties.connect_activate(glib::clone!(@weak obj => move |_, _| {
+                obj.anchor_properties();
+            }));
+            action_group.add_action(&anchor_properties);
+            let anchor_delete = gtk::gio::SimpleAction::new("anchor.delete", None);
+            anchor_delete.connect_activate(glib::clone!(@weak obj => move |_, _| {
+                obj.delete_selected_anchors();
+            }));
+            action_group.add_action(&anchor_delete);
+            let contour_reverse = gtk::gio::SimpleAction::new("contour.reverse", None);
+            contour_reverse.connect_activate(glib::clone!(@weak obj => move |_, _| {
+                obj.reverse_selected_contours();
+            }));
+            action_group.add_action(&contour_reverse);
             self.menubar
                 .insert_action_group("glyph", Some(&action_group));
             obj.insert_action_group("glyph", Some(&action_group));
@@ -236,6 +456,518 @@ impl EditorInner {
 }
 
 impl Editor {
+    /// Insert points at missing extrema of the whole glyph, or only of contours with selected
+    /// points if `selected_contours` is true and the selection isn't empty.
+    pub fn add_extrema(&self, selected_contours: bool) {
+        let state = self.state().borrow();
+        let contour_indices = selected_contours
+            .then(|| {
+                state
+                    .get_selection()
+                    .iter()
+                    .map(|idx| idx.contour_index)
+                    .collect::<Vec<usize>>()
+            })
+            .filter(|idxs| !idxs.is_empty());
+        if let Some(mut action) =
+            Glyph::add_extrema_action(&state.glyph, contour_indices.as_deref())
+        {
+            (action.redo)();
+            state.add_undo_action(action);
+        }
+    }
+
+    /// Open a dialog to simplify the whole glyph, or only the contours with selected points if
+    /// `selected_contours` is true and the selection isn't empty. The result is previewed on
+    /// the canvas while the tolerance is adjusted.
+    pub fn simplify(&self, selected_contours: bool) {
+        const DEFAULT_TOLERANCE: f64 = 1.0;
+
+        let contour_indices =
+            Some(self.selected_contours()).filter(|idxs| selected_contours && !idxs.is_empty());
+        let glyph = self.glyph();
+        let dialog = gtk::Dialog::with_buttons(
+            Some("Simplify"),
+            Some(&self.app().window),
+            gtk::DialogFlags::MODAL | gtk::DialogFlags::DESTROY_WITH_PARENT,
+            &[
+                ("Cancel", gtk::ResponseType::Cancel),
+                ("Simplify", gtk::ResponseType::Ok),
+            ],
+        );
+        let content_box: gtk::Box = dialog.content_area();
+        content_box.set_margin(5);
+        content_box.set_spacing(5);
+        let tolerance = gtk::SpinButton::new(
+            Some(&gtk::Adjustment::new(
+                DEFAULT_TOLERANCE,
+                0.1,
+                100.0,
+                0.1,
+                1.0,
+                0.0,
+            )),
+            0.1,
+            1,
+        );
+        tolerance.set_tooltip_text(Some(
+            "Maximum distance in units between the original and the simplified outline.",
+        ));
+        let count = gtk::Label::new(None);
+        let row = gtk::Box::new(gtk::Orientation::Horizontal, 5);
+        row.add(&gtk::Label::new(Some("Tolerance:")));
+        row.add(&tolerance);
+        content_box.add(&row);
+        content_box.add(&count);
+        let update = clone!(@weak self as obj, @weak glyph, @weak count, @strong contour_indices => move |tolerance: f64| {
+            let glyph = glyph.borrow();
+            let before = glyph.contours.iter().map(Contour::point_count).sum::<usize>();
+            let preview = glyph.simplified_contours(contour_indices.as_deref(), tolerance);
+            let after = preview.as_ref().map_or(before, |contours| {
+                contours.iter().map(Contour::point_count).sum::<usize>()
+            });
+            count.set_text(&format!("Points: {before} → {after}"));
+            *obj.imp().simplify_preview.borrow_mut() = preview;
+            obj.imp().viewport.queue_draw();
+        });
+        update(DEFAULT_TOLERANCE);
+        tolerance.connect_value_changed(clone!(@strong update => move |tolerance| {
+            update(tolerance.value());
+        }));
+        dialog.connect_response(
+            clone!(@weak self as obj, @weak tolerance => move |dialog, response| {
+                obj.imp().simplify_preview.borrow_mut().take();
+                if response == gtk::ResponseType::Ok {
+                    let state = obj.state().borrow();
+                    if let Some(mut action) = Glyph::simplify_action(
+                        &state.glyph,
+                        contour_indices.as_deref(),
+                        tolerance.value(),
+                    ) {
+                        (action.redo)();
+                        state.add_undo_action(action);
+                    }
+                }
+                obj.imp().viewport.queue_draw();
+                dialog.close();
+            }),
+        );
+        dialog.show_all();
+    }
+
+    /// Open a dialog to adjust the curvature comb, and show the comb.
+    pub fn curvature_comb_options(&self) {
+        let dialog = gtk::Dialog::with_buttons(
+            Some("Curvature comb"),
+            Some(&self.app().window),
+            gtk::DialogFlags::DESTROY_WITH_PARENT,
+            &[("Close", gtk::ResponseType::Close)],
+        );
+        let content_box: gtk::Box = dialog.content_area();
+        content_box.set_margin(5);
+        content_box.set_spacing(5);
+        let grid = gtk::Grid::builder()
+            .row_spacing(5)
+            .column_spacing(5)
+            .build();
+        let comb_scale = gtk::SpinButton::new(
+            Some(&gtk::Adjustment::new(
+                self.property::<f64>(Editor::CURVATURE_COMB_SCALE),
+                0.0,
+                1_000_000.0,
+                100.0,
+                1000.0,
+                0.0,
+            )),
+            100.0,
+            0,
+        );
+        comb_scale.set_tooltip_text(Some(
+            "Length of the comb's teeth in units for a curvature of one over a unit.",
+        ));
+        let density = gtk::SpinButton::new(
+            Some(&gtk::Adjustment::new(
+                self.property::<u64>(Editor::CURVATURE_COMB_DENSITY) as f64,
+                2.0,
+                200.0,
+                1.0,
+                10.0,
+                0.0,
+            )),
+            1.0,
+            0,
+        );
+        density.set_tooltip_text(Some("Number of comb teeth per curve."));
+        let selection = gtk::CheckButton::with_label("Only contours with selected points");
+        selection.set_active(self.property::<bool>(Editor::CURVATURE_COMB_SELECTION));
+        grid.attach(&gtk::Label::new(Some("Scale:")), 0, 0, 1, 1);
+        grid.attach(&comb_scale, 1, 0, 1, 1);
+        grid.attach(&gtk::Label::new(Some("Density:")), 0, 1, 1, 1);
+        grid.attach(&density, 1, 1, 1, 1);
+        grid.attach(&selection, 0, 2, 2, 1);
+        content_box.add(&grid);
+        comb_scale.connect_value_changed(clone!(@weak self as obj => move |comb_scale| {
+            obj.set_property(Editor::CURVATURE_COMB_SCALE, comb_scale.value());
+        }));
+        density.connect_value_changed(clone!(@weak self as obj => move |density| {
+            obj.set_property(Editor::CURVATURE_COMB_DENSITY, density.value_as_int() as u64);
+        }));
+        selection.connect_toggled(clone!(@weak self as obj => move |selection| {
+            obj.set_property(Editor::CURVATURE_COMB_SELECTION, selection.is_active());
+        }));
+        self.set_property(Editor::SHOW_CURVATURE_COMB, true);
+        dialog.connect_response(|dialog, _| {
+            dialog.close();
+        });
+        dialog.show_all();
+    }
+
+    /// Convert the selected on-curve points to `point_type`.
+    pub fn set_point_type(&self, point_type: PointType) {
+        let state = self.state().borrow();
+        if let Some(mut action) = state.change_point_type(state.get_selection(), point_type) {
+            (action.redo)();
+            state.add_undo_action(action);
+        }
+    }
+
+    pub fn correct_direction(&self, convention: DirectionConvention) {
+        let state = self.state().borrow();
+        if let Some(mut action) = Glyph::correct_direction_action(&state.glyph, convention) {
+            (action.redo)();
+            state.add_undo_action(action);
+        }
+    }
+
+    /// Indices of contours with selected points, in order and without duplicates.
+    fn selected_contours(&self) -> Vec<usize> {
+        let mut ret = self
+            .state()
+            .borrow()
+            .get_selection()
+            .iter()
+            .map(|idx| idx.contour_index)
+            .collect::<Vec<usize>>();
+        ret.sort_unstable();
+        ret.dedup();
+        ret
+    }
+
+    /// Show the properties of the first contour with selected points.
+    pub fn contour_properties(&self) {
+        let Some(&contour_index) = self.selected_contours().first() else {
+            return;
+        };
+        let contour = {
+            let state = self.state().borrow();
+            let glyph = state.glyph.borrow();
+            Contour::link_siblings(&glyph.contours);
+            glyph.contours[contour_index].clone()
+        };
+        let w = contour.new_property_window(self.app(), false);
+        w.present();
+    }
+
+    /// Open a dialog to edit the base glyph and the affine transformation of the component at
+    /// `index`. Applying the changes is a single undo step.
+    pub fn component_properties(&self, index: usize) {
+        let Some(component) = self.glyph().borrow().components.get(index).cloned() else {
+            return;
+        };
+        let layer: Option<crate::ufo::objects::Layer> =
+            (*self.glyph().borrow().metadata.layer()).clone();
+        let dialog = gtk::Dialog::with_buttons(
+            Some("Component"),
+            Some(&self.app().window),
+            gtk::DialogFlags::MODAL | gtk::DialogFlags::DESTROY_WITH_PARENT,
+            &[
+                ("Cancel", gtk::ResponseType::Cancel),
+                ("Apply", gtk::ResponseType::Ok),
+            ],
+        );
+        let content_box: gtk::Box = dialog.content_area();
+        content_box.set_margin(5);
+        content_box.set_spacing(5);
+        let grid = gtk::Grid::builder()
+            .row_spacing(5)
+            .column_spacing(5)
+            .build();
+        let base = gtk::Entry::new();
+        base.set_text(&component.base_name);
+        if let Some(layer) = layer.as_ref() {
+            let store = gtk::ListStore::new(&[glib::Type::STRING]);
+            for name in layer.glyphs().keys() {
+                store.insert_with_values(None, &[(0, name)]);
+            }
+            let completion = gtk::EntryCompletion::new();
+            completion.set_model(Some(&store));
+            completion.set_text_column(0);
+            base.set_completion(Some(&completion));
+        }
+        let error = gtk::Label::new(None);
+        grid.attach(&gtk::Label::new(Some("Base glyph:")), 0, 0, 1, 1);
+        grid.attach(&base, 1, 0, 1, 1);
+        let fields = [
+            ("X offset:", component.x_offset, 1.0),
+            ("Y offset:", component.y_offset, 1.0),
+            ("X scale:", component.x_scale, 0.01),
+            ("XY scale:", component.xy_scale, 0.01),
+            ("YX scale:", component.yx_scale, 0.01),
+            ("Y scale:", component.y_scale, 0.01),
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(i, (label, value, step))| {
+            let spin = gtk::SpinButton::new(
+                Some(&gtk::Adjustment::new(
+                    value,
+                    -100_000.0,
+                    100_000.0,
+                    step,
+                    10.0 * step,
+                    0.0,
+                )),
+                step,
+                3,
+            );
+            grid.attach(&gtk::Label::new(Some(label)), 0, i as i32 + 1, 1, 1);
+            grid.attach(&spin, 1, i as i32 + 1, 1, 1);
+            spin
+        })
+        .collect::<Vec<_>>();
+        content_box.add(&grid);
+        content_box.add(&error);
+        dialog.connect_response(
+            glib::clone!(@weak self as obj, @weak base, @weak error => move |dialog, response| {
+                if response == gtk::ResponseType::Ok {
+                    let mut new = component.clone();
+                    let name = base.text().to_string();
+                    if name != new.base_name {
+                        let Some(glyph) = layer.as_ref().and_then(|l| l.glyphs().get(&name).cloned())
+                        else {
+                            error.set_text(&format!("No glyph named {name}."));
+                            return;
+                        };
+                        new.base_name = name;
+                        new.base = Rc::downgrade(&glyph);
+                    }
+                    new.set_matrix(Matrix::new(
+                        fields[2].value(),
+                        fields[3].value(),
+                        fields[4].value(),
+                        fields[5].value(),
+                        fields[0].value(),
+                        fields[1].value(),
+                    ));
+                    let state = obj.state().borrow();
+                    let mut action = state.set_component(index, new);
+                    (action.redo)();
+                    state.add_undo_action(action);
+                }
+                dialog.close();
+            }),
+        );
+        dialog.show_all();
+    }
+
+    /// Open a dialog to edit the glyph's metric keys, e.g. `=o` or `=|H+10`. Setting the keys
+    /// and applying their values is a single undo step.
+    pub fn metric_keys(&self) {
+        let glyph = self.glyph();
+        let layer: Option<crate::ufo::objects::Layer> = (*glyph.borrow().metadata.layer()).clone();
+        let dialog = gtk::Dialog::with_buttons(
+            Some("Metric keys"),
+            Some(&self.app().window),
+            gtk::DialogFlags::MODAL | gtk::DialogFlags::DESTROY_WITH_PARENT,
+            &[
+                ("Cancel", gtk::ResponseType::Cancel),
+                ("Apply", gtk::ResponseType::Ok),
+            ],
+        );
+        let content_box: gtk::Box = dialog.content_area();
+        content_box.set_margin(5);
+        content_box.set_spacing(5);
+        let grid = gtk::Grid::builder()
+            .row_spacing(5)
+            .column_spacing(5)
+            .build();
+        let error = gtk::Label::new(None);
+        error.set_line_wrap(true);
+        let entries = Metric::ALL
+            .into_iter()
+            .enumerate()
+            .map(|(i, metric)| {
+                let entry = gtk::Entry::new();
+                entry.set_placeholder_text(Some("e.g. =o or =|H+10"));
+                entry.set_text(glyph.borrow().metric_key(metric).unwrap_or_default());
+                entry.set_activates_default(true);
+                grid.attach(
+                    &gtk::Label::new(Some(&format!("{}:", metric.label()))),
+                    0,
+                    i as i32,
+                    1,
+                    1,
+                );
+                grid.attach(&entry, 1, i as i32, 1, 1);
+                (metric, entry)
+            })
+            .collect::<Vec<_>>();
+        content_box.add(&grid);
+        content_box.add(&error);
+        dialog.set_default_response(gtk::ResponseType::Ok);
+        dialog.connect_response(
+            glib::clone!(@weak self as obj, @weak error => move |dialog, response| {
+                if response != gtk::ResponseType::Ok {
+                    dialog.close();
+                    return;
+                }
+                let mut keys = vec![];
+                for (metric, entry) in entries.iter() {
+                    let key = entry.text().to_string();
+                    if !key.trim().is_empty() {
+                        if let Err(err) = key.parse::<MetricExpr>() {
+                            error.set_text(&format!("{}: {err}", metric.label()));
+                            return;
+                        }
+                    }
+                    keys.push((*metric, Some(key)));
+                }
+                let mut keys_action = Glyph::set_metric_keys_action(&glyph, keys);
+                (keys_action.redo)();
+                let mut glyphs = layer.as_ref().map(|l| l.glyphs().clone()).unwrap_or_default();
+                glyphs.entry(glyph.borrow().name().clone()).or_insert_with(|| glyph.clone());
+                let mut actions = vec![];
+                match Glyph::sync_metrics_action(&glyph, &glyphs) {
+                    Ok(sync_action) => {
+                        actions.push(keys_action);
+                        if let Some(mut sync_action) = sync_action {
+                            (sync_action.redo)();
+                            actions.push(sync_action);
+                        }
+                    }
+                    Err(err) => {
+                        (keys_action.undo)();
+                        error.set_text(&err);
+                        return;
+                    }
+                }
+                let name = glyph.borrow().name().clone();
+                obj.state().borrow().add_undo_action(Action::group(
+                    EventStamp {
+                        t: std::any::TypeId::of::<Glyph>(),
+                        property: "metric-keys",
+                        id: name.into_bytes().into_boxed_slice(),
+                    },
+                    actions,
+                ));
+                dialog.close();
+            }),
+        );
+        dialog.show_all();
+    }
+
+    /// Replace the selected component, or all components if `selected` is false, with their
+    /// outlines.
+    pub fn decompose_components(&self, selected: bool) {
+        let indices = if selected {
+            match self.state().borrow().component_selection {
+                Some(index) => Some(vec![index]),
+                None => return,
+            }
+        } else {
+            None
+        };
+        let state = self.state().borrow();
+        if let Some(mut action) = Glyph::decompose_action(&state.glyph, indices.as_deref()) {
+            (action.redo)();
+            state.add_undo_action(action);
+        }
+    }
+
+    /// Open the base glyph of the component at `index` in a new tab.
+    pub fn open_component_base(&self, index: usize) {
+        let base = self
+            .glyph()
+            .borrow()
+            .components
+            .get(index)
+            .and_then(|c| c.base.upgrade());
+        let Some(base) = base else {
+            return;
+        };
+        self.app().window.edit_glyph(&base);
+    }
+
+    /// Add an anchor under the mouse, select it and open its property window so that it can be
+    /// named right away.
+    pub fn add_anchor(&self) {
+        let UnitPoint(position) = self.viewport.view_to_unit_point(self.viewport.get_mouse());
+        let position = Point::from((position.x.round(), position.y.round()));
+        let anchor = {
+            let state = self.state().borrow();
+            let name = {
+                let glyph = state.glyph.borrow();
+                let anchors = glyph.metadata.anchors.borrow();
+                (0..)
+                    .map(|i| {
+                        if i == 0 {
+                            "anchor".to_string()
+                        } else {
+                            format!("anchor.{i}")
+                        }
+                    })
+                    .find(|n| !anchors.iter().any(|a| &a.name() == n))
+                    .unwrap()
+            };
+            let mut action = state.new_anchor(name, position);
+            (action.redo)();
+            state.add_undo_action(action);
+            state
+                .glyph
+                .borrow()
+                .metadata
+                .anchors
+                .borrow()
+                .last()
+                .cloned()
+        };
+        if let Some(anchor) = anchor {
+            self.set_selection(&[], SelectionModifier::Replace);
+            let mut state = self.state().borrow_mut();
+            state.set_anchor_selection(&[anchor.clone()], SelectionModifier::Replace);
+            state.edit_anchor(&anchor).present();
+        }
+    }
+
+    pub fn anchor_properties(&self) {
+        let state = self.state().borrow();
+        if let Some(anchor) = state.anchor_selection.first() {
+            state.edit_anchor(anchor).present();
+        }
+    }
+
+    pub fn delete_selected_anchors(&self) {
+        let mut state = self.state().borrow_mut();
+        if state.anchor_selection.is_empty() {
+            return;
+        }
+        let mut action = state.delete_anchors(&state.anchor_selection);
+        (action.redo)();
+        state.add_undo_action(action);
+        state.set_anchor_selection(&[], SelectionModifier::Replace);
+    }
+
+    pub fn reverse_selected_contours(&self) {
+        let contour_indices = self.selected_contours();
+        let state = self.state().borrow();
+        for contour_index in contour_indices {
+            let mut action =
+                state.reverse_contour(&state.glyph.borrow().contours[contour_index], contour_index);
+            (action.redo)();
+            state.add_undo_action(action);
+        }
+    }
+
     pub fn make_debug_window(&self) {
         let state = self.state().borrow();
         let glyph = state.glyph.borrow();
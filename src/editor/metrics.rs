@@ -0,0 +1,126 @@
+/*
+ * gerb
+ *
+ * Copyright 2022 - Manos Pitsidianakis
+ *
+ * This file is part of gerb.
+ *
+ * gerb is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * gerb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with gerb. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::*;
+use crate::glyphs::Metric;
+
+/// Label and tooltip of each metrics bar field.
+const FIELDS: [(&str, &str); 3] = [
+    ("LSB", "Left sidebearing"),
+    ("Advance", "Advance width"),
+    ("RSB", "Right sidebearing"),
+];
+
+impl EditorInner {
+    /// Left sidebearing, advance width and right sidebearing fields shown at the bottom of the
+    /// editor. Editing a field applies it to the glyph as an undoable action.
+    pub fn create_metrics_bar(&self, obj: &Editor) -> gtk::Box {
+        let bar = gtk::Box::builder()
+            .name("metrics")
+            .orientation(gtk::Orientation::Horizontal)
+            .spacing(5)
+            .expand(false)
+            .visible(true)
+            .halign(gtk::Align::Center)
+            .valign(gtk::Align::End)
+            .margin_bottom(10)
+            .tooltip_text("Glyph metrics")
+            .build();
+        bar.style_context().add_class("glyph-metrics-bar");
+        let fields = FIELDS.map(|(label, tooltip)| {
+            let spin = gtk::SpinButton::new(
+                Some(&gtk::Adjustment::new(
+                    0.0, -100_000.0, 100_000.0, 1.0, 10.0, 0.0,
+                )),
+                1.0,
+                0,
+            );
+            spin.set_tooltip_text(Some(tooltip));
+            spin.set_width_chars(6);
+            spin.set_visible(true);
+            let label = gtk::Label::new(Some(label));
+            label.set_visible(true);
+            bar.pack_start(&label, false, false, 0);
+            bar.pack_start(&spin, false, false, 0);
+            spin
+        });
+        for (i, spin) in fields.iter().enumerate() {
+            spin.connect_value_changed(clone!(@weak obj => move |spin| {
+                if obj.updating_metrics_bar.get() {
+                    return;
+                }
+                let glyph = obj.glyph();
+                let value = spin.value();
+                let action = match i {
+                    0 => Glyph::set_lsb_action(&glyph, value, false),
+                    1 => Some(Glyph::set_width_action(&glyph, value, false)),
+                    _ => Glyph::set_rsb_action(&glyph, value, false),
+                };
+                if let Some(mut action) = action {
+                    (action.redo)();
+                    obj.state().borrow().add_undo_action(action);
+                }
+                obj.viewport.queue_draw();
+            }));
+        }
+        self.metrics_fields.set(fields).unwrap();
+        bar
+    }
+
+    /// Sets the metrics bar fields to the glyph's current values, without applying them back.
+    pub fn update_metrics_bar(&self) {
+        let (Some(glyph), Some(fields)) = (self.glyph.borrow().clone(), self.metrics_fields.get())
+        else {
+            return;
+        };
+        let (width, sidebearings, keys) = {
+            let glyph = glyph.borrow();
+            (
+                glyph.width().unwrap_or(0.0),
+                glyph.sidebearings(),
+                [Metric::Left, Metric::Width, Metric::Right]
+                    .map(|m| glyph.metric_key(m).map(str::to_string)),
+            )
+        };
+        self.updating_metrics_bar.set(true);
+        let values = [
+            sidebearings.map(|(l, _)| l),
+            Some(width),
+            sidebearings.map(|(_, r)| r),
+        ];
+        for (((spin, value), key), (_, tooltip)) in fields.iter().zip(values).zip(keys).zip(FIELDS)
+        {
+            spin.set_sensitive(value.is_some());
+            let tooltip = match key {
+                Some(key) => format!("{tooltip}, linked to {key}"),
+                None => tooltip.to_string(),
+            };
+            if spin.tooltip_text().as_deref() != Some(tooltip.as_str()) {
+                spin.set_tooltip_text(Some(&tooltip));
+            }
+            let value = value.unwrap_or(0.0).round();
+            if spin.value() != value {
+                spin.set_value(value);
+            }
+        }
+        self.updating_metrics_bar.set(false);
+    }
+}
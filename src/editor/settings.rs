@@ -39,6 +39,11 @@ pub struct EditorSettingsInner {
     show_glyph_guidelines: Cell<bool>,
     show_project_guidelines: Cell<bool>,
     show_metrics_guidelines: Cell<bool>,
+    show_missing_extrema: Cell<bool>,
+    show_curvature_comb: Cell<bool>,
+    curvature_comb_scale: Cell<f64>,
+    curvature_comb_density: Cell<u64>,
+    curvature_comb_selection: Cell<bool>,
 }
 
 #[glib::object_subclass]
@@ -49,9 +54,18 @@ impl ObjectSubclass for EditorSettingsInner {
     type Interfaces = ();
 }
 
+impl EditorSettingsInner {
+    pub const CURVATURE_COMB_SCALE_INIT_VAL: f64 = 5000.0;
+    pub const CURVATURE_COMB_DENSITY_INIT_VAL: u64 = 24;
+}
+
 impl ObjectImpl for EditorSettingsInner {
     fn constructed(&self, obj: &Self::Type) {
         self.parent_constructed(obj);
+        self.curvature_comb_scale
+            .set(EditorSettingsInner::CURVATURE_COMB_SCALE_INIT_VAL);
+        self.curvature_comb_density
+            .set(EditorSettingsInner::CURVATURE_COMB_DENSITY_INIT_VAL);
     }
 
     fn properties() -> &'static [glib::ParamSpec] {
@@ -94,6 +108,45 @@ impl ObjectImpl for EditorSettingsInner {
                         true,
                         glib::ParamFlags::READWRITE | UI_EDITABLE,
                     ),
+                    glib::ParamSpecBoolean::new(
+                        EditorSettings::SHOW_MISSING_EXTREMA,
+                        EditorSettings::SHOW_MISSING_EXTREMA,
+                        "Mark horizontal and vertical extrema that have no on-curve point",
+                        false,
+                        glib::ParamFlags::READWRITE | UI_EDITABLE,
+                    ),
+                    glib::ParamSpecBoolean::new(
+                        EditorSettings::SHOW_CURVATURE_COMB,
+                        EditorSettings::SHOW_CURVATURE_COMB,
+                        "Draw curvature combs along contours",
+                        false,
+                        glib::ParamFlags::READWRITE | UI_EDITABLE,
+                    ),
+                    glib::ParamSpecDouble::new(
+                        EditorSettings::CURVATURE_COMB_SCALE,
+                        EditorSettings::CURVATURE_COMB_SCALE,
+                        "Length of the comb's teeth in units for a curvature of one over a unit",
+                        0.0,
+                        1_000_000.0,
+                        EditorSettingsInner::CURVATURE_COMB_SCALE_INIT_VAL,
+                        glib::ParamFlags::READWRITE | UI_EDITABLE,
+                    ),
+                    glib::ParamSpecUInt64::new(
+                        EditorSettings::CURVATURE_COMB_DENSITY,
+                        EditorSettings::CURVATURE_COMB_DENSITY,
+                        "Number of comb teeth per curve",
+                        2,
+                        200,
+                        EditorSettingsInner::CURVATURE_COMB_DENSITY_INIT_VAL,
+                        glib::ParamFlags::READWRITE | UI_EDITABLE,
+                    ),
+                    glib::ParamSpecBoolean::new(
+                        EditorSettings::CURVATURE_COMB_SELECTION,
+                        EditorSettings::CURVATURE_COMB_SELECTION,
+                        "Only draw curvature combs for contours with selected points",
+                        false,
+                        glib::ParamFlags::READWRITE | UI_EDITABLE,
+                    ),
                 ]
             });
         PROPERTIES.as_ref()
@@ -110,6 +163,13 @@ impl ObjectImpl for EditorSettingsInner {
             EditorSettings::SHOW_METRICS_GUIDELINES => {
                 self.show_metrics_guidelines.get().to_value()
             }
+            EditorSettings::SHOW_MISSING_EXTREMA => self.show_missing_extrema.get().to_value(),
+            EditorSettings::SHOW_CURVATURE_COMB => self.show_curvature_comb.get().to_value(),
+            EditorSettings::CURVATURE_COMB_SCALE => self.curvature_comb_scale.get().to_value(),
+            EditorSettings::CURVATURE_COMB_DENSITY => self.curvature_comb_density.get().to_value(),
+            EditorSettings::CURVATURE_COMB_SELECTION => {
+                self.curvature_comb_selection.get().to_value()
+            }
             _ => unimplemented!("{}", pspec.name()),
         }
     }
@@ -137,6 +197,21 @@ impl ObjectImpl for EditorSettingsInner {
             EditorSettings::SHOW_METRICS_GUIDELINES => {
                 self.show_metrics_guidelines.set(value.get().unwrap());
             }
+            EditorSettings::SHOW_MISSING_EXTREMA => {
+                self.show_missing_extrema.set(value.get().unwrap());
+            }
+            EditorSettings::SHOW_CURVATURE_COMB => {
+                self.show_curvature_comb.set(value.get().unwrap());
+            }
+            EditorSettings::CURVATURE_COMB_SCALE => {
+                self.curvature_comb_scale.set(value.get().unwrap());
+            }
+            EditorSettings::CURVATURE_COMB_DENSITY => {
+                self.curvature_comb_density.set(value.get().unwrap());
+            }
+            EditorSettings::CURVATURE_COMB_SELECTION => {
+                self.curvature_comb_selection.set(value.get().unwrap());
+            }
             _ => unimplemented!("{}", pspec.name()),
         }
     }
@@ -154,6 +229,11 @@ impl EditorSettings {
     pub const SHOW_GLYPH_GUIDELINES: &'static str = "show-glyph-guidelines";
     pub const SHOW_PROJECT_GUIDELINES: &'static str = "show-project-guidelines";
     pub const SHOW_METRICS_GUIDELINES: &'static str = "show-metrics-guidelines";
+    pub const SHOW_MISSING_EXTREMA: &'static str = "show-missing-extrema";
+    pub const SHOW_CURVATURE_COMB: &'static str = "show-curvature-comb";
+    pub const CURVATURE_COMB_SCALE: &'static str = "curvature-comb-scale";
+    pub const CURVATURE_COMB_DENSITY: &'static str = "curvature-comb-density";
+    pub const CURVATURE_COMB_SELECTION: &'static str = "curvature-comb-selection";
 
     pub fn new() -> Self {
         glib::Object::new::<Self>(&[]).unwrap()
@@ -28,6 +28,11 @@ impl Editor {
     pub const SNAP: &'static str = "snap";
     pub const PRECISION: &'static str = "precision";
     pub const PREVIEW_ACTION: &'static str = Self::PREVIEW;
+    pub const MARK_PREVIEW_ACTION: &'static str = Self::MARK_PREVIEW;
+    pub const MARK_PREVIEW_NEXT_ACTION: &'static str = "mark-preview.next";
+    pub const MARK_PREVIEW_PREVIOUS_ACTION: &'static str = "mark-preview.previous";
+    pub const LINE_NEXT_ACTION: &'static str = "line.next";
+    pub const LINE_PREVIOUS_ACTION: &'static str = "line.previous";
     pub const ZOOM_IN_ACTION: &'static str = "zoom.in";
     pub const ZOOM_OUT_ACTION: &'static str = "zoom.out";
     pub const LOCK_ACTION: &'static str = Self::LOCK;
@@ -49,6 +54,7 @@ impl Editor {
     pub const SELECT_ALL_ACTION: &'static str = "select.all";
     pub const SELECT_NONE_ACTION: &'static str = "select.none";
     pub const SELECT_INVERT_ACTION: &'static str = "select.invert";
+    pub const POINT_TYPE_ACTION: &'static str = "point.type";
 }
 
 impl EditorInner {
@@ -77,6 +83,24 @@ impl EditorInner {
                     }
                 })),
             ));
+            sh.push(ShortcutAction::new(
+                "next mark preview".into(),
+                Shortcut::empty().char(']'),
+                Box::new(|group| {
+                    group.activate_action(A::MARK_PREVIEW_NEXT_ACTION, None);
+                    true
+                }),
+                None,
+            ));
+            sh.push(ShortcutAction::new(
+                "previous mark preview".into(),
+                Shortcut::empty().char('['),
+                Box::new(|group| {
+                    group.activate_action(A::MARK_PREVIEW_PREVIOUS_ACTION, None);
+                    true
+                }),
+                None,
+            ));
             sh.push(ShortcutAction::new(
                 "lock x".into(),
                 Shortcut::empty().char('x'),
@@ -226,6 +250,36 @@ impl EditorInner {
                 }));
                 obj.action_group.add_action(&a);
             }
+            for (point_type, key) in PointType::ALL.into_iter().zip(['1', '2', '3', '4']) {
+                sh.push(ShortcutAction::new(
+                    format!("{} point", point_type.name()).into(),
+                    Shortcut::empty().char(key),
+                    Box::new(move |group| {
+                        group.activate_action(
+                            A::POINT_TYPE_ACTION,
+                            Some(&point_type.name().to_variant()),
+                        );
+                        true
+                    }),
+                    None,
+                ));
+            }
+            {
+                let a = gtk::gio::SimpleAction::new(
+                    A::POINT_TYPE_ACTION,
+                    Some(glib::VariantTy::STRING),
+                );
+                a.connect_activate(glib::clone!(@weak obj => move |_, name| {
+                    use glib::FromVariant;
+                    if let Some(point_type) = name
+                        .and_then(String::from_variant)
+                        .and_then(|s| PointType::from_name(&s))
+                    {
+                        obj.set_point_type(point_type);
+                    }
+                }));
+                obj.action_group.add_action(&a);
+            }
             for (name, key, num) in [
                 ("precision 1", '!', Precision::EMPTY),
                 ("precision 3", '@', Precision::_1),
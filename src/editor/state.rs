@@ -31,6 +31,8 @@ pub struct State {
     pub panning_tool: glib::types::Type,
     pub selection: Vec<GlyphPointIndex>,
     pub selection_set: HashSet<uuid::Uuid>,
+    pub anchor_selection: Vec<Anchor>,
+    pub component_selection: Option<usize>,
     pub kd_tree: Rc<RefCell<crate::utils::range_query::KdTree>>,
 }
 
@@ -45,6 +47,8 @@ impl State {
             panning_tool: PanningTool::static_type(),
             selection: vec![],
             selection_set: HashSet::new(),
+            anchor_selection: vec![],
+            component_selection: None,
             kd_tree: Rc::new(RefCell::new(crate::utils::range_query::KdTree::new(&[]))),
         };
 
@@ -114,6 +118,37 @@ impl State {
         }
     }
 
+    pub fn replace_contour(&self, contour: Contour, contour_index: usize) -> Action {
+        let old_contour = self.glyph.borrow().contours[contour_index].clone();
+        let swap = |from: Contour, to: Contour| {
+            clone!(@weak self.glyph as glyph, @weak self.kd_tree as kd_tree, @weak self.viewport as viewport => move || {
+                let mut kd_tree = kd_tree.borrow_mut();
+                for (curve_index, curve) in from.curves().iter().enumerate() {
+                    for idx in curve.points().iter().map(|p| p.glyph_index(contour_index, curve_index)) {
+                        kd_tree.remove(idx);
+                    }
+                }
+                for (curve_index, curve) in to.curves().iter().enumerate() {
+                    for (idx, pos) in curve.points().iter().map(|p| (p.glyph_index(contour_index, curve_index), p.position)) {
+                        kd_tree.add(idx, pos);
+                    }
+                }
+                glyph.borrow_mut().contours[contour_index] = to.clone();
+                viewport.queue_draw();
+            })
+        };
+        Action {
+            stamp: EventStamp {
+                t: std::any::TypeId::of::<Self>(),
+                property: Contour::static_type().name(),
+                id: unsafe { std::mem::transmute::<&[usize], &[u8]>(&[contour_index]).into() },
+            },
+            compress: false,
+            redo: Box::new(swap(old_contour.clone(), contour.clone())),
+            undo: Box::new(swap(contour, old_contour)),
+        }
+    }
+
     pub fn new_guideline(&self, angle: f64, p: Point) -> Action {
         let x = Some(p.x).filter(|&v| v != 0.0);
         let y = Some(p.y).filter(|&v| v != 0.0);
@@ -222,8 +257,224 @@ impl State {
         self.add_undo_action(action);
     }
 
+    /// Returns the anchor closest to `position` that is at most `distance` units away.
+    pub fn anchor_at(&self, position: Point, distance: f64) -> Option<Anchor> {
+        let glyph = self.glyph.borrow();
+        let anchors = glyph.metadata.anchors.borrow();
+        anchors
+            .iter()
+            .map(|a| (a, a.position().distance(position)))
+            .filter(|(_, d)| *d <= distance)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(a, _)| a.clone())
+    }
+
+    pub fn new_anchor(&self, name: String, position: Point) -> Action {
+        let anchor = Anchor::with_position(name, position);
+        Action {
+            stamp: EventStamp {
+                t: std::any::TypeId::of::<Self>(),
+                property: Anchor::static_type().name(),
+                id: Box::new([]),
+            },
+            compress: false,
+            redo: Box::new(
+                clone!(@weak self.glyph as glyph, @weak self.viewport as viewport, @strong anchor => move || {
+                    glyph.borrow().metadata.anchors.borrow_mut().push(anchor.clone());
+                    viewport.queue_draw();
+                }),
+            ),
+            undo: Box::new(
+                clone!(@weak self.glyph as glyph, @weak self.viewport as viewport, @strong anchor => move || {
+                    glyph.borrow().metadata.anchors.borrow_mut().retain(|a| a != &anchor);
+                    viewport.queue_draw();
+                }),
+            ),
+        }
+    }
+
+    pub fn delete_anchors(&self, anchors: &[Anchor]) -> Action {
+        let removed: Rc<Vec<(usize, Anchor)>> = Rc::new(
+            self.glyph
+                .borrow()
+                .metadata
+                .anchors
+                .borrow()
+                .iter()
+                .enumerate()
+                .filter(|(_, a)| anchors.contains(a))
+                .map(|(i, a)| (i, a.clone()))
+                .collect(),
+        );
+        Action {
+            stamp: EventStamp {
+                t: std::any::TypeId::of::<Self>(),
+                property: Anchor::static_type().name(),
+                id: removed
+                    .iter()
+                    .flat_map(|(i, _)| i.to_ne_bytes())
+                    .collect::<Vec<u8>>()
+                    .into(),
+            },
+            compress: false,
+            redo: Box::new(
+                clone!(@strong removed, @weak self.glyph as glyph, @weak self.viewport as viewport => move || {
+                    glyph.borrow().metadata.anchors.borrow_mut().retain(|a| !removed.iter().any(|(_, r)| r == a));
+                    viewport.queue_draw();
+                }),
+            ),
+            undo: Box::new(
+                clone!(@strong removed, @weak self.glyph as glyph, @weak self.viewport as viewport => move || {
+                    let glyph = glyph.borrow();
+                    let mut anchors = glyph.metadata.anchors.borrow_mut();
+                    /* Indices are ascending, so each one is valid once the previous ones are back. */
+                    for (i, a) in removed.iter() {
+                        anchors.insert((*i).min(anchors.len()), a.clone());
+                    }
+                    viewport.queue_draw();
+                }),
+            ),
+        }
+    }
+
+    pub fn transform_anchors(&self, anchors: &[Anchor], m: Matrix) -> Action {
+        let viewport = self.viewport.clone();
+        let anchors = Rc::new(anchors.to_vec());
+        let apply = |m: Matrix| {
+            clone!(@strong anchors, @weak viewport => move || {
+                for a in anchors.iter() {
+                    let (x, y) = m.transform_point(a.x(), a.y());
+                    a.set_property(Anchor::X, x);
+                    a.set_property(Anchor::Y, y);
+                }
+                viewport.queue_draw();
+            })
+        };
+        let (redo, undo) = match m.try_invert() {
+            Ok(inverse) => (apply(m), apply(inverse)),
+            Err(_) => (apply(m), apply(Matrix::identity())),
+        };
+        Action {
+            stamp: EventStamp {
+                t: std::any::TypeId::of::<Self>(),
+                property: Anchor::static_type().name(),
+                id: anchors
+                    .iter()
+                    .flat_map(|a| (a.as_ptr() as usize).to_ne_bytes())
+                    .collect::<Vec<u8>>()
+                    .into(),
+            },
+            compress: false,
+            redo: Box::new(redo),
+            undo: Box::new(undo),
+        }
+    }
+
+    /// Opens a property window for `anchor`. Edits apply right away and are recorded as a
+    /// single undo step when the window is closed.
+    pub fn edit_anchor(&self, anchor: &Anchor) -> PropertyWindow {
+        let initial = (anchor.name(), anchor.x(), anchor.y());
+        let window = anchor.new_property_window(&self.app, false);
+        window.connect_destroy(
+            clone!(@weak anchor, @weak self.app as app, @weak self.viewport as viewport => move |_| {
+                let new = (anchor.name(), anchor.x(), anchor.y());
+                if new == initial {
+                    return;
+                }
+                let set = |(name, x, y): (String, f64, f64)| {
+                    clone!(@weak anchor, @weak viewport => move || {
+                        anchor.set_property(Anchor::NAME, name.clone());
+                        anchor.set_property(Anchor::X, x);
+                        anchor.set_property(Anchor::Y, y);
+                        viewport.queue_draw();
+                    })
+                };
+                app.undo_db.borrow().event(Action {
+                    stamp: EventStamp {
+                        t: std::any::TypeId::of::<Self>(),
+                        property: Anchor::static_type().name(),
+                        id: (anchor.as_ptr() as usize).to_ne_bytes().into(),
+                    },
+                    compress: false,
+                    redo: Box::new(set(new)),
+                    undo: Box::new(set(initial.clone())),
+                });
+                viewport.queue_draw();
+            }),
+        );
+        window
+    }
+
+    pub fn set_anchor_selection(&mut self, anchors: &[Anchor], modifier: SelectionModifier) {
+        use SelectionModifier::*;
+        match modifier {
+            Replace => {
+                self.anchor_selection.clear();
+                self.anchor_selection.extend(anchors.iter().cloned());
+            }
+            Add => {
+                for a in anchors {
+                    if !self.anchor_selection.contains(a) {
+                        self.anchor_selection.push(a.clone());
+                    }
+                }
+            }
+            Remove => {
+                self.anchor_selection.retain(|a| !anchors.contains(a));
+            }
+        }
+        self.viewport.queue_draw();
+    }
+
+    /// Returns an action that replaces the component at `index` with `component`, e.g. to
+    /// change its transformation or its base glyph.
+    pub fn set_component(&self, index: usize, component: Component) -> Action {
+        let old = self.glyph.borrow().components[index].clone();
+        let set = |component: Component| {
+            clone!(@weak self.glyph as glyph, @weak self.viewport as viewport => move || {
+                if let Some(c) = glyph.borrow_mut().components.get_mut(index) {
+                    *c = component.clone();
+                }
+                glyph.borrow().metadata.set_property(GlyphMetadata::MODIFIED, true);
+                viewport.queue_draw();
+            })
+        };
+        Action {
+            stamp: EventStamp {
+                t: std::any::TypeId::of::<Self>(),
+                property: "component",
+                id: index.to_ne_bytes().into(),
+            },
+            compress: false,
+            redo: Box::new(set(component)),
+            undo: Box::new(set(old)),
+        }
+    }
+
+    /// Returns an action that applies `m` after the transformation of the component at `index`.
+    pub fn transform_component(&self, index: usize, m: Matrix) -> Action {
+        let mut component = self.glyph.borrow().components[index].clone();
+        component.set_matrix(Matrix::multiply(&component.matrix(), &m));
+        self.set_component(index, component)
+    }
+
     pub fn transform_selection(&self, m: Matrix, compress: bool) {
-        let mut action = self.transform_points(&self.selection, m);
+        let mut action = if self.anchor_selection.is_empty() {
+            self.transform_points(&self.selection, m)
+        } else if self.selection.is_empty() {
+            self.transform_anchors(&self.anchor_selection, m)
+        } else {
+            let points = self.transform_points(&self.selection, m);
+            let anchors = self.transform_anchors(&self.anchor_selection, m);
+            Action::group(
+                EventStamp {
+                    t: std::any::TypeId::of::<Self>(),
+                    property: Anchor::static_type().name(),
+                    id: points.stamp.id.clone(),
+                },
+                vec![points, anchors],
+            )
+        };
         action.compress = compress;
         (action.redo)();
         self.add_undo_action(action);
@@ -275,6 +526,122 @@ impl State {
         }
     }
 
+    /// Returns an action that converts the on-curve points in `idxs` to `point_type`, moving
+    /// their handles to satisfy the new constraint. Handles, ends of open contours and points
+    /// that can't take the type are skipped; returns `None` if nothing would change.
+    pub fn change_point_type(
+        &self,
+        idxs: &[GlyphPointIndex],
+        point_type: PointType,
+    ) -> Option<Action> {
+        struct Change {
+            index: GlyphPointIndex,
+            prev: Continuity,
+            new: Continuity,
+            old_positions: Vec<(usize, usize, Point)>,
+            new_positions: Vec<(usize, usize, Point)>,
+        }
+
+        let viewport = self.viewport.clone();
+        let mut joints = vec![];
+        let mut changes = vec![];
+        /* Point positions of the affected contours as the changes so far leave them. The glyph
+         * itself is only modified by the action's redo. */
+        let mut positions = std::collections::HashMap::<usize, Vec<Vec<Point>>>::default();
+        {
+            let glyph = self.glyph.borrow();
+            for &index in idxs {
+                let Some(contour) = glyph.contours.get(index.contour_index) else {
+                    continue;
+                };
+                /* Both curves' copies of a point may be selected. */
+                match contour.joint(index) {
+                    Some(joint) if !joints.contains(&(index.contour_index, joint)) => {
+                        joints.push((index.contour_index, joint));
+                    }
+                    _ => continue,
+                }
+                let current = positions.entry(index.contour_index).or_insert_with(|| {
+                    contour
+                        .curves()
+                        .iter()
+                        .map(crate::utils::curves::Bezier::positions)
+                        .collect()
+                });
+                let Some((new, new_positions)) =
+                    contour.point_type_change_in(index, point_type, current)
+                else {
+                    continue;
+                };
+                let Some(prev) = contour.continuity(index) else {
+                    continue;
+                };
+                let old_positions = new_positions
+                    .iter()
+                    .map(|&(c, i, _)| (c, i, current[c][i]))
+                    .collect();
+                /* The next points must see the moved handles. */
+                for &(c, i, position) in &new_positions {
+                    current[c][i] = position;
+                }
+                changes.push(Change {
+                    index,
+                    prev,
+                    new,
+                    old_positions,
+                    new_positions,
+                });
+            }
+        }
+        if changes.is_empty() {
+            return None;
+        }
+        let changes = Rc::new(changes);
+        Some(Action {
+            stamp: EventStamp {
+                t: std::any::TypeId::of::<Self>(),
+                property: Continuity::static_type().name(),
+                id: idxs
+                    .iter()
+                    .map(GlyphPointIndex::as_bytes)
+                    .flat_map(<_>::into_iter)
+                    .collect::<Vec<u8>>()
+                    .into(),
+            },
+            compress: false,
+            redo: Box::new(
+                clone!(@strong changes, @weak self.kd_tree as kd_tree, @weak self.glyph as glyph, @weak viewport => move || {
+                    let mut kd_tree = kd_tree.borrow_mut();
+                    let glyph = glyph.borrow();
+                    for change in changes.iter() {
+                        let contour_index = change.index.contour_index;
+                        let contour = &glyph.contours[contour_index];
+                        contour.change_continuity(change.index, change.new);
+                        for (idx, new_pos) in contour.set_point_positions(contour_index, &change.new_positions) {
+                            kd_tree.add(idx, new_pos);
+                        }
+                    }
+                    viewport.queue_draw();
+                }),
+            ),
+            undo: Box::new(
+                clone!(@strong changes, @weak self.kd_tree as kd_tree, @weak self.glyph as glyph, @weak viewport => move || {
+                    let mut kd_tree = kd_tree.borrow_mut();
+                    let glyph = glyph.borrow();
+                    for change in changes.iter().rev() {
+                        let contour_index = change.index.contour_index;
+                        let contour = &glyph.contours[contour_index];
+                        contour.change_continuity(change.index, change.prev);
+                        for (idx, new_pos) in contour.set_point_positions(contour_index, &change.old_positions) {
+                            kd_tree.add(idx, new_pos);
+                        }
+                    }
+                    viewport.queue_draw();
+                }),
+            ),
+        })
+    }
+
     pub fn set_selection(&mut self, selection: &[GlyphPointIndex], modifier: SelectionModifier) {
         use SelectionModifier::*;
         match modifier {
@@ -301,6 +668,45 @@ impl State {
         }
     }
 
+    /// Rebuild point lookup state after the glyph's contours were changed from outside the
+    /// editor, e.g. by a glyph or project wide command. Selected points that are no longer in
+    /// the glyph are deselected.
+    pub fn reload_contours(&mut self) {
+        {
+            let glyph = self.glyph.borrow();
+            self.selection.retain(|idx| {
+                glyph
+                    .contours
+                    .get(idx.contour_index)
+                    .and_then(|contour| {
+                        let curves = contour.curves();
+                        let found = curves
+                            .get(idx.curve_index)?
+                            .points()
+                            .iter()
+                            .any(|p| p.uuid == idx.uuid);
+                        Some(found)
+                    })
+                    .unwrap_or(false)
+            });
+            self.selection_set = self.selection.iter().map(|idx| idx.uuid).collect();
+            let anchors = glyph.metadata.anchors.borrow();
+            self.anchor_selection.retain(|a| anchors.contains(a));
+            if self
+                .component_selection
+                .map_or(false, |i| i >= glyph.components.len())
+            {
+                self.component_selection = None;
+            }
+        }
+        *self.kd_tree.borrow_mut() = crate::utils::range_query::KdTree::new(&[]);
+        for (contour_index, contour) in self.glyph.borrow().contours.iter().enumerate() {
+            (self.add_contour(contour, contour_index).redo)();
+        }
+        Contour::link_siblings(&self.glyph.borrow().contours);
+        self.viewport.queue_draw();
+    }
+
     pub fn get_selection_set(&self) -> &HashSet<uuid::Uuid> {
         &self.selection_set
     }
@@ -23,14 +23,21 @@ use super::*;
 
 mod bezier;
 mod bspline;
+mod hobby;
 mod image;
+mod knife;
 mod panning;
+mod pencil;
 mod shapes;
 mod tool_impl;
 mod zoom;
 pub use self::image::*;
 pub use bezier::*;
+pub use bspline::*;
+pub use hobby::*;
+pub use knife::*;
 pub use panning::*;
+pub use pencil::*;
 pub use shapes::*;
 pub use tool_impl::*;
 pub use zoom::*;
@@ -155,9 +162,12 @@ impl Tool {
         for t in [
             PanningTool::new().upcast::<ToolImpl>(),
             BezierTool::new().upcast::<ToolImpl>(),
-            //BSplineTool::new().upcast::<ToolImpl>(),
+            PencilTool::new().upcast::<ToolImpl>(),
+            BSplineTool::new().upcast::<ToolImpl>(),
+            HobbyTool::new().upcast::<ToolImpl>(),
             QuadrilateralTool::new().upcast::<ToolImpl>(),
             EllipseTool::new().upcast::<ToolImpl>(),
+            KnifeTool::new().upcast::<ToolImpl>(),
             ImageTool::new(glyph, obj.project.get().unwrap().clone()).upcast::<ToolImpl>(),
             ZoomInTool::new().upcast::<ToolImpl>(),
             ZoomOutTool::new().upcast::<ToolImpl>(),
@@ -19,13 +19,25 @@
  * along with gerb. If not, see <http://www.gnu.org/licenses/>.
  */
 
-use super::tool_impl::*;
+use super::{new_contour_action, tool_impl::*};
+use crate::glyphs::Contour;
+use crate::utils::curves::{math, Bezier};
+use crate::views::canvas::{Layer, LayerBuilder};
 use gtk::Inhibit;
 
 use crate::prelude::*;
 
+// [ref:needs_user_doc]
 #[derive(Default)]
-pub struct BSplineToolInner;
+pub struct BSplineToolInner {
+    layer: OnceCell<Layer>,
+    active: Cell<bool>,
+    clamped: Cell<bool>,
+    /// Control points placed so far, in units.
+    control: RefCell<Vec<Point>>,
+    /// Pointer position, previewed as the next control point.
+    hover: Cell<Option<Point>>,
+}
 
 #[glib::object_subclass]
 impl ObjectSubclass for BSplineToolInner {
@@ -37,23 +49,127 @@ impl ObjectSubclass for BSplineToolInner {
 impl ObjectImpl for BSplineToolInner {
     fn constructed(&self, obj: &Self::Type) {
         self.parent_constructed(obj);
-        obj.set_property::<String>(ToolImpl::NAME, "Create b-spline curve".to_string());
+        obj.set_property::<bool>(BSplineTool::ACTIVE, false);
+        self.clamped.set(true);
+        obj.set_property::<String>(ToolImpl::NAME, "b-spline".to_string());
+        obj.set_property::<String>(ToolImpl::DESCRIPTION, "Create b-spline curve".to_string());
         obj.set_property::<gtk::Image>(
             ToolImpl::ICON,
             crate::resources::icons::BSPLINE_ICON.to_image_widget(),
         );
     }
+
+    fn properties() -> &'static [glib::ParamSpec] {
+        static PROPERTIES: once_cell::sync::Lazy<Vec<glib::ParamSpec>> = once_cell::sync::Lazy::new(
+            || {
+                vec![
+                    glib::ParamSpecBoolean::new(
+                        BSplineTool::ACTIVE,
+                        BSplineTool::ACTIVE,
+                        BSplineTool::ACTIVE,
+                        true,
+                        glib::ParamFlags::READWRITE,
+                    ),
+                    glib::ParamSpecBoolean::new(
+                        BSplineTool::CLAMPED,
+                        BSplineTool::CLAMPED,
+                        "Use clamped knots so that open curves start and end on their first and last control points.",
+                        true,
+                        glib::ParamFlags::READWRITE,
+                    ),
+                ]
+            },
+        );
+        PROPERTIES.as_ref()
+    }
+
+    fn property(&self, _obj: &Self::Type, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+        match pspec.name() {
+            BSplineTool::ACTIVE => self.active.get().to_value(),
+            BSplineTool::CLAMPED => self.clamped.get().to_value(),
+            _ => unimplemented!("{}", pspec.name()),
+        }
+    }
+
+    fn set_property(
+        &self,
+        _obj: &Self::Type,
+        _id: usize,
+        value: &glib::Value,
+        pspec: &glib::ParamSpec,
+    ) {
+        match pspec.name() {
+            BSplineTool::ACTIVE => self.active.set(value.get().unwrap()),
+            BSplineTool::CLAMPED => self.clamped.set(value.get().unwrap()),
+            _ => unimplemented!("{}", pspec.name()),
+        }
+    }
 }
 
 impl ToolImplImpl for BSplineToolInner {
     fn on_button_press_event(
         &self,
         _obj: &ToolImpl,
-        _view: Editor,
-        _viewport: &Canvas,
-        _event: &gtk::gdk::EventButton,
+        view: Editor,
+        viewport: &Canvas,
+        event: &gtk::gdk::EventButton,
     ) -> Inhibit {
-        Inhibit(false)
+        if !self.active.get() {
+            return Inhibit(false);
+        }
+        let UnitPoint(position) = viewport.view_to_unit_point(ViewPoint(event.position().into()));
+        let placed = self.control.borrow().len();
+        match event.button() {
+            gtk::gdk::BUTTON_PRIMARY => {
+                let scale: f64 = viewport
+                    .transformation
+                    .property::<f64>(Transformation::SCALE);
+                let ppu: f64 = viewport
+                    .transformation
+                    .property::<f64>(Transformation::PIXELS_PER_UNIT);
+                let closes = placed >= 3
+                    && self.control.borrow()[0].distance(position)
+                        < BSplineTool::CLOSE_DISTANCE / (scale * ppu);
+                if closes {
+                    self.commit(&view, true);
+                } else {
+                    self.control.borrow_mut().push(position);
+                }
+            }
+            gtk::gdk::BUTTON_SECONDARY if placed > 0 => {
+                self.commit(&view, false);
+            }
+            gtk::gdk::BUTTON_SECONDARY => {
+                let obj = self.instance();
+                let clamped = self.clamped.get();
+                let mut menu = crate::utils::menu::Menu::new()
+                    .title(Some("b-spline".into()))
+                    .separator();
+                for (label, value) in [("knots: uniform", false), ("knots: clamped", true)] {
+                    menu = if value == clamped {
+                        menu.add_button(label)
+                    } else {
+                        menu.add_button_cb(
+                            label,
+                            clone!(@weak obj => move |_| {
+                                obj.set_property::<bool>(BSplineTool::CLAMPED, value);
+                            }),
+                        )
+                    };
+                }
+                menu.add_button_cb(
+                    "done",
+                    clone!(@weak obj, @weak view => move |_| {
+                        obj.on_deactivate(&view);
+                        view.state().borrow_mut().active_tool = glib::types::Type::INVALID;
+                    }),
+                )
+                .popup(event.time());
+            }
+            _ => return Inhibit(false),
+        }
+        viewport.queue_draw();
+        Inhibit(true)
     }
 
     fn on_button_release_event(
@@ -70,14 +186,108 @@ impl ToolImplImpl for BSplineToolInner {
         &self,
         _obj: &ToolImpl,
         _view: Editor,
-        _viewport: &Canvas,
-        _event: &gtk::gdk::EventMotion,
+        viewport: &Canvas,
+        event: &gtk::gdk::EventMotion,
     ) -> Inhibit {
-        Inhibit(false)
+        if !self.active.get() || self.control.borrow().is_empty() {
+            return Inhibit(false);
+        }
+        let UnitPoint(position) = viewport.view_to_unit_point(ViewPoint(event.position().into()));
+        self.hover.set(Some(position));
+        viewport.queue_draw();
+        Inhibit(true)
+    }
+
+    fn setup_toolbox(&self, obj: &ToolImpl, toolbar: &gtk::Toolbar, view: &Editor) {
+        let layer =
+            LayerBuilder::new()
+                .set_name(Some("b-spline"))
+                .set_active(false)
+                .set_hidden(true)
+                .set_callback(Some(Box::new(clone!(@weak view => @default-return Inhibit(false), move |viewport: &Canvas, cr: ContextRef| {
+                    BSplineTool::draw_layer(viewport, cr, view)
+                }))))
+                .build();
+        self.instance()
+            .bind_property(BSplineTool::ACTIVE, &layer, Layer::ACTIVE)
+            .flags(glib::BindingFlags::SYNC_CREATE)
+            .build();
+        self.layer.set(layer.clone()).unwrap();
+        view.viewport.add_post_layer(layer);
+
+        self.parent_setup_toolbox(obj, toolbar, view)
+    }
+
+    fn on_activate(&self, obj: &ToolImpl, view: &Editor) {
+        self.instance()
+            .set_property::<bool>(BSplineTool::ACTIVE, true);
+        view.viewport.set_cursor("crosshair");
+        self.parent_on_activate(obj, view)
+    }
+
+    fn on_deactivate(&self, obj: &ToolImpl, view: &Editor) {
+        self.control.borrow_mut().clear();
+        self.hover.set(None);
+        self.instance()
+            .set_property::<bool>(BSplineTool::ACTIVE, false);
+        view.viewport.set_cursor("default");
+        self.parent_on_deactivate(obj, view)
     }
 }
 
-impl BSplineToolInner {}
+impl BSplineToolInner {
+    /// Cubic Bézier curves tracing the spline of `control`. Closed splines wrap around their
+    /// control points and always use uniform knots.
+    fn cubics(&self, control: &[Point], closed: bool) -> Vec<[Point; 4]> {
+        if closed {
+            let mut control = control.to_vec();
+            control.extend_from_within(..3.min(control.len()));
+            let mut ret =
+                math::bspline_to_cubics(&control, &math::bspline_knots(control.len(), false));
+            /* Make the ends meet exactly. */
+            if let Some(start) = ret.first().map(|c| c[0]) {
+                let last = ret.len() - 1;
+                ret[last][3] = start;
+            }
+            ret
+        } else {
+            math::bspline_to_cubics(
+                control,
+                &math::bspline_knots(control.len(), self.clamped.get()),
+            )
+        }
+    }
+
+    /// Add the spline to the glyph as a new contour and start over.
+    fn commit(&self, view: &Editor, closed: bool) {
+        let control = std::mem::take(&mut *self.control.borrow_mut());
+        self.hover.set(None);
+        let curves = self
+            .cubics(&control, closed)
+            .into_iter()
+            .enumerate()
+            .map(|(i, points)| {
+                let curve = Bezier::new(points.to_vec());
+                /* B-splines are smooth everywhere, except at the ends of open curves. */
+                curve.set_property(Bezier::SMOOTH, closed || i != 0);
+                curve
+            })
+            .collect::<Vec<Bezier>>();
+        if curves.is_empty() {
+            return;
+        }
+        let contour = Contour::new_with_curves(curves);
+        if closed {
+            contour.close();
+        }
+        let state = view.state().borrow();
+        let contour_index = state.glyph.borrow().contours.len();
+        let subaction = state.add_contour(&contour, contour_index);
+        let mut action = new_contour_action(state.glyph.clone(), contour, subaction);
+        (action.redo)();
+        state.add_undo_action(action);
+    }
+}
 
 glib::wrapper! {
     pub struct BSplineTool(ObjectSubclass<BSplineToolInner>)
@@ -91,7 +301,71 @@ impl Default for BSplineTool {
 }
 
 impl BSplineTool {
+    pub const ACTIVE: &'static str = "active";
+    pub const CLAMPED: &'static str = "clamped";
+    /// Distance in pixels from the first control point within which a click closes the curve.
+    const CLOSE_DISTANCE: f64 = 10.0;
+
     pub fn new() -> Self {
         glib::Object::new(&[]).unwrap()
     }
+
+    pub fn draw_layer(viewport: &Canvas, cr: ContextRef, obj: Editor) -> Inhibit {
+        let state = obj.state().borrow();
+        if Self::static_type() != state.active_tool {
+            return Inhibit(false);
+        }
+        let t = state.tools[&state.active_tool]
+            .clone()
+            .downcast::<Self>()
+            .unwrap();
+        let mut control = t.imp().control.borrow().clone();
+        if control.is_empty() {
+            return Inhibit(false);
+        }
+        control.extend(t.imp().hover.get());
+        let scale: f64 = viewport
+            .transformation
+            .property::<f64>(Transformation::SCALE);
+        let ppu: f64 = viewport
+            .transformation
+            .property::<f64>(Transformation::PIXELS_PER_UNIT);
+        let settings = obj.settings.get().unwrap();
+        let line_width = settings.property::<f64>(Settings::LINE_WIDTH) / (scale * ppu);
+        let handle_size = settings.property::<f64>(Settings::HANDLE_SIZE) / (scale * ppu);
+        cr.transform(viewport.transformation.matrix());
+        cr.set_line_width(line_width);
+        {
+            /* Control polygon. */
+            let cr1 = cr.push();
+            cr1.set_source_rgba(0.2, 0.2, 0.2, 0.6);
+            cr1.set_dash(&[4.0 * line_width, 4.0 * line_width], 0.0);
+            cr1.move_to(control[0].x, control[0].y);
+            for p in control.iter().skip(1) {
+                cr1.line_to(p.x, p.y);
+            }
+            cr1.stroke().expect("Invalid cairo surface state");
+            for p in control.iter() {
+                cr1.rectangle(
+                    p.x - handle_size / 2.0,
+                    p.y - handle_size / 2.0,
+                    handle_size,
+                    handle_size,
+                );
+                cr1.fill().expect("Invalid cairo surface state");
+            }
+        }
+        let cubics = t.imp().cubics(&control, false);
+        if let Some(first) = cubics.first() {
+            cr.set_source_rgba(0.0, 0.0, 0.0, 0.9);
+            cr.set_line_width(2.0 * line_width);
+            cr.move_to(first[0].x, first[0].y);
+            for [_, b, c, d] in cubics {
+                cr.curve_to(b.x, b.y, c.x, c.y, d.x, d.y);
+            }
+            cr.stroke().expect("Invalid cairo surface state");
+        }
+
+        Inhibit(true)
+    }
 }
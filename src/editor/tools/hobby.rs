@@ -0,0 +1,398 @@
+/*
+ * gerb
+ *
+ * Copyright 2022 - Manos Pitsidianakis
+ *
+ * This file is part of gerb.
+ *
+ * gerb is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * gerb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with gerb. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::{new_contour_action, tool_impl::*};
+use crate::glyphs::Contour;
+use crate::views::canvas::{Layer, LayerBuilder};
+use gtk::Inhibit;
+
+use crate::prelude::*;
+
+// [ref:needs_user_doc]
+#[derive(Default)]
+pub struct HobbyToolInner {
+    layer: OnceCell<Layer>,
+    active: Cell<bool>,
+    closed: Cell<bool>,
+    /// Points the curve passes through, in units, and whether each one is a corner.
+    knots: RefCell<Vec<(Point, bool)>>,
+    /// Index of the knot being dragged.
+    dragging: Cell<Option<usize>>,
+    /// Pointer position, previewed as the next knot.
+    hover: Cell<Option<Point>>,
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for HobbyToolInner {
+    const NAME: &'static str = "HobbyTool";
+    type ParentType = ToolImpl;
+    type Type = HobbyTool;
+}
+
+impl ObjectImpl for HobbyToolInner {
+    fn constructed(&self, obj: &Self::Type) {
+        self.parent_constructed(obj);
+        obj.set_property::<bool>(HobbyTool::ACTIVE, false);
+        obj.set_property::<String>(ToolImpl::NAME, "hobby".to_string());
+        obj.set_property::<String>(
+            ToolImpl::DESCRIPTION,
+            "Create curve through points".to_string(),
+        );
+        obj.set_property::<gtk::Image>(
+            ToolImpl::ICON,
+            crate::resources::icons::HOBBY_ICON.to_image_widget(),
+        );
+    }
+
+    fn properties() -> &'static [glib::ParamSpec] {
+        static PROPERTIES: once_cell::sync::Lazy<Vec<glib::ParamSpec>> =
+            once_cell::sync::Lazy::new(|| {
+                vec![
+                    glib::ParamSpecBoolean::new(
+                        HobbyTool::ACTIVE,
+                        HobbyTool::ACTIVE,
+                        HobbyTool::ACTIVE,
+                        true,
+                        glib::ParamFlags::READWRITE,
+                    ),
+                    glib::ParamSpecBoolean::new(
+                        HobbyTool::CLOSED,
+                        HobbyTool::CLOSED,
+                        "Whether the curve returns to its first point.",
+                        false,
+                        glib::ParamFlags::READWRITE,
+                    ),
+                ]
+            });
+        PROPERTIES.as_ref()
+    }
+
+    fn property(&self, _obj: &Self::Type, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+        match pspec.name() {
+            HobbyTool::ACTIVE => self.active.get().to_value(),
+            HobbyTool::CLOSED => self.closed.get().to_value(),
+            _ => unimplemented!("{}", pspec.name()),
+        }
+    }
+
+    fn set_property(
+        &self,
+        _obj: &Self::Type,
+        _id: usize,
+        value: &glib::Value,
+        pspec: &glib::ParamSpec,
+    ) {
+        match pspec.name() {
+            HobbyTool::ACTIVE => self.active.set(value.get().unwrap()),
+            HobbyTool::CLOSED => self.closed.set(value.get().unwrap()),
+            _ => unimplemented!("{}", pspec.name()),
+        }
+    }
+}
+
+impl ToolImplImpl for HobbyToolInner {
+    fn on_button_press_event(
+        &self,
+        _obj: &ToolImpl,
+        view: Editor,
+        viewport: &Canvas,
+        event: &gtk::gdk::EventButton,
+    ) -> Inhibit {
+        if !self.active.get() {
+            return Inhibit(false);
+        }
+        let UnitPoint(position) = viewport.view_to_unit_point(ViewPoint(event.position().into()));
+        let scale: f64 = viewport
+            .transformation
+            .property::<f64>(Transformation::SCALE);
+        let ppu: f64 = viewport
+            .transformation
+            .property::<f64>(Transformation::PIXELS_PER_UNIT);
+        let picked = self
+            .knots
+            .borrow()
+            .iter()
+            .position(|(p, _)| p.distance(position) < HobbyTool::PICK_DISTANCE / (scale * ppu));
+        let event_state = event.state();
+        let obj = self.instance();
+        match (event.button(), picked) {
+            (gtk::gdk::BUTTON_PRIMARY, Some(i))
+                if event_state.intersects(gtk::gdk::ModifierType::CONTROL_MASK) =>
+            {
+                let corner = &mut self.knots.borrow_mut()[i].1;
+                *corner = !*corner;
+            }
+            (gtk::gdk::BUTTON_PRIMARY, Some(i)) => {
+                self.dragging.set(Some(i));
+                self.hover.set(None);
+            }
+            (gtk::gdk::BUTTON_PRIMARY, None) => {
+                let corner = event_state.intersects(gtk::gdk::ModifierType::SHIFT_MASK);
+                self.knots.borrow_mut().push((position, corner));
+            }
+            (gtk::gdk::BUTTON_SECONDARY, Some(i)) => {
+                let corner = self.knots.borrow()[i].1;
+                let mut menu = crate::utils::menu::Menu::new()
+                    .title(Some("point".into()))
+                    .separator();
+                for (label, value) in [("type: smooth (G2)", false), ("type: corner", true)] {
+                    menu = if value == corner {
+                        menu.add_button(label)
+                    } else {
+                        menu.add_button_cb(
+                            label,
+                            clone!(@weak obj, @weak view => move |_| {
+                                if let Some(knot) = obj.imp().knots.borrow_mut().get_mut(i) {
+                                    knot.1 = value;
+                                }
+                                view.viewport.queue_draw();
+                            }),
+                        )
+                    };
+                }
+                menu.add_button_cb(
+                    "delete",
+                    clone!(@weak obj, @weak view => move |_| {
+                        let mut knots = obj.imp().knots.borrow_mut();
+                        if i < knots.len() {
+                            knots.remove(i);
+                        }
+                        view.viewport.queue_draw();
+                    }),
+                )
+                .popup(event.time());
+            }
+            (gtk::gdk::BUTTON_SECONDARY, None) => {
+                let placed = self.knots.borrow().len();
+                let mut menu = crate::utils::menu::Menu::new()
+                    .title(Some("curve through points".into()))
+                    .separator();
+                if placed > 0 {
+                    let closed = self.closed.get();
+                    menu = menu
+                        .add_button_cb(
+                            if closed { "open path" } else { "close path" },
+                            clone!(@weak obj, @weak view => move |_| {
+                                obj.set_property::<bool>(HobbyTool::CLOSED, !closed);
+                                view.viewport.queue_draw();
+                            }),
+                        )
+                        .add_button_cb(
+                            "commit",
+                            clone!(@weak obj, @weak view => move |_| {
+                                obj.imp().commit(&view);
+                                view.viewport.queue_draw();
+                            }),
+                        )
+                        .add_button_cb(
+                            "discard",
+                            clone!(@weak obj, @weak view => move |_| {
+                                obj.imp().knots.borrow_mut().clear();
+                                obj.set_property::<bool>(HobbyTool::CLOSED, false);
+                                view.viewport.queue_draw();
+                            }),
+                        );
+                }
+                menu.add_button_cb(
+                    "done",
+                    clone!(@weak obj, @weak view => move |_| {
+                        obj.on_deactivate(&view);
+                        view.state().borrow_mut().active_tool = glib::types::Type::INVALID;
+                    }),
+                )
+                .popup(event.time());
+            }
+            _ => return Inhibit(false),
+        }
+        viewport.queue_draw();
+        Inhibit(true)
+    }
+
+    fn on_button_release_event(
+        &self,
+        _obj: &ToolImpl,
+        _view: Editor,
+        viewport: &Canvas,
+        event: &gtk::gdk::EventButton,
+    ) -> Inhibit {
+        if event.button() != gtk::gdk::BUTTON_PRIMARY || self.dragging.take().is_none() {
+            return Inhibit(false);
+        }
+        viewport.queue_draw();
+        Inhibit(true)
+    }
+
+    fn on_motion_notify_event(
+        &self,
+        _obj: &ToolImpl,
+        _view: Editor,
+        viewport: &Canvas,
+        event: &gtk::gdk::EventMotion,
+    ) -> Inhibit {
+        if !self.active.get() || self.knots.borrow().is_empty() {
+            return Inhibit(false);
+        }
+        let UnitPoint(position) = viewport.view_to_unit_point(ViewPoint(event.position().into()));
+        if let Some(i) = self.dragging.get() {
+            if let Some(knot) = self.knots.borrow_mut().get_mut(i) {
+                knot.0 = position;
+            }
+        } else {
+            self.hover.set(Some(position));
+        }
+        viewport.queue_draw();
+        Inhibit(true)
+    }
+
+    fn setup_toolbox(&self, obj: &ToolImpl, toolbar: &gtk::Toolbar, view: &Editor) {
+        let layer =
+            LayerBuilder::new()
+                .set_name(Some("hobby"))
+                .set_active(false)
+                .set_hidden(true)
+                .set_callback(Some(Box::new(clone!(@weak view => @default-return Inhibit(false), move |viewport: &Canvas, cr: ContextRef| {
+                    HobbyTool::draw_layer(viewport, cr, view)
+                }))))
+                .build();
+        self.instance()
+            .bind_property(HobbyTool::ACTIVE, &layer, Layer::ACTIVE)
+            .flags(glib::BindingFlags::SYNC_CREATE)
+            .build();
+        self.layer.set(layer.clone()).unwrap();
+        view.viewport.add_post_layer(layer);
+
+        self.parent_setup_toolbox(obj, toolbar, view)
+    }
+
+    fn on_activate(&self, obj: &ToolImpl, view: &Editor) {
+        self.instance()
+            .set_property::<bool>(HobbyTool::ACTIVE, true);
+        view.viewport.set_cursor("crosshair");
+        self.parent_on_activate(obj, view)
+    }
+
+    fn on_deactivate(&self, obj: &ToolImpl, view: &Editor) {
+        /* The curve stays editable only while the tool is active. */
+        self.commit(view);
+        self.instance()
+            .set_property::<bool>(HobbyTool::ACTIVE, false);
+        view.viewport.set_cursor("default");
+        self.parent_on_deactivate(obj, view)
+    }
+}
+
+impl HobbyToolInner {
+    /// Add the curve to the glyph as a new contour of cubic Bézier curves and start over.
+    fn commit(&self, view: &Editor) {
+        let knots = std::mem::take(&mut *self.knots.borrow_mut());
+        let closed = self.closed.replace(false);
+        self.dragging.set(None);
+        self.hover.set(None);
+        let Some(contour) = Contour::through_points(&knots, closed) else {
+            return;
+        };
+        let state = view.state().borrow();
+        let contour_index = state.glyph.borrow().contours.len();
+        let subaction = state.add_contour(&contour, contour_index);
+        let mut action = new_contour_action(state.glyph.clone(), contour, subaction);
+        (action.redo)();
+        state.add_undo_action(action);
+    }
+}
+
+glib::wrapper! {
+    pub struct HobbyTool(ObjectSubclass<HobbyToolInner>)
+        @extends ToolImpl;
+}
+
+impl Default for HobbyTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HobbyTool {
+    pub const ACTIVE: &'static str = "active";
+    pub const CLOSED: &'static str = "closed";
+    /// Distance in pixels from a point within which a click picks it up.
+    const PICK_DISTANCE: f64 = 8.0;
+
+    pub fn new() -> Self {
+        glib::Object::new(&[]).unwrap()
+    }
+
+    pub fn draw_layer(viewport: &Canvas, cr: ContextRef, obj: Editor) -> Inhibit {
+        let state = obj.state().borrow();
+        if Self::static_type() != state.active_tool {
+            return Inhibit(false);
+        }
+        let t = state.tools[&state.active_tool]
+            .clone()
+            .downcast::<Self>()
+            .unwrap();
+        let mut knots = t.imp().knots.borrow().clone();
+        if knots.is_empty() {
+            return Inhibit(false);
+        }
+        let closed = t.imp().closed.get();
+        if !closed {
+            knots.extend(t.imp().hover.get().map(|p| (p, false)));
+        }
+        let scale: f64 = viewport
+            .transformation
+            .property::<f64>(Transformation::SCALE);
+        let ppu: f64 = viewport
+            .transformation
+            .property::<f64>(Transformation::PIXELS_PER_UNIT);
+        let settings = obj.settings.get().unwrap();
+        let line_width = settings.property::<f64>(Settings::LINE_WIDTH) / (scale * ppu);
+        let handle_size = settings.property::<f64>(Settings::HANDLE_SIZE) / (scale * ppu);
+        cr.transform(viewport.transformation.matrix());
+        cr.set_line_width(line_width);
+        if let Some(contour) = Contour::through_points(&knots, closed) {
+            cr.set_source_rgba(0.0, 0.0, 0.0, 0.9);
+            cr.set_line_width(2.0 * line_width);
+            contour.append_path(&cr);
+            cr.stroke().expect("Invalid cairo surface state");
+        }
+        cr.set_line_width(line_width);
+        for (p, corner) in t.imp().knots.borrow().iter() {
+            /* Corners are squares, smooth points are circles. */
+            if *corner {
+                cr.rectangle(
+                    p.x - handle_size / 2.0,
+                    p.y - handle_size / 2.0,
+                    handle_size,
+                    handle_size,
+                );
+            } else {
+                cr.new_sub_path();
+                cr.arc(p.x, p.y, handle_size / 2.0, 0.0, 2.0 * std::f64::consts::PI);
+            }
+            cr.set_source_rgba(1.0, 1.0, 1.0, 0.9);
+            cr.fill_preserve().expect("Invalid cairo surface state");
+            cr.set_source_rgba(0.0, 0.0, 0.0, 0.9);
+            cr.stroke().expect("Invalid cairo surface state");
+        }
+
+        Inhibit(true)
+    }
+}
@@ -32,7 +32,7 @@ pub struct ImageToolInner {
     matrix: Cell<cairo::Matrix>,
     color: Cell<Option<Color>>,
     layer: OnceCell<Layer>,
-    glyph: OnceCell<Rc<RefCell<Glyph>>>,
+    project: OnceCell<Project>,
     active: Cell<bool>,
     descender: Cell<f64>,
     ascender: Cell<f64>,
@@ -206,27 +206,38 @@ impl ImageTool {
                 .flags(glib::BindingFlags::SYNC_CREATE)
                 .build();
         }
+        ret.imp().project.set(project).unwrap();
+        ret.set_glyph(&glyph);
 
+        ret
+    }
+
+    /// Show the image of `glyph`, if it has one, e.g. when the editor switches to another
+    /// glyph of its editing line.
+    pub fn set_glyph(&self, glyph: &Rc<RefCell<Glyph>>) {
+        let project = self.imp().project.get().unwrap();
+        self.imp().image_data.borrow_mut().take();
+        self.imp().color.set(None);
+        self.imp().matrix.set(cairo::Matrix::identity());
+        let mut active = false;
         if let Some(image_ref) = glyph.borrow().image.borrow().as_ref() {
             if let Some(file_name) = image_ref.file_name.as_ref() {
                 // [ref:FIXME] error handling
-                *ret.imp().image_data.borrow_mut() = Some(project.load_image(file_name).unwrap());
-                ret.imp().active.set(true);
-                ret.imp().color.set(image_ref.color);
+                *self.imp().image_data.borrow_mut() = Some(project.load_image(file_name).unwrap());
+                active = true;
+                self.imp().color.set(image_ref.color);
                 let xx = image_ref.x_scale;
                 let yy = image_ref.y_scale;
                 let xy = image_ref.xy_scale;
                 let yx = image_ref.yx_scale;
                 let x0 = image_ref.x_offset;
                 let y0 = image_ref.y_offset;
-                ret.imp()
+                self.imp()
                     .matrix
                     .set(cairo::Matrix::new(xx, yx, xy, yy, x0, y0));
             }
         }
-        ret.imp().glyph.set(glyph).unwrap();
-
-        ret
+        self.set_property::<bool>(Self::ACTIVE, active);
     }
 
     pub fn draw_layer(viewport: &Canvas, cr: ContextRef, obj: Editor) -> Inhibit {
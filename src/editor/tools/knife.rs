@@ -0,0 +1,313 @@
+/*
+ * gerb
+ *
+ * Copyright 2022 - Manos Pitsidianakis
+ *
+ * This file is part of gerb.
+ *
+ * gerb is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * gerb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with gerb. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::tool_impl::*;
+use crate::views::canvas::{Layer, LayerBuilder};
+use gtk::Inhibit;
+
+use crate::prelude::*;
+
+// [ref:needs_user_doc]
+#[derive(Default)]
+pub struct KnifeToolInner {
+    layer: OnceCell<Layer>,
+    active: Cell<bool>,
+    close_contours: Cell<bool>,
+    start: Cell<Option<UnitPoint>>,
+    end: Cell<Option<UnitPoint>>,
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for KnifeToolInner {
+    const NAME: &'static str = "KnifeTool";
+    type ParentType = ToolImpl;
+    type Type = KnifeTool;
+}
+
+impl ObjectImpl for KnifeToolInner {
+    fn constructed(&self, obj: &Self::Type) {
+        self.parent_constructed(obj);
+        obj.set_property::<bool>(KnifeTool::ACTIVE, false);
+        obj.set_property::<String>(ToolImpl::NAME, "knife".to_string());
+        obj.set_property::<String>(
+            ToolImpl::DESCRIPTION,
+            "Cut contours along a line".to_string(),
+        );
+        obj.set_property::<gtk::Image>(
+            ToolImpl::ICON,
+            crate::resources::icons::KNIFE_ICON.to_image_widget(),
+        );
+    }
+
+    fn properties() -> &'static [glib::ParamSpec] {
+        static PROPERTIES: once_cell::sync::Lazy<Vec<glib::ParamSpec>> =
+            once_cell::sync::Lazy::new(|| {
+                vec![
+                    glib::ParamSpecBoolean::new(
+                        KnifeTool::ACTIVE,
+                        KnifeTool::ACTIVE,
+                        KnifeTool::ACTIVE,
+                        true,
+                        glib::ParamFlags::READWRITE,
+                    ),
+                    glib::ParamSpecBoolean::new(
+                        KnifeTool::CLOSE_CONTOURS,
+                        KnifeTool::CLOSE_CONTOURS,
+                        "Join the pieces of cut closed contours along the cut line.",
+                        false,
+                        glib::ParamFlags::READWRITE,
+                    ),
+                ]
+            });
+        PROPERTIES.as_ref()
+    }
+
+    fn property(&self, _obj: &Self::Type, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+        match pspec.name() {
+            KnifeTool::ACTIVE => self.active.get().to_value(),
+            KnifeTool::CLOSE_CONTOURS => self.close_contours.get().to_value(),
+            _ => unimplemented!("{}", pspec.name()),
+        }
+    }
+
+    fn set_property(
+        &self,
+        _obj: &Self::Type,
+        _id: usize,
+        value: &glib::Value,
+        pspec: &glib::ParamSpec,
+    ) {
+        match pspec.name() {
+            KnifeTool::ACTIVE => self.active.set(value.get().unwrap()),
+            KnifeTool::CLOSE_CONTOURS => self.close_contours.set(value.get().unwrap()),
+            _ => unimplemented!("{}", pspec.name()),
+        }
+    }
+}
+
+impl ToolImplImpl for KnifeToolInner {
+    fn on_button_press_event(
+        &self,
+        _obj: &ToolImpl,
+        view: Editor,
+        viewport: &Canvas,
+        event: &gtk::gdk::EventButton,
+    ) -> Inhibit {
+        if !self.active.get() {
+            return Inhibit(false);
+        }
+        match (self.start.get(), event.button()) {
+            (None, gtk::gdk::BUTTON_PRIMARY) => {
+                let position = viewport.view_to_unit_point(ViewPoint(event.position().into()));
+                self.start.set(Some(position));
+                self.end.set(Some(position));
+            }
+            (Some(_), gtk::gdk::BUTTON_SECONDARY) => {
+                self.start.set(None);
+                self.end.set(None);
+                viewport.queue_draw();
+            }
+            (None, gtk::gdk::BUTTON_SECONDARY) => {
+                let obj = self.instance();
+                let close_contours = self.close_contours.get();
+                let mut menu = crate::utils::menu::Menu::new()
+                    .title(Some("knife".into()))
+                    .separator();
+                for (label, value) in [
+                    ("cut into open contours", false),
+                    ("cut into closed contours", true),
+                ] {
+                    menu = if value == close_contours {
+                        menu.add_button(label)
+                    } else {
+                        menu.add_button_cb(
+                            label,
+                            clone!(@weak obj => move |_| {
+                                obj.set_property::<bool>(KnifeTool::CLOSE_CONTOURS, value);
+                            }),
+                        )
+                    };
+                }
+                menu.add_button_cb(
+                    "done",
+                    clone!(@weak obj, @weak view => move |_| {
+                        obj.on_deactivate(&view);
+                        view.state().borrow_mut().active_tool = glib::types::Type::INVALID;
+                    }),
+                )
+                .popup(event.time());
+            }
+            _ => return Inhibit(false),
+        }
+        Inhibit(true)
+    }
+
+    fn on_button_release_event(
+        &self,
+        _obj: &ToolImpl,
+        view: Editor,
+        viewport: &Canvas,
+        event: &gtk::gdk::EventButton,
+    ) -> Inhibit {
+        if !self.active.get() || event.button() != gtk::gdk::BUTTON_PRIMARY {
+            return Inhibit(false);
+        }
+        let UnitPoint(start) = match self.start.take() {
+            Some(p) => p,
+            None => return Inhibit(false),
+        };
+        self.end.set(None);
+        let UnitPoint(end) = viewport.view_to_unit_point(ViewPoint(event.position().into()));
+        let state = view.state().borrow();
+        if let Some(mut action) =
+            Glyph::knife_action(&state.glyph, start, end, self.close_contours.get())
+        {
+            (action.redo)();
+            state.add_undo_action(action);
+        }
+        viewport.queue_draw();
+        Inhibit(true)
+    }
+
+    fn on_motion_notify_event(
+        &self,
+        _obj: &ToolImpl,
+        _view: Editor,
+        viewport: &Canvas,
+        event: &gtk::gdk::EventMotion,
+    ) -> Inhibit {
+        if !self.active.get() || self.start.get().is_none() {
+            return Inhibit(false);
+        }
+        self.end.set(Some(
+            viewport.view_to_unit_point(ViewPoint(event.position().into())),
+        ));
+        viewport.queue_draw();
+        Inhibit(true)
+    }
+
+    fn setup_toolbox(&self, obj: &ToolImpl, toolbar: &gtk::Toolbar, view: &Editor) {
+        let layer =
+            LayerBuilder::new()
+                .set_name(Some("knife"))
+                .set_active(false)
+                .set_hidden(true)
+                .set_callback(Some(Box::new(clone!(@weak view => @default-return Inhibit(false), move |viewport: &Canvas, cr: ContextRef| {
+                    KnifeTool::draw_layer(viewport, cr, view)
+                }))))
+                .build();
+        self.instance()
+            .bind_property(KnifeTool::ACTIVE, &layer, Layer::ACTIVE)
+            .flags(glib::BindingFlags::SYNC_CREATE)
+            .build();
+        self.layer.set(layer.clone()).unwrap();
+        view.viewport.add_post_layer(layer);
+
+        self.parent_setup_toolbox(obj, toolbar, view)
+    }
+
+    fn on_activate(&self, obj: &ToolImpl, view: &Editor) {
+        self.instance()
+            .set_property::<bool>(KnifeTool::ACTIVE, true);
+        view.viewport.set_cursor("crosshair");
+        self.parent_on_activate(obj, view)
+    }
+
+    fn on_deactivate(&self, obj: &ToolImpl, view: &Editor) {
+        self.start.set(None);
+        self.end.set(None);
+        self.instance()
+            .set_property::<bool>(KnifeTool::ACTIVE, false);
+        view.viewport.set_cursor("default");
+        self.parent_on_deactivate(obj, view)
+    }
+}
+
+glib::wrapper! {
+    pub struct KnifeTool(ObjectSubclass<KnifeToolInner>)
+        @extends ToolImpl;
+}
+
+impl Default for KnifeTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KnifeTool {
+    pub const ACTIVE: &'static str = "active";
+    pub const CLOSE_CONTOURS: &'static str = "close-contours";
+
+    pub fn new() -> Self {
+        glib::Object::new(&[]).unwrap()
+    }
+
+    pub fn draw_layer(viewport: &Canvas, mut cr: ContextRef, obj: Editor) -> Inhibit {
+        let state = obj.state().borrow();
+        if Self::static_type() != state.active_tool {
+            return Inhibit(false);
+        }
+        let t = state.tools[&state.active_tool]
+            .clone()
+            .downcast::<Self>()
+            .unwrap();
+        let (UnitPoint(start), UnitPoint(end)) = match (t.imp().start.get(), t.imp().end.get()) {
+            (Some(start), Some(end)) => (start, end),
+            _ => return Inhibit(false),
+        };
+        let scale: f64 = viewport
+            .transformation
+            .property::<f64>(Transformation::SCALE);
+        let ppu: f64 = viewport
+            .transformation
+            .property::<f64>(Transformation::PIXELS_PER_UNIT);
+        let line_width = obj
+            .settings
+            .get()
+            .unwrap()
+            .property::<f64>(Settings::LINE_WIDTH)
+            / (scale * ppu);
+        cr.transform(viewport.transformation.matrix());
+        cr.set_line_width(line_width);
+        {
+            let cr1 = cr.push();
+            cr1.set_source_rgba(0.2, 0.2, 0.2, 0.6);
+            cr1.set_dash(&[4.0 * line_width, 4.0 * line_width], 0.0);
+            cr1.move_to(start.x, start.y);
+            cr1.line_to(end.x, end.y);
+            cr1.stroke().expect("Invalid cairo surface state");
+        }
+        /* Mark where the contours will be cut. */
+        let radius = 3.0 * line_width;
+        cr.set_source_rgba(0.9, 0.1, 0.1, 0.8);
+        for contour in state.glyph.borrow().contours.iter() {
+            for curve in contour.curves().iter() {
+                for (t, _) in curve.line_intersections(start, end) {
+                    let p = curve.compute(t);
+                    cr.arc(p.x, p.y, radius, 0.0, 2.0 * std::f64::consts::PI);
+                    cr.fill().expect("Invalid cairo surface state");
+                }
+            }
+        }
+
+        Inhibit(true)
+    }
+}
@@ -36,6 +36,12 @@ pub enum Mode {
     Pan,
     Drag,
     DragGuideline(usize),
+    DragComponent(usize),
+    /// Dragging the left or right sidebearing line of the glyph at `index` of the editing line.
+    DragSidebearing {
+        index: usize,
+        left: bool,
+    },
     ResizeDimensions {
         previous_value: Option<f64>,
     },
@@ -162,7 +168,7 @@ impl ToolImplImpl for PanningToolInner {
                     .set_property::<bool>(PanningTool::ACTIVE, true);
                 viewport.set_cursor("crosshair");
             }
-            m @ Mode::Drag | m @ Mode::DragGuideline(_)
+            m @ Mode::Drag | m @ Mode::DragGuideline(_) | m @ Mode::DragComponent(_)
                 if event_button == gtk::gdk::BUTTON_PRIMARY =>
             {
                 Lock::clear(&view);
@@ -176,12 +182,28 @@ impl ToolImplImpl for PanningToolInner {
                 self.instance()
                     .set_property::<bool>(PanningTool::ACTIVE, false);
                 self.set_default_cursor(&view);
-                if matches!(
+                if let (Mode::DragComponent(index), gtk::gdk::EventType::DoubleButtonPress) =
+                    (m, event.event_type())
+                {
+                    view.open_component_base(index);
+                } else if matches!(
                     (m, event.event_type()),
                     (Mode::Drag, gtk::gdk::EventType::DoubleButtonPress)
                 ) {
                     let UnitPoint(position) =
                         viewport.view_to_unit_point(ViewPoint(event.position().into()));
+                    /* Double-clicking a segment inserts a point there, double-clicking a point
+                     * selects its whole contour. */
+                    let on_point = !view
+                        .state()
+                        .borrow()
+                        .kd_tree
+                        .borrow()
+                        .query_point(position, (10.0 / (scale * ppu)).ceil() as i64)
+                        .is_empty();
+                    if !on_point && insert_point(&view, position) {
+                        return Inhibit(true);
+                    }
                     let state = view.state().borrow();
                     let curve_query = {
                         let glyph = state.glyph.borrow();
@@ -214,6 +236,17 @@ impl ToolImplImpl for PanningToolInner {
                     }
                 }
             }
+            Mode::None
+                if event_button == gtk::gdk::BUTTON_PRIMARY
+                    && event.state().contains(gtk::gdk::ModifierType::MOD1_MASK) =>
+            {
+                // [ref:needs_user_doc]
+                let UnitPoint(position) =
+                    viewport.view_to_unit_point(ViewPoint(event.position().into()));
+                if !insert_point(&view, position) {
+                    return Inhibit(false);
+                }
+            }
             Mode::None if event_button == gtk::gdk::BUTTON_PRIMARY => {
                 let event_position = event.position();
                 let uposition @ UnitPoint(position) =
@@ -238,6 +271,30 @@ impl ToolImplImpl for PanningToolInner {
                         undo_db.event(action);
                     }
                 }
+                let anchor = view
+                    .state()
+                    .borrow()
+                    .anchor_at(position, 10.0 / (scale * ppu));
+                if let Some(anchor) = anchor {
+                    let modifier: SelectionModifier = event.state().into();
+                    let is_selected = view.state().borrow().anchor_selection.contains(&anchor);
+                    if !is_selected || modifier != SelectionModifier::Replace {
+                        if modifier == SelectionModifier::Replace {
+                            view.set_selection(&[], SelectionModifier::Replace);
+                        }
+                        view.state()
+                            .borrow_mut()
+                            .set_anchor_selection(&[anchor], modifier);
+                    }
+                    if modifier != SelectionModifier::Remove {
+                        self.instance()
+                            .set_property::<bool>(PanningTool::ACTIVE, true);
+                        self.mode.set(Mode::Drag);
+                        view.set_property(Editor::MODIFYING_IN_PROCESS, true);
+                        viewport.set_cursor("grab");
+                    }
+                    return Inhibit(true);
+                }
                 let mut is_guideline: bool = false;
                 for (i, g) in view
                     .state()
@@ -281,6 +338,12 @@ impl ToolImplImpl for PanningToolInner {
                             || !pts.iter().any(|i| current_selection.contains(&i.uuid))
                         {
                             drop(state);
+                            if SelectionModifier::from(event.state()) == SelectionModifier::Replace
+                            {
+                                let mut state = view.state().borrow_mut();
+                                state.set_anchor_selection(&[], SelectionModifier::Replace);
+                                state.component_selection = None;
+                            }
                             view.set_selection(&pts, event.state().into());
                             pts.is_empty()
                         } else {
@@ -314,6 +377,16 @@ impl ToolImplImpl for PanningToolInner {
                             }
                         }
                         view.hovering.set(None);
+                        let component = view.state().borrow().glyph.borrow().component_at(position);
+                        view.state().borrow_mut().component_selection = component;
+                        if let Some(index) = component {
+                            self.instance()
+                                .set_property::<bool>(PanningTool::ACTIVE, true);
+                            self.mode.set(Mode::DragComponent(index));
+                            viewport.set_cursor("grab");
+                            viewport.queue_draw();
+                            return Inhibit(true);
+                        }
                         self.instance()
                             .set_property::<bool>(PanningTool::ACTIVE, true);
                         if viewport.property::<bool>(Canvas::SHOW_TOTAL_AREA) {
@@ -334,6 +407,13 @@ impl ToolImplImpl for PanningToolInner {
                                 return Inhibit(true);
                             }
                         }
+                        if let Some((index, left)) = self.sidebearing_at(&view, viewport, position)
+                        {
+                            self.mode.set(Mode::DragSidebearing { index, left });
+                            view.set_property(Editor::MODIFYING_IN_PROCESS, true);
+                            viewport.set_cursor("col-resize");
+                            return Inhibit(true);
+                        }
                         self.is_selection_empty.set(true);
                         self.selection_upper_left.set(uposition);
                         self.selection_bottom_right.set(uposition);
@@ -352,6 +432,16 @@ impl ToolImplImpl for PanningToolInner {
             Mode::Select if event_button == gtk::gdk::BUTTON_PRIMARY => {
                 let event_position = event.position();
                 let position = viewport.view_to_unit_point(ViewPoint(event_position.into()));
+                if event.event_type() == gtk::gdk::EventType::DoubleButtonPress {
+                    if let Some(index) = view.line_neighbour_at(position.0.x) {
+                        self.mode.set(Mode::None);
+                        self.instance()
+                            .set_property::<bool>(PanningTool::ACTIVE, false);
+                        self.set_default_cursor(&view);
+                        view.activate_line_glyph(index);
+                        return Inhibit(true);
+                    }
+                }
                 self.selection_upper_left.set(position);
                 self.selection_bottom_right.set(position);
                 self.is_selection_empty.set(true);
@@ -363,6 +453,36 @@ impl ToolImplImpl for PanningToolInner {
                 let event_position = event.position();
                 let UnitPoint(position) =
                     viewport.view_to_unit_point(ViewPoint(event_position.into()));
+                let anchor = view
+                    .state()
+                    .borrow()
+                    .anchor_at(position, 10.0 / (scale * ppu));
+                if let Some(anchor) = anchor {
+                    let menu = crate::utils::menu::Menu::new()
+                        .title(Some(std::borrow::Cow::from(format!(
+                            "Anchor {}",
+                            anchor.name()
+                        ))))
+                        .separator()
+                        .add_button_cb(
+                            "Edit",
+                            clone!(@weak anchor, @weak view =>  move |_| {
+                                view.state().borrow().edit_anchor(&anchor).present();
+                            }),
+                        )
+                        .add_button_cb(
+                            "Delete",
+                            clone!(@weak anchor, @weak view =>  move |_| {
+                                let mut state = view.state().borrow_mut();
+                                let mut action = state.delete_anchors(&[anchor.clone()]);
+                                (action.redo)();
+                                state.add_undo_action(action);
+                                state.set_anchor_selection(&[anchor], SelectionModifier::Remove);
+                            }),
+                        );
+                    menu.popup(event.time());
+                    return Inhibit(true);
+                }
                 for (i, g) in view
                     .state()
                     .borrow()
@@ -403,6 +523,39 @@ impl ToolImplImpl for PanningToolInner {
                         return Inhibit(true);
                     }
                 }
+                let component = view.state().borrow().glyph.borrow().component_at(position);
+                if let Some(index) = component {
+                    view.state().borrow_mut().component_selection = Some(index);
+                    viewport.queue_draw();
+                    let base_name = view.state().borrow().glyph.borrow().components[index]
+                        .base_name
+                        .clone();
+                    crate::utils::menu::Menu::new()
+                        .title(Some(std::borrow::Cow::from(format!(
+                            "Component {base_name}"
+                        ))))
+                        .separator()
+                        .add_button_cb(
+                            "Properties",
+                            clone!(@weak view => move |_| {
+                                view.component_properties(index);
+                            }),
+                        )
+                        .add_button_cb(
+                            "Decompose",
+                            clone!(@weak view => move |_| {
+                                view.decompose_components(true);
+                            }),
+                        )
+                        .add_button_cb(
+                            "Open base glyph",
+                            clone!(@weak view => move |_| {
+                                view.open_component_base(index);
+                            }),
+                        )
+                        .popup(event.time());
+                    return Inhibit(true);
+                }
                 self.is_selection_empty.set(true);
                 view.set_selection(&[], SelectionModifier::Replace);
 
@@ -423,25 +576,11 @@ impl ToolImplImpl for PanningToolInner {
             Mode::ResizeDimensions { previous_value }
                 if event_button == gtk::gdk::BUTTON_PRIMARY =>
             {
-                let (metadata, new_value) = {
-                    let state = view.state().borrow();
-                    let glyph = state.glyph.borrow();
-                    (glyph.metadata.clone(), glyph.width())
-                };
-                let action = Action {
-                    stamp: EventStamp {
-                        t: std::any::TypeId::of::<Editor>(),
-                        property: GlyphMetadata::static_type().name(),
-                        id: unsafe { std::mem::transmute::<&[usize], &[u8]>(&[]).into() },
-                    },
-                    compress: false,
-                    redo: Box::new(clone!(@weak metadata => move || {
-                        metadata.width.set(new_value);
-                    })),
-                    undo: Box::new(clone!(@weak metadata => move || {
-                        metadata.width.set(previous_value);
-                    })),
-                };
+                let glyph = view.state().borrow().glyph.clone();
+                let new_value = glyph.borrow().width();
+                glyph.borrow().width.set(previous_value);
+                let mut action = Glyph::set_width_action(&glyph, new_value.unwrap_or(0.0), false);
+                (action.redo)();
                 let app: &Application = view.app();
                 let undo_db = app.undo_db.borrow();
                 undo_db.event(action);
@@ -477,10 +616,28 @@ impl ToolImplImpl for PanningToolInner {
                 .kd_tree
                 .borrow()
                 .query_region((upper_left.0, bottom_right.0));
-            if !pts.is_empty() {
+            let anchors = {
+                let (a, b) = (upper_left.0, bottom_right.0);
+                let state = view.state().borrow();
+                let glyph = state.glyph.borrow();
+                let anchors = glyph.metadata.anchors.borrow();
+                anchors
+                    .iter()
+                    .filter(|anchor| {
+                        let p = anchor.position();
+                        (a.x.min(b.x)..=a.x.max(b.x)).contains(&p.x)
+                            && (a.y.min(b.y)..=a.y.max(b.y)).contains(&p.y)
+                    })
+                    .cloned()
+                    .collect::<Vec<_>>()
+            };
+            if !pts.is_empty() || !anchors.is_empty() {
                 self.is_selection_empty.set(false);
             }
             view.set_selection(&pts, event.state().into());
+            view.state()
+                .borrow_mut()
+                .set_anchor_selection(&anchors, event.state().into());
             self.mode.set(Mode::None);
             self.set_default_cursor(&view);
             return Inhibit(true);
@@ -545,6 +702,37 @@ impl ToolImplImpl for PanningToolInner {
                     .set_property::<bool>(PanningTool::ACTIVE, false);
                 self.set_default_cursor(&view);
             }
+            Mode::DragSidebearing { index, .. } if event_button == gtk::gdk::BUTTON_PRIMARY => {
+                self.mode.set(Mode::None);
+                self.instance()
+                    .set_property::<bool>(PanningTool::ACTIVE, false);
+                view.set_property(Editor::MODIFYING_IN_PROCESS, false);
+                self.set_default_cursor(&view);
+                /* The edited glyph's dependents are updated when modification ends, but
+                 * not those of the other glyphs of the editing line. */
+                let (line, active) = view.line();
+                if let Some(glyph) = line
+                    .get(index)
+                    .and_then(|p| p.glyph.as_ref())
+                    .filter(|_| index != active)
+                {
+                    let (layer, name) = {
+                        let glyph = glyph.borrow();
+                        (glyph.metadata.layer().clone(), glyph.name().clone())
+                    };
+                    if let Some(action) =
+                        layer.and_then(|layer| layer.update_metric_dependents(&name))
+                    {
+                        view.app().undo_db.borrow().amend(action);
+                    }
+                }
+            }
+            Mode::DragComponent(_) if event_button == gtk::gdk::BUTTON_PRIMARY => {
+                self.mode.set(Mode::None);
+                self.instance()
+                    .set_property::<bool>(PanningTool::ACTIVE, false);
+                self.set_default_cursor(&view);
+            }
             Mode::DragGuideline(idx) if event_button == gtk::gdk::BUTTON_PRIMARY => {
                 let event_position = event.position();
                 let ruler_breadth = viewport.property::<f64>(Canvas::RULER_BREADTH_PIXELS);
@@ -604,58 +792,40 @@ impl ToolImplImpl for PanningToolInner {
                     let mut menu = crate::utils::menu::Menu::new()
                         .title(Some(format!("{:?}", corner_continuity).into()))
                         .separator();
-                    menu = if !matches!(corner_continuity, Continuity::Velocity) {
-                        menu.add_button_cb(
-                            "make smooth",
-                            clone!(@strong view => move |_| {
-                                let new_val = Continuity::Velocity;
-                                let mut action = change_continuity(&view, idx,corner_continuity, new_val);
-                                (action.redo)();
-                                let app: &Application = view
-                                    .app();
-                                let undo_db = app.undo_db.borrow();
-                                undo_db.event(action);
-                            }),
-                        )
-                    } else {
-                        menu.add_button("make smooth")
-                    };
-                    menu = if !matches!(corner_continuity, Continuity::Positional) {
-                        menu.add_button_cb(
-                            "make corner",
-                            clone!(@strong view => move |_| {
-                                let new_val = Continuity::Positional;
-                                let mut action = change_continuity(&view, idx,corner_continuity, new_val);
-                                (action.redo)();
-                                let app: &Application = view
-                                    .app();
-                                let undo_db = app.undo_db.borrow();
-                                undo_db.event(action);
-                            }),
-                        )
-                    } else {
-                        menu.add_button("make corner")
-                    };
-                    menu = if !matches!(corner_continuity, Continuity::Tangent { .. }) {
-                        menu.add_button_cb(
-                            "make tangent",
-                            clone!(@strong view => move |_| {
-                                let new_val = Continuity::Tangent { beta: 1.00 };
-                                let mut action = change_continuity(&view, idx,corner_continuity, new_val);
-                                (action.redo)();
-                                let app: &Application = view
-                                    .app();
-                                let undo_db = app.undo_db.borrow();
-                                undo_db.event(action);
-                            }),
-                        )
-                    } else {
-                        menu.add_button("make tangent")
+                    let current = match corner_continuity {
+                        Continuity::Positional => PointType::Corner,
+                        Continuity::Velocity => PointType::Smooth,
+                        Continuity::Tangent { .. } => PointType::Tangent,
                     };
+                    for point_type in PointType::ALL {
+                        let label = format!("make {}", point_type.label().to_lowercase());
+                        menu = if point_type == current {
+                            menu.add_button(&label)
+                        } else {
+                            menu.add_button_cb(
+                                &label,
+                                clone!(@strong view => move |_| {
+                                    let state = view.state().borrow();
+                                    if let Some(mut action) = state.change_point_type(&[idx], point_type) {
+                                        (action.redo)();
+                                        state.add_undo_action(action);
+                                    }
+                                }),
+                            )
+                        };
+                    }
                     menu.popup(event.time());
                     return Inhibit(true);
                 } else if let Some(((contour_index, _), _curve)) = on_curve_query {
+                    let UnitPoint(position) =
+                        viewport.view_to_unit_point(ViewPoint(event.position().into()));
                     crate::utils::menu::Menu::new()
+                        .add_button_cb(
+                            "insert point",
+                            clone!(@strong view => move |_| {
+                                insert_point(&view, position);
+                            }),
+                        )
                         .add_button_cb(
                             "reverse",
                             clone!(@strong view => move |_| {
@@ -668,6 +838,19 @@ impl ToolImplImpl for PanningToolInner {
                                 undo_db.event(action);
                             }),
                         )
+                        .add_button_cb(
+                            "properties",
+                            clone!(@strong view => move |_| {
+                                let contour = {
+                                    let state = view.state().borrow();
+                                    let glyph = state.glyph.borrow();
+                                    Contour::link_siblings(&glyph.contours);
+                                    glyph.contours[contour_index].clone()
+                                };
+                                let w = contour.new_property_window(view.app(), false);
+                                w.present();
+                            }),
+                        )
                         .popup(event.time());
                     return Inhibit(true);
                 }
@@ -802,6 +985,14 @@ impl ToolImplImpl for PanningToolInner {
         let state = view.state().borrow();
         let UnitPoint(position) = viewport.view_to_unit_point(ViewPoint(event.position().into()));
         if !self.instance().property::<bool>(PanningTool::ACTIVE) {
+            if state.anchor_at(position, 10.0 / (scale * ppu)).is_some() {
+                viewport.set_cursor("grab");
+                return Inhibit(false);
+            }
+            if self.sidebearing_at(&view, viewport, position).is_some() {
+                viewport.set_cursor("col-resize");
+                return Inhibit(false);
+            }
             let glyph = state.glyph.borrow();
             let pts = state
                 .kd_tree
@@ -946,6 +1137,54 @@ impl ToolImplImpl for PanningToolInner {
                 m.translate(delta.x, delta.y);
                 state.transform_guideline(idx, m, 0.0);
             }
+            Mode::DragSidebearing { index, left } => {
+                let mouse: ViewPoint = viewport.get_mouse();
+                let dx = (event.position().0 - mouse.0.x) / (scale * ppu);
+                let (line, active) = view.line();
+                if let Some(glyph) = line.get(index).and_then(|p| p.glyph.as_ref()) {
+                    let width = glyph.borrow().width().unwrap_or(0.0);
+                    let action = if left {
+                        let lsb = glyph.borrow().sidebearings().map(|(lsb, _)| lsb);
+                        lsb.and_then(|lsb| Glyph::set_lsb_action(glyph, lsb - dx, true))
+                    } else {
+                        Some(Glyph::set_width_action(glyph, width + dx, true))
+                    };
+                    if let Some(mut action) = action {
+                        (action.redo)();
+                        state.add_undo_action(action);
+                    }
+                    if index < active {
+                        /* The edited glyph stays at the origin, so glyphs before it move left
+                         * as they get wider. Move the camera to keep the dragged glyph in
+                         * place instead. */
+                        let delta = glyph.borrow().width().unwrap_or(0.0) - width;
+                        viewport
+                            .transformation
+                            .move_camera_by_delta(ViewPoint((delta * scale * ppu, 0.0).into()));
+                    }
+                }
+            }
+            Mode::DragComponent(index) => {
+                let mouse: ViewPoint = viewport.get_mouse();
+                let mut delta =
+                    (<_ as Into<Point>>::into(event.position()) - mouse.0) / (scale * ppu);
+                delta.y *= -1.0;
+                match Lock::from_bits(view.property(Editor::LOCK)) {
+                    Some(Lock::X) => {
+                        delta.y = 0.0;
+                    }
+                    Some(Lock::Y) => {
+                        delta.x = 0.0;
+                    }
+                    _ => {}
+                }
+                let mut m = Matrix::identity();
+                m.translate(delta.x, delta.y);
+                let mut action = state.transform_component(index, m);
+                action.compress = true;
+                (action.redo)();
+                state.add_undo_action(action);
+            }
             Mode::Pan => {
                 if warp_cursor {
                     let (width, height) = (
@@ -1065,6 +1304,43 @@ impl ToolImplImpl for PanningToolInner {
 }
 
 impl PanningToolInner {
+    /// The glyph of the editing line, by index, whose left (`true`) or right (`false`)
+    /// sidebearing line is at `position`, within the vertical extent of the font's metrics. The
+    /// edited glyph's lines come first, and where two other glyphs meet, the right sidebearing
+    /// of the first one.
+    fn sidebearing_at(
+        &self,
+        view: &Editor,
+        viewport: &Canvas,
+        position: Point,
+    ) -> Option<(usize, bool)> {
+        let scale: f64 = viewport
+            .transformation
+            .property::<f64>(Transformation::SCALE);
+        let ppu: f64 = viewport
+            .transformation
+            .property::<f64>(Transformation::PIXELS_PER_UNIT);
+        let descender = view.property::<f64>(Editor::DESCENDER);
+        let ascender = view.property::<f64>(Editor::ASCENDER);
+        if !(descender.min(0.0)..=ascender).contains(&position.y) {
+            return None;
+        }
+        let distance = 6.0 / (scale * ppu);
+        let (line, active) = view.line();
+        let on_line = |i: usize| {
+            let p = &line[i];
+            p.glyph.as_ref()?;
+            if (position.x - p.x).abs() <= distance {
+                Some((i, true))
+            } else if (position.x - p.x - p.width).abs() <= distance {
+                Some((i, false))
+            } else {
+                None
+            }
+        };
+        on_line(active).or_else(|| (0..line.len()).find_map(on_line))
+    }
+
     fn set_default_cursor(&self, view: &Editor) {
         if let Some(pixbuf) = self.cursor.get().unwrap().clone() {
             view.viewport.set_cursor_from_pixbuf(pixbuf);
@@ -1079,7 +1355,10 @@ impl PanningToolInner {
         move_action.connect_activate(glib::clone!(@weak view, @weak obj => move |_, _| {
             let state = view.state().borrow();
             match obj.imp().mode.get() {
-                Mode::None if !state.get_selection_set().is_empty() => {
+                Mode::None
+                    if !state.get_selection_set().is_empty()
+                        || !state.anchor_selection.is_empty() =>
+                {
                     obj.set_property::<bool>(PanningTool::ACTIVE, true);
                     obj.imp().mode.set(Mode::Drag);
                     view.set_property(Editor::MODIFYING_IN_PROCESS, true);
@@ -1432,12 +1711,19 @@ impl PanningTool {
             }
         }
         let state = view.state().borrow();
-        if state.get_selection_set().is_empty() {
-            let mut delta: Point = m * Point::from((0.0, 0.0));
-            delta.x *= -1.0;
-            view.viewport
-                .transformation
-                .move_camera_by_delta(ViewPoint(delta));
+        if state.get_selection_set().is_empty() && state.anchor_selection.is_empty() {
+            if let Some(index) = state.component_selection {
+                let mut action = state.transform_component(index, m);
+                action.compress = true;
+                (action.redo)();
+                state.add_undo_action(action);
+            } else {
+                let mut delta: Point = m * Point::from((0.0, 0.0));
+                delta.x *= -1.0;
+                view.viewport
+                    .transformation
+                    .move_camera_by_delta(ViewPoint(delta));
+            }
         } else {
             state.transform_selection(m, true);
         }
@@ -1449,9 +1735,15 @@ impl PanningTool {
             SelectionAction::All => {
                 let pts = view.state().borrow().kd_tree.borrow().all();
                 view.set_selection(&pts, SelectionModifier::Replace);
+                let mut state = view.state().borrow_mut();
+                let anchors = state.glyph.borrow().metadata.anchors.borrow().clone();
+                state.set_anchor_selection(&anchors, SelectionModifier::Replace);
             }
             SelectionAction::None => {
                 view.set_selection(&[], SelectionModifier::Replace);
+                view.state()
+                    .borrow_mut()
+                    .set_anchor_selection(&[], SelectionModifier::Replace);
             }
         }
         view.queue_draw();
@@ -1523,28 +1815,35 @@ fn snap_to_closest_anchor(
     candidates.first().map(|(p, _)| *p)
 }
 
-fn change_continuity(
-    view: &Editor,
-    index: GlyphPointIndex,
-    prev_val: Continuity,
-    new_val: Continuity,
-) -> Action {
-    let contour: Contour = {
-        let c = view.state().borrow().glyph.borrow().contours[index.contour_index].clone();
-        c
+/// Split the curve under `position` by inserting a new smooth on-curve point at the closest
+/// location on it, and select that point. Returns `false` if there is no curve under `position`.
+fn insert_point(view: &Editor, position: Point) -> bool {
+    let (contour_index, curve_index, t) = {
+        let state = view.state().borrow();
+        let glyph = state.glyph.borrow();
+        match glyph.on_curve_query(position, &[]) {
+            Some(((contour_index, curve_index), curve)) => {
+                (contour_index, curve_index, curve.nearest(position).0)
+            }
+            None => return false,
+        }
+    };
+    let state = view.state().borrow();
+    let new_contour = match state.glyph.borrow().contours[contour_index].with_split(curve_index, t)
+    {
+        Some(c) => c,
+        None => return false,
     };
-    Action {
-        stamp: EventStamp {
-            t: std::any::TypeId::of::<Editor>(),
-            property: Contour::static_type().name(),
-            id: unsafe { std::mem::transmute::<&[usize], &[u8]>(&[index.contour_index]).into() },
-        },
-        compress: false,
-        redo: Box::new(clone!(@weak contour, @weak view => move || {
-            contour.change_continuity(index, new_val);
-        })),
-        undo: Box::new(clone!(@weak contour, @weak view => move || {
-            contour.change_continuity(index, prev_val);
-        })),
+    let new_point = new_contour.curves()[curve_index]
+        .points()
+        .last()
+        .map(|cp| cp.glyph_index(contour_index, curve_index));
+    let mut action = state.replace_contour(new_contour, contour_index);
+    (action.redo)();
+    state.add_undo_action(action);
+    drop(state);
+    if let Some(idx) = new_point {
+        view.set_selection(&[idx], SelectionModifier::Replace);
     }
+    true
 }
@@ -0,0 +1,368 @@
+/*
+ * gerb
+ *
+ * Copyright 2022 - Manos Pitsidianakis
+ *
+ * This file is part of gerb.
+ *
+ * gerb is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * gerb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with gerb. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::{new_contour_action, tool_impl::*};
+use crate::glyphs::Contour;
+use crate::views::canvas::{Layer, LayerBuilder};
+use gtk::Inhibit;
+
+use crate::prelude::*;
+
+// [ref:needs_user_doc]
+#[derive(Default)]
+pub struct PencilToolInner {
+    layer: OnceCell<Layer>,
+    active: Cell<bool>,
+    smoothing: Cell<f64>,
+    corner_angle: Cell<f64>,
+    /// Pointer positions of the stroke being drawn, in units.
+    stroke: RefCell<Vec<Point>>,
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for PencilToolInner {
+    const NAME: &'static str = "PencilTool";
+    type ParentType = ToolImpl;
+    type Type = PencilTool;
+}
+
+impl ObjectImpl for PencilToolInner {
+    fn constructed(&self, obj: &Self::Type) {
+        self.parent_constructed(obj);
+        obj.set_property::<bool>(PencilTool::ACTIVE, false);
+        self.smoothing.set(PencilTool::SMOOTHING_DEFAULT);
+        self.corner_angle.set(PencilTool::CORNER_ANGLE_DEFAULT);
+        obj.set_property::<String>(ToolImpl::NAME, "pencil".to_string());
+        obj.set_property::<String>(ToolImpl::DESCRIPTION, "Draw freehand contours".to_string());
+        obj.set_property::<gtk::Image>(
+            ToolImpl::ICON,
+            crate::resources::icons::PENCIL_ICON.to_image_widget(),
+        );
+    }
+
+    fn properties() -> &'static [glib::ParamSpec] {
+        static PROPERTIES: once_cell::sync::Lazy<Vec<glib::ParamSpec>> =
+            once_cell::sync::Lazy::new(|| {
+                vec![
+                    glib::ParamSpecBoolean::new(
+                        PencilTool::ACTIVE,
+                        PencilTool::ACTIVE,
+                        PencilTool::ACTIVE,
+                        true,
+                        glib::ParamFlags::READWRITE,
+                    ),
+                    glib::ParamSpecDouble::new(
+                        PencilTool::SMOOTHING,
+                        PencilTool::SMOOTHING,
+                        "Largest distance in pixels between the stroke and the fitted curves.",
+                        0.1,
+                        100.0,
+                        PencilTool::SMOOTHING_DEFAULT,
+                        glib::ParamFlags::READWRITE,
+                    ),
+                    glib::ParamSpecDouble::new(
+                        PencilTool::CORNER_ANGLE,
+                        PencilTool::CORNER_ANGLE,
+                        "Turns sharper than this many degrees become corners.",
+                        0.0,
+                        180.0,
+                        PencilTool::CORNER_ANGLE_DEFAULT,
+                        glib::ParamFlags::READWRITE,
+                    ),
+                ]
+            });
+        PROPERTIES.as_ref()
+    }
+
+    fn property(&self, _obj: &Self::Type, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+        match pspec.name() {
+            PencilTool::ACTIVE => self.active.get().to_value(),
+            PencilTool::SMOOTHING => self.smoothing.get().to_value(),
+            PencilTool::CORNER_ANGLE => self.corner_angle.get().to_value(),
+            _ => unimplemented!("{}", pspec.name()),
+        }
+    }
+
+    fn set_property(
+        &self,
+        _obj: &Self::Type,
+        _id: usize,
+        value: &glib::Value,
+        pspec: &glib::ParamSpec,
+    ) {
+        match pspec.name() {
+            PencilTool::ACTIVE => self.active.set(value.get().unwrap()),
+            PencilTool::SMOOTHING => self.smoothing.set(value.get().unwrap()),
+            PencilTool::CORNER_ANGLE => self.corner_angle.set(value.get().unwrap()),
+            _ => unimplemented!("{}", pspec.name()),
+        }
+    }
+}
+
+impl ToolImplImpl for PencilToolInner {
+    fn on_button_press_event(
+        &self,
+        _obj: &ToolImpl,
+        view: Editor,
+        viewport: &Canvas,
+        event: &gtk::gdk::EventButton,
+    ) -> Inhibit {
+        if !self.active.get() {
+            return Inhibit(false);
+        }
+        let drawing = !self.stroke.borrow().is_empty();
+        match (drawing, event.button()) {
+            (false, gtk::gdk::BUTTON_PRIMARY) => {
+                let UnitPoint(position) =
+                    viewport.view_to_unit_point(ViewPoint(event.position().into()));
+                self.stroke.borrow_mut().push(position);
+            }
+            (true, gtk::gdk::BUTTON_SECONDARY) => {
+                self.stroke.borrow_mut().clear();
+                viewport.queue_draw();
+            }
+            (false, gtk::gdk::BUTTON_SECONDARY) => {
+                let obj = self.instance();
+                let mut menu = crate::utils::menu::Menu::new()
+                    .title(Some("pencil".into()))
+                    .separator();
+                for (label, property, value, current) in [
+                    (
+                        "smoothing: low",
+                        PencilTool::SMOOTHING,
+                        1.0,
+                        self.smoothing.get(),
+                    ),
+                    (
+                        "smoothing: medium",
+                        PencilTool::SMOOTHING,
+                        PencilTool::SMOOTHING_DEFAULT,
+                        self.smoothing.get(),
+                    ),
+                    (
+                        "smoothing: high",
+                        PencilTool::SMOOTHING,
+                        8.0,
+                        self.smoothing.get(),
+                    ),
+                    (
+                        "corners: normal",
+                        PencilTool::CORNER_ANGLE,
+                        PencilTool::CORNER_ANGLE_DEFAULT,
+                        self.corner_angle.get(),
+                    ),
+                    (
+                        "corners: sharp only",
+                        PencilTool::CORNER_ANGLE,
+                        100.0,
+                        self.corner_angle.get(),
+                    ),
+                    (
+                        "corners: none",
+                        PencilTool::CORNER_ANGLE,
+                        180.0,
+                        self.corner_angle.get(),
+                    ),
+                ] {
+                    menu = if value == current {
+                        menu.add_button(label)
+                    } else {
+                        menu.add_button_cb(
+                            label,
+                            clone!(@weak obj => move |_| {
+                                obj.set_property::<f64>(property, value);
+                            }),
+                        )
+                    };
+                }
+                menu.add_button_cb(
+                    "done",
+                    clone!(@weak obj, @weak view => move |_| {
+                        obj.on_deactivate(&view);
+                        view.state().borrow_mut().active_tool = glib::types::Type::INVALID;
+                    }),
+                )
+                .popup(event.time());
+            }
+            _ => return Inhibit(false),
+        }
+        Inhibit(true)
+    }
+
+    fn on_button_release_event(
+        &self,
+        _obj: &ToolImpl,
+        view: Editor,
+        viewport: &Canvas,
+        event: &gtk::gdk::EventButton,
+    ) -> Inhibit {
+        if !self.active.get() || event.button() != gtk::gdk::BUTTON_PRIMARY {
+            return Inhibit(false);
+        }
+        let mut stroke = std::mem::take(&mut *self.stroke.borrow_mut());
+        if stroke.is_empty() {
+            return Inhibit(false);
+        }
+        let UnitPoint(position) = viewport.view_to_unit_point(ViewPoint(event.position().into()));
+        stroke.push(position);
+        let scale: f64 = viewport
+            .transformation
+            .property::<f64>(Transformation::SCALE);
+        let ppu: f64 = viewport
+            .transformation
+            .property::<f64>(Transformation::PIXELS_PER_UNIT);
+        let pixel = 1.0 / (scale * ppu);
+        /* Strokes that end near where they started are closed. */
+        let closed = stroke.len() > 2
+            && stroke[0].distance(position) < PencilTool::CLOSE_DISTANCE * pixel
+            && stroke
+                .iter()
+                .any(|p| p.distance(position) > 2.0 * PencilTool::CLOSE_DISTANCE * pixel);
+        if let Some(contour) = Contour::from_stroke(
+            &stroke,
+            self.smoothing.get() * pixel,
+            self.corner_angle.get().to_radians(),
+            closed,
+        ) {
+            let state = view.state().borrow();
+            let contour_index = state.glyph.borrow().contours.len();
+            let subaction = state.add_contour(&contour, contour_index);
+            let mut action = new_contour_action(state.glyph.clone(), contour, subaction);
+            (action.redo)();
+            state.add_undo_action(action);
+        }
+        viewport.queue_draw();
+        Inhibit(true)
+    }
+
+    fn on_motion_notify_event(
+        &self,
+        _obj: &ToolImpl,
+        _view: Editor,
+        viewport: &Canvas,
+        event: &gtk::gdk::EventMotion,
+    ) -> Inhibit {
+        if !self.active.get() || self.stroke.borrow().is_empty() {
+            return Inhibit(false);
+        }
+        let UnitPoint(position) = viewport.view_to_unit_point(ViewPoint(event.position().into()));
+        self.stroke.borrow_mut().push(position);
+        viewport.queue_draw();
+        Inhibit(true)
+    }
+
+    fn setup_toolbox(&self, obj: &ToolImpl, toolbar: &gtk::Toolbar, view: &Editor) {
+        let layer =
+            LayerBuilder::new()
+                .set_name(Some("pencil"))
+                .set_active(false)
+                .set_hidden(true)
+                .set_callback(Some(Box::new(clone!(@weak view => @default-return Inhibit(false), move |viewport: &Canvas, cr: ContextRef| {
+                    PencilTool::draw_layer(viewport, cr, view)
+                }))))
+                .build();
+        self.instance()
+            .bind_property(PencilTool::ACTIVE, &layer, Layer::ACTIVE)
+            .flags(glib::BindingFlags::SYNC_CREATE)
+            .build();
+        self.layer.set(layer.clone()).unwrap();
+        view.viewport.add_post_layer(layer);
+
+        self.parent_setup_toolbox(obj, toolbar, view)
+    }
+
+    fn on_activate(&self, obj: &ToolImpl, view: &Editor) {
+        self.instance()
+            .set_property::<bool>(PencilTool::ACTIVE, true);
+        view.viewport.set_cursor("crosshair");
+        self.parent_on_activate(obj, view)
+    }
+
+    fn on_deactivate(&self, obj: &ToolImpl, view: &Editor) {
+        self.stroke.borrow_mut().clear();
+        self.instance()
+            .set_property::<bool>(PencilTool::ACTIVE, false);
+        view.viewport.set_cursor("default");
+        self.parent_on_deactivate(obj, view)
+    }
+}
+
+glib::wrapper! {
+    pub struct PencilTool(ObjectSubclass<PencilToolInner>)
+        @extends ToolImpl;
+}
+
+impl Default for PencilTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PencilTool {
+    pub const ACTIVE: &'static str = "active";
+    pub const SMOOTHING: &'static str = "smoothing";
+    pub const CORNER_ANGLE: &'static str = "corner-angle";
+    pub const SMOOTHING_DEFAULT: f64 = 3.0;
+    pub const CORNER_ANGLE_DEFAULT: f64 = 60.0;
+    /// Distance in pixels within which a stroke's end closes it.
+    const CLOSE_DISTANCE: f64 = 10.0;
+
+    pub fn new() -> Self {
+        glib::Object::new(&[]).unwrap()
+    }
+
+    pub fn draw_layer(viewport: &Canvas, cr: ContextRef, obj: Editor) -> Inhibit {
+        let state = obj.state().borrow();
+        if Self::static_type() != state.active_tool {
+            return Inhibit(false);
+        }
+        let t = state.tools[&state.active_tool]
+            .clone()
+            .downcast::<Self>()
+            .unwrap();
+        let stroke = t.imp().stroke.borrow();
+        let Some(first) = stroke.first() else {
+            return Inhibit(false);
+        };
+        let scale: f64 = viewport
+            .transformation
+            .property::<f64>(Transformation::SCALE);
+        let ppu: f64 = viewport
+            .transformation
+            .property::<f64>(Transformation::PIXELS_PER_UNIT);
+        let line_width = obj
+            .settings
+            .get()
+            .unwrap()
+            .property::<f64>(Settings::LINE_WIDTH)
+            / (scale * ppu);
+        cr.transform(viewport.transformation.matrix());
+        cr.set_line_width(line_width);
+        cr.set_source_rgba(0.2, 0.2, 0.2, 0.6);
+        cr.move_to(first.x, first.y);
+        for p in stroke.iter().skip(1) {
+            cr.line_to(p.x, p.y);
+        }
+        cr.stroke().expect("Invalid cairo surface state");
+
+        Inhibit(true)
+    }
+}
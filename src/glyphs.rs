@@ -33,10 +33,21 @@ use gtk::cairo::Matrix;
 use gtk::{glib::prelude::*, subclass::prelude::*};
 use uuid::Uuid;
 
+mod anchors;
+pub use anchors::*;
+mod composites;
+pub use composites::*;
+mod metric_keys;
+pub use metric_keys::*;
+mod spacing;
+pub use spacing::*;
+mod text;
+pub use text::*;
+
 mod guidelines;
 pub use guidelines::*;
 
-pub use crate::ufo::glif::{self, Advance, Anchor, ImageRef, Unicode};
+pub use crate::ufo::glif::{self, Advance, ImageRef, Unicode};
 
 mod contours;
 pub use contours::*;
@@ -56,6 +67,80 @@ pub struct Component {
     pub y_scale: f64,
 }
 
+impl Component {
+    /// The component's affine transformation, from base glyph to composite glyph units.
+    pub fn matrix(&self) -> Matrix {
+        Matrix::new(
+            self.x_scale,
+            self.xy_scale,
+            self.yx_scale,
+            self.y_scale,
+            self.x_offset,
+            self.y_offset,
+        )
+    }
+
+    pub fn set_matrix(&mut self, m: Matrix) {
+        self.x_scale = m.xx();
+        self.xy_scale = m.yx();
+        self.yx_scale = m.xy();
+        self.y_scale = m.yy();
+        self.x_offset = m.x0();
+        self.y_offset = m.y0();
+    }
+
+    /// The base glyph's outline in composite glyph units, with nested components decomposed
+    /// as well. Bases that are missing or that refer back to a glyph already being decomposed
+    /// are skipped.
+    pub fn decomposed(&self) -> Vec<Contour> {
+        let mut ret = vec![];
+        /* Base glyphs that can't be loaded are drawn as empty. */
+        _ = self.decompose_into(Matrix::identity(), &mut vec![], &mut ret);
+        ret
+    }
+
+    /// Like [`Component::decomposed`], but fails if a base glyph can't be loaded instead of
+    /// leaving its outline out.
+    pub fn try_decomposed(&self) -> Result<Vec<Contour>, Box<dyn std::error::Error>> {
+        let mut ret = vec![];
+        self.decompose_into(Matrix::identity(), &mut vec![], &mut ret)?;
+        Ok(ret)
+    }
+
+    fn decompose_into(
+        &self,
+        outer: Matrix,
+        stack: &mut Vec<String>,
+        ret: &mut Vec<Contour>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let Some(base) = self.base.upgrade() else {
+            return Ok(());
+        };
+        if stack.contains(&self.base_name) {
+            return Ok(());
+        }
+        Glyph::load(&base)?;
+        let m = Matrix::multiply(&self.matrix(), &outer);
+        let base = base.borrow();
+        ret.extend(base.contours.iter().map(|c| c.transformed(m)));
+        stack.push(self.base_name.clone());
+        for c in base.components.iter() {
+            c.decompose_into(m, stack, ret)?;
+        }
+        stack.pop();
+        Ok(())
+    }
+
+    /// Whether `position` lies inside the filled area of the component.
+    pub fn contains(&self, position: Point) -> bool {
+        self.decomposed()
+            .iter()
+            .map(|c| c.winding_number(position))
+            .sum::<i64>()
+            != 0
+    }
+}
+
 #[derive(Debug, Ord, PartialOrd, Clone, PartialEq, Eq)]
 pub enum GlyphKind {
     Char(char),
@@ -237,57 +322,122 @@ impl Default for GlyphDrawingOptions<'_> {
 }
 
 impl Glyph {
+    /// Creates a glyph for every entry of a layer's `contents.plist`. Only names and filenames
+    /// are known at this point, outlines are parsed on first access with [`Glyph::load`].
     #[allow(clippy::type_complexity)]
     pub fn from_ufo(
         root_path: PathBuf,
         contents: &ufo::Contents,
     ) -> Result<IndexMap<String, Rc<RefCell<Self>>>, Box<dyn std::error::Error>> {
         let mut ret: IndexMap<String, Rc<RefCell<Self>>> = IndexMap::default();
-        let mut glyphs_with_refs: Vec<Rc<_>> = vec![];
-        let mut path = root_path;
 
         for (name, filename) in contents.glyphs().iter() {
-            path.push(filename);
-            use std::fs::File;
-            use std::io::prelude::*;
-            let mut file = match File::open(&path) {
-                Err(err) => return Err(format!("Couldn't open {}: {}", path.display(), err).into()),
-                Ok(file) => file,
-            };
+            let path = root_path.join(filename);
+            if !path.is_file() {
+                return Err(
+                    format!("Couldn't open {}: file does not exist.", path.display()).into(),
+                );
+            }
+            let glyph = Self::new_unloaded(name.clone(), filename.clone(), path);
+            ret.insert(name.into(), Rc::new(RefCell::new(glyph)));
+        }
+        Ok(ret)
+    }
 
-            let mut s = String::new();
-            if let Err(err) = file.read_to_string(&mut s) {
-                return Err(format!("Couldn't read {}: {}", path.display(), err).into());
+    /// A placeholder glyph whose outline will be parsed from `glif_path` by [`Glyph::load`].
+    pub fn new_unloaded(name: String, filename: String, glif_path: PathBuf) -> Self {
+        let ret = Self::from(GlyphKind::Component(name));
+        *ret.metadata.filename.borrow_mut() = filename;
+        *ret.metadata.glif_path.borrow_mut() = Some(glif_path);
+        ret
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        self.metadata.glif_path.borrow().is_none()
+    }
+
+    /// Why [`Glyph::load`] failed, if it did.
+    pub fn load_error(&self) -> Option<String> {
+        self.metadata.load_error.borrow().clone()
+    }
+
+    /// Parses the `.glif` file of a glyph created with [`Glyph::new_unloaded`]. Does nothing if
+    /// the glyph has already been loaded. Components are linked to their base glyphs through the
+    /// glyph's [`GlyphMetadata::LAYER`]. If the file can't be parsed the glyph stays unloaded and
+    /// every call returns the same error.
+    pub fn load(glyph: &Rc<RefCell<Self>>) -> Result<(), Box<dyn std::error::Error>> {
+        let metadata = glyph.borrow().metadata.clone();
+        if let Some(err) = metadata.load_error.borrow().as_ref() {
+            return Err(err.clone().into());
+        }
+        let Some(path) = metadata.glif_path.borrow_mut().take() else {
+            return Ok(());
+        };
+        match glif::Glif::from_path(&path) {
+            Ok((glif, source)) => {
+                Self::set_parsed(glyph, glif, source);
+                metadata.notify(GlyphMetadata::LOADED);
+                Ok(())
             }
-            let g: Result<glif::Glif, _> = glif::Glif::from_str(&s);
-            match g {
-                Err(err) => {
-                    eprintln!("couldn't parse {}: {}", path.display(), err);
-                }
-                Ok(g) => {
-                    let glyph: Self = g.into();
-                    *glyph.metadata.filename.borrow_mut() = filename.clone();
-                    *glyph.metadata.glif_source.borrow_mut() = s;
-                    let has_components = !glyph.components.is_empty();
-                    let glyph = Rc::new(RefCell::new(glyph));
-                    if has_components {
-                        glyphs_with_refs.push(glyph.clone());
-                    }
-                    ret.insert(name.into(), glyph);
-                }
+            Err(err) => {
+                /* An empty outline must not be saved over the file, so the glyph stays
+                 * unloaded and isn't parsed again. */
+                *metadata.glif_path.borrow_mut() = Some(path);
+                *metadata.load_error.borrow_mut() = Some(err.to_string());
+                Err(err)
             }
-            path.pop();
         }
+    }
+
+    /// Like [`Glyph::load`], with a `.glif` file that has already been parsed, e.g. by
+    /// [`glif::parse_in_thread_pool`].
+    pub fn load_parsed(glyph: &Rc<RefCell<Self>>, glif: glif::Glif, source: String) {
+        if glyph
+            .borrow()
+            .metadata
+            .glif_path
+            .borrow_mut()
+            .take()
+            .is_none()
+        {
+            return;
+        }
+        let metadata = glyph.borrow().metadata.clone();
+        Self::set_parsed(glyph, glif, source);
+        metadata.notify(GlyphMetadata::LOADED);
+    }
 
-        for g in glyphs_with_refs {
-            let mut deref = g.borrow_mut();
-            for c in deref.components.iter_mut() {
-                if let Some(o) = ret.get(&c.base_name) {
-                    c.base = Rc::downgrade(o);
+    fn set_parsed(glyph: &Rc<RefCell<Self>>, glif: glif::Glif, source: String) {
+        let Self {
+            contours,
+            mut components,
+            guidelines,
+            lib,
+            metadata,
+        } = glif.into();
+        if !components.is_empty() {
+            if let Some(layer) = glyph.borrow().metadata.layer().as_ref() {
+                let glyphs = layer.glyphs();
+                for c in components.iter_mut() {
+                    if let Some(o) = glyphs.get(&c.base_name) {
+                        c.base = Rc::downgrade(o);
+                    }
                 }
             }
         }
-        Ok(ret)
+        Contour::link_siblings(&contours);
+        let mut g = glyph.borrow_mut();
+        g.contours = contours;
+        g.components = components;
+        g.guidelines = guidelines;
+        g.lib = lib;
+        *g.metadata.kinds.borrow_mut() = metadata.kinds.take();
+        *g.metadata.unicode.borrow_mut() = metadata.unicode.take();
+        *g.metadata.anchors.borrow_mut() = metadata.anchors.take();
+        *g.metadata.image.borrow_mut() = metadata.image.take();
+        g.metadata.advance.set(metadata.advance.get());
+        g.metadata.width.set(metadata.width.get());
+        *g.metadata.glif_source.borrow_mut() = source;
     }
 
     pub fn new(name: String, char: char, curves: Vec<Bezier>) -> Self {
@@ -339,7 +489,19 @@ impl Glyph {
         Self::new(name, char, vec![])
     }
 
-    pub fn draw(&self, mut cr: ContextRef, options: GlyphDrawingOptions<'_>) {
+    pub fn draw(&self, cr: ContextRef, options: GlyphDrawingOptions<'_>) {
+        self.draw_nested(cr, options, &mut vec![]);
+    }
+
+    /// Draws the glyph and, recursively, its components. `stack` holds the names of the glyphs
+    /// being drawn so that a component that refers back to one of them is drawn as a placeholder
+    /// like a missing base instead of recursing forever.
+    fn draw_nested(
+        &self,
+        mut cr: ContextRef,
+        options: GlyphDrawingOptions<'_>,
+        stack: &mut Vec<String>,
+    ) {
         if self.is_empty() {
             return;
         }
@@ -658,33 +820,65 @@ impl Glyph {
             }
         }
         drop(cr1);
+        stack.push(self.name().to_string());
         for component in self.components.iter() {
-            if let Some(rc) = component.base.upgrade() {
-                let glyph = rc.borrow();
-                let crc = cr.push();
-                crc.transform(matrix);
-                let matrix = Matrix::new(
-                    component.x_scale,
-                    component.xy_scale,
-                    component.yx_scale,
-                    component.y_scale,
-                    component.x_offset,
-                    component.y_offset,
-                );
-                glyph.draw(
-                    crc,
-                    GlyphDrawingOptions {
-                        matrix,
-                        handle: None,
-                        corner: None,
-                        smooth_corner: None,
-                        direction_arrow: None,
-                        selection: None,
-                        ..options
-                    },
-                );
+            let crc = cr.push();
+            crc.transform(matrix);
+            let base = component
+                .base
+                .upgrade()
+                .filter(|_| !stack.contains(&component.base_name));
+            let Some(rc) = base else {
+                Self::draw_missing_component(crc, component, options);
+                continue;
+            };
+            if let Err(err) = Self::load(&rc) {
+                eprintln!("{err}");
             }
+            let glyph = rc.borrow();
+            glyph.draw_nested(
+                crc,
+                GlyphDrawingOptions {
+                    matrix: component.matrix(),
+                    handle: None,
+                    corner: None,
+                    smooth_corner: None,
+                    direction_arrow: None,
+                    selection: None,
+                    ..options
+                },
+                stack,
+            );
         }
+        stack.pop();
+    }
+
+    /// Placeholder for a component whose base glyph is missing or part of a cycle: a dashed,
+    /// crossed out box where the base glyph would be, labelled with its name.
+    fn draw_missing_component(
+        cr: ContextRef,
+        component: &Component,
+        options: GlyphDrawingOptions<'_>,
+    ) {
+        let (width, height) = (0.5 * options.units_per_em, 0.7 * options.units_per_em);
+        cr.transform(component.matrix());
+        cr.set_line_width(options.outline.size);
+        cr.set_source_color_alpha(Color::from_hex("#e53935")); // [ref:hardcoded_color_value]
+        cr.set_dash(
+            &[4.0 * options.outline.size, 4.0 * options.outline.size],
+            0.0,
+        );
+        cr.rectangle(0.0, 0.0, width, height);
+        cr.move_to(0.0, 0.0);
+        cr.line_to(width, height);
+        cr.move_to(width, 0.0);
+        cr.line_to(0.0, height);
+        cr.stroke().unwrap();
+        /* Undo the vertical flip of the unit coordinates so that the label isn't mirrored. */
+        cr.move_to(0.0, -options.units_per_em / 20.0);
+        cr.scale(1.0, -1.0);
+        cr.set_font_size(options.units_per_em / 10.0);
+        cr.show_text(&component.base_name).unwrap();
     }
 
     /*
@@ -802,12 +996,425 @@ impl Glyph {
         None
     }
 
+    /// Returns an action that replaces all the glyph's contours with `contours`. Both directions
+    /// mark the glyph as modified, which also notifies any open editor to reload its outline.
+    pub fn set_contours_action(glyph: &Rc<RefCell<Self>>, contours: Vec<Contour>) -> Action {
+        let old_contours = glyph.borrow().contours.clone();
+        let name = glyph.borrow().name().clone();
+        Action {
+            stamp: EventStamp {
+                t: std::any::TypeId::of::<Self>(),
+                property: "contours",
+                id: name.into_bytes().into_boxed_slice(),
+            },
+            compress: false,
+            redo: Box::new(clone!(@weak glyph => move || {
+                glyph.borrow_mut().contours = contours.clone();
+                glyph.borrow().metadata.set_property(GlyphMetadata::MODIFIED, true);
+            })),
+            undo: Box::new(clone!(@weak glyph => move || {
+                glyph.borrow_mut().contours = old_contours.clone();
+                glyph.borrow().metadata.set_property(GlyphMetadata::MODIFIED, true);
+            })),
+        }
+    }
+
+    /// Cycles of component references among `glyphs`, each as the list of glyph names along the
+    /// cycle with the first name repeated at the end, e.g. `["a", "b", "a"]`. Nested components
+    /// are followed to any depth by base glyph name; glyphs that haven't been loaded yet are
+    /// treated as having no components.
+    pub fn component_cycles(glyphs: &IndexMap<String, Rc<RefCell<Self>>>) -> Vec<Vec<String>> {
+        #[derive(Clone, Copy, PartialEq, Eq)]
+        enum Mark {
+            Visiting,
+            Done,
+        }
+
+        fn visit(
+            name: &str,
+            glyphs: &IndexMap<String, Rc<RefCell<Glyph>>>,
+            marks: &mut std::collections::HashMap<String, Mark>,
+            path: &mut Vec<String>,
+            ret: &mut Vec<Vec<String>>,
+        ) {
+            match marks.get(name) {
+                Some(Mark::Done) => return,
+                Some(Mark::Visiting) => {
+                    let start = path.iter().position(|n| n == name).unwrap_or(0);
+                    let mut cycle = path[start..].to_vec();
+                    cycle.push(name.to_string());
+                    ret.push(cycle);
+                    return;
+                }
+                None => {}
+            }
+            let Some(glyph) = glyphs.get(name) else {
+                return;
+            };
+            let bases = glyph
+                .borrow()
+                .components
+                .iter()
+                .map(|c| c.base_name.clone())
+                .collect::<Vec<String>>();
+            marks.insert(name.to_string(), Mark::Visiting);
+            path.push(name.to_string());
+            for base in bases {
+                visit(&base, glyphs, marks, path, ret);
+            }
+            path.pop();
+            marks.insert(name.to_string(), Mark::Done);
+        }
+
+        let mut ret = vec![];
+        let mut marks = std::collections::HashMap::default();
+        for name in glyphs.keys() {
+            visit(name, glyphs, &mut marks, &mut vec![], &mut ret);
+        }
+        ret
+    }
+
+    /// Index of the topmost component whose filled area contains `position`.
+    pub fn component_at(&self, position: Point) -> Option<usize> {
+        self.components.iter().rposition(|c| c.contains(position))
+    }
+
+    /// Returns an action that replaces the components in `indices`, or all of them if it is
+    /// `None`, with their outlines. Returns `None` if there is nothing to decompose or a base
+    /// glyph can't be loaded. Both directions mark the glyph as modified, like
+    /// [`Glyph::set_contours_action`].
+    pub fn decompose_action(
+        glyph: &Rc<RefCell<Self>>,
+        indices: Option<&[usize]>,
+    ) -> Option<Action> {
+        let (old_contours, old_components) = {
+            let glyph = glyph.borrow();
+            (glyph.contours.clone(), glyph.components.clone())
+        };
+        let mut contours = old_contours.clone();
+        let mut components = vec![];
+        for (i, c) in old_components.iter().enumerate() {
+            if indices.map_or(true, |idxs| idxs.contains(&i)) {
+                contours.extend(c.try_decomposed().ok()?);
+            } else {
+                components.push(c.clone());
+            }
+        }
+        if components.len() == old_components.len() {
+            return None;
+        }
+        let name = glyph.borrow().name().clone();
+        let set = |contours: Vec<Contour>, components: Vec<Component>| {
+            clone!(@weak glyph => move || {
+                {
+                    let mut glyph = glyph.borrow_mut();
+                    glyph.contours = contours.clone();
+                    glyph.components = components.clone();
+                }
+                glyph.borrow().metadata.set_property(GlyphMetadata::MODIFIED, true);
+            })
+        };
+        Some(Action {
+            stamp: EventStamp {
+                t: std::any::TypeId::of::<Self>(),
+                property: "components",
+                id: name.into_bytes().into_boxed_slice(),
+            },
+            compress: false,
+            redo: Box::new(set(contours, components)),
+            undo: Box::new(set(old_contours, old_components)),
+        })
+    }
+
+    /// Returns an action that inserts on-curve points at the missing extrema of the contours
+    /// in `contour_indices`, or of every contour if it is `None`. Returns `None` if nothing
+    /// would change. The glyph is loaded first if necessary.
+    pub fn add_extrema_action(
+        glyph: &Rc<RefCell<Self>>,
+        contour_indices: Option<&[usize]>,
+    ) -> Option<Action> {
+        if !glyph.borrow().is_loaded() {
+            Self::load(glyph).ok()?;
+        }
+        let mut changed = false;
+        let contours = glyph
+            .borrow()
+            .contours
+            .iter()
+            .enumerate()
+            .map(|(i, contour)| {
+                if contour_indices.map_or(true, |idxs| idxs.contains(&i)) {
+                    if let Some(new) = contour.with_extrema() {
+                        changed = true;
+                        return new;
+                    }
+                }
+                contour.clone()
+            })
+            .collect::<Vec<Contour>>();
+        changed.then(|| Self::set_contours_action(glyph, contours))
+    }
+
+    /// Returns an action that cuts every contour crossed by the line segment from `a` to `b`,
+    /// see [`Contour::knife`]. Returns `None` if the line crosses no contour.
+    pub fn knife_action(
+        glyph: &Rc<RefCell<Self>>,
+        a: Point,
+        b: Point,
+        close: bool,
+    ) -> Option<Action> {
+        let mut changed = false;
+        let contours = glyph
+            .borrow()
+            .contours
+            .iter()
+            .flat_map(|contour| {
+                if let Some(pieces) = contour.knife(a, b, close) {
+                    changed = true;
+                    pieces
+                } else {
+                    vec![contour.clone()]
+                }
+            })
+            .collect::<Vec<Contour>>();
+        changed.then(|| Self::set_contours_action(glyph, contours))
+    }
+
+    /// Copies of the contours with the contours in `contour_indices`, or every contour if it
+    /// is `None`, simplified with [`Contour::simplified`]. Returns `None` if nothing would
+    /// change.
+    pub fn simplified_contours(
+        &self,
+        contour_indices: Option<&[usize]>,
+        tolerance: f64,
+    ) -> Option<Vec<Contour>> {
+        let mut changed = false;
+        let contours = self
+            .contours
+            .iter()
+            .enumerate()
+            .map(|(i, contour)| {
+                if contour_indices.map_or(true, |idxs| idxs.contains(&i)) {
+                    if let Some(new) = contour.simplified(tolerance) {
+                        changed = true;
+                        return new;
+                    }
+                }
+                contour.clone()
+            })
+            .collect::<Vec<Contour>>();
+        changed.then_some(contours)
+    }
+
+    /// Returns an action that simplifies contours, see [`Glyph::simplified_contours`].
+    pub fn simplify_action(
+        glyph: &Rc<RefCell<Self>>,
+        contour_indices: Option<&[usize]>,
+        tolerance: f64,
+    ) -> Option<Action> {
+        if !glyph.borrow().is_loaded() {
+            Self::load(glyph).ok()?;
+        }
+        let contours = glyph
+            .borrow()
+            .simplified_contours(contour_indices, tolerance)?;
+        Some(Self::set_contours_action(glyph, contours))
+    }
+
+    /// Returns an action that reverses the closed contours that don't follow `convention`, see
+    /// [`Contour::with_direction`]. Returns `None` if nothing would change. The glyph is loaded
+    /// first if necessary.
+    pub fn correct_direction_action(
+        glyph: &Rc<RefCell<Self>>,
+        convention: DirectionConvention,
+    ) -> Option<Action> {
+        if !glyph.borrow().is_loaded() {
+            Self::load(glyph).ok()?;
+        }
+        let contours = Contour::with_direction(&glyph.borrow().contours, convention)?;
+        Some(Self::set_contours_action(glyph, contours))
+    }
+
+    /// Tight bounding box of the outline, including the decomposed components, as `(min, max)`
+    /// corners. Returns `None` if the glyph has no curves.
+    pub fn bounds(&self) -> Option<(Point, Point)> {
+        let decomposed = self
+            .components
+            .iter()
+            .flat_map(Component::decomposed)
+            .collect::<Vec<Contour>>();
+        self.contours
+            .iter()
+            .chain(decomposed.iter())
+            .flat_map(|contour| {
+                contour
+                    .curves()
+                    .iter()
+                    .map(Bezier::bounding_box)
+                    .collect::<Vec<_>>()
+            })
+            .reduce(|(min, max), (cmin, cmax)| {
+                (
+                    (min.x.min(cmin.x), min.y.min(cmin.y)).into(),
+                    (max.x.max(cmax.x), max.y.max(cmax.y)).into(),
+                )
+            })
+    }
+
+    /// Left and right sidebearings computed from [`Glyph::bounds`] and the advance width.
+    pub fn sidebearings(&self) -> Option<(f64, f64)> {
+        let (min, max) = self.bounds()?;
+        Some((min.x, self.width().unwrap_or(0.0) - max.x))
+    }
+
+    /// Returns an action that moves the contours, components and anchors by `dx` horizontally
+    /// and sets the advance width to `width`. Consecutive actions with `compress` set are undone
+    /// together, which is what dragging a sidebearing produces. Contour points are moved in
+    /// place, so they keep their identity and the editor's selection stays valid.
+    fn metrics_action(glyph: &Rc<RefCell<Self>>, dx: f64, width: f64, compress: bool) -> Action {
+        #[derive(Clone)]
+        struct Snapshot {
+            components: Vec<Component>,
+            anchors: Vec<(Anchor, f64)>,
+            width: f64,
+        }
+
+        let old = {
+            let glyph = glyph.borrow();
+            Snapshot {
+                components: glyph.components.clone(),
+                anchors: glyph
+                    .metadata
+                    .anchors
+                    .borrow()
+                    .iter()
+                    .map(|a| (a.clone(), a.property::<f64>(Anchor::X)))
+                    .collect(),
+                width: glyph.width().unwrap_or(0.0),
+            }
+        };
+        let new = Snapshot {
+            components: old
+                .components
+                .iter()
+                .cloned()
+                .map(|mut c| {
+                    c.x_offset += dx;
+                    c
+                })
+                .collect(),
+            anchors: old
+                .anchors
+                .iter()
+                .map(|(a, x)| (a.clone(), x + dx))
+                .collect(),
+            width,
+        };
+        /* Every point of every contour, to translate with [`Contour::transform_points`]. */
+        let points: Rc<Vec<(Contour, Vec<GlyphPointIndex>)>> = Rc::new(if dx == 0.0 {
+            vec![]
+        } else {
+            glyph
+                .borrow()
+                .contours
+                .iter()
+                .enumerate()
+                .map(|(contour_index, contour)| {
+                    let idxs = contour
+                        .curves()
+                        .iter()
+                        .enumerate()
+                        .flat_map(|(curve_index, curve)| {
+                            curve
+                                .points()
+                                .iter()
+                                .map(|p| p.glyph_index(contour_index, curve_index))
+                                .collect::<Vec<_>>()
+                        })
+                        .collect();
+                    (contour.clone(), idxs)
+                })
+                .collect()
+        });
+        let set = |snapshot: Snapshot, dx: f64| {
+            clone!(@weak glyph, @strong points => move || {
+                let metadata = {
+                    let mut glyph = glyph.borrow_mut();
+                    let mut m = Matrix::identity();
+                    m.translate(dx, 0.0);
+                    for (contour_index, (contour, idxs)) in points.iter().enumerate() {
+                        contour.transform_points(contour_index, idxs, m);
+                    }
+                    glyph.components = snapshot.components.clone();
+                    for (anchor, x) in snapshot.anchors.iter() {
+                        anchor.set_property(Anchor::X, *x);
+                    }
+                    glyph.metadata.width.set(Some(snapshot.width));
+                    let height = glyph.metadata.advance.get().map_or(0.0, |a| a.height);
+                    glyph.metadata.advance.set(Some(Advance {
+                        width: snapshot.width,
+                        height,
+                    }));
+                    glyph.metadata.clone()
+                };
+                metadata.notify(GlyphMetadata::WIDTH);
+                metadata.set_property(GlyphMetadata::MODIFIED, true);
+            })
+        };
+        let name = glyph.borrow().name().clone();
+        Action {
+            stamp: EventStamp {
+                t: std::any::TypeId::of::<Self>(),
+                property: "metrics",
+                id: name.into_bytes().into_boxed_slice(),
+            },
+            compress,
+            redo: Box::new(set(new, dx)),
+            undo: Box::new(set(old, -dx)),
+        }
+    }
+
+    /// Returns an action that sets the left sidebearing to `lsb` by moving the outline, keeping
+    /// the right sidebearing. Returns `None` if the glyph has no curves.
+    pub fn set_lsb_action(glyph: &Rc<RefCell<Self>>, lsb: f64, compress: bool) -> Option<Action> {
+        let (current, _) = glyph.borrow().sidebearings()?;
+        let dx = lsb - current;
+        let width = glyph.borrow().width().unwrap_or(0.0) + dx;
+        Some(Self::metrics_action(glyph, dx, width.max(0.0), compress))
+    }
+
+    /// Returns an action that sets the right sidebearing to `rsb` by changing the advance
+    /// width. Returns `None` if the glyph has no curves.
+    pub fn set_rsb_action(glyph: &Rc<RefCell<Self>>, rsb: f64, compress: bool) -> Option<Action> {
+        let (_, max) = glyph.borrow().bounds()?;
+        Some(Self::metrics_action(
+            glyph,
+            0.0,
+            (max.x + rsb).max(0.0),
+            compress,
+        ))
+    }
+
+    /// Returns an action that sets the advance width to `width`.
+    pub fn set_width_action(glyph: &Rc<RefCell<Self>>, width: f64, compress: bool) -> Action {
+        Self::metrics_action(glyph, 0.0, width.max(0.0), compress)
+    }
+
     pub fn save(&self, prefix: &Path) -> Result<(), Box<dyn std::error::Error>> {
         use std::fs::OpenOptions;
         use std::io::Write;
 
-        let glif: glif::Glif = self.into();
         let path = prefix.join(&*self.filename());
+        if let Some(source) = self.metadata.glif_path.borrow().as_ref() {
+            /* Nothing could have modified it, the file on disk is up to date. It only has to be
+             * copied when saving somewhere else. */
+            if path.canonicalize().ok() != source.canonicalize().ok() {
+                std::fs::copy(source, &path)?;
+            }
+            return Ok(());
+        }
+
+        let glif: glif::Glif = self.into();
         let mut file = OpenOptions::new()
             .read(false)
             .write(true)
@@ -868,7 +1475,7 @@ impl From<glif::Glif> for Glyph {
         *ret.metadata.name.borrow_mut() = name;
         *ret.metadata.kinds.borrow_mut() = kinds;
         *ret.metadata.unicode.borrow_mut() = unicode;
-        *ret.metadata.anchors.borrow_mut() = anchors;
+        *ret.metadata.anchors.borrow_mut() = anchors.into_iter().map(Anchor::from).collect();
         *ret.metadata.image.borrow_mut() = image;
         ret.metadata.advance.set(advance);
         ret.metadata.width.set(advance.map(|a| a.width));
@@ -0,0 +1,188 @@
+/*
+ * gerb
+ *
+ * Copyright 2022 - Manos Pitsidianakis
+ *
+ * This file is part of gerb.
+ *
+ * gerb is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * gerb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with gerb. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use super::*;
+use glib::{ParamFlags, ParamSpec, ParamSpecDouble, ParamSpecString, Value};
+
+#[derive(Debug, Default)]
+pub struct AnchorInner {
+    name: RefCell<String>,
+    x: Cell<f64>,
+    y: Cell<f64>,
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for AnchorInner {
+    const NAME: &'static str = "Anchor";
+    type Type = Anchor;
+    type ParentType = glib::Object;
+    type Interfaces = ();
+}
+
+impl ObjectImpl for AnchorInner {
+    fn properties() -> &'static [ParamSpec] {
+        static PROPERTIES: once_cell::sync::Lazy<Vec<ParamSpec>> =
+            once_cell::sync::Lazy::new(|| {
+                vec![
+                    ParamSpecString::new(
+                        Anchor::NAME,
+                        Anchor::NAME,
+                        "Anchor name, e.g. top or _top for marks.",
+                        Some(""),
+                        ParamFlags::READWRITE | UI_EDITABLE,
+                    ),
+                    ParamSpecDouble::new(
+                        Anchor::X,
+                        Anchor::X,
+                        Anchor::X,
+                        f64::MIN,
+                        f64::MAX,
+                        0.0,
+                        ParamFlags::READWRITE | UI_EDITABLE,
+                    ),
+                    ParamSpecDouble::new(
+                        Anchor::Y,
+                        Anchor::Y,
+                        Anchor::Y,
+                        f64::MIN,
+                        f64::MAX,
+                        0.0,
+                        ParamFlags::READWRITE | UI_EDITABLE,
+                    ),
+                ]
+            });
+        PROPERTIES.as_ref()
+    }
+
+    fn property(&self, _obj: &Self::Type, _id: usize, pspec: &ParamSpec) -> glib::Value {
+        match pspec.name() {
+            Anchor::NAME => self.name.borrow().to_value(),
+            Anchor::X => self.x.get().to_value(),
+            Anchor::Y => self.y.get().to_value(),
+            _ => unimplemented!("{}", pspec.name()),
+        }
+    }
+
+    fn set_property(&self, _obj: &Self::Type, _id: usize, value: &Value, pspec: &ParamSpec) {
+        match pspec.name() {
+            Anchor::NAME => {
+                *self.name.borrow_mut() =
+                    value.get::<Option<String>>().unwrap().unwrap_or_default();
+            }
+            Anchor::X => {
+                let val: f64 = value.get().unwrap();
+                if val.is_finite() {
+                    self.x.set(val);
+                }
+            }
+            Anchor::Y => {
+                let val: f64 = value.get().unwrap();
+                if val.is_finite() {
+                    self.y.set(val);
+                }
+            }
+            _ => unimplemented!("{}", pspec.name()),
+        }
+    }
+}
+
+impl AnchorInner {
+    #[inline(always)]
+    pub fn name(&self) -> String {
+        self.name.borrow().clone()
+    }
+
+    #[inline(always)]
+    pub fn x(&self) -> f64 {
+        self.x.get()
+    }
+
+    #[inline(always)]
+    pub fn y(&self) -> f64 {
+        self.y.get()
+    }
+
+    #[inline(always)]
+    pub fn position(&self) -> Point {
+        (self.x.get(), self.y.get()).into()
+    }
+}
+
+glib::wrapper! {
+    pub struct Anchor(ObjectSubclass<AnchorInner>);
+}
+
+impl std::ops::Deref for Anchor {
+    type Target = AnchorInner;
+    fn deref(&self) -> &Self::Target {
+        self.imp()
+    }
+}
+
+impl Default for Anchor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<glif::Anchor> for Anchor {
+    fn from(v: glif::Anchor) -> Self {
+        let glif::Anchor { name, x, y } = v;
+        Self::with_position(name, (x, y).into())
+    }
+}
+
+impl From<&Anchor> for glif::Anchor {
+    fn from(v: &Anchor) -> Self {
+        Self {
+            name: v.name(),
+            x: v.x(),
+            y: v.y(),
+        }
+    }
+}
+
+impl Anchor {
+    pub const NAME: &'static str = "name";
+    pub const X: &'static str = "x";
+    pub const Y: &'static str = "y";
+
+    pub fn new() -> Self {
+        let ret: Self = glib::Object::new::<Self>(&[]).unwrap();
+        ret
+    }
+
+    pub fn with_position(name: String, position: Point) -> Self {
+        let ret = Self::new();
+        *ret.name.borrow_mut() = name;
+        ret.x.set(position.x);
+        ret.y.set(position.y);
+        ret
+    }
+
+    /// Anchors whose name starts with an underscore belong to marks; they are attached to the
+    /// base glyph's anchor with the same name without the underscore.
+    pub fn is_mark(&self) -> bool {
+        self.name.borrow().starts_with('_')
+    }
+}
+
+impl_property_window!(Anchor);
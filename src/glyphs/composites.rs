@@ -0,0 +1,504 @@
+/*
+ * gerb
+ *
+ * Copyright 2022 - Manos Pitsidianakis
+ *
+ * This file is part of gerb.
+ *
+ * gerb is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * gerb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with gerb. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Building composite glyphs, e.g. accented letters, out of recipes like
+//! `Aacute = A + acutecomb@top`.
+
+use super::*;
+use crate::unicode::decompositions;
+
+/// One component of a [`Recipe`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecipePart {
+    pub glyph: String,
+    /// For marks, the name of the anchor the mark's `_` anchor is attached to, e.g. `top` for
+    /// `_top`. Parts without one are placed after the previous spacing part, like in a
+    /// ligature.
+    pub anchor: Option<String>,
+}
+
+/// A composite glyph recipe, written as `Aacute = A + acutecomb@top`, optionally followed by
+/// a codepoint: `Aacute = A + acutecomb@top | 00C1`.
+///
+/// The first part is the base glyph. Marks are attached to the anchor of the same name of the
+/// base or, if a previous mark has it, of the previous mark, so marks can be stacked. The
+/// advance width is the sum of the advance widths of the parts without an anchor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recipe {
+    pub name: String,
+    pub unicode: Option<char>,
+    pub parts: Vec<RecipePart>,
+}
+
+/// The result of [`Recipe::build`].
+#[derive(Debug, Clone)]
+pub struct Composite {
+    pub components: Vec<Component>,
+    pub width: f64,
+    /// Anchors of the parts that are left for further marks to attach to, in composite glyph
+    /// units.
+    pub anchors: Vec<(String, Point)>,
+}
+
+impl std::str::FromStr for Recipe {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, rest) = s
+            .split_once('=')
+            .ok_or_else(|| format!("Expected `name = base + mark@anchor`, got `{s}`."))?;
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(format!("Missing glyph name in `{s}`."));
+        }
+        let (rest, unicode) = match rest.split_once('|') {
+            Some((rest, hex)) => {
+                let hex = hex.trim();
+                let hex = hex
+                    .strip_prefix("U+")
+                    .or_else(|| hex.strip_prefix("u+"))
+                    .unwrap_or(hex);
+                let c = u32::from_str_radix(hex, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| format!("`{hex}` is not a valid codepoint."))?;
+                (rest, Some(c))
+            }
+            None => (rest, None),
+        };
+        let parts = rest
+            .split('+')
+            .map(|part| {
+                let part = part.trim();
+                let (glyph, anchor) = match part.split_once('@') {
+                    Some((glyph, anchor)) => (glyph.trim(), Some(anchor.trim().to_string())),
+                    None => (part, None),
+                };
+                if glyph.is_empty() || anchor.as_ref().map_or(false, |a| a.is_empty()) {
+                    return Err(format!("Invalid component `{part}` in `{s}`."));
+                }
+                Ok(RecipePart {
+                    glyph: glyph.to_string(),
+                    anchor,
+                })
+            })
+            .collect::<Result<Vec<RecipePart>, String>>()?;
+        if parts[0].anchor.is_some() {
+            return Err(format!(
+                "The base glyph `{}` of `{name}` can't be attached to an anchor.",
+                parts[0].glyph
+            ));
+        }
+        Ok(Self {
+            name: name.to_string(),
+            unicode,
+            parts,
+        })
+    }
+}
+
+impl std::fmt::Display for Recipe {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(fmt, "{} =", self.name)?;
+        for (i, part) in self.parts.iter().enumerate() {
+            if i > 0 {
+                write!(fmt, " +")?;
+            }
+            write!(fmt, " {}", part.glyph)?;
+            if let Some(ref anchor) = part.anchor {
+                write!(fmt, "@{anchor}")?;
+            }
+        }
+        if let Some(c) = self.unicode {
+            write!(fmt, " | {:04X}", c as u32)?;
+        }
+        Ok(())
+    }
+}
+
+impl Recipe {
+    /// Parses one recipe per line, skipping empty lines and lines starting with `#`.
+    pub fn parse_list(s: &str) -> Result<Vec<Self>, String> {
+        s.lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+            .map(str::parse)
+            .collect()
+    }
+
+    /// Derives the recipe of `c` from its canonical decomposition, with the glyphs in `glyphs`
+    /// whose codepoint is the base character and the combining marks. Marks without a glyph of
+    /// their codepoint are looked up by their conventional name, e.g. `acutecomb`. If there's
+    /// no glyph for `c` yet, its name is the base's name followed by the marks' names without
+    /// `comb`, e.g. `Aacute`.
+    pub fn from_char(c: char, glyphs: &IndexMap<String, Rc<RefCell<Glyph>>>) -> Option<Self> {
+        Self::from_char_with_names(c, glyphs, &char_names(glyphs))
+    }
+
+    fn from_char_with_names(
+        c: char,
+        glyphs: &IndexMap<String, Rc<RefCell<Glyph>>>,
+        names: &std::collections::HashMap<char, String>,
+    ) -> Option<Self> {
+        let (base, marks) = decompositions::decompose(c)?;
+        let base = names.get(&base)?.clone();
+        let mut parts = vec![RecipePart {
+            glyph: base.clone(),
+            anchor: None,
+        }];
+        let mut name = base;
+        for mark in marks {
+            let (mark_name, anchor) = decompositions::mark_info(mark)?;
+            let glyph = names.get(&mark).cloned().or_else(|| {
+                glyphs
+                    .contains_key(mark_name)
+                    .then(|| mark_name.to_string())
+            })?;
+            name.push_str(mark_name.trim_end_matches("comb"));
+            parts.push(RecipePart {
+                glyph,
+                anchor: Some(anchor.to_string()),
+            });
+        }
+        Some(Self {
+            name: names.get(&c).cloned().unwrap_or(name),
+            unicode: Some(c),
+            parts,
+        })
+    }
+
+    /// Recipes for every precomposed character of the Unicode decomposition tables whose
+    /// parts exist in `glyphs` and whose glyph doesn't exist yet or has no outline.
+    pub fn from_unicode(glyphs: &IndexMap<String, Rc<RefCell<Glyph>>>) -> Vec<Self> {
+        let names = char_names(glyphs);
+        decompositions::CANONICAL_DECOMPOSITIONS
+            .iter()
+            .filter_map(|&(c, _, _)| Self::from_char_with_names(c, glyphs, &names))
+            .filter(|r| {
+                glyphs
+                    .get(&r.name)
+                    .map_or(true, |g| g.borrow().contours.is_empty())
+            })
+            .collect()
+    }
+
+    /// Places the parts of the recipe, aligning each mark's `_anchor` with the `anchor` of the
+    /// base or of a previous mark.
+    pub fn build(
+        &self,
+        glyphs: &IndexMap<String, Rc<RefCell<Glyph>>>,
+    ) -> Result<Composite, String> {
+        let mut components = vec![];
+        let mut anchors: Vec<(String, Point)> = vec![];
+        let mut width = 0.0;
+        for part in self.parts.iter() {
+            let glyph = glyphs.get(&part.glyph).ok_or_else(|| {
+                format!("`{}`: glyph `{}` does not exist.", self.name, part.glyph)
+            })?;
+            if let Err(err) = Glyph::load(glyph) {
+                return Err(format!("`{}`: {err}", self.name));
+            }
+            let g = glyph.borrow();
+            let part_anchors = g.metadata.anchors.borrow();
+            let offset = if let Some(ref anchor) = part.anchor {
+                let (_, target) = anchors
+                    .iter()
+                    .rev()
+                    .find(|(name, _)| name == anchor)
+                    .ok_or_else(|| {
+                        format!(
+                            "`{}`: no `{anchor}` anchor to attach `{}` to.",
+                            self.name, part.glyph
+                        )
+                    })?;
+                let mark_anchor = format!("_{anchor}");
+                let own = part_anchors
+                    .iter()
+                    .find(|a| a.name() == mark_anchor)
+                    .ok_or_else(|| {
+                        format!(
+                            "`{}`: mark `{}` has no `{mark_anchor}` anchor.",
+                            self.name, part.glyph
+                        )
+                    })?;
+                *target - own.position()
+            } else {
+                let offset = Point::from((width, 0.0));
+                width += g.metadata.width().unwrap_or_default();
+                offset
+            };
+            anchors.extend(
+                part_anchors
+                    .iter()
+                    .filter(|a| !a.is_mark())
+                    .map(|a| (a.name(), a.position() + offset)),
+            );
+            components.push(Component {
+                base_name: part.glyph.clone(),
+                base: Rc::downgrade(glyph),
+                x_offset: offset.x,
+                y_offset: offset.y,
+                x_scale: 1.0,
+                xy_scale: 0.0,
+                yx_scale: 0.0,
+                y_scale: 1.0,
+            });
+        }
+        /* Keep the last position of each anchor, e.g. the top of the topmost mark. */
+        let mut deduped: Vec<(String, Point)> = vec![];
+        for (name, position) in anchors.into_iter().rev() {
+            if !deduped.iter().any(|(n, _)| *n == name) {
+                deduped.push((name, position));
+            }
+        }
+        deduped.reverse();
+        Ok(Composite {
+            components,
+            width,
+            anchors: deduped,
+        })
+    }
+
+    /// Returns an action that builds the recipe in `layer`, replacing the components, advance
+    /// width and anchors of the glyph if it exists and has no outline of its own. Otherwise a
+    /// new, empty glyph is added right away, outside the undo history like any new glyph, and
+    /// the action fills it in. Returns the new glyph, if one was added.
+    pub fn apply(
+        &self,
+        layer: &ufo::objects::Layer,
+    ) -> Result<(Action, Option<GlyphMetadata>), String> {
+        let composite = self.build(&layer.glyphs())?;
+        let anchors = composite
+            .anchors
+            .iter()
+            .map(|(name, position)| Anchor::with_position(name.clone(), *position))
+            .collect::<Vec<Anchor>>();
+        let advance = Some(Advance {
+            width: composite.width,
+            height: 0.0,
+        });
+        let existing = layer.glyphs().get(&self.name).cloned();
+        let (glyph, new_glyph) = if let Some(glyph) = existing {
+            if let Err(err) = Glyph::load(&glyph) {
+                return Err(format!("`{}`: {err}", self.name));
+            }
+            if !glyph.borrow().contours.is_empty() {
+                return Err(format!(
+                    "`{}` already exists and has contours of its own.",
+                    self.name
+                ));
+            }
+            (glyph, None)
+        } else {
+            let metadata = GlyphMetadata::new();
+            metadata.set_property(GlyphMetadata::NAME, self.name.clone());
+            let filename = {
+                let existing = layer
+                    .contents_plist
+                    .borrow()
+                    .glyphs()
+                    .values()
+                    .map(|f| f.to_lowercase())
+                    .collect::<HashSet<String>>();
+                ufo::user_name_to_file_name(&self.name, "", ".glif", &existing)
+            };
+            metadata.set_property(GlyphMetadata::FILENAME, filename);
+            if let Some(c) = self.unicode {
+                *metadata.kinds.borrow_mut() = (GlyphKind::Char(c), vec![]);
+                *metadata.unicode.borrow_mut() = vec![Unicode::new(format!("{:04X}", c as u32))];
+            } else {
+                *metadata.kinds.borrow_mut() = (GlyphKind::Component(self.name.clone()), vec![]);
+            }
+            let glyph: Glyph = metadata.clone().into();
+            let glyph = Rc::new(RefCell::new(glyph));
+            metadata.glyph_ref.set(glyph.clone()).unwrap();
+            layer
+                .new_glyph(self.name.clone(), glyph.clone())
+                .map_err(|err| err.to_string())?;
+            metadata.set_property(GlyphMetadata::MODIFIED, true);
+            (glyph, Some(metadata))
+        };
+        let old = {
+            let g = glyph.borrow();
+            let anchors = g.metadata.anchors.borrow().clone();
+            (
+                g.components.clone(),
+                anchors,
+                g.metadata.advance.get(),
+                g.metadata.width.get(),
+            )
+        };
+        let new = (
+            composite.components,
+            anchors,
+            advance,
+            Some(composite.width),
+        );
+        let set = |(components, anchors, advance, width): (
+            Vec<Component>,
+            Vec<Anchor>,
+            Option<Advance>,
+            Option<f64>,
+        )| {
+            clone!(@weak glyph => move || {
+                let metadata = {
+                    let mut g = glyph.borrow_mut();
+                    g.components = components.clone();
+                    *g.metadata.anchors.borrow_mut() = anchors.clone();
+                    g.metadata.advance.set(advance);
+                    g.metadata.width.set(width);
+                    g.metadata.clone()
+                };
+                metadata.set_property(GlyphMetadata::MODIFIED, true);
+            })
+        };
+        let action = Action {
+            stamp: EventStamp {
+                t: std::any::TypeId::of::<Glyph>(),
+                property: "components",
+                id: self.name.clone().into_bytes().into_boxed_slice(),
+            },
+            compress: false,
+            redo: Box::new(set(new)),
+            undo: Box::new(set(old)),
+        };
+        Ok((action, new_glyph))
+    }
+}
+
+/// Maps codepoints to the names of the glyphs in `glyphs` that encode them.
+pub fn char_names(
+    glyphs: &IndexMap<String, Rc<RefCell<Glyph>>>,
+) -> std::collections::HashMap<char, String> {
+    let mut ret = std::collections::HashMap::default();
+    for (name, glyph) in glyphs.iter() {
+        for u in glyph.borrow().metadata.unicode().iter() {
+            if let Ok(GlyphKind::Char(c)) = GlyphKind::try_from(u) {
+                ret.entry(c).or_insert_with(|| name.clone());
+            }
+        }
+    }
+    ret
+}
+
+#[test]
+fn test_recipe_parse() {
+    let r: Recipe = "Aacute = A + acutecomb@top".parse().unwrap();
+    assert_eq!(r.name, "Aacute");
+    assert_eq!(r.unicode, None);
+    assert_eq!(
+        r.parts,
+        vec![
+            RecipePart {
+                glyph: "A".into(),
+                anchor: None
+            },
+            RecipePart {
+                glyph: "acutecomb".into(),
+                anchor: Some("top".into())
+            }
+        ]
+    );
+    assert_eq!(r.to_string(), "Aacute = A + acutecomb@top");
+    let r: Recipe = "Ecircumflexacute=E+circumflexcomb@top+acutecomb@top|U+1EBE"
+        .parse()
+        .unwrap();
+    assert_eq!(r.unicode, Some('\u{1EBE}'));
+    assert_eq!(r.parts.len(), 3);
+    assert_eq!(
+        r.to_string(),
+        "Ecircumflexacute = E + circumflexcomb@top + acutecomb@top | 1EBE"
+    );
+    assert!("A + acutecomb@top".parse::<Recipe>().is_err());
+    assert!("Aacute = acutecomb@top + A".parse::<Recipe>().is_err());
+    assert!("Aacute = A + @top".parse::<Recipe>().is_err());
+    assert_eq!(
+        Recipe::parse_list("# comment\n\nAacute = A + acutecomb@top\nf_f = f + f\n")
+            .unwrap()
+            .len(),
+        2
+    );
+}
+
+#[test]
+fn test_recipe_build() {
+    let new_glyph = |name: &str, c: char, width: f64, anchors: &[(&str, (f64, f64))]| {
+        let glyph = Glyph::new_empty(name.to_string(), c);
+        glyph.metadata.width.set(Some(width));
+        *glyph.metadata.unicode.borrow_mut() = vec![Unicode::new(format!("{:04X}", c as u32))];
+        *glyph.metadata.anchors.borrow_mut() = anchors
+            .iter()
+            .map(|(n, p)| Anchor::with_position(n.to_string(), (*p).into()))
+            .collect();
+        (name.to_string(), Rc::new(RefCell::new(glyph)))
+    };
+    let glyphs: IndexMap<String, Rc<RefCell<Glyph>>> = [
+        new_glyph("A", 'A', 600.0, &[("top", (300.0, 700.0))]),
+        new_glyph(
+            "acutecomb",
+            '\u{0301}',
+            0.0,
+            &[("_top", (100.0, 500.0)), ("top", (110.0, 700.0))],
+        ),
+        new_glyph("gravecomb", '\u{0300}', 0.0, &[("_top", (-50.0, 500.0))]),
+    ]
+    .into_iter()
+    .collect();
+
+    let r = Recipe::from_char('Á', &glyphs).unwrap();
+    assert_eq!(r.to_string(), "Aacute = A + acutecomb@top | 00C1");
+    let c = r.build(&glyphs).unwrap();
+    assert_eq!(c.width, 600.0);
+    assert_eq!(c.components.len(), 2);
+    assert_eq!(
+        (c.components[0].x_offset, c.components[0].y_offset),
+        (0.0, 0.0)
+    );
+    assert_eq!(
+        (c.components[1].x_offset, c.components[1].y_offset),
+        (200.0, 200.0)
+    );
+    assert_eq!(c.anchors, vec![("top".to_string(), (310.0, 900.0).into())]);
+
+    /* Stacked marks attach to the previous mark's anchor. */
+    let r: Recipe = "Aacutegrave = A + acutecomb@top + gravecomb@top"
+        .parse()
+        .unwrap();
+    let c = r.build(&glyphs).unwrap();
+    assert_eq!(
+        (c.components[2].x_offset, c.components[2].y_offset),
+        (360.0, 400.0)
+    );
+
+    /* Parts without an anchor are spaced out. */
+    let r: Recipe = "A_A = A + A".parse().unwrap();
+    let c = r.build(&glyphs).unwrap();
+    assert_eq!(c.width, 1200.0);
+    assert_eq!(c.components[1].x_offset, 600.0);
+
+    assert!("Agrave = A + gravecomb@bottom"
+        .parse::<Recipe>()
+        .unwrap()
+        .build(&glyphs)
+        .is_err());
+    assert!(Recipe::from_char('Ç', &glyphs).is_none());
+    assert_eq!(Recipe::from_unicode(&glyphs).len(), 2);
+}
@@ -43,6 +43,14 @@ impl std::ops::Deref for Contour {
 impl Contour {
     pub const OPEN: &'static str = "open";
     pub const BIGGEST_CURVE: &'static str = "biggest-curve";
+    pub const SIGNED_AREA: &'static str = "signed-area";
+    pub const DIRECTION: &'static str = "direction";
+    pub const NESTING: &'static str = "nesting";
+    pub const ROLE: &'static str = "role";
+    /// Extrema closer than this to an existing on-curve point are not considered missing.
+    pub const EXTREMA_TOLERANCE: f64 = 0.5;
+    /// Parameter values closer than this to an end of a curve are treated as the end point.
+    const T_EPSILON: f64 = 1e-6;
 
     pub fn new() -> Self {
         let ret: Self = glib::Object::new::<Self>(&[]).unwrap();
@@ -135,19 +143,17 @@ impl Contour {
         if curve.points().is_empty() {
             return;
         }
-        let new_len = curve.approx_length();
+        let new_len = curve.length();
         if let Some(b) = self
             .imp()
             .biggest_curve
             .get()
-            .filter(
-                |&BiggestCurve {
-                     approx_length: len, ..
-                 }| new_len > len && (new_len - len).abs() < 0.05,
-            )
+            .filter(|&BiggestCurve { length: len, .. }| {
+                new_len > len && (new_len - len).abs() < 0.05
+            })
             .or(Some(BiggestCurve {
                 index: curves.len(),
-                approx_length: new_len,
+                length: new_len,
             }))
         {
             self.imp().is_contour_modified.set(false);
@@ -243,6 +249,175 @@ impl Contour {
         }
     }
 
+    /// Returns a reversed copy of this contour that shares no state with it.
+    pub fn reversed(&self) -> Self {
+        let ret = Self::from_curves(
+            self.curves().iter().map(Self::copy_curve).collect(),
+            self.imp().open.get(),
+        );
+        ret.reverse_direction();
+        ret
+    }
+
+    /// Returns a copy of this contour with `m` applied to all of its points. If `m` mirrors,
+    /// the copy is reversed so that it keeps its direction.
+    pub fn transformed(&self, m: Matrix) -> Self {
+        let ret = Self::from_curves(
+            self.curves()
+                .iter()
+                .map(|curve| {
+                    let ret = Bezier::new(curve.positions().into_iter().map(|p| m * p).collect());
+                    ret.set_property(Bezier::SMOOTH, curve.property::<bool>(Bezier::SMOOTH));
+                    ret
+                })
+                .collect(),
+            self.imp().open.get(),
+        );
+        if m.xx() * m.yy() - m.xy() * m.yx() < 0.0 {
+            ret.reverse_direction();
+        }
+        ret.recalc_continuities();
+        ret
+    }
+
+    /// Signed area enclosed by the contour; positive if it runs counter-clockwise in font
+    /// units (y pointing up). Open contours are treated as if closed by a straight line.
+    pub fn signed_area(&self) -> f64 {
+        let curves = self.curves();
+        let (Some(first), Some(last)) = (curves.first(), curves.last()) else {
+            return 0.0;
+        };
+        let closing = [
+            last.points().last().unwrap().position,
+            first.points()[0].position,
+        ];
+        curves.iter().map(Bezier::signed_area).sum::<f64>() + math::signed_area(&closing)
+    }
+
+    pub fn is_clockwise(&self) -> bool {
+        self.signed_area() < 0.0
+    }
+
+    /// Winding number of this contour around `point`: how many times it goes around it
+    /// counter-clockwise, minus clockwise. Always `0` for open contours.
+    pub fn winding_number(&self, point: Point) -> i64 {
+        if self.imp().open.get() {
+            return 0;
+        }
+        let curves = self.curves();
+        let x_max = curves
+            .iter()
+            .map(|c| c.bounding_box().1.x)
+            .fold(f64::NEG_INFINITY, f64::max);
+        if x_max < point.x {
+            return 0;
+        }
+        /* Count signed crossings of a ray going right from `point`. A crossing on an on-curve
+         * point is counted once, at the start of the following curve. */
+        let ray_end = Point::from((x_max + 1.0, point.y));
+        let mut ret = 0;
+        for curve in curves.iter() {
+            let positions = curve.positions();
+            let derivative = math::derivative(&positions);
+            for (t, _) in curve.line_intersections(point, ray_end) {
+                if t > 1.0 - Self::T_EPSILON {
+                    continue;
+                }
+                let dy = math::evaluate(&derivative, t).y;
+                if dy > 0.0 {
+                    ret += 1;
+                } else if dy < 0.0 {
+                    ret -= 1;
+                }
+            }
+        }
+        ret
+    }
+
+    /// A point on the contour used to test whether it lies inside other contours.
+    fn sample_point(&self) -> Option<Point> {
+        self.curves().first().map(|c| c.compute(0.5))
+    }
+
+    /// For each contour, how many of the other closed contours enclose it. Contours with even
+    /// depth are outer contours, those with odd depth are counters.
+    pub fn nesting(contours: &[Self]) -> Vec<usize> {
+        contours
+            .iter()
+            .enumerate()
+            .map(|(i, contour)| {
+                let Some(sample) = contour.sample_point() else {
+                    return 0;
+                };
+                contours
+                    .iter()
+                    .enumerate()
+                    .filter(|&(j, other)| j != i && other.winding_number(sample) != 0)
+                    .count()
+            })
+            .collect()
+    }
+
+    /// Makes each contour compute its [`Contour::NESTING`] property among `contours`, the
+    /// contours of its glyph. Nesting is computed from their current shape when it is read.
+    pub fn link_siblings(contours: &[Self]) {
+        let siblings = contours
+            .iter()
+            .map(ObjectExt::downgrade)
+            .collect::<Vec<_>>();
+        for contour in contours {
+            *contour.imp().siblings.borrow_mut() = siblings.clone();
+        }
+    }
+
+    /// How many of the contours linked with [`Contour::link_siblings`] enclose this one, see
+    /// [`Contour::nesting`].
+    pub fn current_nesting(&self) -> usize {
+        let Some(sample) = self.sample_point() else {
+            return 0;
+        };
+        self.imp()
+            .siblings
+            .borrow()
+            .iter()
+            .filter_map(glib::object::WeakRef::upgrade)
+            .filter(|other| other != self && other.winding_number(sample) != 0)
+            .count()
+    }
+
+    /// Whether this contour, at nesting depth `depth`, runs in the direction `convention`
+    /// expects. Open contours have no direction and are always correct.
+    pub fn has_direction(&self, convention: DirectionConvention, depth: usize) -> bool {
+        if self.imp().open.get() {
+            return true;
+        }
+        let outer = depth % 2 == 0;
+        let clockwise = self.is_clockwise();
+        match convention {
+            DirectionConvention::PostScript => clockwise != outer,
+            DirectionConvention::TrueType => clockwise == outer,
+        }
+    }
+
+    /// Returns copies of `contours` with every closed contour running in the direction
+    /// `convention` expects, or `None` if they already do.
+    pub fn with_direction(contours: &[Self], convention: DirectionConvention) -> Option<Vec<Self>> {
+        let mut changed = false;
+        let ret = contours
+            .iter()
+            .zip(Self::nesting(contours))
+            .map(|(contour, depth)| {
+                if contour.has_direction(convention, depth) {
+                    contour.clone()
+                } else {
+                    changed = true;
+                    contour.reversed()
+                }
+            })
+            .collect();
+        changed.then_some(ret)
+    }
+
     // [ref:needs_unit_test]
     pub fn transform_points(
         &self,
@@ -568,6 +743,652 @@ impl Contour {
         cl().unwrap_or((None, None))
     }
 
+    /// Horizontal and vertical extrema of `curve` that are not already on one of its end points,
+    /// as `(t, position)` pairs sorted by `t`.
+    fn curve_missing_extrema(curve: &Bezier) -> Vec<(f64, Point)> {
+        let positions = curve.positions();
+        let (first, last) = match (positions.first(), positions.last()) {
+            (Some(&first), Some(&last)) => (first, last),
+            _ => return vec![],
+        };
+        let mut ret: Vec<(f64, Point)> = vec![];
+        for t in curve.extrema() {
+            let p = curve.compute(t);
+            if p.distance(first) < Self::EXTREMA_TOLERANCE
+                || p.distance(last) < Self::EXTREMA_TOLERANCE
+                || ret.last().map_or(false, |&(_, prev)| {
+                    p.distance(prev) < Self::EXTREMA_TOLERANCE
+                })
+            {
+                continue;
+            }
+            ret.push((t, p));
+        }
+        ret
+    }
+
+    /// Positions of horizontal and vertical extrema that have no on-curve point.
+    pub fn missing_extrema(&self) -> Vec<Point> {
+        self.curves()
+            .iter()
+            .flat_map(Self::curve_missing_extrema)
+            .map(|(_, p)| p)
+            .collect()
+    }
+
+    /// Returns a copy of this contour with an on-curve point inserted at every missing extremum,
+    /// or `None` if it has none. The shape is unchanged: curves are split, not refitted, and the
+    /// new points are smooth.
+    pub fn with_extrema(&self) -> Option<Self> {
+        let mut changed = false;
+        let mut curves = vec![];
+        for curve in self.curves().iter() {
+            let extrema = Self::curve_missing_extrema(curve);
+            if extrema.is_empty() {
+                curves.push(curve.clone());
+                continue;
+            }
+            changed = true;
+            let mut rest = curve.clone();
+            let mut prev_t = 0.0;
+            for (t, _) in extrema {
+                let (left, right) = Self::split_smooth(&rest, (t - prev_t) / (1.0 - prev_t));
+                curves.push(left);
+                rest = right;
+                prev_t = t;
+            }
+            curves.push(rest);
+        }
+        changed.then(|| Self::from_curves(curves, self.imp().open.get()))
+    }
+
+    /// Like [`Contour::new_with_curves`] but keeps the continuities already set on `curves`.
+    fn from_curves(curves: Vec<Bezier>, open: bool) -> Self {
+        let ret: Self = glib::Object::new::<Self>(&[]).unwrap();
+        ret.imp().open.set(open);
+        *ret.imp().curves.borrow_mut() = curves;
+        ret
+    }
+
+    /// Split `curve` at `t` and make the new on-curve point smooth.
+    fn split_smooth(curve: &Bezier, t: f64) -> (Bezier, Bezier) {
+        let (left, right) = curve.split(t);
+        Self::smooth_join(&left, &right);
+        (left, right)
+    }
+
+    /// Make the point where `left` ends and `right` starts smooth and set its continuity.
+    fn smooth_join(left: &Bezier, right: &Bezier) {
+        right.set_property(Bezier::SMOOTH, true);
+        let continuity = {
+            let leftp = left.points();
+            let rightp = right.points();
+            Self::calc_smooth_continuity(
+                <Vec<CurvePoint> as AsRef<[CurvePoint]>>::as_ref(&leftp),
+                <Vec<CurvePoint> as AsRef<[CurvePoint]>>::as_ref(&rightp),
+            )
+        };
+        left.set_property(Bezier::CONTINUITY_OUT, Some(continuity));
+        right.set_property(Bezier::CONTINUITY_IN, Some(continuity));
+    }
+
+    /// Fit a freehand stroke with cubic curves that are no further than `tolerance` from it;
+    /// larger values smooth out more of the jitter and give fewer points. Where the stroke
+    /// turns by more than `corner_angle` radians the on-curve point is a corner, elsewhere
+    /// points are smooth. Returns `None` if the stroke is too short.
+    pub fn from_stroke(
+        points: &[Point],
+        tolerance: f64,
+        corner_angle: f64,
+        closed: bool,
+    ) -> Option<Self> {
+        let tolerance = tolerance.max(Self::EXTREMA_TOLERANCE / 10.0);
+        /* Corners are measured over this distance, so that jitter isn't mistaken for them. */
+        let radius = 4.0 * tolerance;
+        let mut points = points.to_vec();
+        points.dedup_by(|a, b| a.distance(*b) < tolerance / 4.0);
+        if closed {
+            while points.len() > 2 && points[points.len() - 1].distance(points[0]) < radius {
+                points.pop();
+            }
+            if points.len() < 3 {
+                return None;
+            }
+        } else if points.len() < 2 {
+            return None;
+        }
+        let len = points.len();
+        /* Index of the first point at least `radius` away from point `i`, walking in
+         * direction `step`, wrapping around if `closed`. */
+        let neighbour = |i: usize, step: isize| -> Option<usize> {
+            let mut j = i;
+            for _ in 1..len {
+                j = if closed {
+                    (j as isize + step).rem_euclid(len as isize) as usize
+                } else {
+                    usize::try_from(j as isize + step)
+                        .ok()
+                        .filter(|&j| j < len)?
+                };
+                if points[j].distance(points[i]) >= radius {
+                    return Some(j);
+                }
+            }
+            None
+        };
+        let mut candidates = (0..len)
+            .filter_map(|i| {
+                let (prev, next) = (neighbour(i, -1)?, neighbour(i, 1)?);
+                let incoming = (points[i] - points[prev]).unit();
+                let outgoing = (points[next] - points[i]).unit();
+                let turn = incoming.dot(outgoing).clamp(-1.0, 1.0).acos();
+                (turn > corner_angle).then_some((i, turn))
+            })
+            .collect::<Vec<(usize, f64)>>();
+        /* Keep only the sharpest point of each bend. */
+        candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+        let mut corners: Vec<usize> = vec![];
+        for (i, _) in candidates {
+            if corners
+                .iter()
+                .all(|&c| points[c].distance(points[i]) >= radius)
+            {
+                corners.push(i);
+            }
+        }
+        corners.sort_unstable();
+        /* Runs of points between corners, each fitted separately. */
+        let mut runs: Vec<(Vec<Point>, bool)> = vec![];
+        if closed {
+            match corners.first() {
+                Some(&first) => {
+                    for (k, &c) in corners.iter().enumerate() {
+                        let end = corners.get(k + 1).copied().unwrap_or(first + len);
+                        runs.push(((c..=end).map(|j| points[j % len]).collect(), true));
+                    }
+                }
+                None => {
+                    let mut run = points.clone();
+                    run.push(points[0]);
+                    runs.push((run, false));
+                }
+            }
+        } else {
+            let mut start = 0;
+            for c in corners.into_iter().chain(std::iter::once(len - 1)) {
+                if c > start {
+                    runs.push((points[start..=c].to_vec(), true));
+                    start = c;
+                }
+            }
+        }
+        let tangent_at = |run: &[Point], from_start: bool| -> Point {
+            let origin = if from_start {
+                run[0]
+            } else {
+                run[run.len() - 1]
+            };
+            let mut iter: Box<dyn Iterator<Item = &Point>> = if from_start {
+                Box::new(run.iter())
+            } else {
+                Box::new(run.iter().rev())
+            };
+            let far = iter
+                .find(|p| p.distance(origin) >= radius)
+                .or_else(|| if from_start { run.last() } else { run.first() })
+                .copied()
+                .unwrap_or(origin);
+            far - origin
+        };
+        let mut curves = vec![];
+        for (run, corner) in runs {
+            let (start_tangent, end_tangent) = if corner {
+                (tangent_at(&run, true), tangent_at(&run, false))
+            } else {
+                /* A closed stroke without corners: make the start point smooth. */
+                let tangent = tangent_at(&run, true).unit() - tangent_at(&run, false).unit();
+                (tangent, -1.0 * tangent)
+            };
+            for (k, fitted) in
+                math::fit_cubics(&run, Some(start_tangent), Some(end_tangent), tolerance)
+                    .into_iter()
+                    .enumerate()
+            {
+                let curve = Bezier::new(fitted.to_vec());
+                curve.set_property(Bezier::SMOOTH, k != 0 || !corner);
+                curves.push(curve);
+            }
+        }
+        if curves.is_empty() {
+            return None;
+        }
+        let ret = Self::new_with_curves(curves);
+        if closed {
+            ret.close();
+        }
+        Some(ret)
+    }
+
+    /// Build a contour passing through `points` with Hobby's curves. Each point is paired with
+    /// whether it is a corner; the curves are G2-like across the other points, and runs between
+    /// corners are solved separately with curl ends. Two adjacent corners are joined by a line.
+    pub fn through_points(points: &[(Point, bool)], closed: bool) -> Option<Self> {
+        let mut points = points.to_vec();
+        points.dedup_by(|a, b| a.0.distance(b.0) < Self::EXTREMA_TOLERANCE);
+        if closed
+            && points.len() > 1
+            && points[0].0.distance(points[points.len() - 1].0) < Self::EXTREMA_TOLERANCE
+        {
+            points.pop();
+        }
+        let len = points.len();
+        if len < 2 || (closed && len < 3) {
+            return None;
+        }
+        let mut runs: Vec<(Vec<Point>, bool)> = vec![];
+        if closed {
+            match points.iter().position(|(_, corner)| *corner) {
+                Some(first) => {
+                    let mut run = vec![points[first].0];
+                    for j in (first + 1)..=(first + len) {
+                        let (p, corner) = points[j % len];
+                        run.push(p);
+                        if corner {
+                            runs.push((std::mem::replace(&mut run, vec![p]), false));
+                        }
+                    }
+                }
+                None => {
+                    let positions = points.iter().map(|(p, _)| *p).collect::<Vec<Point>>();
+                    runs.push((positions, true));
+                }
+            }
+        } else {
+            let mut run = vec![points[0].0];
+            for (j, &(p, corner)) in points.iter().enumerate().skip(1) {
+                run.push(p);
+                if corner || j == len - 1 {
+                    runs.push((std::mem::replace(&mut run, vec![p]), false));
+                }
+            }
+        }
+        let mut curves = vec![];
+        for (run, cyclic) in runs {
+            if run.len() == 2 {
+                curves.push(Bezier::new(run));
+                continue;
+            }
+            for (k, cubic) in math::hobby(&run, cyclic).into_iter().enumerate() {
+                let curve = Bezier::new(cubic.to_vec());
+                curve.set_property(Bezier::SMOOTH, k != 0 || cyclic);
+                curves.push(curve);
+            }
+        }
+        if curves.is_empty() {
+            return None;
+        }
+        let ret = Self::new_with_curves(curves);
+        if closed {
+            ret.close();
+        }
+        Some(ret)
+    }
+
+    /// Append the outline of this contour to the current path of `cr`.
+    pub fn append_path(&self, cr: &gtk::cairo::Context) {
+        let curves = self.curves();
+        let Some(start) = curves
+            .first()
+            .and_then(|c| c.points().first().map(|p| p.position))
+        else {
+            return;
+        };
+        cr.move_to(start.x, start.y);
+        for curve in curves.iter() {
+            match curve.positions().as_slice() {
+                [_, b] => cr.line_to(b.x, b.y),
+                [a, b, c] => {
+                    /* Degree elevation of the quadratic. */
+                    let (c1, c2) = (*a + (2.0 / 3.0) * (*b - *a), *c + (2.0 / 3.0) * (*b - *c));
+                    cr.curve_to(c1.x, c1.y, c2.x, c2.y, c.x, c.y);
+                }
+                [_, b, c, d] => cr.curve_to(b.x, b.y, c.x, c.y, d.x, d.y),
+                _ => {}
+            }
+        }
+        if !self.imp().open.get() {
+            cr.close_path();
+        }
+    }
+
+    /// Samples of the curvature comb of each curve, as `(position, unit normal, curvature)`
+    /// triples at `samples` evenly spaced parameters including both ends. The normal points
+    /// to the left of the direction of travel, so that the centre of curvature lies at
+    /// `position + normal / curvature`.
+    pub fn curvature_comb(&self, samples: usize) -> Vec<Vec<(Point, Point, f64)>> {
+        let samples = samples.max(2);
+        self.curves()
+            .iter()
+            .map(|curve| {
+                let positions = curve.positions();
+                let derivative = math::derivative(&positions);
+                (0..samples)
+                    .map(|i| {
+                        let t = i as f64 / (samples - 1) as f64;
+                        let tangent = math::evaluate(&derivative, t).unit();
+                        let normal = if tangent.norm().is_normal() {
+                            Point::from((-tangent.y, tangent.x))
+                        } else {
+                            Point::default()
+                        };
+                        (
+                            math::evaluate(&positions, t),
+                            normal,
+                            math::curvature(&positions, t),
+                        )
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Joins between curves where the tangent is continuous but the curvature is not, as
+    /// `(position, unit normal, curvature before, curvature after)`, with the normal as in
+    /// [`Self::curvature_comb`]. Corners are left out since they are deliberate.
+    pub fn curvature_discontinuities(&self) -> Vec<(Point, Point, f64, f64)> {
+        /* About one degree. */
+        const MAX_ANGLE_COS: f64 = 0.9998;
+        const RELATIVE_TOLERANCE: f64 = 0.05;
+        const ABSOLUTE_TOLERANCE: f64 = 1e-6;
+
+        let curves = self.curves();
+        let len = curves.len();
+        let joins = if self.imp().open.get() {
+            len.saturating_sub(1)
+        } else {
+            len
+        };
+        (0..joins)
+            .filter_map(|i| {
+                let (prev, next) = (curves[i].positions(), curves[(i + 1) % len].positions());
+                let incoming = math::evaluate(&math::derivative(&prev), 1.0).unit();
+                let outgoing = math::evaluate(&math::derivative(&next), 0.0).unit();
+                let cos = incoming.dot(outgoing);
+                if cos.is_nan() || cos < MAX_ANGLE_COS {
+                    return None;
+                }
+                let (before, after) = (math::curvature(&prev, 1.0), math::curvature(&next, 0.0));
+                let tolerance =
+                    ABSOLUTE_TOLERANCE.max(RELATIVE_TOLERANCE * before.abs().max(after.abs()));
+                let normal = Point::from((-outgoing.y, outgoing.x));
+                ((before - after).abs() > tolerance).then_some((next[0], normal, before, after))
+            })
+            .collect()
+    }
+
+    /// Number of on-curve and off-curve points.
+    pub fn point_count(&self) -> usize {
+        let curves = self.curves();
+        let ret = curves
+            .iter()
+            .map(|c| c.points().len().saturating_sub(1))
+            .sum::<usize>();
+        if self.imp().open.get() && !curves.is_empty() {
+            ret + 1
+        } else {
+            ret
+        }
+    }
+
+    /// Returns a copy of this contour where runs of curves between corners are refitted with as
+    /// few cubic curves as possible while staying within `tolerance` units of the original
+    /// outline, or `None` if no run could be reduced.
+    ///
+    /// On-curve points that aren't smooth and the ends of straight lines are kept, along with
+    /// their continuity; new points in between are smooth.
+    pub fn simplified(&self, tolerance: f64) -> Option<Self> {
+        const SAMPLES_PER_CURVE: usize = 16;
+
+        let open = self.imp().open.get();
+        let curves = self.curves();
+        let len = curves.len();
+        if len < 2 {
+            return None;
+        }
+        let is_line = |i: usize| curves[i].points().len() < 3;
+        /* Junction `i` is where curve `i` starts. */
+        let is_kept = |i: usize| {
+            (open && i == 0)
+                || !curves[i].property::<bool>(Bezier::SMOOTH)
+                || is_line(i)
+                || is_line((i + len - 1) % len)
+        };
+        let start = match (0..len).find(|&i| is_kept(i)) {
+            Some(i) => i,
+            /* A closed contour without corners, keep its start point. */
+            None => 0,
+        };
+        let mut runs: Vec<Vec<usize>> = vec![];
+        for i in (start..len).chain(0..start) {
+            match runs.last_mut() {
+                Some(run) if !is_kept(i) => run.push(i),
+                _ => runs.push(vec![i]),
+            }
+        }
+        let mut changed = false;
+        let mut new_curves = vec![];
+        for run in runs {
+            let first = &curves[run[0]];
+            let last = &curves[run[run.len() - 1]];
+            if run.len() < 2 || run.iter().any(|&i| is_line(i)) {
+                new_curves.extend(run.iter().map(|&i| Self::copy_curve(&curves[i])));
+                continue;
+            }
+            let mut samples = vec![];
+            for &i in &run {
+                let positions = curves[i].positions();
+                samples.extend(
+                    (0..SAMPLES_PER_CURVE)
+                        .map(|j| math::evaluate(&positions, j as f64 / SAMPLES_PER_CURVE as f64)),
+                );
+            }
+            let last_positions = last.positions();
+            samples.push(last_positions[last_positions.len() - 1]);
+            let first_positions = first.positions();
+            let start_tangent = math::evaluate(&math::derivative(&first_positions), 0.0);
+            let end_tangent = -1.0 * math::evaluate(&math::derivative(&last_positions), 1.0);
+            let fitted =
+                math::fit_cubics(&samples, Some(start_tangent), Some(end_tangent), tolerance);
+            if fitted.is_empty() || fitted.len() >= run.len() {
+                new_curves.extend(run.iter().map(|&i| Self::copy_curve(&curves[i])));
+                continue;
+            }
+            changed = true;
+            let fitted = fitted
+                .into_iter()
+                .map(|points| Bezier::new(points.to_vec()))
+                .collect::<Vec<Bezier>>();
+            for w in fitted.windows(2) {
+                Self::smooth_join(&w[0], &w[1]);
+            }
+            let (new_first, new_last) = (&fitted[0], &fitted[fitted.len() - 1]);
+            new_first.set_property(Bezier::SMOOTH, first.property::<bool>(Bezier::SMOOTH));
+            new_first.set_property(
+                Bezier::CONTINUITY_IN,
+                first.property::<Option<Continuity>>(Bezier::CONTINUITY_IN),
+            );
+            new_last.set_property(
+                Bezier::CONTINUITY_OUT,
+                last.property::<Option<Continuity>>(Bezier::CONTINUITY_OUT),
+            );
+            new_curves.extend(fitted);
+        }
+        changed.then(|| Self::from_curves(new_curves, open))
+    }
+
+    /// Returns a copy of this contour with the curve at `curve_index` split at `t`, or `None` if
+    /// there is no such curve or `t` is on one of its end points. The new point is smooth.
+    pub fn with_split(&self, curve_index: usize, t: f64) -> Option<Self> {
+        if !(Self::T_EPSILON..=(1.0 - Self::T_EPSILON)).contains(&t) {
+            return None;
+        }
+        let mut curves = self.curves().to_vec();
+        let (left, right) = Self::split_smooth(curves.get(curve_index)?, t);
+        curves.splice(curve_index..=curve_index, [left, right]);
+        Some(Self::from_curves(curves, self.imp().open.get()))
+    }
+
+    /// Returns a copy of `curve` that shares no state with it.
+    fn copy_curve(curve: &Bezier) -> Bezier {
+        let ret = Bezier::new(curve.positions());
+        ret.set_property(Bezier::SMOOTH, curve.property::<bool>(Bezier::SMOOTH));
+        for prop in [Bezier::CONTINUITY_IN, Bezier::CONTINUITY_OUT] {
+            ret.set_property(prop, curve.property::<Option<Continuity>>(prop));
+        }
+        ret
+    }
+
+    /// Cut this contour along the line segment from `a` to `b`, or return `None` if the line
+    /// doesn't cross it.
+    ///
+    /// The pieces are open contours, unless `close` is set and this contour is closed: then
+    /// the pieces are joined pairwise with straight lines along the cut, so that cutting a
+    /// closed shape in two yields two closed shapes. If the line ends inside the shape, that is
+    /// there is an odd number of crossings, the pieces are left open.
+    pub fn knife(&self, a: Point, b: Point, close: bool) -> Option<Vec<Self>> {
+        struct Piece {
+            start: Option<usize>,
+            end: Option<usize>,
+            curves: Vec<Bezier>,
+        }
+
+        let open = self.imp().open.get();
+        let curves = self.curves();
+        let last_index = curves.len().checked_sub(1)?;
+        /* Crossings as (curve index, t, position along the knife), in contour order. A
+         * crossing on an on-curve point is reported once, at the start of the following curve. */
+        let mut cuts: Vec<(usize, f64, f64)> = vec![];
+        for (i, curve) in curves.iter().enumerate() {
+            for (t, s) in curve.line_intersections(a, b) {
+                if t > 1.0 - Self::T_EPSILON || (open && i == 0 && t < Self::T_EPSILON) {
+                    continue;
+                }
+                cuts.push((i, t.max(0.0), s));
+            }
+        }
+        if cuts.is_empty() {
+            return None;
+        }
+        cuts.sort_by(|x, y| x.0.cmp(&y.0).then(x.1.total_cmp(&y.1)));
+        let cut_positions = cuts
+            .iter()
+            .map(|&(i, t, _)| curves[i].compute(t))
+            .collect::<Vec<Point>>();
+
+        let mut pieces = vec![];
+        let mut current = Piece {
+            start: None,
+            end: None,
+            curves: vec![],
+        };
+        let mut cuts_iter = cuts.iter().enumerate().peekable();
+        for (i, curve) in curves.iter().enumerate() {
+            let mut rest = Self::copy_curve(curve);
+            let mut prev_t = 0.0;
+            while let Some((cut, &(_, t, _))) = cuts_iter.next_if(|&(_, &(ci, _, _))| ci == i) {
+                if t > prev_t + Self::T_EPSILON {
+                    let (left, right) = rest.split((t - prev_t) / (1.0 - prev_t));
+                    current.curves.push(left);
+                    rest = right;
+                    prev_t = t;
+                }
+                current.end = Some(cut);
+                pieces.push(std::mem::replace(
+                    &mut current,
+                    Piece {
+                        start: Some(cut),
+                        end: None,
+                        curves: vec![],
+                    },
+                ));
+            }
+            current.curves.push(rest);
+            if i == last_index {
+                pieces.push(std::mem::replace(
+                    &mut current,
+                    Piece {
+                        start: None,
+                        end: None,
+                        curves: vec![],
+                    },
+                ));
+            }
+        }
+        if !open {
+            /* The contour wraps around, so the piece before the first cut continues the piece
+             * after the last one. */
+            let first = pieces.remove(0);
+            let last = pieces.last_mut().unwrap();
+            last.curves.extend(first.curves);
+            last.end = first.end;
+        }
+        pieces.retain(|p| !p.curves.is_empty());
+        for piece in &pieces {
+            let first = &piece.curves[0];
+            first.set_property(Bezier::SMOOTH, false);
+            first.set_property(Bezier::CONTINUITY_IN, Some(Continuity::Positional));
+            piece.curves[piece.curves.len() - 1]
+                .set_property(Bezier::CONTINUITY_OUT, Some(Continuity::Positional));
+        }
+
+        if open || !close || cuts.len() % 2 != 0 {
+            return Some(
+                pieces
+                    .into_iter()
+                    .map(|p| Self::from_curves(p.curves, true))
+                    .collect(),
+            );
+        }
+
+        /* Pair up crossings that are next to each other along the knife: the segment between
+         * each pair lies inside the shape and becomes an edge of the new contours. */
+        let mut along_knife = (0..cuts.len()).collect::<Vec<usize>>();
+        along_knife.sort_by(|&x, &y| cuts[x].2.total_cmp(&cuts[y].2));
+        let mut partner = vec![0; cuts.len()];
+        for pair in along_knife.chunks_exact(2) {
+            partner[pair[0]] = pair[1];
+            partner[pair[1]] = pair[0];
+        }
+        let mut ret = vec![];
+        let mut visited = vec![false; pieces.len()];
+        for first in 0..pieces.len() {
+            let mut curves = vec![];
+            let mut next = first;
+            while !visited[next] {
+                visited[next] = true;
+                let piece = &pieces[next];
+                curves.extend(piece.curves.iter().cloned());
+                let (end, start) = match piece.end.map(|end| (end, partner[end])) {
+                    Some(v) => v,
+                    None => break,
+                };
+                let bridge = Bezier::new(vec![cut_positions[end], cut_positions[start]]);
+                for prop in [Bezier::CONTINUITY_IN, Bezier::CONTINUITY_OUT] {
+                    bridge.set_property(prop, Some(Continuity::Positional));
+                }
+                curves.push(bridge);
+                match pieces.iter().position(|p| p.start == Some(start)) {
+                    Some(n) => next = n,
+                    None => break,
+                }
+            }
+            if !curves.is_empty() {
+                ret.push(Self::from_curves(curves, false));
+            }
+        }
+        Some(ret)
+    }
+
     pub fn pop_curve(&self) -> Option<Bezier> {
         let mut curves = self.curves.borrow_mut();
         if curves.is_empty() {
@@ -576,72 +1397,211 @@ impl Contour {
         curves.pop()
     }
 
-    // [ref:needs_unit_test]
-    pub fn change_continuity(
+    /// The curves meeting at the on-curve point `index`, as `(incoming, outgoing)` curve
+    /// indices. Returns `None` for handles and for the ends of open contours.
+    pub fn joint(
         &self,
         GlyphPointIndex {
             contour_index: _,
             curve_index,
             uuid,
         }: GlyphPointIndex,
+    ) -> Option<(usize, usize)> {
+        let curves = self.curves();
+        let len = curves.len();
+        let closed = !self.imp().open.get();
+        let points = curves.get(curve_index)?.points();
+        let i = points.iter().position(|cp| cp.uuid == uuid)?;
+        if i + 1 == points.len() && i != 0 {
+            if curve_index + 1 < len {
+                Some((curve_index, curve_index + 1))
+            } else {
+                closed.then_some((curve_index, 0))
+            }
+        } else if i == 0 {
+            if curve_index > 0 {
+                Some((curve_index - 1, curve_index))
+            } else {
+                closed.then_some((len - 1, curve_index))
+            }
+        } else {
+            None
+        }
+    }
+
+    /// The continuity at the on-curve point `index`. Points without a recorded continuity are
+    /// corners.
+    pub fn continuity(&self, index: GlyphPointIndex) -> Option<Continuity> {
+        let (_, next_idx) = self.joint(index)?;
+        Some(
+            self.curves()[next_idx]
+                .property::<Option<Continuity>>(Bezier::CONTINUITY_IN)
+                .unwrap_or_default(),
+        )
+    }
+
+    /// Set the continuity at the on-curve point `index` and return the previous one. Points
+    /// without a recorded continuity are corners. Handles are left as they are; see
+    /// [`Self::point_type_change`].
+    pub fn change_continuity(
+        &self,
+        index: GlyphPointIndex,
         continuity: Continuity,
     ) -> Option<Continuity> {
-        let (index, degree) = self
+        let prev_value = self.continuity(index)?;
+        let (prev_idx, next_idx) = self.joint(index)?;
+        let curves = self.curves();
+        curves[prev_idx].set_property(Bezier::CONTINUITY_OUT, Some(continuity));
+        curves[next_idx].set_property(Bezier::CONTINUITY_IN, Some(continuity));
+        curves[next_idx].set_property(Bezier::SMOOTH, !continuity.is_positional());
+        Some(prev_value)
+    }
+
+    /// Handle positions that give the on-curve point `index` the type `point_type`, as
+    /// `(curve index, point index, position)` triples, and the continuity to record for it.
+    ///
+    /// Between two curves the handles are made collinear through the point, along the bisector
+    /// of their current directions. Next to a line, the curve's handle is aligned with the line
+    /// instead. Returns `None` if `index` doesn't join two curves, or if there are no handles to
+    /// adjust.
+    pub fn point_type_change(
+        &self,
+        index: GlyphPointIndex,
+        point_type: PointType,
+    ) -> Option<(Continuity, Vec<(usize, usize, Point)>)> {
+        let positions = self
             .curves()
-            .get(curve_index)?
-            .points()
             .iter()
-            .enumerate()
-            .find(|(_, cp)| cp.uuid == uuid)
-            .map(|(i, cp)| (i, cp.degree))?;
-        let prev_value = if degree == Some(index) {
-            self.curves()[curve_index].property(Bezier::CONTINUITY_OUT)
-        } else {
-            self.curves()[curve_index].property(Bezier::CONTINUITY_IN)
-        };
-        match prev_value {
-            None => todo!(),
-            Some(c) if c != continuity => {
-                let curves_no = self.curves().len();
-                if degree == Some(index) {
-                    self.curves
-                        .borrow_mut()
-                        .get(curve_index)?
-                        .set_property(Bezier::CONTINUITY_OUT, Some(continuity));
-                    self.curves
-                        .borrow_mut()
-                        .get(if curve_index == 0 {
-                            curves_no - 1
-                        } else {
-                            curve_index - 1
-                        })?
-                        .set_property(Bezier::CONTINUITY_IN, Some(continuity));
-                } else {
-                    self.curves
-                        .borrow_mut()
-                        .get(curve_index)?
-                        .set_property(Bezier::CONTINUITY_IN, Some(continuity));
-                    self.curves
-                        .borrow_mut()
-                        .get(if curve_index == 0 {
-                            curves_no - 1
-                        } else {
-                            curve_index - 1
-                        })?
-                        .set_property(Bezier::CONTINUITY_OUT, continuity);
-                }
+            .map(Bezier::positions)
+            .collect::<Vec<_>>();
+        self.point_type_change_in(index, point_type, &positions)
+    }
+
+    /// Like [`Self::point_type_change`], but with the points of each curve at `positions`
+    /// instead of where they currently are.
+    pub fn point_type_change_in(
+        &self,
+        index: GlyphPointIndex,
+        point_type: PointType,
+        positions: &[Vec<Point>],
+    ) -> Option<(Continuity, Vec<(usize, usize, Point)>)> {
+        const EPSILON: f64 = 1e-9;
 
-                Some(c)
+        let (prev_idx, next_idx) = self.joint(index)?;
+        if point_type == PointType::Corner {
+            return Some((Continuity::Positional, vec![]));
+        }
+        let (prev, next) = (positions.get(prev_idx)?, positions.get(next_idx)?);
+        let (pl, nl) = (prev.len(), next.len());
+        if pl < 2 || nl < 2 || (pl == 2 && nl == 2) {
+            return None;
+        }
+        let center = next[0];
+        let project = |p: Point, direction: Point| center + (p - center).dot(direction) * direction;
+        if pl == 2 || nl == 2 {
+            let mut ret = vec![];
+            if pl == 2 {
+                let direction = (center - prev[0]).unit();
+                let length = (next[1] - center).norm();
+                if !direction.norm().is_normal() || length < EPSILON {
+                    return None;
+                }
+                ret.push((next_idx, 1, center + length * direction));
+                /* Zero curvature at the point, like the line's. */
+                if point_type == PointType::Curvature && nl == 4 {
+                    ret.push((next_idx, 2, project(next[2], direction)));
+                }
+            } else {
+                let direction = (next[1] - center).unit();
+                let length = (center - prev[pl - 2]).norm();
+                if !direction.norm().is_normal() || length < EPSILON {
+                    return None;
+                }
+                ret.push((prev_idx, pl - 2, center - length * direction));
+                if point_type == PointType::Curvature && pl == 4 {
+                    ret.push((prev_idx, 1, project(prev[1], direction)));
+                }
             }
-            Some(c) => Some(c),
+            return Some((Continuity::Tangent { beta: 1.0 }, ret));
+        }
+        let (a, b) = ((center - prev[pl - 2]).norm(), (next[1] - center).norm());
+        if a < EPSILON || b < EPSILON {
+            return None;
         }
+        let bisector = (next[1] - center).unit() - (prev[pl - 2] - center).unit();
+        if bisector.norm() < EPSILON {
+            return None;
+        }
+        let tangent = bisector.unit();
+        let (a, b) = match point_type {
+            PointType::Corner => unreachable!(),
+            PointType::Smooth => ((a + b) / 2.0, (a + b) / 2.0),
+            PointType::Tangent => (a, b),
+            PointType::Curvature => {
+                /* The curvature at the ends of a cubic is 2/3 h / l², where l is the length of
+                 * the handle and h the distance of the other control point from the tangent.
+                 * Balance the lengths so that the curvatures match, keeping their product. */
+                let cross = |p: Point| tangent.x * p.y - tangent.y * p.x;
+                match (pl, nl) {
+                    (4, 4) => {
+                        let (h_prev, h_next) = (cross(prev[1] - center), cross(next[2] - center));
+                        if h_prev * h_next > EPSILON {
+                            let a_new = (a * b * (h_prev / h_next).sqrt()).sqrt();
+                            (a_new, a * b / a_new)
+                        } else {
+                            (a, b)
+                        }
+                    }
+                    _ => (a, b),
+                }
+            }
+        };
+        let continuity = if point_type == PointType::Smooth {
+            Continuity::Velocity
+        } else {
+            Continuity::Tangent { beta: b / a }
+        };
+        Some((
+            continuity,
+            vec![
+                (prev_idx, pl - 2, center - a * tangent),
+                (next_idx, 1, center + b * tangent),
+            ],
+        ))
+    }
+
+    /// Move points to `positions`, given as `(curve index, point index, position)` triples, and
+    /// return their new glyph indices and positions.
+    pub fn set_point_positions(
+        &self,
+        contour_index: usize,
+        positions: &[(usize, usize, Point)],
+    ) -> Vec<(GlyphPointIndex, Point)> {
+        let curves = self.curves();
+        positions
+            .iter()
+            .filter_map(|&(curve_index, point_index, position)| {
+                let (uuid, position) =
+                    curves.get(curve_index)?.modify_point(point_index, |cp| {
+                        cp.position = position;
+                    })?;
+                Some((
+                    GlyphPointIndex {
+                        contour_index,
+                        curve_index,
+                        uuid,
+                    },
+                    position,
+                ))
+            })
+            .collect()
     }
 }
 
 #[derive(Copy, Clone, Debug)]
 struct BiggestCurve {
     index: usize,
-    approx_length: f64,
+    length: f64,
 }
 
 #[derive(Default)]
@@ -650,6 +1610,8 @@ pub struct ContourInner {
     curves: RefCell<Vec<Bezier>>,
     biggest_curve: Cell<Option<BiggestCurve>>,
     pub is_contour_modified: Cell<bool>,
+    /// Contours of the same glyph, see [`Contour::link_siblings`].
+    siblings: RefCell<Vec<glib::object::WeakRef<Contour>>>,
 }
 
 impl std::fmt::Debug for ContourInner {
@@ -688,7 +1650,7 @@ impl ObjectImpl for ContourInner {
                         Contour::OPEN,
                         Contour::OPEN,
                         true,
-                        glib::ParamFlags::READABLE,
+                        glib::ParamFlags::READABLE | UI_READABLE,
                     ),
                     glib::ParamSpecUInt64::new(
                         Contour::BIGGEST_CURVE,
@@ -699,12 +1661,44 @@ impl ObjectImpl for ContourInner {
                         0,
                         glib::ParamFlags::READABLE,
                     ),
+                    glib::ParamSpecDouble::new(
+                        Contour::SIGNED_AREA,
+                        Contour::SIGNED_AREA,
+                        "Area enclosed by the contour; negative if it runs clockwise.",
+                        f64::MIN,
+                        f64::MAX,
+                        0.0,
+                        glib::ParamFlags::READABLE | UI_READABLE,
+                    ),
+                    glib::ParamSpecString::new(
+                        Contour::DIRECTION,
+                        Contour::DIRECTION,
+                        "Clockwise or counter-clockwise.",
+                        None,
+                        glib::ParamFlags::READABLE | UI_READABLE,
+                    ),
+                    glib::ParamSpecUInt64::new(
+                        Contour::NESTING,
+                        Contour::NESTING,
+                        "Number of other contours enclosing this one.",
+                        0,
+                        u64::MAX,
+                        0,
+                        glib::ParamFlags::READABLE | UI_READABLE,
+                    ),
+                    glib::ParamSpecString::new(
+                        Contour::ROLE,
+                        Contour::ROLE,
+                        "Outer contour or counter, depending on nesting.",
+                        None,
+                        glib::ParamFlags::READABLE | UI_READABLE,
+                    ),
                 ]
             });
         PROPERTIES.as_ref()
     }
 
-    fn property(&self, _obj: &Self::Type, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+    fn property(&self, obj: &Self::Type, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
         match pspec.name() {
             Contour::OPEN => self.open.get().to_value(),
             Contour::BIGGEST_CURVE => {
@@ -716,7 +1710,7 @@ impl ObjectImpl for ContourInner {
                         true,
                         Some(BiggestCurve {
                             index: ret,
-                            approx_length: d,
+                            length: d,
                             ..
                         }),
                     ) => Some((ret, d)),
@@ -726,7 +1720,7 @@ impl ObjectImpl for ContourInner {
                 let ret = curves
                     .iter()
                     .enumerate()
-                    .map(|(i, c)| (i, c.approx_length()))
+                    .map(|(i, c)| (i, c.length()))
                     .fold((0, f64::NEG_INFINITY), |(prev, acc), (i, len)| {
                         if len > acc {
                             (i, len)
@@ -738,18 +1732,34 @@ impl ObjectImpl for ContourInner {
                 if let Some((ret, d)) = prev.filter(|&(_, prev)| (prev - ret.1).abs() < 20.00) {
                     self.biggest_curve.set(Some(BiggestCurve {
                         index: ret,
-                        approx_length: d,
+                        length: d,
                     }));
                     self.is_contour_modified.set(false);
                     return (ret as u64).to_value();
                 }
                 self.biggest_curve.set(Some(BiggestCurve {
                     index: ret.0,
-                    approx_length: ret.1,
+                    length: ret.1,
                 }));
                 self.is_contour_modified.set(false);
                 (ret.0 as u64).to_value()
             }
+            Contour::SIGNED_AREA => obj.signed_area().to_value(),
+            Contour::DIRECTION => if self.open.get() {
+                "none (open contour)"
+            } else if obj.is_clockwise() {
+                "clockwise"
+            } else {
+                "counter-clockwise"
+            }
+            .to_value(),
+            Contour::NESTING => (obj.current_nesting() as u64).to_value(),
+            Contour::ROLE => if obj.current_nesting() % 2 == 0 {
+                "outer"
+            } else {
+                "counter"
+            }
+            .to_value(),
             _ => unimplemented!("{}", pspec.name()),
         }
     }
@@ -779,3 +1789,563 @@ impl Continuity {
         matches!(self, Self::Positional)
     }
 }
+
+impl_property_window!(Contour);
+
+/// Which way closed contours should run. Counters always run opposite to the contour that
+/// encloses them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DirectionConvention {
+    /// Outer contours run counter-clockwise, as in PostScript/CFF outlines and UFO sources.
+    PostScript,
+    /// Outer contours run clockwise, as in TrueType outlines.
+    TrueType,
+}
+
+impl DirectionConvention {
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::PostScript => "postscript",
+            Self::TrueType => "truetype",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        [Self::PostScript, Self::TrueType]
+            .into_iter()
+            .find(|c| c.name() == name)
+    }
+}
+
+/// Point types an on-curve point can be converted to, from the least to the most constrained.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PointType {
+    /// The handles move independently.
+    Corner,
+    /// The handles are collinear and of equal length (C1).
+    Smooth,
+    /// The handles are collinear but keep their lengths (G1). Next to a line, the curve's handle
+    /// follows the line.
+    Tangent,
+    /// Like [`PointType::Tangent`], with handle lengths that also make the curvature continuous
+    /// (G2).
+    Curvature,
+}
+
+impl PointType {
+    pub const ALL: [Self; 4] = [Self::Corner, Self::Smooth, Self::Tangent, Self::Curvature];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Corner => "corner",
+            Self::Smooth => "smooth",
+            Self::Tangent => "tangent",
+            Self::Curvature => "g2",
+        }
+    }
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Corner => "Corner",
+            Self::Smooth => "Smooth",
+            Self::Tangent => "Tangent",
+            Self::Curvature => "Curvature (G2)",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|t| t.name() == name)
+    }
+}
+
+#[test]
+fn test_contour_extrema() {
+    // A circle with its on-curve points on the extrema.
+    let circle = Contour::new_with_curves(
+        [
+            [(0.0, 100.0), (0.0, 155.0), (45.0, 200.0), (100.0, 200.0)],
+            [
+                (100.0, 200.0),
+                (155.0, 200.0),
+                (200.0, 155.0),
+                (200.0, 100.0),
+            ],
+            [(200.0, 100.0), (200.0, 45.0), (155.0, 0.0), (100.0, 0.0)],
+            [(100.0, 0.0), (45.0, 0.0), (0.0, 45.0), (0.0, 100.0)],
+        ]
+        .iter()
+        .map(|c| Bezier::new(c.iter().map(|&p| p.into()).collect()))
+        .collect(),
+    );
+    circle.close();
+    assert!(circle.missing_extrema().is_empty());
+    assert!(circle.with_extrema().is_none());
+
+    let s_curve = Contour::new_with_curves(vec![Bezier::new(vec![
+        (0.0, 0.0).into(),
+        (100.0, 0.0).into(),
+        (-100.0, 100.0).into(),
+        (0.0, 100.0).into(),
+    ])]);
+    assert_eq!(s_curve.missing_extrema().len(), 2);
+    let with_extrema = s_curve.with_extrema().unwrap();
+    assert!(with_extrema.missing_extrema().is_empty());
+    let curves = with_extrema.curves();
+    assert_eq!(curves.len(), 3);
+    assert!(curves[1].property::<bool>(Bezier::SMOOTH));
+    assert!(!curves[1]
+        .property::<Option<Continuity>>(Bezier::CONTINUITY_IN)
+        .unwrap()
+        .is_positional());
+    // The shape is unchanged.
+    let original = s_curve.curves()[0].clone();
+    for t in [0.1, 0.3, 0.5, 0.7, 0.9] {
+        let expected = original.compute(t);
+        let found = curves
+            .iter()
+            .map(|c| c.nearest(expected).1.distance(expected))
+            .fold(f64::INFINITY, f64::min);
+        assert!(found < 1e-6);
+    }
+}
+
+#[test]
+fn test_contour_transformed() {
+    let square = Contour::new_with_curves(
+        [
+            ((0.0, 0.0), (100.0, 0.0)),
+            ((100.0, 0.0), (100.0, 100.0)),
+            ((100.0, 100.0), (0.0, 100.0)),
+            ((0.0, 100.0), (0.0, 0.0)),
+        ]
+        .into_iter()
+        .map(|(a, b)| Bezier::new(vec![a.into(), b.into()]))
+        .collect(),
+    );
+    square.close();
+    let area = square.signed_area();
+
+    let mut m = Matrix::identity();
+    m.translate(50.0, 20.0);
+    m.scale(2.0, 2.0);
+    let moved = square.transformed(m);
+    assert!((moved.signed_area() - 4.0 * area).abs() < 1e-6);
+    assert_eq!(moved.winding_number((60.0, 30.0).into()), 1);
+    assert_eq!(moved.winding_number((30.0, 30.0).into()), 0);
+    // The original is left as is.
+    assert_eq!(square.curves()[0].points()[0].position, (0.0, 0.0).into());
+
+    // Mirroring keeps the direction.
+    let mirrored = square.transformed(Matrix::new(-1.0, 0.0, 0.0, 1.0, 0.0, 0.0));
+    assert!((mirrored.signed_area() - area).abs() < 1e-6);
+    assert_eq!(mirrored.winding_number((-50.0, 50.0).into()), 1);
+}
+
+#[test]
+fn test_contour_split_knife() {
+    let square = || {
+        let contour = Contour::new_with_curves(
+            [
+                ((0.0, 0.0), (100.0, 0.0)),
+                ((100.0, 0.0), (100.0, 100.0)),
+                ((100.0, 100.0), (0.0, 100.0)),
+                ((0.0, 100.0), (0.0, 0.0)),
+            ]
+            .into_iter()
+            .map(|(a, b)| Bezier::new(vec![a.into(), b.into()]))
+            .collect(),
+        );
+        contour.close();
+        contour
+    };
+    let contour = square();
+    assert!(contour.with_split(0, 0.0).is_none());
+    assert!(contour.with_split(4, 0.5).is_none());
+    let split = contour.with_split(1, 0.25).unwrap();
+    assert_eq!(split.curves().len(), 5);
+    assert_eq!(
+        split.curves()[1].points().last().unwrap().position,
+        (100.0, 25.0).into()
+    );
+    assert!(!split.property::<bool>(Contour::OPEN));
+    assert_eq!(contour.curves().len(), 4);
+
+    assert!(contour
+        .knife((200.0, -10.0).into(), (200.0, 110.0).into(), true)
+        .is_none());
+
+    let open = contour
+        .knife((50.0, -10.0).into(), (50.0, 110.0).into(), false)
+        .unwrap();
+    assert_eq!(open.len(), 2);
+    for piece in &open {
+        assert!(piece.property::<bool>(Contour::OPEN));
+        assert_eq!(piece.curves().len(), 3);
+    }
+
+    let closed = contour
+        .knife((50.0, -10.0).into(), (50.0, 110.0).into(), true)
+        .unwrap();
+    assert_eq!(closed.len(), 2);
+    for piece in &closed {
+        assert!(!piece.property::<bool>(Contour::OPEN));
+        let curves = piece.curves();
+        assert_eq!(curves.len(), 4);
+        /* Every curve starts where the previous one ends. */
+        for (prev, next) in curves.iter().zip(curves.iter().cycle().skip(1)) {
+            assert_eq!(
+                prev.points().last().unwrap().position,
+                next.points()[0].position
+            );
+        }
+    }
+    /* The original is left untouched. */
+    assert_eq!(contour.curves().len(), 4);
+    assert!(!contour.property::<bool>(Contour::OPEN));
+}
+
+#[test]
+fn test_contour_direction() {
+    let square = |x0: f64, y0: f64, size: f64| {
+        let (x1, y1) = (x0 + size, y0 + size);
+        let contour = Contour::new_with_curves(
+            [
+                ((x0, y0), (x1, y0)),
+                ((x1, y0), (x1, y1)),
+                ((x1, y1), (x0, y1)),
+                ((x0, y1), (x0, y0)),
+            ]
+            .into_iter()
+            .map(|(a, b)| Bezier::new(vec![a.into(), b.into()]))
+            .collect(),
+        );
+        contour.close();
+        contour
+    };
+    let outer = square(0.0, 0.0, 100.0);
+    assert!((outer.signed_area() - 10000.0).abs() < 1e-9);
+    assert!(!outer.is_clockwise());
+    assert_eq!(outer.winding_number((50.0, 50.0).into()), 1);
+    assert_eq!(outer.winding_number((150.0, 50.0).into()), 0);
+    let reversed = outer.reversed();
+    assert!(reversed.is_clockwise());
+    assert_eq!(reversed.winding_number((50.0, 50.0).into()), -1);
+    assert!(!outer.is_clockwise());
+
+    /* An "o": both contours counter-clockwise, then a separate dot. */
+    let contours = vec![
+        outer,
+        square(25.0, 25.0, 50.0),
+        square(200.0, 0.0, 10.0).reversed(),
+    ];
+    assert_eq!(Contour::nesting(&contours), vec![0, 1, 0]);
+    let postscript = Contour::with_direction(&contours, DirectionConvention::PostScript).unwrap();
+    assert_eq!(
+        postscript
+            .iter()
+            .map(Contour::is_clockwise)
+            .collect::<Vec<_>>(),
+        vec![false, true, false]
+    );
+    assert!(Contour::with_direction(&postscript, DirectionConvention::PostScript).is_none());
+    let truetype = Contour::with_direction(&postscript, DirectionConvention::TrueType).unwrap();
+    assert_eq!(
+        truetype
+            .iter()
+            .map(Contour::is_clockwise)
+            .collect::<Vec<_>>(),
+        vec![true, false, true]
+    );
+    Contour::link_siblings(&truetype);
+    assert_eq!(truetype[1].property::<String>(Contour::ROLE), "counter");
+    assert_eq!(truetype[2].property::<String>(Contour::ROLE), "outer");
+    /* Moving the dot into the counter changes its nesting without relinking. */
+    let dot = truetype[2]
+        .curves()
+        .iter()
+        .enumerate()
+        .flat_map(|(j, c)| {
+            c.points()
+                .iter()
+                .map(|p| p.glyph_index(2, j))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    truetype[2].transform_points(2, &dot, Matrix::new(1.0, 0.0, 0.0, 1.0, -150.0, 50.0));
+    assert_eq!(truetype[2].property::<u64>(Contour::NESTING), 2);
+}
+
+#[test]
+fn test_contour_simplify() {
+    /* A circle traced with many short smooth cubics, with one corner at the start. */
+    const N: usize = 32;
+    let point = |i: usize| {
+        let a = 2.0 * std::f64::consts::PI * i as f64 / N as f64;
+        Point::from((100.0 * a.cos(), 100.0 * a.sin()))
+    };
+    let tangent = |i: usize| {
+        let a = 2.0 * std::f64::consts::PI * i as f64 / N as f64;
+        Point::from((-a.sin(), a.cos()))
+    };
+    /* Handle length for a circular arc of angle 2π/N. */
+    let k = 100.0 * 4.0 / 3.0 * (std::f64::consts::PI / (2.0 * N as f64)).tan();
+    let contour = Contour::new_with_curves(
+        (0..N)
+            .map(|i| {
+                let curve = Bezier::new(vec![
+                    point(i),
+                    point(i) + k * tangent(i),
+                    point(i + 1) - k * tangent(i + 1),
+                    point(i + 1),
+                ]);
+                curve.set_property(Bezier::SMOOTH, i != 0);
+                curve
+            })
+            .collect(),
+    );
+    contour.close();
+    assert_eq!(contour.point_count(), 3 * N);
+    let simplified = contour.simplified(0.5).unwrap();
+    assert!(simplified.point_count() < contour.point_count() / 4);
+    assert!(!simplified.property::<bool>(Contour::OPEN));
+    let curves = simplified.curves();
+    /* The corner is kept in place and is still a corner. */
+    assert_eq!(curves[0].points()[0].position, point(0));
+    assert!(!curves[0].property::<bool>(Bezier::SMOOTH));
+    assert!(curves[1..]
+        .iter()
+        .all(|c| c.property::<bool>(Bezier::SMOOTH)));
+    for i in 0..N {
+        let p = point(i);
+        let d = curves
+            .iter()
+            .map(|c| c.nearest(p).1.distance(p))
+            .fold(f64::INFINITY, f64::min);
+        assert!(d < 0.5 + 1e-6, "{d}");
+    }
+    /* Straight lines are left alone. */
+    let square = Contour::new_with_curves(
+        [
+            ((0.0, 0.0), (100.0, 0.0)),
+            ((100.0, 0.0), (100.0, 100.0)),
+            ((100.0, 100.0), (0.0, 100.0)),
+        ]
+        .into_iter()
+        .map(|(a, b)| Bezier::new(vec![a.into(), b.into()]))
+        .collect(),
+    );
+    assert!(square.simplified(10.0).is_none());
+}
+
+#[test]
+fn test_contour_from_stroke() {
+    /* An "L" drawn with a slight jitter. */
+    let mut stroke = (0..=50)
+        .map(|i| Point::from((2.0 * f64::from(i), if i % 2 == 0 { 0.2 } else { -0.2 })))
+        .collect::<Vec<_>>();
+    stroke.extend((1..=50).map(|i| Point::from((100.0, 2.0 * f64::from(i)))));
+    let contour = Contour::from_stroke(&stroke, 1.0, std::f64::consts::FRAC_PI_4, false).unwrap();
+    assert!(contour.property::<bool>(Contour::OPEN));
+    let curves = contour.curves();
+    assert_eq!(curves.len(), 2);
+    assert!(!curves[1].property::<bool>(Bezier::SMOOTH));
+    assert!(curves[0].points()[0].position.distance(stroke[0]) < 1e-9);
+    assert!(curves[1].points()[0].position.distance((100.0, 0.2).into()) < 1e-9);
+    assert!(
+        curves[1].points()[3]
+            .position
+            .distance((100.0, 100.0).into())
+            < 1e-9
+    );
+
+    /* A closed circle has no corners. */
+    let circle = (0..100)
+        .map(|i| {
+            let a = 2.0 * std::f64::consts::PI * f64::from(i) / 100.0;
+            Point::from((100.0 * a.cos(), 100.0 * a.sin()))
+        })
+        .collect::<Vec<_>>();
+    let contour = Contour::from_stroke(&circle, 1.0, std::f64::consts::FRAC_PI_4, true).unwrap();
+    assert!(!contour.property::<bool>(Contour::OPEN));
+    let curves = contour.curves();
+    assert!(curves.len() <= 8);
+    assert!(curves.iter().all(|c| c.property::<bool>(Bezier::SMOOTH)));
+    for p in &circle {
+        let d = curves
+            .iter()
+            .map(|c| c.nearest(*p).1.distance(*p))
+            .fold(f64::INFINITY, f64::min);
+        assert!(d < 1.0 + 1e-6, "{d}");
+    }
+    assert!(Contour::from_stroke(&circle[..1], 1.0, 1.0, false).is_none());
+}
+
+#[test]
+fn test_contour_through_points() {
+    let square = [(1.0, 0.0), (0.0, 1.0), (-1.0, 0.0), (0.0, -1.0)]
+        .into_iter()
+        .map(|p| (Point::from(p), false))
+        .collect::<Vec<_>>();
+    let contour = Contour::through_points(&square, true).unwrap();
+    assert!(!contour.property::<bool>(Contour::OPEN));
+    let curves = contour.curves();
+    assert_eq!(curves.len(), 4);
+    assert!(curves.iter().all(|c| c.property::<bool>(Bezier::SMOOTH)));
+
+    /* A corner splits the closed path: it starts there, and its neighbours stay smooth. */
+    let mut cornered = square.clone();
+    cornered[1].1 = true;
+    let curves = Contour::through_points(&cornered, true).unwrap().curves();
+    assert_eq!(curves.len(), 4);
+    assert!(curves[0].points()[0].position.distance((0.0, 1.0).into()) < 1e-9);
+    assert!(!curves[0].property::<bool>(Bezier::SMOOTH));
+    assert!(curves[1..]
+        .iter()
+        .all(|c| c.property::<bool>(Bezier::SMOOTH)));
+
+    /* Adjacent corners are joined by lines. */
+    let open = [
+        ((0.0, 0.0), true),
+        ((10.0, 0.0), true),
+        ((20.0, 10.0), false),
+        ((30.0, 0.0), false),
+    ]
+    .into_iter()
+    .map(|(p, c)| (Point::from(p), c))
+    .collect::<Vec<_>>();
+    let contour = Contour::through_points(&open, false).unwrap();
+    assert!(contour.property::<bool>(Contour::OPEN));
+    let curves = contour.curves();
+    assert_eq!(curves.len(), 3);
+    assert_eq!(curves[0].points().len(), 2);
+    assert!(curves[2].points()[3].position.distance((30.0, 0.0).into()) < 1e-9);
+    assert!(Contour::through_points(&open[..1], false).is_none());
+}
+
+#[test]
+fn test_contour_point_type() {
+    /* Two cubics meeting at a corner at (100, 0). */
+    let contour = Contour::new_with_curves(
+        [
+            [(0.0, 0.0), (30.0, 60.0), (80.0, 40.0), (100.0, 0.0)],
+            [(100.0, 0.0), (130.0, 20.0), (170.0, 80.0), (200.0, 0.0)],
+        ]
+        .iter()
+        .map(|c| Bezier::new(c.iter().copied().map(Point::from).collect()))
+        .collect(),
+    );
+    let joint = contour.curves()[1].points()[0].glyph_index(0, 1);
+    let end = contour.curves()[1].points()[3].glyph_index(0, 1);
+    assert_eq!(contour.joint(joint), Some((0, 1)));
+    assert_eq!(contour.joint(end), None);
+    assert_eq!(
+        contour.change_continuity(joint, Continuity::Velocity),
+        Some(Continuity::Positional)
+    );
+    assert!(contour.curves()[1].property::<bool>(Bezier::SMOOTH));
+
+    let handles = |contour: &Contour| {
+        let curves = contour.curves();
+        (curves[0].positions(), curves[1].positions())
+    };
+    let center = Point::from((100.0, 0.0));
+    let cross = |a: Point, b: Point| a.x * b.y - a.y * b.x;
+
+    let (continuity, positions) = contour.point_type_change(joint, PointType::Smooth).unwrap();
+    assert!(matches!(continuity, Continuity::Velocity));
+    contour.set_point_positions(0, &positions);
+    let (prev, next) = handles(&contour);
+    assert!((next[1] - center).distance(center - prev[2]) < 1e-9);
+
+    let (_, positions) = contour
+        .point_type_change(joint, PointType::Curvature)
+        .unwrap();
+    contour.set_point_positions(0, &positions);
+    let (prev, next) = handles(&contour);
+    let (a, b) = ((center - prev[2]).norm(), (next[1] - center).norm());
+    assert!(cross(center - prev[2], next[1] - center).abs() < 1e-9);
+    let tangent = (next[1] - center).unit();
+    let kappa_prev = cross(tangent, prev[1] - center) / (a * a);
+    let kappa_next = cross(tangent, next[2] - center) / (b * b);
+    assert!(
+        (kappa_prev - kappa_next).abs() < 1e-9,
+        "{kappa_prev} {kappa_next}"
+    );
+
+    /* Next to a line, the curve's handle follows it. */
+    let contour = Contour::new_with_curves(vec![
+        Bezier::new(vec![(0.0, 0.0).into(), (100.0, 0.0).into()]),
+        Bezier::new(
+            [(100.0, 0.0), (120.0, 30.0), (170.0, 80.0), (200.0, 0.0)]
+                .into_iter()
+                .map(Point::from)
+                .collect(),
+        ),
+    ]);
+    let joint = contour.curves()[1].points()[0].glyph_index(0, 1);
+    let (_, positions) = contour
+        .point_type_change(joint, PointType::Tangent)
+        .unwrap();
+    assert_eq!(positions.len(), 1);
+    let (curve, point, position) = positions[0];
+    assert_eq!((curve, point), (1, 1));
+    assert!(position.y.abs() < 1e-9);
+    assert!((position.x - 100.0 - 13.0f64.sqrt() * 10.0).abs() < 1e-9);
+    assert_eq!(
+        contour
+            .point_type_change(joint, PointType::Corner)
+            .map(|(_, p)| p.len()),
+        Some(0)
+    );
+    assert_eq!(PointType::from_name("g2"), Some(PointType::Curvature));
+}
+
+#[test]
+fn test_contour_curvature_comb() {
+    let circle = Contour::new_with_curves(
+        [
+            [(0.0, 100.0), (0.0, 155.0), (45.0, 200.0), (100.0, 200.0)],
+            [
+                (100.0, 200.0),
+                (155.0, 200.0),
+                (200.0, 155.0),
+                (200.0, 100.0),
+            ],
+            [(200.0, 100.0), (200.0, 45.0), (155.0, 0.0), (100.0, 0.0)],
+            [(100.0, 0.0), (45.0, 0.0), (0.0, 45.0), (0.0, 100.0)],
+        ]
+        .iter()
+        .map(|c| Bezier::new(c.iter().copied().map(Point::from).collect()))
+        .collect(),
+    );
+    circle.close();
+    let comb = circle.curvature_comb(9);
+    assert_eq!(comb.len(), 4);
+    for (position, normal, curvature) in comb.into_iter().flatten() {
+        /* Clockwise, with the centre of curvature at the centre of the circle. */
+        assert!((curvature + 0.01).abs() < 0.001, "{curvature}");
+        let centre = position + (1.0 / curvature) * normal;
+        assert!(centre.distance((100.0, 100.0).into()) < 10.0);
+    }
+    assert!(circle.curvature_discontinuities().is_empty());
+
+    /* A stadium: the curvature jumps where the straight sides meet the round ends. */
+    let stadium = Contour::new_with_curves(
+        [
+            vec![(0.0, 0.0), (100.0, 0.0)],
+            vec![(100.0, 0.0), (155.0, 0.0), (155.0, 100.0), (100.0, 100.0)],
+            vec![(100.0, 100.0), (0.0, 100.0)],
+            vec![(0.0, 100.0), (-55.0, 100.0), (-55.0, 0.0), (0.0, 0.0)],
+        ]
+        .iter()
+        .map(|c| Bezier::new(c.iter().copied().map(Point::from).collect()))
+        .collect(),
+    );
+    stadium.close();
+    let discontinuities = stadium.curvature_discontinuities();
+    assert_eq!(discontinuities.len(), 4);
+    assert!(discontinuities[0].1.distance((0.0, 1.0).into()) < 1e-12);
+    assert_eq!(discontinuities[0].2, 0.0);
+    assert!(discontinuities[0].3 > 0.0);
+    assert_eq!(discontinuities[0].0, Point::from((100.0, 0.0)));
+}
@@ -36,6 +36,11 @@ pub struct GlyphMetadataInner {
     pub kinds: RefCell<(GlyphKind, Vec<GlyphKind>)>,
     pub filename: RefCell<String>,
     pub glif_source: RefCell<String>,
+    /// Path of the `.glif` file while its outline hasn't been parsed yet, see [`Glyph::load`].
+    pub glif_path: RefCell<Option<PathBuf>>,
+    /// Why `glif_path` couldn't be parsed. The glyph then stays unloaded, so that saving
+    /// leaves its file as it is.
+    pub load_error: RefCell<Option<String>>,
     pub glyph_ref: OnceCell<Rc<RefCell<Glyph>>>,
 }
 
@@ -93,6 +98,13 @@ impl ObjectImpl for GlyphMetadataInner {
                         glib::ParamFlags::READWRITE | UI_EDITABLE,
                     ),
                     def_param!(f64 GlyphMetadata::WIDTH, f64::MIN, 0.0),
+                    glib::ParamSpecBoolean::new(
+                        GlyphMetadata::LOADED,
+                        GlyphMetadata::LOADED,
+                        "Whether the glyph's outline has been parsed.",
+                        true,
+                        glib::ParamFlags::READABLE,
+                    ),
                     glib::ParamSpecObject::new(
                         GlyphMetadata::LAYER,
                         GlyphMetadata::LAYER,
@@ -125,6 +137,7 @@ impl ObjectImpl for GlyphMetadataInner {
             GlyphMetadata::FILENAME => Some(self.filename.borrow().to_string()).to_value(),
             GlyphMetadata::WIDTH => self.width.get().unwrap_or_default().to_value(),
             GlyphMetadata::LAYER => self.layer.borrow().to_value(),
+            GlyphMetadata::LOADED => self.glif_path.borrow().is_none().to_value(),
             _ => unimplemented!("{}", pspec.name()),
         }
     }
@@ -194,6 +207,7 @@ impl GlyphMetadata {
     pub const NAME: &'static str = "name";
     pub const LAYER: &'static str = "layer";
     pub const WIDTH: &'static str = "width";
+    pub const LOADED: &'static str = "loaded";
 
     pub fn new() -> Self {
         let ret: Self = glib::Object::new::<Self>(&[]).unwrap();
@@ -0,0 +1,472 @@
+/*
+ * gerb
+ *
+ * Copyright 2022 - Manos Pitsidianakis
+ *
+ * This file is part of gerb.
+ *
+ * gerb is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * gerb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with gerb. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Metric keys: advance width and sidebearings that follow other glyphs, stored in the glyph
+//! lib with the same keys Glyphs.app uses, e.g. `=o`, `=|o` for the opposite side of `o`, or
+//! `=H*0.5+10`.
+
+use super::*;
+
+/// A glyph metric that can be linked to other glyphs with a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Metric {
+    Left,
+    Right,
+    Width,
+}
+
+impl Metric {
+    pub const ALL: [Self; 3] = [Self::Left, Self::Right, Self::Width];
+
+    /// Glyph lib key the metric key is stored under.
+    pub const fn lib_key(self) -> &'static str {
+        match self {
+            Self::Left => "com.schriftgestaltung.Glyphs.glyph.leftMetricsKey",
+            Self::Right => "com.schriftgestaltung.Glyphs.glyph.rightMetricsKey",
+            Self::Width => "com.schriftgestaltung.Glyphs.glyph.widthMetricsKey",
+        }
+    }
+
+    /// The metric a key refers to with `|`: the other sidebearing, or the width itself.
+    pub const fn opposite(self) -> Self {
+        match self {
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
+            Self::Width => Self::Width,
+        }
+    }
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Left => "LSB",
+            Self::Right => "RSB",
+            Self::Width => "Width",
+        }
+    }
+}
+
+/// A parsed metric key: arithmetic with `+ - * /` and parentheses over numbers and glyph
+/// names. A glyph name stands for the same metric of that glyph, or the opposite one if it is
+/// prefixed with `|`. A leading `=` is optional.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MetricExpr {
+    Number(f64),
+    Glyph { name: String, opposite: bool },
+    Neg(Box<Self>),
+    Binary(char, Box<Self>, Box<Self>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Name(String),
+    Op(char),
+}
+
+fn tokenize(s: &str) -> Result<Vec<Token>, String> {
+    const OPERATORS: &str = "+-*/()|";
+    let mut ret = vec![];
+    let mut chars = s.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if OPERATORS.contains(c) {
+            ret.push(Token::Op(c));
+            chars.next();
+        } else if c.is_ascii_digit() || c == '.' {
+            let mut number = String::new();
+            while let Some(&c) = chars.peek().filter(|c| c.is_ascii_digit() || **c == '.') {
+                number.push(c);
+                chars.next();
+            }
+            if number == "." || chars.peek().map_or(false, |c| c.is_alphabetic()) {
+                /* A name that starts with a dot or digits, e.g. `.notdef`. */
+                while let Some(&c) = chars
+                    .peek()
+                    .filter(|c| !c.is_whitespace() && !OPERATORS.contains(**c))
+                {
+                    number.push(c);
+                    chars.next();
+                }
+                ret.push(Token::Name(number));
+                continue;
+            }
+            ret.push(Token::Number(
+                number
+                    .parse()
+                    .map_err(|_| format!("`{number}` is not a number."))?,
+            ));
+        } else {
+            let mut name = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() || (OPERATORS.contains(c) && c != '-') {
+                    break;
+                }
+                if c == '-' {
+                    /* Names like `a-cy` contain hyphens, `H-10` is a subtraction. */
+                    let mut rest = chars.clone();
+                    rest.next();
+                    if !rest.peek().map_or(false, |c| c.is_alphabetic()) {
+                        break;
+                    }
+                }
+                name.push(c);
+                chars.next();
+            }
+            ret.push(Token::Name(name));
+        }
+    }
+    Ok(ret)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let ret = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        ret
+    }
+
+    fn expr(&mut self) -> Result<MetricExpr, String> {
+        let mut lhs = self.term()?;
+        while let Some(&Token::Op(op @ ('+' | '-'))) = self.peek() {
+            self.next();
+            lhs = MetricExpr::Binary(op, Box::new(lhs), Box::new(self.term()?));
+        }
+        Ok(lhs)
+    }
+
+    fn term(&mut self) -> Result<MetricExpr, String> {
+        let mut lhs = self.factor()?;
+        while let Some(&Token::Op(op @ ('*' | '/'))) = self.peek() {
+            self.next();
+            lhs = MetricExpr::Binary(op, Box::new(lhs), Box::new(self.factor()?));
+        }
+        Ok(lhs)
+    }
+
+    fn factor(&mut self) -> Result<MetricExpr, String> {
+        match self.next() {
+            Some(Token::Number(n)) => Ok(MetricExpr::Number(n)),
+            Some(Token::Name(name)) => Ok(MetricExpr::Glyph {
+                name,
+                opposite: false,
+            }),
+            Some(Token::Op('|')) => match self.next() {
+                Some(Token::Name(name)) => Ok(MetricExpr::Glyph {
+                    name,
+                    opposite: true,
+                }),
+                _ => Err("Expected a glyph name after `|`.".to_string()),
+            },
+            Some(Token::Op('-')) => Ok(MetricExpr::Neg(Box::new(self.factor()?))),
+            Some(Token::Op('(')) => {
+                let ret = self.expr()?;
+                if self.next() != Some(Token::Op(')')) {
+                    return Err("Missing `)`.".to_string());
+                }
+                Ok(ret)
+            }
+            Some(Token::Op(c)) => Err(format!("Unexpected `{c}`.")),
+            None => Err("Unexpected end of key.".to_string()),
+        }
+    }
+}
+
+impl std::str::FromStr for MetricExpr {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let s = s.strip_prefix('=').unwrap_or(s);
+        let mut parser = Parser {
+            tokens: tokenize(s)?,
+            pos: 0,
+        };
+        let ret = parser.expr()?;
+        if let Some(t) = parser.peek() {
+            return Err(format!("Unexpected {t:?} in `{s}`."));
+        }
+        Ok(ret)
+    }
+}
+
+impl MetricExpr {
+    /// Evaluates the key for `metric`, with `lookup` returning the value of a metric of
+    /// another glyph.
+    pub fn evaluate(
+        &self,
+        metric: Metric,
+        lookup: &dyn Fn(&str, Metric) -> Result<f64, String>,
+    ) -> Result<f64, String> {
+        match self {
+            Self::Number(n) => Ok(*n),
+            Self::Glyph { name, opposite } => {
+                lookup(name, if *opposite { metric.opposite() } else { metric })
+            }
+            Self::Neg(e) => Ok(-e.evaluate(metric, lookup)?),
+            Self::Binary(op, lhs, rhs) => {
+                let (lhs, rhs) = (lhs.evaluate(metric, lookup)?, rhs.evaluate(metric, lookup)?);
+                match op {
+                    '+' => Ok(lhs + rhs),
+                    '-' => Ok(lhs - rhs),
+                    '*' => Ok(lhs * rhs),
+                    _ if rhs == 0.0 => Err("Division by zero.".to_string()),
+                    _ => Ok(lhs / rhs),
+                }
+            }
+        }
+    }
+
+    /// Names of the glyphs the key refers to.
+    pub fn references(&self) -> Vec<&str> {
+        match self {
+            Self::Number(_) => vec![],
+            Self::Glyph { name, .. } => vec![name.as_str()],
+            Self::Neg(e) => e.references(),
+            Self::Binary(_, lhs, rhs) => {
+                let mut ret = lhs.references();
+                ret.extend(rhs.references());
+                ret
+            }
+        }
+    }
+}
+
+impl Glyph {
+    /// The metric key for `metric` stored in the glyph lib, if any.
+    pub fn metric_key(&self, metric: Metric) -> Option<&str> {
+        self.lib
+            .get(metric.lib_key())
+            .and_then(plist::Value::as_string)
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+    }
+
+    /// Current value of `metric`. Sidebearings are `None` if the glyph has no outline.
+    pub fn metric(&self, metric: Metric) -> Option<f64> {
+        match metric {
+            Metric::Left => self.sidebearings().map(|(l, _)| l),
+            Metric::Right => self.sidebearings().map(|(_, r)| r),
+            Metric::Width => Some(self.width().unwrap_or(0.0)),
+        }
+    }
+
+    /// Names of the glyphs this glyph's metric keys refer to. Keys that don't parse are
+    /// ignored.
+    pub fn metric_key_references(&self) -> Vec<String> {
+        Metric::ALL
+            .into_iter()
+            .filter_map(|m| self.metric_key(m)?.parse::<MetricExpr>().ok())
+            .flat_map(|e| {
+                e.references()
+                    .into_iter()
+                    .map(str::to_string)
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// Values the metric keys evaluate to, looking up other glyphs in `glyphs`. The left
+    /// sidebearing is evaluated first, so that keys referring to this glyph's own left
+    /// sidebearing see its new value.
+    pub fn metric_key_targets(
+        &self,
+        glyphs: &IndexMap<String, Rc<RefCell<Self>>>,
+    ) -> Result<Vec<(Metric, f64)>, String> {
+        let own_name = self.name().clone();
+        let mut ret: Vec<(Metric, f64)> = vec![];
+        for metric in Metric::ALL {
+            let Some(key) = self.metric_key(metric) else {
+                continue;
+            };
+            let expr = key
+                .parse::<MetricExpr>()
+                .map_err(|err| format!("{} key `{key}` of `{own_name}`: {err}", metric.label()))?;
+            let lookup = |name: &str, m: Metric| -> Result<f64, String> {
+                if name == own_name {
+                    if let Some((_, v)) = ret.iter().find(|(r, _)| *r == m) {
+                        return Ok(*v);
+                    }
+                    return self
+                        .metric(m)
+                        .ok_or_else(|| format!("`{name}` has no outline."));
+                }
+                let glyph = glyphs
+                    .get(name)
+                    .ok_or_else(|| format!("Unknown glyph `{name}`."))?;
+                Self::load(glyph).map_err(|err| err.to_string())?;
+                let value = glyph.borrow().metric(m);
+                value.ok_or_else(|| format!("`{name}` has no outline."))
+            };
+            let value = expr
+                .evaluate(metric, &lookup)
+                .map_err(|err| format!("{} key `{key}` of `{own_name}`: {err}", metric.label()))?;
+            ret.push((metric, value));
+        }
+        Ok(ret)
+    }
+
+    /// Metrics whose current value differs from what their key evaluates to. Keys that can't
+    /// be evaluated are reported as out of sync as well.
+    pub fn out_of_sync_metrics(&self, glyphs: &IndexMap<String, Rc<RefCell<Self>>>) -> Vec<Metric> {
+        match self.metric_key_targets(glyphs) {
+            Ok(targets) => targets
+                .into_iter()
+                .filter(|(m, v)| self.metric(*m).map_or(true, |c| (c - v).abs() > 0.01))
+                .map(|(m, _)| m)
+                .collect(),
+            Err(_) => Metric::ALL
+                .into_iter()
+                .filter(|m| self.metric_key(*m).is_some())
+                .collect(),
+        }
+    }
+
+    /// Returns an action that applies the values of the glyph's metric keys, moving the outline
+    /// for the left sidebearing and changing the advance width for the right sidebearing or
+    /// width. Returns `None` if the metrics are already in sync.
+    pub fn sync_metrics_action(
+        glyph: &Rc<RefCell<Self>>,
+        glyphs: &IndexMap<String, Rc<RefCell<Self>>>,
+    ) -> Result<Option<Action>, String> {
+        let (dx, width) = {
+            let g = glyph.borrow();
+            let targets = g.metric_key_targets(glyphs)?;
+            let target = |m: Metric| targets.iter().find(|(t, _)| *t == m).map(|(_, v)| *v);
+            let dx = match (target(Metric::Left), g.metric(Metric::Left)) {
+                (Some(lsb), Some(current)) => lsb - current,
+                _ => 0.0,
+            };
+            let width = g.width().unwrap_or(0.0);
+            let width = match (target(Metric::Right), g.bounds(), target(Metric::Width)) {
+                (Some(rsb), Some((_, max)), _) => max.x + dx + rsb,
+                (_, _, Some(width)) => width,
+                _ => width + dx,
+            };
+            (dx, width.max(0.0))
+        };
+        if dx.abs() <= 0.01 && (width - glyph.borrow().width().unwrap_or(0.0)).abs() <= 0.01 {
+            return Ok(None);
+        }
+        Ok(Some(Self::metrics_action(glyph, dx, width, false)))
+    }
+
+    /// Returns an action that stores `keys` in the glyph lib, removing the keys that are
+    /// `None` or empty.
+    pub fn set_metric_keys_action(
+        glyph: &Rc<RefCell<Self>>,
+        keys: Vec<(Metric, Option<String>)>,
+    ) -> Action {
+        let old_lib = glyph.borrow().lib.clone();
+        let mut lib = old_lib.clone();
+        for (metric, key) in keys {
+            match key.map(|k| k.trim().to_string()).filter(|k| !k.is_empty()) {
+                Some(key) => {
+                    lib.insert(metric.lib_key().to_string(), plist::Value::String(key));
+                }
+                None => {
+                    lib.shift_remove(metric.lib_key());
+                }
+            }
+        }
+        let name = glyph.borrow().name().clone();
+        let set = |lib: IndexMap<String, plist::Value>| {
+            clone!(@weak glyph => move || {
+                glyph.borrow_mut().lib = lib.clone();
+                glyph.borrow().metadata.set_property(GlyphMetadata::MODIFIED, true);
+            })
+        };
+        Action {
+            stamp: EventStamp {
+                t: std::any::TypeId::of::<Self>(),
+                property: "lib",
+                id: name.into_bytes().into_boxed_slice(),
+            },
+            compress: false,
+            redo: Box::new(set(lib)),
+            undo: Box::new(set(old_lib)),
+        }
+    }
+}
+
+#[test]
+fn test_metric_key_parse() {
+    use MetricExpr::{Binary, Number};
+
+    let glyph = |name: &str, opposite| MetricExpr::Glyph {
+        name: name.to_string(),
+        opposite,
+    };
+    assert_eq!("=o".parse::<MetricExpr>(), Ok(glyph("o", false)));
+    assert_eq!("=|o".parse::<MetricExpr>(), Ok(glyph("o", true)));
+    assert_eq!(" 50 ".parse::<MetricExpr>(), Ok(Number(50.0)));
+    assert_eq!("=a-cy".parse::<MetricExpr>(), Ok(glyph("a-cy", false)));
+    assert_eq!(
+        "=H-10".parse::<MetricExpr>(),
+        Ok(Binary(
+            '-',
+            Box::new(glyph("H", false)),
+            Box::new(Number(10.0))
+        ))
+    );
+    assert_eq!(
+        "=.notdef".parse::<MetricExpr>(),
+        Ok(glyph(".notdef", false))
+    );
+    assert!("=".parse::<MetricExpr>().is_err());
+    assert!("=o)".parse::<MetricExpr>().is_err());
+    assert!("=(o".parse::<MetricExpr>().is_err());
+    assert!("=|".parse::<MetricExpr>().is_err());
+    assert_eq!(
+        "=(n + |o) / 2".parse::<MetricExpr>().unwrap().references(),
+        vec!["n", "o"]
+    );
+}
+
+#[test]
+fn test_metric_key_evaluate() {
+    let lookup = |name: &str, metric: Metric| -> Result<f64, String> {
+        match (name, metric) {
+            ("H", Metric::Left) => Ok(80.0),
+            ("H", Metric::Right) => Ok(70.0),
+            ("H", Metric::Width) => Ok(600.0),
+            _ => Err(format!("Unknown glyph `{name}`.")),
+        }
+    };
+    let eval = |key: &str, metric| key.parse::<MetricExpr>().unwrap().evaluate(metric, &lookup);
+    assert_eq!(eval("=H", Metric::Left), Ok(80.0));
+    assert_eq!(eval("=|H", Metric::Left), Ok(70.0));
+    assert_eq!(eval("=H*0.5+10", Metric::Right), Ok(45.0));
+    assert_eq!(eval("=-H + 2 * (H - 50)", Metric::Left), Ok(20.0));
+    assert_eq!(eval("=|H", Metric::Width), Ok(600.0));
+    assert_eq!(eval("=H/2", Metric::Width), Ok(300.0));
+    assert!(eval("=H/0", Metric::Width).is_err());
+    assert!(eval("=n", Metric::Width).is_err());
+}
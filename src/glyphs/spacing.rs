@@ -0,0 +1,211 @@
+/*
+ * gerb
+ *
+ * Copyright 2022 - Manos Pitsidianakis
+ *
+ * This file is part of gerb.
+ *
+ * gerb is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * gerb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with gerb. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Spacing many glyphs at once: setting, adjusting or copying sidebearings and advance widths.
+
+use super::*;
+
+/// What to do with one metric in a [`Spacing`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum SpacingOp {
+    #[default]
+    Keep,
+    Set(f64),
+    Add(f64),
+    /// Percentage of the current value.
+    Percent(f64),
+    /// The same metric of the source glyph, see [`Spacing::apply`].
+    Copy,
+}
+
+impl SpacingOp {
+    fn apply(self, value: f64, source: Option<f64>) -> f64 {
+        match self {
+            Self::Keep => value,
+            Self::Set(v) => v,
+            Self::Add(v) => value + v,
+            Self::Percent(v) => value * v / 100.0,
+            Self::Copy => source.unwrap_or(value),
+        }
+    }
+}
+
+/// Sidebearings and advance width of a glyph. Sidebearings are `None` if the glyph has no
+/// outline.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Metrics {
+    pub lsb: Option<f64>,
+    pub rsb: Option<f64>,
+    pub width: f64,
+}
+
+/// A spacing operation applied to many glyphs at once.
+///
+/// The sidebearings are changed first, moving the outline for the left one and changing the
+/// advance width for either. The width operation then changes the advance width again by
+/// changing the right sidebearing. Centering splits the sum of the resulting sidebearings
+/// equally, keeping the advance width.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Spacing {
+    pub lsb: SpacingOp,
+    pub rsb: SpacingOp,
+    pub width: SpacingOp,
+    pub center: bool,
+}
+
+impl Spacing {
+    /// Returns the metrics of a glyph with metrics `current` after the operation. `source`
+    /// are the metrics of the glyph [`SpacingOp::Copy`] copies from.
+    pub fn apply(&self, current: Metrics, source: Option<Metrics>) -> Metrics {
+        let Metrics { lsb, rsb, width } = current;
+        let (Some(lsb), Some(rsb)) = (lsb, rsb) else {
+            return Metrics {
+                width: self.width.apply(width, source.map(|s| s.width)).max(0.0),
+                ..current
+            };
+        };
+        let ink = width - lsb - rsb;
+        let mut new_lsb = self.lsb.apply(lsb, source.and_then(|s| s.lsb));
+        let mut new_rsb = self.rsb.apply(rsb, source.and_then(|s| s.rsb));
+        if self.width != SpacingOp::Keep {
+            let new_width = self.width.apply(width, source.map(|s| s.width));
+            new_rsb = new_width - new_lsb - ink;
+        }
+        if self.center {
+            let total = new_lsb + new_rsb;
+            new_lsb = total / 2.0;
+            new_rsb = total / 2.0;
+        }
+        Metrics {
+            lsb: Some(new_lsb),
+            rsb: Some(new_rsb),
+            width: (new_lsb + ink + new_rsb).max(0.0),
+        }
+    }
+}
+
+impl Glyph {
+    /// Current sidebearings and advance width.
+    pub fn metrics(&self) -> Metrics {
+        let sidebearings = self.sidebearings();
+        Metrics {
+            lsb: sidebearings.map(|(l, _)| l),
+            rsb: sidebearings.map(|(_, r)| r),
+            width: self.width().unwrap_or(0.0),
+        }
+    }
+
+    /// Returns an action that gives the glyph the metrics `target`, moving the outline for the
+    /// left sidebearing. Returns `None` if nothing would change.
+    pub fn set_metrics_action(glyph: &Rc<RefCell<Self>>, target: Metrics) -> Option<Action> {
+        let current = glyph.borrow().metrics();
+        let dx = match (target.lsb, current.lsb) {
+            (Some(new), Some(old)) => new - old,
+            _ => 0.0,
+        };
+        if dx.abs() <= 0.001 && (target.width - current.width).abs() <= 0.001 {
+            return None;
+        }
+        Some(Self::metrics_action(glyph, dx, target.width, false))
+    }
+}
+
+#[test]
+fn test_spacing_apply() {
+    let current = Metrics {
+        lsb: Some(50.0),
+        rsb: Some(30.0),
+        width: 580.0,
+    };
+    let source = Metrics {
+        lsb: Some(70.0),
+        rsb: Some(60.0),
+        width: 700.0,
+    };
+    let apply = |spacing: Spacing| spacing.apply(current, Some(source));
+
+    assert_eq!(apply(Spacing::default()), current);
+    assert_eq!(
+        apply(Spacing {
+            lsb: SpacingOp::Set(60.0),
+            rsb: SpacingOp::Add(-10.0),
+            ..Spacing::default()
+        }),
+        Metrics {
+            lsb: Some(60.0),
+            rsb: Some(20.0),
+            width: 580.0,
+        }
+    );
+    assert_eq!(
+        apply(Spacing {
+            lsb: SpacingOp::Percent(50.0),
+            width: SpacingOp::Set(600.0),
+            ..Spacing::default()
+        }),
+        Metrics {
+            lsb: Some(25.0),
+            rsb: Some(75.0),
+            width: 600.0,
+        }
+    );
+    assert_eq!(
+        apply(Spacing {
+            center: true,
+            ..Spacing::default()
+        }),
+        Metrics {
+            lsb: Some(40.0),
+            rsb: Some(40.0),
+            width: 580.0,
+        }
+    );
+    assert_eq!(
+        apply(Spacing {
+            lsb: SpacingOp::Copy,
+            rsb: SpacingOp::Copy,
+            ..Spacing::default()
+        }),
+        Metrics {
+            lsb: Some(70.0),
+            rsb: Some(60.0),
+            width: 630.0,
+        }
+    );
+    let empty = Metrics {
+        lsb: None,
+        rsb: None,
+        width: 500.0,
+    };
+    assert_eq!(
+        Spacing {
+            lsb: SpacingOp::Set(10.0),
+            width: SpacingOp::Copy,
+            ..Spacing::default()
+        }
+        .apply(empty, Some(source)),
+        Metrics {
+            lsb: None,
+            rsb: None,
+            width: 700.0,
+        }
+    );
+}
@@ -0,0 +1,172 @@
+/*
+ * gerb
+ *
+ * Copyright 2022 - Manos Pitsidianakis
+ *
+ * This file is part of gerb.
+ *
+ * gerb is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * gerb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with gerb. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Laying out a line of text, e.g. `HHxHH` or `/a/a.sc/a`, with the glyphs of a layer.
+
+use super::*;
+use std::collections::HashMap;
+
+/// A glyph of a line of text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TextItem {
+    /// A glyph name, either from a `/glyphname` escape or of the glyph that encodes a
+    /// character.
+    Name(String),
+    /// A character no glyph encodes.
+    Missing(char),
+}
+
+impl std::fmt::Display for TextItem {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Name(name) => write!(fmt, "{name}"),
+            Self::Missing(c) => write!(fmt, "{c}"),
+        }
+    }
+}
+
+/// Splits `text` into glyphs. `/name` stands for the glyph called `name`: the name ends at the
+/// next whitespace or slash, and a single space after it is skipped so that `/a.sc b` is two
+/// glyphs. A slash that isn't followed by a name, or `//`, is the slash character itself.
+/// `char_names` maps characters to the names of the glyphs that encode them, see
+/// [`char_names`].
+pub fn parse_text(text: &str, char_names: &HashMap<char, String>) -> Vec<TextItem> {
+    let from_char = |c: char| {
+        char_names
+            .get(&c)
+            .map_or(TextItem::Missing(c), |name| TextItem::Name(name.clone()))
+    };
+    let mut ret = vec![];
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '/' {
+            ret.push(from_char(c));
+            continue;
+        }
+        let mut name = String::new();
+        while let Some(&n) = chars.peek() {
+            if n == '/' || n.is_whitespace() {
+                break;
+            }
+            name.push(n);
+            chars.next();
+        }
+        if name.is_empty() {
+            if chars.peek() == Some(&'/') {
+                chars.next();
+            }
+            ret.push(from_char('/'));
+            continue;
+        }
+        if chars.peek() == Some(&' ') {
+            chars.next();
+        }
+        ret.push(TextItem::Name(name));
+    }
+    ret
+}
+
+/// A glyph of a laid out line of text.
+#[derive(Debug, Clone)]
+pub struct PlacedGlyph {
+    pub item: TextItem,
+    /// `None` if the layer has no such glyph.
+    pub glyph: Option<Rc<RefCell<Glyph>>>,
+    /// Position of the glyph origin in font units, kerning included.
+    pub x: f64,
+    /// Advance width, or `missing_width` for missing glyphs.
+    pub width: f64,
+    /// Kerning between the previous glyph and this one.
+    pub kerning: f64,
+}
+
+/// Places the glyphs of `text` one after the other by their advance widths. `kerning`, if
+/// given, returns the kerning value between two glyph names. Glyphs the layer doesn't have
+/// take up `missing_width`.
+pub fn layout_text(
+    text: &str,
+    glyphs: &IndexMap<String, Rc<RefCell<Glyph>>>,
+    kerning: Option<&dyn Fn(&str, &str) -> f64>,
+    missing_width: f64,
+) -> Vec<PlacedGlyph> {
+    let mut ret: Vec<PlacedGlyph> = vec![];
+    let mut x = 0.0;
+    for item in parse_text(text, &char_names(glyphs)) {
+        let glyph = match item {
+            TextItem::Name(ref name) => glyphs.get(name).cloned(),
+            TextItem::Missing(_) => None,
+        };
+        let kerning = match (kerning, ret.last().map(|p| &p.item), &item) {
+            (Some(kerning), Some(TextItem::Name(prev)), TextItem::Name(name))
+                if glyph.is_some() =>
+            {
+                kerning(prev, name)
+            }
+            _ => 0.0,
+        };
+        let width = glyph
+            .as_ref()
+            .map_or(missing_width, |g| g.borrow().width().unwrap_or(0.0));
+        x += kerning;
+        ret.push(PlacedGlyph {
+            item,
+            glyph,
+            x,
+            width,
+            kerning,
+        });
+        x += width;
+    }
+    ret
+}
+
+#[test]
+fn test_parse_text() {
+    let char_names = HashMap::from([
+        ('T', "T".to_string()),
+        ('a', "a".to_string()),
+        ('x', "x".to_string()),
+        ('/', "slash".to_string()),
+    ]);
+    let name = |n: &str| TextItem::Name(n.to_string());
+    assert_eq!(
+        parse_text("Ta/a.sc /T/uni0301 x//", &char_names),
+        vec![
+            name("T"),
+            name("a"),
+            name("a.sc"),
+            name("T"),
+            name("uni0301"),
+            name("x"),
+            name("slash"),
+        ]
+    );
+    assert_eq!(
+        parse_text("a é/", &char_names),
+        vec![
+            name("a"),
+            TextItem::Missing(' '),
+            TextItem::Missing('é'),
+            name("slash"),
+        ]
+    );
+    assert!(parse_text("", &char_names).is_empty());
+}
@@ -141,7 +141,9 @@ pub mod prelude {
     pub use editor::*;
     pub use error::Error;
     pub use glyphs::metadata::GlyphMetadata;
-    pub use glyphs::{Continuity, Glyph, GlyphPointIndex, Guideline};
+    pub use glyphs::{
+        Continuity, DirectionConvention, Glyph, GlyphPointIndex, Guideline, PointType,
+    };
     pub use gtk::prelude::*;
     pub use gtk::subclass::prelude::ObjectSubclassIsExt;
     pub use indexmap::{IndexMap, IndexSet};
@@ -161,8 +163,8 @@ pub mod prelude {
     // utility traits:
     pub use utils::{Modified, StyleReadOnly};
     pub use views::{
-        canvas, canvas::CanvasSettings, Canvas, Collection, Overlay, Transformation, UnitPoint,
-        ViewPoint,
+        canvas, canvas::CanvasSettings, Canvas, Collection, Overlay, TextPreview, Transformation,
+        UnitPoint, ViewPoint,
     };
     pub use window::Workspace;
 
@@ -35,7 +35,7 @@ fn main() {
         OptionFlags::IN_MAIN,
         OptionArg::Filename,
         "UFO project path to load on launch",
-        Some("Specify a UFO directory in your filesystem to load on launch"),
+        Some("Specify a UFO directory or .ufoz archive in your filesystem to load on launch"),
     );
     app.add_main_option(
         "version",
@@ -38,11 +38,20 @@ pub struct ProjectInner {
     modified: Cell<bool>,
     pub last_saved: RefCell<Option<u64>>,
     pub path: RefCell<PathBuf>,
+    /// `.ufoz` archive this project was opened from, if any. `path` then points to the extracted
+    /// UFO directory and saving also writes the archive.
+    pub archive_path: RefCell<Option<PathBuf>>,
+    /// Temporary directory the archive was extracted to, removed by [`Project::close`].
+    extracted_dir: RefCell<Option<PathBuf>>,
     pub guidelines: RefCell<Vec<Guideline>>,
     pub metric_guidelines: RefCell<Vec<Guideline>>,
     pub fontinfo: RefCell<FontInfo>,
     pub metainfo: RefCell<MetaInfo>,
     pub layercontents: RefCell<LayerContents>,
+    /// Contents of `groups.plist` and `kerning.plist`. They are only read; saving the project
+    /// leaves both files as they are.
+    pub groups: RefCell<ufo::Groups>,
+    pub kerning: RefCell<ufo::Kerning>,
     pub default_layer: ufo::objects::Layer,
     pub background_layer: RefCell<Option<ufo::objects::Layer>>,
     pub all_layers: RefCell<Vec<ufo::objects::Layer>>,
@@ -57,11 +66,15 @@ impl Default for ProjectInner {
             modified: Cell::new(false),
             last_saved: RefCell::new(None),
             path: RefCell::new(std::env::current_dir().unwrap_or_default()),
+            archive_path: RefCell::new(None),
+            extracted_dir: RefCell::new(None),
             guidelines: RefCell::new(vec![]),
             metric_guidelines: RefCell::new(vec![]),
             fontinfo: RefCell::new(FontInfo::new()),
             metainfo: RefCell::new(MetaInfo::default()),
             layercontents: RefCell::new(LayerContents::default()),
+            groups: RefCell::new(ufo::Groups::default()),
+            kerning: RefCell::new(ufo::Kerning::default()),
             default_layer: ufo::objects::Layer::new(),
             background_layer: RefCell::new(None),
             all_layers: RefCell::new(vec![]),
@@ -174,6 +187,24 @@ impl Project {
 
     pub fn from_path(path: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error>> {
         let path = path.as_ref();
+        if ufo::ufoz::is_ufoz(path) {
+            let archive = std::fs::canonicalize(path)?;
+            let extracted = ufo::ufoz::extract(&archive)
+                .map_err(|err| format!("couldn't extract {}:\n\n{}", archive.display(), err))?;
+            let extracted_dir = extracted.parent().map(Path::to_path_buf);
+            let ret = match Self::from_path_inner(&extracted) {
+                Ok(ret) => ret,
+                Err(err) => {
+                    if let Some(dir) = extracted_dir {
+                        _ = std::fs::remove_dir_all(dir);
+                    }
+                    return Err(err);
+                }
+            };
+            *ret.archive_path.borrow_mut() = Some(archive);
+            *ret.extracted_dir.borrow_mut() = extracted_dir;
+            return Ok(ret);
+        }
         Self::from_path_inner(path)
     }
 
@@ -220,6 +251,16 @@ impl Project {
         *ret.all_layers.borrow_mut() = all_layers;
         *ret.layercontents.borrow_mut() = layercontents;
         path.pop();
+        path.push("groups.plist");
+        let groups = ufo::Groups::from_path(&path)
+            .map_err(|err| format!("couldn't read groups.plist:\n\n{}", err))?;
+        path.pop();
+        path.push("kerning.plist");
+        let kerning = ufo::Kerning::from_path(&path)
+            .map_err(|err| format!("couldn't read kerning.plist:\n\n{}", err))?;
+        path.pop();
+        *ret.groups.borrow_mut() = groups;
+        *ret.kerning.borrow_mut() = kerning;
         let name = fontinfo.family_name.borrow().clone();
         if !name.is_empty() {
             ret.set_property(Self::NAME, name);
@@ -327,10 +368,51 @@ impl Project {
         {
             g.set_property(Guideline::MODIFIED, false);
         }
+        if let Some(archive) = self.archive_path.borrow().as_deref() {
+            ufo::ufoz::write(&self.path.borrow(), archive)?;
+        }
         self.set_property(Self::MODIFIED, false);
         Ok(())
     }
 
+    /// Removes the temporary directory a `.ufoz` archive was extracted to. Changes that weren't
+    /// saved back to the archive are lost.
+    pub fn close(&self) {
+        if let Some(dir) = self.extracted_dir.borrow_mut().take() {
+            if let Err(err) = std::fs::remove_dir_all(&dir) {
+                eprintln!("Could not remove {}: {err}", dir.display());
+            }
+        }
+    }
+
+    /// Writes the project, including unsaved changes, as a `.ufoz` archive at `archive`. The
+    /// project itself isn't saved; the archive is made from a temporary copy.
+    pub fn export_ufoz(&self, archive: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        let path = self.path.borrow().clone();
+        let Some(name) = path.file_name() else {
+            return Err(format!("Invalid project path {}", path.display()).into());
+        };
+        let tmp_dir = std::env::temp_dir().join(format!("gerb-{}", ufo::make_random_identifier()));
+        let copy = tmp_dir.join(name);
+        let result = self
+            .save_copy(&copy)
+            .and_then(|()| ufo::ufoz::write(&copy, archive));
+        _ = std::fs::remove_dir_all(&tmp_dir);
+        result
+    }
+
+    /// Copies the project directory to `destination` and writes unsaved changes into the copy.
+    fn save_copy(&self, destination: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        ufo::copy_directory(&self.path.borrow(), destination)?;
+        self.fontinfo
+            .borrow()
+            .save_copy(&destination.join("fontinfo.plist"))?;
+        for obj in self.all_layers.borrow().iter().filter(|obj| obj.modified()) {
+            obj.save_copy(destination)?;
+        }
+        Ok(())
+    }
+
     pub fn create(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
         let mut path: PathBuf = std::fs::canonicalize(Path::new(path))
             .map_err(|err| format!("Path looks invalid:\n\n{err}"))?;
@@ -384,6 +466,13 @@ impl Project {
     pub fn fontinfo(&self) -> FieldRef<'_, FontInfo> {
         self.fontinfo.borrow().into()
     }
+
+    /// Kerning value between the glyphs named `first` and `second`.
+    pub fn kerning_pair(&self, first: &str, second: &str) -> f64 {
+        self.kerning
+            .borrow()
+            .pair(&self.groups.borrow(), first, second)
+    }
 }
 
 impl Default for Project {
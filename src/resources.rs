@@ -100,6 +100,9 @@ pub mod icons {
     decl_icon! {BSPLINE_ICON, "./resources/b-spline-icon"}
     decl_icon! {RECTANGLE_ICON, "./resources/rectangle-icon"}
     decl_icon! {ELLIPSE_ICON, "./resources/ellipse-icon"}
+    decl_icon! {KNIFE_ICON, "./resources/knife-icon"}
+    decl_icon! {PENCIL_ICON, "./resources/pencil-icon"}
+    decl_icon! {HOBBY_ICON, "./resources/hobby-icon"}
     decl_icon! {RIGHT_MOUSE_BUTTON, "./resources/icons/right_mouse_button"}
     decl_icon! {LEFT_MOUSE_BUTTON, "./resources/icons/left_mouse_button"}
     decl_icon! {ESC_BUTTON, "./resources/icons/esc_button"}
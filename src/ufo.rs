@@ -21,12 +21,12 @@
 
 #[cfg(feature = "python")]
 pub mod export;
-#[cfg(feature = "python")]
 pub mod import;
 
 pub mod constants;
 pub mod glif;
 pub mod objects;
+pub mod ufoz;
 
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
@@ -503,7 +503,7 @@ impl FontInfo {
 /// </dict>
 /// </plist>
 /// ```
-#[derive(Default, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[derive(Clone, Default, PartialEq, Eq, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Contents {
     #[serde(flatten)]
@@ -576,6 +576,24 @@ impl Contents {
         self.glyphs.insert(name, filename);
         self.modified = true;
     }
+
+    /// Changes the name of a glyph, keeping its filename and its position.
+    pub fn rename(&mut self, old_name: &str, new_name: String) {
+        if !self.glyphs.contains_key(old_name) {
+            return;
+        }
+        self.glyphs = std::mem::take(&mut self.glyphs)
+            .into_iter()
+            .map(|(k, v)| {
+                if k == old_name {
+                    (new_name.clone(), v)
+                } else {
+                    (k, v)
+                }
+            })
+            .collect();
+        self.modified = true;
+    }
 }
 
 /// metainfo.plist
@@ -895,6 +913,104 @@ impl Lib {
     }
 }
 
+/// groups.plist
+///
+/// UFO3 Spec:
+///
+/// > This file contains data that maps glyphs to groups. This file is optional.
+///
+/// Kerning groups are prefixed with `public.kern1.` for the first side of a pair and
+/// `public.kern2.` for the second side.
+///
+/// # Specification
+///
+/// <https://unifiedfontobject.org/versions/ufo3/groups.plist/>
+#[derive(Default, PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Groups {
+    pub groups: IndexMap<String, Vec<String>>,
+}
+
+impl Groups {
+    pub fn from_path(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        if !path.exists() {
+            // > This file is optional.
+            return Ok(Self::default());
+        }
+        let retval: Self = plist::from_file(path)?;
+        Ok(retval)
+    }
+
+    pub fn new_from_str(xml: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(plist::from_reader_xml(std::io::Cursor::new(xml))?)
+    }
+
+    /// The kerning group of `glyph` for the given side of a pair, if any.
+    pub fn kerning_group(&self, glyph: &str, first: bool) -> Option<&str> {
+        let prefix = if first {
+            "public.kern1."
+        } else {
+            "public.kern2."
+        };
+        self.groups
+            .iter()
+            .find(|(name, members)| name.starts_with(prefix) && members.iter().any(|m| m == glyph))
+            .map(|(name, _)| name.as_str())
+    }
+}
+
+/// kerning.plist
+///
+/// UFO3 Spec:
+///
+/// > This file contains kerning data. This file is optional.
+///
+/// Maps the first side of a pair to the second side and the kerning value. Either side can be
+/// a glyph name or a kerning group, see [`Groups`].
+///
+/// # Specification
+///
+/// <https://unifiedfontobject.org/versions/ufo3/kerning.plist/>
+#[derive(Default, PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Kerning {
+    pub pairs: IndexMap<String, IndexMap<String, f64>>,
+}
+
+impl Kerning {
+    pub fn from_path(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        if !path.exists() {
+            // > This file is optional.
+            return Ok(Self::default());
+        }
+        let retval: Self = plist::from_file(path)?;
+        Ok(retval)
+    }
+
+    pub fn new_from_str(xml: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(plist::from_reader_xml(std::io::Cursor::new(xml))?)
+    }
+
+    /// Kerning value of the pair `first`, `second`, looked up in the order the specification
+    /// requires: glyph and glyph, glyph and group, group and glyph, group and group.
+    pub fn pair(&self, groups: &Groups, first: &str, second: &str) -> f64 {
+        let first_group = groups.kerning_group(first, true);
+        let second_group = groups.kerning_group(second, false);
+        [
+            (Some(first), Some(second)),
+            (Some(first), second_group),
+            (first_group, Some(second)),
+            (first_group, second_group),
+        ]
+        .into_iter()
+        .find_map(|pair| match pair {
+            (Some(a), Some(b)) => self.pairs.get(a).and_then(|p| p.get(b)).copied(),
+            _ => None,
+        })
+        .unwrap_or(0.0)
+    }
+}
+
 #[test]
 fn test_fontinfo_plist_parse() {
     let p: FontInfo = FontInfo::new_from_str(PLIST).unwrap();
@@ -1428,7 +1544,192 @@ pub fn make_random_identifier() -> String {
         .collect()
 }
 
+/// Copies the directory `input` with all its contents to `output`, creating it if needed.
+pub fn copy_directory(input: &Path, output: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    std::fs::create_dir_all(output)?;
+    for entry in std::fs::read_dir(input)? {
+        let path = entry?.path();
+        let Some(file_name) = path.file_name() else {
+            continue;
+        };
+        if path.is_dir() {
+            copy_directory(&path, &output.join(file_name))?;
+        } else {
+            std::fs::copy(&path, output.join(file_name))?;
+        }
+    }
+    Ok(())
+}
+
 #[test]
 fn test_make_random_identifier() {
     assert_eq!(make_random_identifier().len(), 10);
 }
+
+/// Converts a user name, such as a glyph or layer name, to a file name following the common
+/// algorithm of the UFO conventions.
+///
+/// `existing` holds the lowercased file names already in use, so that the result doesn't clash
+/// with them on case-insensitive file systems.
+///
+/// <https://unifiedfontobject.org/versions/ufo3/conventions/#common-user-name-to-file-name-algorithm>
+pub fn user_name_to_file_name(
+    name: &str,
+    prefix: &str,
+    suffix: &str,
+    existing: &std::collections::HashSet<String>,
+) -> String {
+    const ILLEGAL: &[char] = &['"', '*', '+', '/', ':', '<', '>', '?', '[', '\\', ']', '|'];
+    const RESERVED: &[&str] = &[
+        "con", "prn", "aux", "clock$", "nul", "com1", "com2", "com3", "com4", "com5", "com6",
+        "com7", "com8", "com9", "lpt1", "lpt2", "lpt3", "lpt4", "lpt5", "lpt6", "lpt7", "lpt8",
+        "lpt9",
+    ];
+    const MAX_LENGTH: usize = 255;
+    const COUNTER_LENGTH: usize = 15;
+
+    let mut filtered = String::with_capacity(name.len());
+    for c in name.chars() {
+        if ILLEGAL.contains(&c) || c.is_ascii_control() {
+            filtered.push('_');
+        } else if c.is_uppercase() {
+            filtered.push(c);
+            filtered.push('_');
+        } else {
+            filtered.push(c);
+        }
+    }
+    if filtered.starts_with('.') {
+        filtered.replace_range(..1, "_");
+    }
+    let filtered = filtered
+        .split('.')
+        .map(|part| {
+            if RESERVED.contains(&part.to_lowercase().as_str()) {
+                format!("_{part}")
+            } else {
+                part.to_string()
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(".");
+    let limit = MAX_LENGTH.saturating_sub(prefix.chars().count() + suffix.chars().count());
+    let filtered = filtered.chars().take(limit).collect::<String>();
+    let full_name = format!("{prefix}{filtered}{suffix}");
+    if !existing.contains(&full_name.to_lowercase()) {
+        return full_name;
+    }
+    let filtered = filtered
+        .chars()
+        .take(limit.saturating_sub(COUNTER_LENGTH))
+        .collect::<String>();
+    (1..)
+        .map(|counter| format!("{prefix}{filtered}{counter:015}{suffix}"))
+        .find(|full_name| !existing.contains(&full_name.to_lowercase()))
+        .unwrap_or(full_name)
+}
+
+#[test]
+fn test_user_name_to_file_name() {
+    let existing = std::collections::HashSet::new();
+    for (name, expected) in [
+        ("a", "a"),
+        ("A", "A_"),
+        ("AE", "A_E_"),
+        ("Ae", "A_e"),
+        ("ae", "ae"),
+        ("aE", "aE_"),
+        ("a.alt", "a.alt"),
+        ("A.alt", "A_.alt"),
+        ("A.Alt", "A_.A_lt"),
+        ("A.aLt", "A_.aL_t"),
+        ("A.alT", "A_.alT_"),
+        ("T_H", "T__H_"),
+        ("T_h", "T__h"),
+        ("t_h", "t_h"),
+        ("F_F_I", "F__F__I_"),
+        ("f_f_i", "f_f_i"),
+        ("Aacute_V.swash", "A_acute_V_.swash"),
+        (".notdef", "_notdef"),
+        ("con", "_con"),
+        ("CON", "C_O_N_"),
+        ("con.alt", "_con.alt"),
+        ("alt.con", "alt._con"),
+    ] {
+        assert_eq!(
+            user_name_to_file_name(name, "", "", &existing),
+            expected,
+            "{name}"
+        );
+    }
+    assert_eq!(
+        user_name_to_file_name("A", "", ".glif", &existing),
+        "A_.glif"
+    );
+    let existing = std::collections::HashSet::from(["a_.glif".to_string()]);
+    assert_eq!(
+        user_name_to_file_name("A", "", ".glif", &existing),
+        "A_000000000000001.glif"
+    );
+}
+
+#[test]
+fn test_kerning_pair() {
+    let groups = Groups::new_from_str(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple Computer//DTD PLIST 1.0//EN"
+"http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+  <key>public.kern1.O</key>
+  <array>
+    <string>O</string>
+    <string>D</string>
+  </array>
+  <key>public.kern2.O</key>
+  <array>
+    <string>O</string>
+    <string>C</string>
+  </array>
+</dict>
+</plist>"#,
+    )
+    .unwrap();
+    let kerning = Kerning::new_from_str(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple Computer//DTD PLIST 1.0//EN"
+"http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+  <key>T</key>
+  <dict>
+    <key>o</key>
+    <integer>-80</integer>
+    <key>public.kern2.O</key>
+    <integer>-20</integer>
+  </dict>
+  <key>public.kern1.O</key>
+  <dict>
+    <key>public.kern2.O</key>
+    <integer>10</integer>
+    <key>V</key>
+    <real>-35.5</real>
+  </dict>
+  <key>D</key>
+  <dict>
+    <key>O</key>
+    <integer>5</integer>
+  </dict>
+</dict>
+</plist>"#,
+    )
+    .unwrap();
+    assert_eq!(groups.kerning_group("D", true), Some("public.kern1.O"));
+    assert_eq!(groups.kerning_group("D", false), None);
+    assert_eq!(kerning.pair(&groups, "T", "o"), -80.0);
+    assert_eq!(kerning.pair(&groups, "T", "C"), -20.0);
+    assert_eq!(kerning.pair(&groups, "O", "V"), -35.5);
+    assert_eq!(kerning.pair(&groups, "O", "C"), 10.0);
+    assert_eq!(kerning.pair(&groups, "D", "O"), 5.0);
+    assert_eq!(kerning.pair(&groups, "o", "T"), 0.0);
+}
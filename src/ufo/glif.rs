@@ -29,6 +29,9 @@ use serde::{Deserialize, Serialize};
 
 use glib::subclass::types::ObjectSubclassIsExt;
 use indexmap::IndexMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{mpsc, Arc, Mutex};
 
 fn color_serialize<S>(v: &Option<Color>, serializer: S) -> Result<S::Ok, S::Error>
 where
@@ -398,6 +401,7 @@ pub struct Glif {
     pub guidelines: Vec<Guideline>,
     // [ref:FIXME]: impl ser de
     // <https://github.com/ebarnard/rust-plist/issues/79>
+    // Until then the lib is read and written by [`Glif::from_str`] and [`Glif::to_xml`].
     #[serde(
         default,
         skip,
@@ -434,13 +438,58 @@ where
 impl Glif {
     #[allow(dead_code)]
     pub fn to_xml(&self) -> String {
-        format!(
-            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n{}\n",
-            quick_xml::se::to_string(&self).unwrap(),
-        )
+        let mut xml = quick_xml::se::to_string(&self).unwrap();
+        if let Some(lib) = lib_to_xml(&self.lib) {
+            if let Some(end) = xml.rfind("</glyph>") {
+                xml.insert_str(end, &format!("<lib>{lib}</lib>"));
+            }
+        }
+        format!("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n{xml}\n")
+    }
+
+    /// Reads and parses a `.glif` file, returning the parsed value along with the file's
+    /// contents.
+    pub fn from_path(path: &Path) -> Result<(Self, String), Box<dyn std::error::Error>> {
+        let s = std::fs::read_to_string(path)
+            .map_err(|err| format!("Couldn't read {}: {}", path.display(), err))?;
+        let glif = Self::from_str(&s)
+            .map_err(|err| format!("couldn't parse {}: {}", path.display(), err))?;
+        Ok((glif, s))
     }
 }
 
+/// Result of parsing a `.glif` file on a worker thread, see [`parse_in_thread_pool`].
+pub type ParseResult = Result<(Glif, String), String>;
+
+/// Reads and parses `.glif` files on a pool of worker threads, one per available core. Results
+/// are sent back in no particular order as soon as they are ready, tagged with the key they were
+/// submitted with. Workers stop early if the receiver is dropped.
+pub fn parse_in_thread_pool<K: Send + 'static>(
+    jobs: Vec<(K, PathBuf)>,
+) -> mpsc::Receiver<(K, ParseResult)> {
+    let (tx, rx) = mpsc::channel();
+    let workers = std::thread::available_parallelism()
+        .map(std::num::NonZeroUsize::get)
+        .unwrap_or(1)
+        .min(jobs.len());
+    let jobs = Arc::new(Mutex::new(jobs.into_iter()));
+    for _ in 0..workers {
+        let tx = tx.clone();
+        let jobs = Arc::clone(&jobs);
+        std::thread::spawn(move || loop {
+            let job = jobs.lock().unwrap().next();
+            let Some((key, path)) = job else {
+                break;
+            };
+            let result = Glif::from_path(&path).map_err(|err| err.to_string());
+            if tx.send((key, result)).is_err() {
+                break;
+            }
+        });
+    }
+    rx
+}
+
 impl From<&glyphs::Glyph> for Glif {
     fn from(glyph: &glyphs::Glyph) -> Self {
         let mut outline: Vec<OutlineEntry> =
@@ -528,7 +577,13 @@ impl From<&glyphs::Glyph> for Glif {
             image: glyph.metadata.image.borrow().clone(),
             advance: glyph.metadata.advance.get(),
             outline: Some(Outline { contours: outline }),
-            anchors: glyph.metadata.anchors.borrow().clone(),
+            anchors: glyph
+                .metadata
+                .anchors
+                .borrow()
+                .iter()
+                .map(Into::into)
+                .collect(),
             guidelines: glyph.guidelines().iter().map(Into::into).collect(),
             lib: glyph.lib.clone(),
         }
@@ -538,8 +593,42 @@ impl From<&glyphs::Glyph> for Glif {
 impl std::str::FromStr for Glif {
     type Err = Box<dyn std::error::Error>;
     fn from_str(s: &str) -> Result<Self, Box<dyn std::error::Error>> {
-        Ok(quick_xml::de::from_str(s)?)
+        let mut ret: Self = quick_xml::de::from_str(s)?;
+        ret.lib = lib_from_xml(s)?;
+        Ok(ret)
+    }
+}
+
+/// Parses the `<lib>` element of a `.glif` file, if any, as a property list dictionary.
+fn lib_from_xml(s: &str) -> Result<IndexMap<String, plist::Value>, Box<dyn std::error::Error>> {
+    let (Some(start), Some(end)) = (s.find("<lib>"), s.rfind("</lib>")) else {
+        return Ok(IndexMap::default());
+    };
+    let Some(inner) = s.get(start + "<lib>".len()..end) else {
+        return Ok(IndexMap::default());
+    };
+    let plist = format!("<plist version=\"1.0\">{inner}</plist>");
+    let dict = plist::Value::from_reader_xml(plist.as_bytes())
+        .map_err(|err| format!("Invalid glyph lib: {err}"))?
+        .into_dictionary()
+        .ok_or("Invalid glyph lib: not a dictionary.")?;
+    Ok(dict.into_iter().collect())
+}
+
+/// Serializes `lib` as the contents of a `<lib>` element, or `None` if it is empty.
+fn lib_to_xml(lib: &IndexMap<String, plist::Value>) -> Option<String> {
+    if lib.is_empty() {
+        return None;
     }
+    let dict: plist::Dictionary = lib.clone().into_iter().collect();
+    let mut buf = vec![];
+    plist::Value::Dictionary(dict)
+        .to_writer_xml(&mut buf)
+        .ok()?;
+    let plist = String::from_utf8(buf).ok()?;
+    let start = plist.find("<dict")?;
+    let end = plist.rfind("</plist>")?;
+    Some(plist[start..end].trim().to_string())
 }
 
 impl Default for Glif {
@@ -568,6 +657,49 @@ fn test_glif_write() {
     assert_eq!(g.to_xml(), g2.to_xml());
 }
 
+#[test]
+fn test_glif_lib() {
+    let g = Glif::from_str(_LOWERCASE_B_GLIF).unwrap();
+    assert_eq!(
+        g.lib
+            .get("com.schriftgestaltung.Glyphs.lastChange")
+            .and_then(plist::Value::as_string),
+        Some("2018-04-05 15:21:53 +0000")
+    );
+    assert_eq!(g.lib.len(), 2);
+    let g2 = Glif::from_str(&g.to_xml()).unwrap();
+    assert_eq!(g.lib, g2.lib);
+    let g3 = Glif::from_str(_UPPERCASE_A_GLIF).unwrap();
+    assert!(g3.lib.is_empty());
+    assert!(!g3.to_xml().contains("<lib>"));
+}
+
+#[test]
+fn test_glif_anchors() {
+    use glib::ObjectExt;
+
+    let glif: Glif = quick_xml::de::from_str(_UPPERCASE_A_GLIF).unwrap();
+    let anchors = glif.anchors.clone();
+    let glyph: glyphs::Glyph = glif.into();
+    {
+        let glyph_anchors = glyph.metadata.anchors.borrow();
+        assert_eq!(glyph_anchors.len(), 3);
+        assert_eq!(glyph_anchors[0].name(), "aboveUC");
+        assert_eq!(glyph_anchors[0].position(), (271.0, 678.0).into());
+        glyph_anchors[2].set_property(glyphs::Anchor::Y, 10.0);
+    }
+    let glif2 = Glif::from(&glyph);
+    assert_eq!(glif2.anchors[..2], anchors[..2]);
+    assert_eq!(
+        glif2.anchors[2],
+        Anchor {
+            name: "ogonekUC".to_string(),
+            x: 483.0,
+            y: 10.0
+        }
+    );
+}
+
 const _LOWERCASE_B_GLIF: &str = r##"<?xml version="1.0" encoding="UTF-8"?>
 <glyph name="b" format="2">
 	<unicode hex="0062"/>
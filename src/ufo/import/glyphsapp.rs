@@ -0,0 +1,1463 @@
+/*
+ * gerb
+ *
+ * Copyright 2022 - Manos Pitsidianakis
+ *
+ * This file is part of gerb.
+ *
+ * gerb is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * gerb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with gerb. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Import of Glyphs.app `.glyphs` sources, both Glyphs 2 and Glyphs 3 file formats.
+//!
+//! Every font master is written as a separate UFO, and a designspace document referencing all of
+//! them is written next to them. Master layers become the default layer of each UFO, master
+//! backgrounds become `public.background` and other layers associated with a master (brace,
+//! bracket and backup layers) become extra UFO layers.
+//!
+//! Custom parameters that have a `fontinfo.plist` equivalent are converted, the rest are stored
+//! in the UFO lib with the `com.schriftgestaltung.customParameter.` prefix like `glyphsLib` does.
+
+use super::{gen_setter, openstep, write_dictionary, UFOInstance};
+use crate::ufo::{glif, user_name_to_file_name, LayerContents, MetaInfo};
+use indexmap::IndexMap;
+use plist::{Dictionary, Value};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone)]
+pub struct Glyphs2UFOOptions {
+    glyphs_file: PathBuf,
+    output_dir: Option<PathBuf>,
+    designspace_path: Option<PathBuf>,
+}
+
+impl Glyphs2UFOOptions {
+    pub fn new(glyphs_file: PathBuf) -> Self {
+        Self {
+            glyphs_file,
+            output_dir: None,
+            designspace_path: None,
+        }
+    }
+
+    gen_setter! {
+        output_dir: Option<PathBuf>,
+        designspace_path: Option<PathBuf>,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ParameterKind {
+    Number,
+    Boolean,
+    String,
+}
+
+/// Custom parameters that have a `fontinfo.plist` equivalent.
+const CUSTOM_PARAMETERS: &[(&str, &str, ParameterKind)] = &[
+    (
+        "hheaAscender",
+        "openTypeHheaAscender",
+        ParameterKind::Number,
+    ),
+    (
+        "hheaDescender",
+        "openTypeHheaDescender",
+        ParameterKind::Number,
+    ),
+    ("hheaLineGap", "openTypeHheaLineGap", ParameterKind::Number),
+    (
+        "typoAscender",
+        "openTypeOS2TypoAscender",
+        ParameterKind::Number,
+    ),
+    (
+        "typoDescender",
+        "openTypeOS2TypoDescender",
+        ParameterKind::Number,
+    ),
+    (
+        "typoLineGap",
+        "openTypeOS2TypoLineGap",
+        ParameterKind::Number,
+    ),
+    ("winAscent", "openTypeOS2WinAscent", ParameterKind::Number),
+    ("winDescent", "openTypeOS2WinDescent", ParameterKind::Number),
+    (
+        "strikeoutSize",
+        "openTypeOS2StrikeoutSize",
+        ParameterKind::Number,
+    ),
+    (
+        "strikeoutPosition",
+        "openTypeOS2StrikeoutPosition",
+        ParameterKind::Number,
+    ),
+    (
+        "weightClass",
+        "openTypeOS2WeightClass",
+        ParameterKind::Number,
+    ),
+    ("widthClass", "openTypeOS2WidthClass", ParameterKind::Number),
+    (
+        "underlinePosition",
+        "postscriptUnderlinePosition",
+        ParameterKind::Number,
+    ),
+    (
+        "underlineThickness",
+        "postscriptUnderlineThickness",
+        ParameterKind::Number,
+    ),
+    ("blueScale", "postscriptBlueScale", ParameterKind::Number),
+    ("blueShift", "postscriptBlueShift", ParameterKind::Number),
+    (
+        "isFixedPitch",
+        "postscriptIsFixedPitch",
+        ParameterKind::Boolean,
+    ),
+    ("vendorID", "openTypeOS2VendorID", ParameterKind::String),
+    (
+        "versionString",
+        "openTypeNameVersion",
+        ParameterKind::String,
+    ),
+    ("uniqueID", "openTypeNameUniqueID", ParameterKind::String),
+    ("license", "openTypeNameLicense", ParameterKind::String),
+    (
+        "licenseURL",
+        "openTypeNameLicenseURL",
+        ParameterKind::String,
+    ),
+    (
+        "description",
+        "openTypeNameDescription",
+        ParameterKind::String,
+    ),
+    (
+        "sampleText",
+        "openTypeNameSampleText",
+        ParameterKind::String,
+    ),
+    ("trademark", "trademark", ParameterKind::String),
+    (
+        "compatibleFullName",
+        "openTypeNameCompatibleFullName",
+        ParameterKind::String,
+    ),
+    (
+        "postscriptFontName",
+        "postscriptFontName",
+        ParameterKind::String,
+    ),
+    (
+        "postscriptFullName",
+        "postscriptFullName",
+        ParameterKind::String,
+    ),
+    (
+        "styleMapFamilyName",
+        "styleMapFamilyName",
+        ParameterKind::String,
+    ),
+    (
+        "styleMapStyleName",
+        "styleMapStyleName",
+        ParameterKind::String,
+    ),
+];
+
+/// Glyphs 3 font properties that have a `fontinfo.plist` equivalent. Glyphs 2 stores some of
+/// them as top level keys with the same name.
+const PROPERTIES: &[(&str, &str)] = &[
+    ("copyright", "copyright"),
+    ("copyrights", "copyright"),
+    ("designer", "openTypeNameDesigner"),
+    ("designers", "openTypeNameDesigner"),
+    ("designerURL", "openTypeNameDesignerURL"),
+    ("manufacturer", "openTypeNameManufacturer"),
+    ("manufacturers", "openTypeNameManufacturer"),
+    ("manufacturerURL", "openTypeNameManufacturerURL"),
+    ("licenses", "openTypeNameLicense"),
+    ("licenseURL", "openTypeNameLicenseURL"),
+    ("descriptions", "openTypeNameDescription"),
+    ("trademarks", "trademark"),
+    ("sampleTexts", "openTypeNameSampleText"),
+    ("vendorID", "openTypeOS2VendorID"),
+    ("versionString", "openTypeNameVersion"),
+    ("uniqueID", "openTypeNameUniqueID"),
+    ("postscriptFontName", "postscriptFontName"),
+    ("postscriptFullNames", "postscriptFullName"),
+    ("compatibleFullNames", "openTypeNameCompatibleFullName"),
+];
+
+const LIB_CUSTOM_PARAMETER_PREFIX: &str = "com.schriftgestaltung.customParameter";
+const LIB_GLYPH_ORDER: &str = "public.glyphOrder";
+const LIB_SKIP_EXPORT_GLYPHS: &str = "public.skipExportGlyphs";
+const BACKGROUND_LAYER: &str = "public.background";
+/// Advance width Glyphs.app assumes when a layer has none.
+const DEFAULT_WIDTH: f64 = 600.0;
+
+/// A font master, see [`parse_master`].
+#[derive(Debug)]
+struct Master {
+    id: String,
+    name: String,
+    location: Vec<f64>,
+    info: Dictionary,
+    lib: Dictionary,
+}
+
+/// Converts `options.glyphs_file` to UFOv3, one UFO per master, and writes a designspace
+/// document referencing them.
+///
+/// UFOs are written to `options.output_dir`, or next to the `.glyphs` file if it's `None`.
+pub fn import(options: Glyphs2UFOOptions) -> Result<Vec<UFOInstance>, Box<dyn std::error::Error>> {
+    let glyphs_file = &options.glyphs_file;
+    let source = std::fs::read_to_string(glyphs_file)
+        .map_err(|err| format!("Could not read {}: {err}", glyphs_file.display()))?;
+    let root = openstep::parse(&source)
+        .map_err(|err| format!("Could not parse {}: {err}", glyphs_file.display()))?;
+    let font = root
+        .as_dictionary()
+        .ok_or_else(|| format!("{} is not a valid .glyphs file.", glyphs_file.display()))?;
+    let version3 = number(font, ".formatVersion")
+        .map(|v| v >= 3.0)
+        .unwrap_or(false);
+
+    let output_dir = options.output_dir.unwrap_or_else(|| {
+        glyphs_file
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| PathBuf::from("."))
+    });
+    let stem = glyphs_file
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "font".to_string());
+    let designspace_path = options
+        .designspace_path
+        .unwrap_or_else(|| output_dir.join(format!("{stem}.designspace")));
+
+    let family_name = string(font, "familyName").unwrap_or("Untitled");
+    let axes = parse_axes(font, version3);
+    let metric_types = dicts(font, "metrics")
+        .map(|m| {
+            if m.contains_key("filter") {
+                None
+            } else {
+                string(m, "type")
+            }
+        })
+        .collect::<Vec<Option<&str>>>();
+    let masters = dicts(font, "fontMaster")
+        .map(|m| parse_master(m, version3, axes.len(), &metric_types))
+        .collect::<Vec<Master>>();
+    if masters.is_empty() {
+        return Err(format!("{} has no font masters.", glyphs_file.display()).into());
+    }
+    let glyphs = dicts(font, "glyphs").collect::<Vec<&Dictionary>>();
+
+    let (info, lib) = font_info(font, family_name, &glyphs);
+    let groups = kerning_groups(&glyphs, version3);
+    let glyph_names_by_id = glyphs
+        .iter()
+        .filter_map(|g| Some((string(g, "id")?, string(g, "glyphname")?)))
+        .collect::<HashMap<&str, &str>>();
+    let kerning = font
+        .get(if version3 { "kerningLTR" } else { "kerning" })
+        .and_then(Value::as_dictionary);
+
+    /* Check every master before writing anything, so that nothing is left behind. */
+    let directory_names = masters
+        .iter()
+        .map(|master| {
+            format!(
+                "{}-{}.ufo",
+                family_name.replace(' ', ""),
+                master.name.replace(' ', "")
+            )
+        })
+        .collect::<Vec<String>>();
+    for directory_name in &directory_names {
+        let full_path = output_dir.join(directory_name);
+        if full_path.exists() {
+            return Err(format!("{} already exists.", full_path.display()).into());
+        }
+    }
+    std::fs::create_dir_all(&output_dir)?;
+    let mut instances = Vec::with_capacity(masters.len());
+    for (master, directory_name) in masters.iter().zip(directory_names) {
+        let full_path = output_dir.join(&directory_name);
+        let mut master_info = info.clone();
+        master_info.insert("styleName".into(), Value::String(master.name.clone()));
+        for (k, v) in master.info.iter() {
+            master_info.insert(k.clone(), v.clone());
+        }
+        let mut master_lib = lib.clone();
+        for (k, v) in master.lib.iter() {
+            master_lib.insert(k.clone(), v.clone());
+        }
+        let master_kerning = kerning
+            .and_then(|k| k.get(&master.id))
+            .and_then(Value::as_dictionary)
+            .map(|k| convert_kerning(k, &glyph_names_by_id));
+        let layers = master_layers(master, &glyphs, version3);
+        if let Err(err) = write_ufo(
+            &full_path,
+            master_info,
+            master_lib,
+            &groups,
+            master_kerning,
+            layers,
+        ) {
+            for path in instances
+                .iter()
+                .map(|i: &UFOInstance| &i.full_path)
+                .chain(std::iter::once(&full_path))
+            {
+                _ = std::fs::remove_dir_all(path);
+            }
+            return Err(err);
+        }
+        instances.push(UFOInstance {
+            directory_name,
+            full_path,
+            family_name: family_name.to_string(),
+            style_name: master.name.clone(),
+        });
+    }
+    write_designspace(&designspace_path, &axes, &masters, &instances)?;
+    Ok(instances)
+}
+
+fn string<'a>(dict: &'a Dictionary, key: &str) -> Option<&'a str> {
+    dict.get(key).and_then(Value::as_string)
+}
+
+fn number(dict: &Dictionary, key: &str) -> Option<f64> {
+    string(dict, key)?.trim().parse().ok()
+}
+
+fn array<'a>(dict: &'a Dictionary, key: &str) -> &'a [Value] {
+    dict.get(key)
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+fn dicts<'a>(dict: &'a Dictionary, key: &str) -> impl Iterator<Item = &'a Dictionary> {
+    array(dict, key).iter().filter_map(Value::as_dictionary)
+}
+
+/// Parses a list of numbers, either a Glyphs 3 array `(1, 2)` or a Glyphs 2 string `"{1, 2}"`.
+fn numbers(value: Option<&Value>) -> Vec<f64> {
+    match value {
+        Some(Value::Array(values)) => values
+            .iter()
+            .filter_map(|v| v.as_string()?.trim().parse().ok())
+            .collect(),
+        Some(Value::String(s)) => s
+            .trim_matches(|c: char| c == '{' || c == '}' || c.is_whitespace())
+            .split(',')
+            .filter_map(|n| n.trim().parse().ok())
+            .collect(),
+        _ => vec![],
+    }
+}
+
+fn number_value(v: f64) -> Value {
+    if v.fract() == 0.0 && v.abs() < 1e15 {
+        Value::Integer((v as i64).into())
+    } else {
+        Value::Real(v)
+    }
+}
+
+/// Returns the name and tag of each axis.
+fn parse_axes(font: &Dictionary, version3: bool) -> Vec<(String, String)> {
+    let axes = if version3 {
+        dicts(font, "axes")
+            .filter_map(|a| Some((string(a, "name")?, string(a, "tag")?)))
+            .map(|(name, tag)| (name.to_string(), tag.to_string()))
+            .collect::<Vec<_>>()
+    } else {
+        custom_parameters(font)
+            .find(|(name, _)| *name == "Axes")
+            .and_then(|(_, value)| value.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_dictionary)
+            .filter_map(|a| Some((string(a, "Name")?, string(a, "Tag")?)))
+            .map(|(name, tag)| (name.to_string(), tag.to_string()))
+            .collect::<Vec<_>>()
+    };
+    if axes.is_empty() && !version3 {
+        return vec![
+            ("Weight".to_string(), "wght".to_string()),
+            ("Width".to_string(), "wdth".to_string()),
+        ];
+    }
+    axes
+}
+
+fn custom_parameters(dict: &Dictionary) -> impl Iterator<Item = (&str, &Value)> {
+    dicts(dict, "customParameters").filter_map(|p| Some((string(p, "name")?, p.get("value")?)))
+}
+
+/// Stores custom parameters in `info` if they have a `fontinfo.plist` equivalent, or in `lib`.
+fn apply_custom_parameters(
+    dict: &Dictionary,
+    owner: &str,
+    info: &mut Dictionary,
+    lib: &mut Dictionary,
+) {
+    for (name, value) in custom_parameters(dict) {
+        match name {
+            "Axes" | "Master Name" => continue,
+            "glyphOrder" => {
+                lib.insert(LIB_GLYPH_ORDER.into(), value.clone());
+                continue;
+            }
+            _ => {}
+        }
+        if let Some(&(_, key, kind)) = CUSTOM_PARAMETERS.iter().find(|(n, _, _)| *n == name) {
+            let converted = match kind {
+                ParameterKind::String => Some(value.clone()),
+                ParameterKind::Number => value
+                    .as_string()
+                    .and_then(|s| s.trim().parse().ok())
+                    .map(number_value),
+                ParameterKind::Boolean => value
+                    .as_string()
+                    .and_then(|s| s.trim().parse::<f64>().ok())
+                    .map(|v| Value::Boolean(v != 0.0)),
+            };
+            if let Some(converted) = converted {
+                info.insert(key.into(), converted);
+                continue;
+            }
+        }
+        lib.insert(
+            format!("{LIB_CUSTOM_PARAMETER_PREFIX}.{owner}.{name}"),
+            value.clone(),
+        );
+    }
+}
+
+/// Returns the value of a Glyphs 3 property, preferring the default language if it is localized.
+fn property_value(property: &Dictionary) -> Option<&Value> {
+    if let Some(value) = property.get("value") {
+        return Some(value);
+    }
+    let values = dicts(property, "values").collect::<Vec<_>>();
+    values
+        .iter()
+        .find(|v| matches!(string(v, "language"), Some("dflt" | "ENG")))
+        .or_else(|| values.first())
+        .copied()
+        .and_then(|v| v.get("value"))
+}
+
+/// Returns the `fontinfo.plist` and `lib.plist` values shared by all masters.
+fn font_info(
+    font: &Dictionary,
+    family_name: &str,
+    glyphs: &[&Dictionary],
+) -> (Dictionary, Dictionary) {
+    let mut info = Dictionary::new();
+    let mut lib = Dictionary::new();
+    info.insert("familyName".into(), Value::String(family_name.to_string()));
+    for (key, ufo_key) in [
+        ("unitsPerEm", "unitsPerEm"),
+        ("versionMajor", "versionMajor"),
+        ("versionMinor", "versionMinor"),
+    ] {
+        if let Some(v) = number(font, key) {
+            info.insert(ufo_key.into(), number_value(v));
+        }
+    }
+    for &(key, ufo_key) in PROPERTIES {
+        if let Some(v) = font.get(key).filter(|v| v.as_string().is_some()) {
+            info.insert(ufo_key.into(), v.clone());
+        }
+    }
+    for property in dicts(font, "properties") {
+        let (Some(key), Some(value)) = (string(property, "key"), property_value(property)) else {
+            continue;
+        };
+        match PROPERTIES.iter().find(|(k, _)| *k == key) {
+            Some((_, ufo_key)) => {
+                info.insert((*ufo_key).into(), value.clone());
+            }
+            None => {
+                lib.insert(
+                    format!("com.schriftgestaltung.properties.{key}"),
+                    value.clone(),
+                );
+            }
+        }
+    }
+    lib.insert(
+        LIB_GLYPH_ORDER.into(),
+        Value::Array(
+            glyphs
+                .iter()
+                .filter_map(|g| string(g, "glyphname"))
+                .map(|n| Value::String(n.to_string()))
+                .collect(),
+        ),
+    );
+    let skip_export = glyphs
+        .iter()
+        .filter(|g| string(g, "export") == Some("0"))
+        .filter_map(|g| string(g, "glyphname"))
+        .map(|n| Value::String(n.to_string()))
+        .collect::<Vec<_>>();
+    if !skip_export.is_empty() {
+        lib.insert(LIB_SKIP_EXPORT_GLYPHS.into(), Value::Array(skip_export));
+    }
+    apply_custom_parameters(font, "GSFont", &mut info, &mut lib);
+    (info, lib)
+}
+
+fn parse_master(
+    master: &Dictionary,
+    version3: bool,
+    axes_count: usize,
+    metric_types: &[Option<&str>],
+) -> Master {
+    let id = string(master, "id").unwrap_or_default().to_string();
+    let master_name = custom_parameters(master)
+        .find(|(name, _)| *name == "Master Name")
+        .and_then(|(_, v)| v.as_string());
+    let name = if version3 {
+        string(master, "name").unwrap_or("Regular").to_string()
+    } else if let Some(name) = master_name {
+        name.to_string()
+    } else {
+        let parts = ["width", "weight", "custom"]
+            .into_iter()
+            .filter_map(|key| string(master, key))
+            .filter(|part| *part != "Regular")
+            .collect::<Vec<_>>();
+        if parts.is_empty() {
+            "Regular".to_string()
+        } else {
+            parts.join(" ")
+        }
+    };
+    let location = if version3 {
+        numbers(master.get("axesValues"))
+    } else {
+        [
+            ("weightValue", 100.0),
+            ("widthValue", 100.0),
+            ("customValue", 0.0),
+            ("customValue1", 0.0),
+            ("customValue2", 0.0),
+            ("customValue3", 0.0),
+        ]
+        .into_iter()
+        .take(axes_count)
+        .map(|(key, default)| number(master, key).unwrap_or(default))
+        .collect()
+    };
+
+    let mut metrics = HashMap::new();
+    if version3 {
+        for (metric, value) in metric_types.iter().zip(array(master, "metricValues")) {
+            let (Some(metric), Some(value)) = (metric, value.as_dictionary()) else {
+                continue;
+            };
+            metrics.insert(*metric, number(value, "pos").unwrap_or(0.0));
+        }
+    } else {
+        for (key, metric, default) in [
+            ("ascender", "ascender", 800.0),
+            ("capHeight", "cap height", 700.0),
+            ("xHeight", "x-height", 500.0),
+            ("descender", "descender", -200.0),
+            ("italicAngle", "italic angle", 0.0),
+        ] {
+            metrics.insert(metric, number(master, key).unwrap_or(default));
+        }
+    }
+    let mut info = Dictionary::new();
+    for (metric, key) in [
+        ("ascender", "ascender"),
+        ("cap height", "capHeight"),
+        ("x-height", "xHeight"),
+        ("descender", "descender"),
+        ("italic angle", "italicAngle"),
+    ] {
+        if let Some(&v) = metrics.get(metric) {
+            if key == "italicAngle" && v == 0.0 {
+                continue;
+            }
+            info.insert(key.into(), number_value(v));
+        }
+    }
+    let mut lib = Dictionary::new();
+    apply_custom_parameters(master, "GSFontMaster", &mut info, &mut lib);
+    Master {
+        id,
+        name,
+        location,
+        info,
+        lib,
+    }
+}
+
+/// Returns `groups.plist`, built from each glyph's kerning groups.
+fn kerning_groups(glyphs: &[&Dictionary], version3: bool) -> Dictionary {
+    let (left_key, right_key) = if version3 {
+        ("kernLeft", "kernRight")
+    } else {
+        ("leftKerningGroup", "rightKerningGroup")
+    };
+    let mut groups: IndexMap<String, Vec<Value>> = IndexMap::new();
+    for glyph in glyphs {
+        let Some(name) = string(glyph, "glyphname") else {
+            continue;
+        };
+        /* The right side group is used when the glyph is on the left side of a pair. */
+        for (key, prefix) in [(right_key, "public.kern1."), (left_key, "public.kern2.")] {
+            if let Some(group) = string(glyph, key) {
+                groups
+                    .entry(format!("{prefix}{group}"))
+                    .or_default()
+                    .push(Value::String(name.to_string()));
+            }
+        }
+    }
+    groups
+        .into_iter()
+        .map(|(k, v)| (k, Value::Array(v)))
+        .collect()
+}
+
+fn convert_kerning(kerning: &Dictionary, glyph_names_by_id: &HashMap<&str, &str>) -> Dictionary {
+    let key = |k: &str| -> String {
+        if let Some(group) = k.strip_prefix("@MMK_L_") {
+            format!("public.kern1.{group}")
+        } else if let Some(group) = k.strip_prefix("@MMK_R_") {
+            format!("public.kern2.{group}")
+        } else {
+            glyph_names_by_id.get(k).copied().unwrap_or(k).to_string()
+        }
+    };
+    kerning
+        .iter()
+        .filter_map(|(first, pairs)| Some((first, pairs.as_dictionary()?)))
+        .map(|(first, pairs)| {
+            let pairs = pairs
+                .iter()
+                .filter_map(|(second, value)| {
+                    let value = value.as_string()?.trim().parse().ok()?;
+                    Some((key(second.as_str()), number_value(value)))
+                })
+                .collect::<Dictionary>();
+            (key(first.as_str()), Value::Dictionary(pairs))
+        })
+        .collect()
+}
+
+/// Returns the layers of a master and their glyphs, starting with the default layer.
+fn master_layers(
+    master: &Master,
+    glyphs: &[&Dictionary],
+    version3: bool,
+) -> Vec<(String, Vec<glif::Glif>)> {
+    let mut layers: IndexMap<String, Vec<glif::Glif>> = IndexMap::new();
+    layers.insert("public.default".into(), vec![]);
+    for glyph in glyphs {
+        let Some(name) = string(glyph, "glyphname") else {
+            continue;
+        };
+        let unicodes = unicodes(glyph, version3);
+        for layer in dicts(glyph, "layers") {
+            let layer_id = string(layer, "layerId");
+            if layer_id == Some(master.id.as_str()) {
+                let glif = layer_to_glif(name, &unicodes, layer, None, version3);
+                let width = glif.advance.map(|a| a.width);
+                layers
+                    .entry("public.default".into())
+                    .or_default()
+                    .push(glif);
+                if let Some(background) = layer.get("background").and_then(Value::as_dictionary) {
+                    layers
+                        .entry(BACKGROUND_LAYER.into())
+                        .or_default()
+                        .push(layer_to_glif(name, &[], background, width, version3));
+                }
+            } else if string(layer, "associatedMasterId") == Some(master.id.as_str()) {
+                let layer_name = string(layer, "name")
+                    .or(layer_id)
+                    .unwrap_or("Layer")
+                    .to_string();
+                layers
+                    .entry(layer_name)
+                    .or_default()
+                    .push(layer_to_glif(name, &unicodes, layer, None, version3));
+            }
+        }
+    }
+    layers.into_iter().collect()
+}
+
+/// Returns the hex codepoints of a glyph; Glyphs 2 stores them as hex, Glyphs 3 as decimal.
+fn unicodes(glyph: &Dictionary, version3: bool) -> Vec<String> {
+    let values: Vec<&str> = match glyph.get("unicode") {
+        Some(Value::String(s)) => s.split(',').collect(),
+        Some(Value::Array(a)) => a.iter().filter_map(Value::as_string).collect(),
+        _ => vec![],
+    };
+    values
+        .into_iter()
+        .filter_map(|v| {
+            let v = v.trim();
+            if version3 {
+                v.parse::<u32>().ok()
+            } else {
+                u32::from_str_radix(v, 16).ok()
+            }
+        })
+        .map(|c| format!("{c:04X}"))
+        .collect()
+}
+
+fn layer_to_glif(
+    name: &str,
+    unicodes: &[String],
+    layer: &Dictionary,
+    width: Option<f64>,
+    version3: bool,
+) -> glif::Glif {
+    let mut contours = vec![];
+    if version3 {
+        for shape in dicts(layer, "shapes") {
+            if shape.contains_key("ref") {
+                contours.push(glif::OutlineEntry::Component(component(shape, true)));
+            } else {
+                contours.push(glif::OutlineEntry::Contour(contour(shape, true)));
+            }
+        }
+    } else {
+        for path in dicts(layer, "paths") {
+            contours.push(glif::OutlineEntry::Contour(contour(path, false)));
+        }
+        for c in dicts(layer, "components") {
+            contours.push(glif::OutlineEntry::Component(component(c, false)));
+        }
+    }
+    let anchors = dicts(layer, "anchors")
+        .filter_map(|a| {
+            let position = numbers(a.get(if version3 { "pos" } else { "position" }));
+            Some(glif::Anchor {
+                name: string(a, "name")?.to_string(),
+                x: position.first().copied().unwrap_or(0.0),
+                y: position.get(1).copied().unwrap_or(0.0),
+            })
+        })
+        .collect();
+    let width = number(layer, "width").or(width).unwrap_or(DEFAULT_WIDTH);
+    glif::Glif {
+        name: name.to_string(),
+        format: Some("2".to_string()),
+        unicode: unicodes.iter().cloned().map(glif::Unicode::new).collect(),
+        image: None,
+        advance: Some(glif::Advance { width, height: 0.0 }),
+        outline: if contours.is_empty() {
+            None
+        } else {
+            Some(glif::Outline { contours })
+        },
+        anchors,
+        guidelines: vec![],
+        lib: IndexMap::new(),
+    }
+}
+
+fn contour(path: &Dictionary, version3: bool) -> glif::Contour {
+    let closed = string(path, "closed") == Some("1");
+    let mut points = array(path, "nodes")
+        .iter()
+        .filter_map(|node| {
+            /* Glyphs 2 nodes are strings like "10 20 CURVE SMOOTH", Glyphs 3 nodes are arrays
+             * like (10, 20, cs). */
+            let (x, y, kind, smooth) = if version3 {
+                let node = node.as_array()?;
+                let kind = node.get(2)?.as_string()?;
+                let (kind, smooth) = match kind.strip_suffix('s') {
+                    Some(kind) => (kind, true),
+                    None => (kind, false),
+                };
+                (
+                    node.first()?.as_string()?.parse::<f64>().ok()?,
+                    node.get(1)?.as_string()?.parse::<f64>().ok()?,
+                    kind,
+                    smooth,
+                )
+            } else {
+                let mut parts = node.as_string()?.split_whitespace();
+                (
+                    parts.next()?.parse::<f64>().ok()?,
+                    parts.next()?.parse::<f64>().ok()?,
+                    parts.next()?,
+                    parts.next() == Some("SMOOTH"),
+                )
+            };
+            let type_ = match kind {
+                "l" | "LINE" => glif::PointKind::Line,
+                "c" | "CURVE" => glif::PointKind::Curve,
+                "q" | "QCURVE" => glif::PointKind::Qcurve,
+                _ => glif::PointKind::Offcurve,
+            };
+            Some(glif::Point {
+                x,
+                y,
+                name: None,
+                identifier: None,
+                type_,
+                smooth: if smooth { Some(true) } else { None },
+            })
+        })
+        .collect::<Vec<_>>();
+    if !closed {
+        if let Some(first) = points.first_mut() {
+            first.type_ = glif::PointKind::Move;
+            first.smooth = None;
+        }
+    }
+    glif::Contour {
+        identifier: None,
+        point: points,
+    }
+}
+
+fn component(component: &Dictionary, version3: bool) -> glif::Component {
+    let (base, [x_scale, xy_scale, yx_scale, y_scale, x_offset, y_offset]) = if version3 {
+        let pos = numbers(component.get("pos"));
+        let scale = numbers(component.get("scale"));
+        let (sx, sy) = (
+            scale.first().copied().unwrap_or(1.0),
+            scale.get(1).copied().unwrap_or(1.0),
+        );
+        let angle = number(component, "angle").unwrap_or(0.0).to_radians();
+        let (sin, cos) = angle.sin_cos();
+        (
+            string(component, "ref"),
+            [
+                sx * cos,
+                sx * sin,
+                -sy * sin,
+                sy * cos,
+                pos.first().copied().unwrap_or(0.0),
+                pos.get(1).copied().unwrap_or(0.0),
+            ],
+        )
+    } else {
+        let transform = numbers(component.get("transform"));
+        let transform =
+            <[f64; 6]>::try_from(transform.as_slice()).unwrap_or([1.0, 0.0, 0.0, 1.0, 0.0, 0.0]);
+        (string(component, "name"), transform)
+    };
+    glif::Component {
+        base: base.unwrap_or_default().to_string(),
+        x_offset,
+        y_offset,
+        x_scale,
+        xy_scale,
+        yx_scale,
+        y_scale,
+    }
+}
+
+fn write_ufo(
+    path: &Path,
+    info: Dictionary,
+    lib: Dictionary,
+    groups: &Dictionary,
+    kerning: Option<Dictionary>,
+    layers: Vec<(String, Vec<glif::Glif>)>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    std::fs::create_dir_all(path)?;
+    MetaInfo::default().save(&path.join("metainfo.plist"))?;
+    write_dictionary(info, &path.join("fontinfo.plist"))?;
+    if !lib.is_empty() {
+        write_dictionary(lib, &path.join("lib.plist"))?;
+    }
+    if !groups.is_empty() {
+        write_dictionary(groups.clone(), &path.join("groups.plist"))?;
+    }
+    if let Some(kerning) = kerning.filter(|k| !k.is_empty()) {
+        write_dictionary(kerning, &path.join("kerning.plist"))?;
+    }
+    let mut layercontents = LayerContents {
+        layers: IndexMap::new(),
+        objects: IndexMap::default(),
+    };
+    let mut dir_names = HashSet::new();
+    for (i, (name, glifs)) in layers.into_iter().enumerate() {
+        let dir_name = if i == 0 {
+            "glyphs".to_string()
+        } else {
+            user_name_to_file_name(&name, "glyphs.", "", &dir_names)
+        };
+        dir_names.insert(dir_name.to_lowercase());
+        let dir = path.join(&dir_name);
+        std::fs::create_dir_all(&dir)?;
+        let mut file_names = HashSet::new();
+        let mut contents = Dictionary::new();
+        for glif in glifs {
+            let file_name = user_name_to_file_name(&glif.name, "", ".glif", &file_names);
+            file_names.insert(file_name.to_lowercase());
+            std::fs::write(dir.join(&file_name), glif.to_xml())?;
+            contents.insert(glif.name.clone(), Value::String(file_name));
+        }
+        write_dictionary(contents, &dir.join("contents.plist"))?;
+        layercontents.layers.insert(name, dir_name);
+    }
+    layercontents.save(&path.join("layercontents.plist"))?;
+    Ok(())
+}
+
+fn write_designspace(
+    path: &Path,
+    axes: &[(String, String)],
+    masters: &[Master],
+    instances: &[UFOInstance],
+) -> Result<(), Box<dyn std::error::Error>> {
+    use quick_xml::escape::escape;
+    use std::fmt::Write;
+
+    let mut xml = String::from(
+        "<?xml version='1.0' encoding='UTF-8'?>\n<designspace format=\"4.1\">\n  <axes>\n",
+    );
+    for (i, (name, tag)) in axes.iter().enumerate() {
+        let values = masters
+            .iter()
+            .filter_map(|m| m.location.get(i).copied())
+            .collect::<Vec<f64>>();
+        let minimum = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let maximum = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let default = values.first().copied().unwrap_or(0.0);
+        let (minimum, maximum) = if values.is_empty() {
+            (0.0, 0.0)
+        } else {
+            (minimum, maximum)
+        };
+        writeln!(
+            xml,
+            "    <axis tag=\"{}\" name=\"{}\" minimum=\"{minimum}\" maximum=\"{maximum}\" default=\"{default}\"/>",
+            escape(tag),
+            escape(name)
+        )?;
+    }
+    xml.push_str("  </axes>\n  <sources>\n");
+    let designspace_dir = path.parent();
+    for (master, instance) in masters.iter().zip(instances) {
+        let filename = if instance.full_path.parent() == designspace_dir {
+            instance.directory_name.clone()
+        } else {
+            instance.full_path.display().to_string()
+        };
+        writeln!(
+            xml,
+            "    <source filename=\"{}\" name=\"{} {}\" familyname=\"{}\" stylename=\"{}\">\n      <location>",
+            escape(&filename),
+            escape(&instance.family_name),
+            escape(&instance.style_name),
+            escape(&instance.family_name),
+            escape(&instance.style_name),
+        )?;
+        for ((name, _), value) in axes.iter().zip(&master.location) {
+            writeln!(
+                xml,
+                "        <dimension name=\"{}\" xvalue=\"{value}\"/>",
+                escape(name)
+            )?;
+        }
+        xml.push_str("      </location>\n    </source>\n");
+    }
+    xml.push_str("  </sources>\n</designspace>\n");
+    std::fs::write(path, xml)?;
+    Ok(())
+}
+
+pub fn import_action_cb(window: gtk::Window) {
+    use crate::prelude::*;
+
+    let dialog = gtk::FileChooserNative::new(
+        Some("Select glyphs file"),
+        Some(&window),
+        gtk::FileChooserAction::Open,
+        None,
+        None,
+    );
+    let filter = gtk::FileFilter::new();
+    filter.add_pattern("*.glyphs");
+    filter.set_name(Some("Glyphs file (.glyphs)"));
+    dialog.add_filter(&filter);
+
+    return_if_not_ok_or_accept!(dialog.run());
+    dialog.hide();
+
+    let Some(f) = dialog.filename() else {
+        return;
+    };
+    let Some(path) = f.to_str() else {
+        return;
+    };
+    match import(Glyphs2UFOOptions::new(path.into()).output_dir(None)) {
+        Ok(instances) => {
+            if instances.len() == 1 {
+                window.emit_by_name::<()>(
+                    "open-project",
+                    &[&instances[0].full_path.display().to_string()],
+                );
+            } else {
+                let dialog = gtk::Dialog::builder()
+                    .attached_to(&window)
+                    .application(&window.application().unwrap())
+                    .destroy_with_parent(true)
+                    .modal(true)
+                    .build();
+                dialog.add_button("Open", gtk::ResponseType::Accept);
+                dialog.add_button("Close", gtk::ResponseType::Close);
+                let b = dialog.content_area();
+                b.pack_start(&gtk::Label::builder().label(&format!(
+                                "Generated more than once instance, select and open one manually.\n\nGenerated {} instances:",
+                                instances.len(),
+                    )).visible(true).wrap(true).halign(gtk::Align::Start).build(), true, false, 5);
+                let listbox = gtk::ListBox::new();
+                listbox.set_selection_mode(gtk::SelectionMode::Single);
+                listbox.set_visible(true);
+                listbox.set_valign(gtk::Align::Center);
+                let mut rows = vec![];
+                for i in &instances {
+                    let row = gtk::ListBoxRow::builder()
+                        .child(
+                            &gtk::Label::builder()
+                                .label(&format!(
+                                    "{} <i>{}</i> <tt>{}</tt>",
+                                    i.family_name.as_str(),
+                                    i.style_name.as_str(),
+                                    i.full_path.as_path().display()
+                                ))
+                                .use_markup(true)
+                                .visible(true)
+                                .wrap(true)
+                                .halign(gtk::Align::Start)
+                                .build(),
+                        )
+                        .visible(true)
+                        .selectable(true)
+                        .build();
+                    listbox.add(&row);
+                    rows.push((i.full_path.as_path().display().to_string(), row));
+                }
+                b.pack_start(&listbox, true, false, 5);
+                b.set_border_width(15);
+                b.set_spacing(5);
+                b.set_valign(gtk::Align::Start);
+                dialog.set_title("Info: generated more than once instance, open one manually.");
+                for _ in 0..3 {
+                    match dialog.run() {
+                        gtk::ResponseType::Accept => {
+                            if let Some(path) = listbox.selected_row().and_then(|row| {
+                                rows.iter()
+                                    .find_map(|(p, r)| if r == &row { Some(p) } else { None })
+                            }) {
+                                dialog.emit_close();
+                                window.emit_by_name::<()>("open-project", &[&path]);
+                            }
+                        }
+                        gtk::ResponseType::Close => {
+                            dialog.emit_close();
+                        }
+                        gtk::ResponseType::DeleteEvent => {
+                            dialog.emit_close();
+                            break;
+                        }
+                        _other => unreachable!("{_other:?}"),
+                    }
+                }
+            }
+        }
+        Err(err) => {
+            let dialog = crate::utils::widgets::new_simple_error_dialog(
+                Some("Error: could not convert Glyphs file to UFOv3"),
+                &err.to_string(),
+                None,
+                &window,
+            );
+            dialog.run();
+            dialog.emit_close();
+        }
+    }
+}
+
+#[test]
+fn test_glyphs_import() {
+    const GLYPHS2: &str = r#"{
+familyName = "Test Sans";
+unitsPerEm = 1000;
+versionMajor = 1;
+designer = "Someone";
+customParameters = (
+{
+name = typoAscender;
+value = 750;
+},
+{
+name = "Don't use Production Names";
+value = 1;
+}
+);
+fontMaster = (
+{
+id = "M1";
+weightValue = 400;
+ascender = 760;
+capHeight = 700;
+xHeight = 480;
+descender = -240;
+},
+{
+id = "M2";
+weight = Bold;
+weightValue = 700;
+}
+);
+glyphs = (
+{
+glyphname = A;
+unicode = 0041;
+rightKerningGroup = A;
+leftKerningGroup = A;
+layers = (
+{
+layerId = "M1";
+width = 600;
+anchors = (
+{
+name = top;
+position = "{300, 700}";
+}
+);
+paths = (
+{
+closed = 1;
+nodes = (
+"0 0 LINE",
+"300 700 LINE",
+"600 0 LINE"
+);
+}
+);
+background = {
+paths = (
+{
+closed = 0;
+nodes = (
+"0 0 LINE",
+"10 10 LINE"
+);
+}
+);
+};
+},
+{
+layerId = "M2";
+width = 650;
+},
+{
+layerId = "L1";
+associatedMasterId = "M1";
+name = "Backup";
+width = 600;
+}
+);
+},
+{
+glyphname = Aacute;
+unicode = 00C1;
+export = 0;
+layers = (
+{
+layerId = "M1";
+width = 600;
+components = (
+{
+name = A;
+},
+{
+name = acutecomb;
+transform = "{1, 0, 0, 1, 300, 700}";
+}
+);
+},
+{
+layerId = "M2";
+width = 650;
+}
+);
+},
+{
+glyphname = V;
+unicode = 0056;
+leftKerningGroup = V;
+layers = (
+{
+layerId = "M1";
+width = 600;
+}
+);
+}
+);
+kerning = {
+M1 = {
+"@MMK_L_A" = {
+"@MMK_R_V" = -40;
+};
+};
+};
+}"#;
+    const GLYPHS3: &str = r#"{
+.formatVersion = 3;
+axes = (
+{
+name = Weight;
+tag = wght;
+}
+);
+familyName = "Test Serif";
+fontMaster = (
+{
+axesValues = (
+400
+);
+id = m01;
+metricValues = (
+{
+pos = 800;
+},
+{
+over = -16;
+}
+);
+name = Regular;
+}
+);
+glyphs = (
+{
+glyphname = B;
+kernLeft = B;
+layers = (
+{
+anchors = (
+{
+name = top;
+pos = (250,700);
+}
+);
+layerId = m01;
+shapes = (
+{
+closed = 1;
+nodes = (
+(0,0,l),
+(100,0,o),
+(200,100,o),
+(200,200,cs),
+(0,200,l)
+);
+},
+{
+angle = 90;
+pos = (10,20);
+ref = A;
+}
+);
+width = 500;
+}
+);
+unicode = 66;
+}
+);
+kerningLTR = {
+m01 = {
+B = {
+"@MMK_R_B" = 10;
+};
+};
+};
+metrics = (
+{
+type = ascender;
+},
+{
+type = baseline;
+}
+);
+properties = (
+{
+key = copyrights;
+values = (
+{
+language = dflt;
+value = "Copyright";
+}
+);
+}
+);
+unitsPerEm = 1000;
+}"#;
+    let tmp = std::env::temp_dir().join(format!(
+        "gerb-test-glyphs-{}",
+        crate::ufo::make_random_identifier()
+    ));
+    std::fs::create_dir_all(&tmp).unwrap();
+    let read = |path: PathBuf| Value::from_file(path).unwrap().into_dictionary().unwrap();
+
+    /* Glyphs 2 */
+    std::fs::write(tmp.join("Test2.glyphs"), GLYPHS2).unwrap();
+    let instances = import(Glyphs2UFOOptions::new(tmp.join("Test2.glyphs"))).unwrap();
+    assert_eq!(instances.len(), 2);
+    assert_eq!(instances[0].directory_name, "TestSans-Regular.ufo");
+    assert_eq!(instances[1].style_name, "Bold");
+    let regular = &instances[0].full_path;
+    let fontinfo = read(regular.join("fontinfo.plist"));
+    assert_eq!(
+        fontinfo
+            .get("openTypeNameDesigner")
+            .and_then(Value::as_string),
+        Some("Someone")
+    );
+    assert_eq!(
+        fontinfo
+            .get("openTypeOS2TypoAscender")
+            .and_then(Value::as_signed_integer),
+        Some(750)
+    );
+    assert_eq!(
+        fontinfo.get("ascender").and_then(Value::as_signed_integer),
+        Some(760)
+    );
+    let lib = read(regular.join("lib.plist"));
+    assert!(
+        lib.contains_key("com.schriftgestaltung.customParameter.GSFont.Don't use Production Names")
+    );
+    assert_eq!(
+        lib.get(LIB_SKIP_EXPORT_GLYPHS)
+            .and_then(Value::as_array)
+            .map(Vec::len),
+        Some(1)
+    );
+    let groups = read(regular.join("groups.plist"));
+    assert!(groups.contains_key("public.kern1.A"));
+    assert!(groups.contains_key("public.kern2.V"));
+    let kerning = read(regular.join("kerning.plist"));
+    assert_eq!(
+        kerning
+            .get("public.kern1.A")
+            .and_then(Value::as_dictionary)
+            .and_then(|d| d.get("public.kern2.V"))
+            .and_then(Value::as_signed_integer),
+        Some(-40)
+    );
+    assert!(!instances[1].full_path.join("kerning.plist").exists());
+    let layercontents = std::fs::read_to_string(regular.join("layercontents.plist")).unwrap();
+    assert!(layercontents.contains(BACKGROUND_LAYER));
+    assert!(layercontents.contains("Backup"));
+
+    /* An existing master directory stops the import before anything is written. */
+    std::fs::remove_dir_all(&instances[1].full_path).unwrap();
+    assert!(import(Glyphs2UFOOptions::new(tmp.join("Test2.glyphs"))).is_err());
+    assert!(!instances[1].full_path.exists());
+
+    let contents = read(regular.join("glyphs").join("contents.plist"));
+    assert_eq!(
+        contents.get("A").and_then(Value::as_string),
+        Some("A_.glif")
+    );
+    let (a, _) = glif::Glif::from_path(&regular.join("glyphs").join("A_.glif")).unwrap();
+    assert_eq!(a.unicode[0].hex(), "0041");
+    assert_eq!(a.anchors.len(), 1);
+    assert_eq!((a.anchors[0].x, a.anchors[0].y), (300.0, 700.0));
+    assert_eq!(a.advance.unwrap().width, 600.0);
+    let (aacute, _) = glif::Glif::from_path(&regular.join("glyphs").join("A_acute.glif")).unwrap();
+    let components = aacute
+        .outline
+        .unwrap()
+        .contours
+        .into_iter()
+        .filter_map(|c| match c {
+            glif::OutlineEntry::Component(c) => Some(c),
+            glif::OutlineEntry::Contour(_) => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(components.len(), 2);
+    assert_eq!(components[1].base, "acutecomb");
+    assert_eq!(
+        (components[1].x_offset, components[1].y_offset),
+        (300.0, 700.0)
+    );
+    let designspace = std::fs::read_to_string(tmp.join("Test2.designspace")).unwrap();
+    assert!(designspace
+        .contains(r#"<axis tag="wght" name="Weight" minimum="400" maximum="700" default="400"/>"#));
+    assert!(designspace.contains(r#"filename="TestSans-Bold.ufo""#));
+    assert!(import(Glyphs2UFOOptions::new(tmp.join("Test2.glyphs"))).is_err());
+
+    /* Glyphs 3 */
+    std::fs::write(tmp.join("Test3.glyphs"), GLYPHS3).unwrap();
+    let instances = import(Glyphs2UFOOptions::new(tmp.join("Test3.glyphs"))).unwrap();
+    assert_eq!(instances.len(), 1);
+    let regular = &instances[0].full_path;
+    let fontinfo = read(regular.join("fontinfo.plist"));
+    assert_eq!(
+        fontinfo.get("copyright").and_then(Value::as_string),
+        Some("Copyright")
+    );
+    assert_eq!(
+        fontinfo.get("ascender").and_then(Value::as_signed_integer),
+        Some(800)
+    );
+    let (b, _) = glif::Glif::from_path(&regular.join("glyphs").join("B_.glif")).unwrap();
+    assert_eq!(b.unicode[0].hex(), "0042");
+    assert_eq!(b.anchors[0].name, "top");
+    let outline = b.outline.unwrap().contours;
+    let glif::OutlineEntry::Contour(ref contour) = outline[0] else {
+        panic!("expected contour");
+    };
+    assert_eq!(contour.point.len(), 5);
+    assert_eq!(contour.point[3].type_, glif::PointKind::Curve);
+    assert_eq!(contour.point[3].smooth, Some(true));
+    let glif::OutlineEntry::Component(ref component) = outline[1] else {
+        panic!("expected component");
+    };
+    assert_eq!(component.base, "A");
+    assert!(component.x_scale.abs() < 1e-9 && (component.xy_scale - 1.0).abs() < 1e-9);
+    assert_eq!((component.x_offset, component.y_offset), (10.0, 20.0));
+    let kerning = read(regular.join("kerning.plist"));
+    assert!(kerning
+        .get("B")
+        .and_then(Value::as_dictionary)
+        .map(|d| d.contains_key("public.kern2.B"))
+        .unwrap_or(false));
+    std::fs::remove_dir_all(&tmp).unwrap();
+}
@@ -0,0 +1,312 @@
+/*
+ * gerb
+ *
+ * Copyright 2022 - Manos Pitsidianakis
+ *
+ * This file is part of gerb.
+ *
+ * gerb is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * gerb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with gerb. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Parser for the OpenStep ("old-style" ASCII) property list format used by `.glyphs` files.
+//!
+//! Dictionaries, arrays and data are returned as their [`plist::Value`] equivalents. The format
+//! has no number type, so quoted and unquoted strings alike are returned as
+//! [`plist::Value::String`].
+
+use plist::{Dictionary, Value};
+
+pub fn parse(input: &str) -> Result<Value, String> {
+    let mut parser = Parser {
+        input: input.as_bytes(),
+        pos: 0,
+    };
+    parser.skip_whitespace()?;
+    let value = parser.value()?;
+    parser.skip_whitespace()?;
+    if parser.pos != parser.input.len() {
+        return Err(parser.error("trailing characters after the root value"));
+    }
+    Ok(value)
+}
+
+struct Parser<'a> {
+    input: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, msg: &str) -> String {
+        let line = self.input[..self.pos.min(self.input.len())]
+            .iter()
+            .filter(|b| **b == b'\n')
+            .count()
+            + 1;
+        format!("line {line}: {msg}")
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.pos).copied()
+    }
+
+    fn expect(&mut self, c: u8) -> Result<(), String> {
+        self.skip_whitespace()?;
+        if self.peek() != Some(c) {
+            return Err(self.error(&format!("expected `{}`", c as char)));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn skip_whitespace(&mut self) -> Result<(), String> {
+        while let Some(c) = self.peek() {
+            if c.is_ascii_whitespace() {
+                self.pos += 1;
+            } else if self.input[self.pos..].starts_with(b"//") {
+                while !matches!(self.peek(), None | Some(b'\n')) {
+                    self.pos += 1;
+                }
+            } else if self.input[self.pos..].starts_with(b"/*") {
+                let Some(end) = self.input[self.pos + 2..]
+                    .windows(2)
+                    .position(|w| w == b"*/")
+                else {
+                    return Err(self.error("unterminated comment"));
+                };
+                self.pos += end + 4;
+            } else {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn value(&mut self) -> Result<Value, String> {
+        self.skip_whitespace()?;
+        match self.peek() {
+            Some(b'{') => self.dictionary(),
+            Some(b'(') => self.array(),
+            Some(b'<') => self.data(),
+            Some(b'"') | Some(b'\'') => self.quoted_string().map(Value::String),
+            Some(c) if is_unquoted_char(c) => self.unquoted_string().map(Value::String),
+            Some(c) => Err(self.error(&format!("unexpected character `{}`", c as char))),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    fn dictionary(&mut self) -> Result<Value, String> {
+        self.expect(b'{')?;
+        let mut dict = Dictionary::new();
+        loop {
+            self.skip_whitespace()?;
+            if self.peek() == Some(b'}') {
+                self.pos += 1;
+                return Ok(Value::Dictionary(dict));
+            }
+            let key = match self.value()? {
+                Value::String(key) => key,
+                _ => return Err(self.error("dictionary keys must be strings")),
+            };
+            self.expect(b'=')?;
+            let value = self.value()?;
+            self.expect(b';')?;
+            dict.insert(key, value);
+        }
+    }
+
+    fn array(&mut self) -> Result<Value, String> {
+        self.expect(b'(')?;
+        let mut array = vec![];
+        loop {
+            self.skip_whitespace()?;
+            if self.peek() == Some(b')') {
+                self.pos += 1;
+                return Ok(Value::Array(array));
+            }
+            array.push(self.value()?);
+            self.skip_whitespace()?;
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b')') => {}
+                _ => return Err(self.error("expected `,` or `)` in array")),
+            }
+        }
+    }
+
+    fn data(&mut self) -> Result<Value, String> {
+        self.expect(b'<')?;
+        let mut digits = vec![];
+        loop {
+            match self.peek() {
+                Some(b'>') => {
+                    self.pos += 1;
+                    break;
+                }
+                Some(c) if c.is_ascii_hexdigit() => digits.push(c),
+                Some(c) if c.is_ascii_whitespace() => {}
+                _ => return Err(self.error("invalid data literal")),
+            }
+            self.pos += 1;
+        }
+        if digits.len() % 2 != 0 {
+            return Err(self.error("data literal has an odd number of hex digits"));
+        }
+        let bytes = digits
+            .chunks(2)
+            .map(|pair| {
+                u8::from_str_radix(std::str::from_utf8(pair).unwrap_or_default(), 16)
+                    .unwrap_or_default()
+            })
+            .collect();
+        Ok(Value::Data(bytes))
+    }
+
+    fn unquoted_string(&mut self) -> Result<String, String> {
+        let start = self.pos;
+        while self.peek().map(is_unquoted_char).unwrap_or(false) {
+            self.pos += 1;
+        }
+        Ok(String::from_utf8_lossy(&self.input[start..self.pos]).into_owned())
+    }
+
+    fn quoted_string(&mut self) -> Result<String, String> {
+        let Some(quote) = self.peek() else {
+            return Err(self.error("unexpected end of input"));
+        };
+        self.pos += 1;
+        let mut bytes = vec![];
+        loop {
+            let Some(c) = self.peek() else {
+                return Err(self.error("unterminated string"));
+            };
+            self.pos += 1;
+            if c == quote {
+                break;
+            }
+            if c != b'\\' {
+                bytes.push(c);
+                continue;
+            }
+            let Some(escaped) = self.peek() else {
+                return Err(self.error("unterminated string"));
+            };
+            self.pos += 1;
+            match escaped {
+                b'n' => push_char(&mut bytes, '\n'),
+                b't' => push_char(&mut bytes, '\t'),
+                b'r' => push_char(&mut bytes, '\r'),
+                b'a' => push_char(&mut bytes, '\x07'),
+                b'b' => push_char(&mut bytes, '\x08'),
+                b'f' => push_char(&mut bytes, '\x0c'),
+                b'v' => push_char(&mut bytes, '\x0b'),
+                b'U' | b'u' => {
+                    let end = (self.pos + 4).min(self.input.len());
+                    let hex = std::str::from_utf8(&self.input[self.pos..end]).unwrap_or_default();
+                    let c = u32::from_str_radix(hex, 16)
+                        .ok()
+                        .and_then(char::from_u32)
+                        .ok_or_else(|| self.error("invalid unicode escape"))?;
+                    self.pos = end;
+                    push_char(&mut bytes, c);
+                }
+                b'0'..=b'7' => {
+                    let start = self.pos - 1;
+                    while self.pos < start + 3 && matches!(self.peek(), Some(b'0'..=b'7')) {
+                        self.pos += 1;
+                    }
+                    let octal =
+                        std::str::from_utf8(&self.input[start..self.pos]).unwrap_or_default();
+                    let c = u32::from_str_radix(octal, 8)
+                        .ok()
+                        .and_then(char::from_u32)
+                        .ok_or_else(|| self.error("invalid octal escape"))?;
+                    push_char(&mut bytes, c);
+                }
+                other => bytes.push(other),
+            }
+        }
+        String::from_utf8(bytes).map_err(|err| self.error(&err.to_string()))
+    }
+}
+
+fn push_char(bytes: &mut Vec<u8>, c: char) {
+    let mut buf = [0; 4];
+    bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+}
+
+fn is_unquoted_char(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, b'_' | b'$' | b'+' | b'/' | b':' | b'.' | b'-')
+}
+
+#[test]
+fn test_openstep_parse() {
+    let value = parse(
+        r#"{
+// comment
+.formatVersion = 3;
+familyName = "My \"Font\"";
+unitsPerEm = 1000;
+empty = ();
+/* another
+   comment */
+list = (1, "two", {a = b;},);
+nested = {
+"key with spaces" = -1.5;
+};
+data = <00ff 10>;
+escaped = "\012\U00e9";
+}"#,
+    )
+    .unwrap();
+    let dict = value.as_dictionary().unwrap();
+    assert_eq!(
+        dict.get(".formatVersion").and_then(Value::as_string),
+        Some("3")
+    );
+    assert_eq!(
+        dict.get("familyName").and_then(Value::as_string),
+        Some("My \"Font\"")
+    );
+    assert_eq!(
+        dict.get("empty").and_then(Value::as_array).map(Vec::len),
+        Some(0)
+    );
+    let list = dict.get("list").and_then(Value::as_array).unwrap();
+    assert_eq!(list.len(), 3);
+    assert_eq!(list[1].as_string(), Some("two"));
+    assert_eq!(
+        list[2]
+            .as_dictionary()
+            .and_then(|d| d.get("a"))
+            .and_then(Value::as_string),
+        Some("b")
+    );
+    assert_eq!(
+        dict.get("nested")
+            .and_then(Value::as_dictionary)
+            .and_then(|d| d.get("key with spaces"))
+            .and_then(Value::as_string),
+        Some("-1.5")
+    );
+    assert_eq!(
+        dict.get("data").and_then(Value::as_data),
+        Some([0x00, 0xff, 0x10].as_slice())
+    );
+    assert_eq!(
+        dict.get("escaped").and_then(Value::as_string),
+        Some("\n\u{e9}")
+    );
+    assert!(parse("{a = b}").is_err());
+    assert!(parse("(1, 2").is_err());
+}
@@ -0,0 +1,818 @@
+/*
+ * gerb
+ *
+ * Copyright 2022 - Manos Pitsidianakis
+ *
+ * This file is part of gerb.
+ *
+ * gerb is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * gerb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with gerb. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Conversion of UFOv1/UFOv2 directories to UFOv3.
+//!
+//! The conversion follows what `fontTools.ufoLib` does when upgrading a UFO:
+//!
+//! - `fontinfo.plist` keys from UFOv1 are renamed and values that must be integers in UFOv3 are
+//!   rounded.
+//! - `.glif` files are converted from format 1 to format 2, turning single point named `move`
+//!   contours into `<anchor>` elements.
+//! - kerning groups are renamed with the `public.kern1.` and `public.kern2.` prefixes.
+//! - the default layer gets a `layercontents.plist`.
+//!
+//! Anything that can't be converted losslessly is returned as a list of messages along with the
+//! new UFO.
+
+use super::{gen_setter, write_dictionary, UFOInstance};
+use crate::ufo::{copy_directory, glif, LayerContents, MetaInfo};
+use plist::{Dictionary, Value};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+#[derive(Debug, Clone)]
+pub struct UFO2ToUFO3Options {
+    input_dir: PathBuf,
+    output_dir: PathBuf,
+}
+
+impl UFO2ToUFO3Options {
+    pub fn new(input_dir: PathBuf, output_dir: PathBuf) -> Self {
+        Self {
+            input_dir,
+            output_dir,
+        }
+    }
+
+    gen_setter! {
+        input_dir: PathBuf,
+        output_dir: PathBuf,
+    }
+}
+
+/// UFOv1 `fontinfo.plist` keys and their UFOv2/v3 names.
+const FONTINFO_RENAMES: &[(&str, &str)] = &[
+    ("menuName", "styleMapFamilyName"),
+    ("designer", "openTypeNameDesigner"),
+    ("designerURL", "openTypeNameDesignerURL"),
+    ("createdBy", "openTypeNameManufacturer"),
+    ("vendorURL", "openTypeNameManufacturerURL"),
+    ("license", "openTypeNameLicense"),
+    ("licenseURL", "openTypeNameLicenseURL"),
+    ("ttVersion", "openTypeNameVersion"),
+    ("ttUniqueID", "openTypeNameUniqueID"),
+    ("notice", "openTypeNameDescription"),
+    ("msCharSet", "postscriptWindowsCharacterSet"),
+    ("fontName", "postscriptFontName"),
+    ("fullName", "postscriptFullName"),
+    ("slantAngle", "postscriptSlantAngle"),
+    ("weightName", "postscriptWeightName"),
+    ("uniqueID", "postscriptUniqueID"),
+    ("fondName", "macintoshFONDName"),
+    ("fondID", "macintoshFONDFamilyID"),
+    ("ttVendor", "openTypeOS2VendorID"),
+    ("weightValue", "openTypeOS2WeightClass"),
+    ("widthName", "openTypeOS2WidthClass"),
+    ("fontStyle", "styleMapStyleName"),
+];
+
+/// `fontinfo.plist` keys that may be floats in UFOv2 but must be integers in UFOv3.
+const FONTINFO_FLOAT_TO_INT: &[&str] = &[
+    "openTypeHeadLowestRecPPEM",
+    "openTypeHheaAscender",
+    "openTypeHheaDescender",
+    "openTypeHheaLineGap",
+    "openTypeHheaCaretOffset",
+    "openTypeOS2TypoAscender",
+    "openTypeOS2TypoDescender",
+    "openTypeOS2TypoLineGap",
+    "openTypeOS2WinAscent",
+    "openTypeOS2WinDescent",
+    "openTypeVheaVertTypoAscender",
+    "openTypeVheaVertTypoDescender",
+    "openTypeVheaVertTypoLineGap",
+    "openTypeVheaCaretOffset",
+];
+
+/// `fontinfo.plist` keys that must be non-negative in UFOv3.
+const FONTINFO_NON_NEGATIVE: &[&str] = &[
+    "unitsPerEm",
+    "versionMinor",
+    "openTypeHeadLowestRecPPEM",
+    "openTypeOS2WinAscent",
+    "openTypeOS2WinDescent",
+];
+
+/// UFOv1 `msCharSet` values and their `postscriptWindowsCharacterSet` equivalents.
+const MS_CHAR_SET: &[(i64, i64)] = &[
+    (0, 1),
+    (1, 2),
+    (2, 3),
+    (77, 4),
+    (128, 5),
+    (129, 6),
+    (130, 7),
+    (134, 8),
+    (136, 9),
+    (161, 10),
+    (162, 11),
+    (163, 12),
+    (177, 13),
+    (178, 14),
+    (186, 15),
+    (200, 16),
+    (204, 17),
+    (222, 18),
+    (238, 19),
+    (255, 20),
+];
+
+/// UFOv1 `widthName` values and their `openTypeOS2WidthClass` equivalents.
+const WIDTH_NAMES: &[(&str, i64)] = &[
+    ("Ultra-condensed", 1),
+    ("Extra-condensed", 2),
+    ("Condensed", 3),
+    ("Semi-condensed", 4),
+    ("Medium (normal)", 5),
+    ("Semi-expanded", 6),
+    ("Expanded", 7),
+    ("Extra-expanded", 8),
+    ("Ultra-expanded", 9),
+];
+
+/// UFOv1 `fontStyle` values and their `styleMapStyleName` equivalents.
+const FONT_STYLES: &[(i64, &str)] = &[
+    (64, "regular"),
+    (1, "italic"),
+    (32, "bold"),
+    (33, "bold italic"),
+];
+
+/// UFOv1 `lib.plist` keys that held OpenType features, which are not moved to `features.fea`.
+const LIB_UFO1_FEATURE_KEYS: &[&str] = &[
+    "org.robofab.opentype.features",
+    "org.robofab.opentype.featureorder",
+    "org.robofab.opentype.classes",
+];
+
+/// RoboFont's UFOv2 glyph lib key for background images.
+const LIB_ROBOFONT_IMAGE: &str = "com.typemytype.robofont.image";
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// Converts `options.input_dir` to UFOv3.
+///
+/// The result is written to `options.output_dir` if it is empty or doesn't exist, otherwise to a
+/// new directory inside it named after the input. Returns the new UFO along with a description of
+/// everything that could not be converted losslessly.
+pub fn import(
+    options: UFO2ToUFO3Options,
+) -> Result<(UFOInstance, Vec<String>), Box<dyn std::error::Error>> {
+    let input = std::fs::canonicalize(&options.input_dir).map_err(|err| {
+        format!(
+            "Could not read input path {}: {err}",
+            options.input_dir.display()
+        )
+    })?;
+    let metainfo = read_dictionary(&input.join("metainfo.plist"))?.ok_or_else(|| {
+        format!(
+            "{} has no metainfo.plist, it is not a UFO directory.",
+            input.display()
+        )
+    })?;
+    let format_version = metainfo
+        .get("formatVersion")
+        .and_then(Value::as_unsigned_integer)
+        .ok_or("metainfo.plist has no valid formatVersion.")?;
+    match format_version {
+        1 | 2 => {}
+        3 => return Err(format!("{} is already a UFOv3 directory.", input.display()).into()),
+        other => return Err(format!("Unsupported UFO format version {other}.").into()),
+    }
+    let output = output_path(&input, &options.output_dir)?;
+    std::fs::create_dir_all(&output)?;
+    let mut lossy = vec![];
+
+    MetaInfo::default().save(&output.join("metainfo.plist"))?;
+
+    let mut fontinfo = read_dictionary(&input.join("fontinfo.plist"))?.unwrap_or_default();
+    convert_fontinfo(&mut fontinfo, &mut lossy);
+    write_dictionary(fontinfo.clone(), &output.join("fontinfo.plist"))?;
+
+    let mut groups = read_dictionary(&input.join("groups.plist"))?;
+    if let Some(kerning) = read_dictionary(&input.join("kerning.plist"))? {
+        let groups = groups.get_or_insert_with(Dictionary::new);
+        let kerning = convert_kerning(groups, kerning, &mut lossy);
+        write_dictionary(kerning, &output.join("kerning.plist"))?;
+    }
+    if let Some(groups) = groups {
+        write_dictionary(groups, &output.join("groups.plist"))?;
+    }
+
+    if let Some(lib) = read_dictionary(&input.join("lib.plist"))? {
+        for key in LIB_UFO1_FEATURE_KEYS {
+            if lib.contains_key(key) {
+                lossy.push(format!(
+                    "lib.plist: UFOv1 feature data in {key} was not moved to features.fea."
+                ));
+            }
+        }
+        std::fs::copy(input.join("lib.plist"), output.join("lib.plist"))?;
+    }
+    if input.join("features.fea").exists() {
+        std::fs::copy(input.join("features.fea"), output.join("features.fea"))?;
+    }
+
+    convert_glyphs(&input.join("glyphs"), &output.join("glyphs"), &mut lossy)?;
+    LayerContents::default().save(&output.join("layercontents.plist"))?;
+
+    if input.join("data").is_dir() {
+        copy_directory(&input.join("data"), &output.join("data"))?;
+    }
+    if input.join("images").is_dir() {
+        convert_images(&input.join("images"), &output.join("images"), &mut lossy)?;
+    }
+
+    const KNOWN_ENTRIES: &[&str] = &[
+        "metainfo.plist",
+        "fontinfo.plist",
+        "groups.plist",
+        "kerning.plist",
+        "lib.plist",
+        "features.fea",
+        "glyphs",
+        "data",
+        "images",
+    ];
+    for entry in std::fs::read_dir(&input)? {
+        let name = entry?.file_name();
+        let name = name.to_string_lossy();
+        if !KNOWN_ENTRIES.contains(&name.as_ref()) {
+            lossy.push(format!("Skipped unknown entry {name}."));
+        }
+    }
+
+    let string_value = |key: &str| {
+        fontinfo
+            .get(key)
+            .and_then(Value::as_string)
+            .unwrap_or_default()
+            .to_string()
+    };
+    Ok((
+        UFOInstance {
+            directory_name: output
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            family_name: string_value("familyName"),
+            style_name: string_value("styleName"),
+            full_path: output,
+        },
+        lossy,
+    ))
+}
+
+/// Returns `output` if it can be used as the UFO directory, otherwise a new directory inside it.
+fn output_path(input: &Path, output: &Path) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    if !output.exists() || std::fs::read_dir(output)?.next().is_none() {
+        return Ok(output.to_path_buf());
+    }
+    let mut name = input
+        .file_name()
+        .ok_or_else(|| format!("Invalid input path {}", input.display()))?
+        .to_string_lossy()
+        .to_string();
+    if Path::new(&name)
+        .extension()
+        .map(|ext| ext != "ufo")
+        .unwrap_or(true)
+    {
+        name.push_str(".ufo");
+    }
+    let ret = output.join(name);
+    if ret.exists() {
+        return Err(format!(
+            "Output directory {} is not empty and {} already exists.",
+            output.display(),
+            ret.display()
+        )
+        .into());
+    }
+    Ok(ret)
+}
+
+fn read_dictionary(path: &Path) -> Result<Option<Dictionary>, Box<dyn std::error::Error>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let value = Value::from_file(path)
+        .map_err(|err| format!("Could not read {}: {err}", path.display()))?;
+    Ok(Some(value.into_dictionary().ok_or_else(|| {
+        format!("{} is not a dictionary.", path.display())
+    })?))
+}
+
+fn convert_fontinfo(info: &mut Dictionary, lossy: &mut Vec<String>) {
+    for &(old, new) in FONTINFO_RENAMES {
+        let Some(value) = info.remove(old) else {
+            continue;
+        };
+        if info.contains_key(new) {
+            lossy.push(format!(
+                "fontinfo.plist: dropped UFOv1 key {old} because {new} is already set."
+            ));
+            continue;
+        }
+        let value = match old {
+            "fontStyle" => value.as_signed_integer().and_then(|v| {
+                FONT_STYLES
+                    .iter()
+                    .find(|(k, _)| *k == v)
+                    .map(|(_, s)| Value::String(s.to_string()))
+            }),
+            "widthName" => value.as_string().and_then(|v| {
+                WIDTH_NAMES
+                    .iter()
+                    .find(|(k, _)| *k == v)
+                    .map(|(_, w)| Value::Integer((*w).into()))
+            }),
+            "msCharSet" => value.as_signed_integer().and_then(|v| {
+                MS_CHAR_SET
+                    .iter()
+                    .find(|(k, _)| *k == v)
+                    .map(|(_, c)| Value::Integer((*c).into()))
+            }),
+            _ => Some(value),
+        };
+        match value {
+            Some(value) => {
+                info.insert(new.to_string(), value);
+            }
+            None => {
+                lossy.push(format!(
+                    "fontinfo.plist: dropped UFOv1 key {old}, its value has no {new} equivalent."
+                ));
+            }
+        }
+    }
+    for &key in FONTINFO_FLOAT_TO_INT {
+        let Some(v) = info.get(key).and_then(Value::as_real) else {
+            continue;
+        };
+        let rounded = v.round();
+        if rounded != v {
+            lossy.push(format!(
+                "fontinfo.plist: rounded {key} from {v} to {rounded}."
+            ));
+        }
+        info.insert(key.to_string(), Value::Integer((rounded as i64).into()));
+    }
+    for &key in FONTINFO_NON_NEGATIVE {
+        let negative = match info.get(key) {
+            Some(Value::Integer(i)) => i.as_signed().map(|i| i < 0).unwrap_or(false),
+            Some(Value::Real(f)) => *f < 0.0,
+            _ => false,
+        };
+        if negative {
+            info.remove(key);
+            lossy.push(format!(
+                "fontinfo.plist: dropped {key} because it is negative."
+            ));
+        }
+    }
+}
+
+/// Renames kerning groups to `public.kern1.*`/`public.kern2.*` and returns the updated kerning.
+///
+/// Groups used on the first side of a pair or named with the `@MMK_L_` prefix become first side
+/// groups, and likewise for `@MMK_R_` and the second side. The original groups are kept.
+fn convert_kerning(
+    groups: &mut Dictionary,
+    kerning: Dictionary,
+    lossy: &mut Vec<String>,
+) -> Dictionary {
+    let mut first = vec![];
+    let mut second = vec![];
+    for (side1, pairs) in kerning.iter() {
+        if groups.contains_key(side1) && !first.contains(side1) {
+            first.push(side1.clone());
+        }
+        for side2 in pairs.as_dictionary().into_iter().flat_map(Dictionary::keys) {
+            if groups.contains_key(side2) && !second.contains(side2) {
+                second.push(side2.clone());
+            }
+        }
+    }
+    for name in groups.keys() {
+        if name.starts_with("@MMK_L_") && !first.contains(name) {
+            first.push(name.clone());
+        } else if name.starts_with("@MMK_R_") && !second.contains(name) {
+            second.push(name.clone());
+        }
+    }
+    let first = rename_groups(groups, &first, "public.kern1.");
+    let second = rename_groups(groups, &second, "public.kern2.");
+
+    let mut ret = Dictionary::new();
+    for (side1, pairs) in kerning {
+        let Value::Dictionary(pairs) = pairs else {
+            lossy.push(format!(
+                "kerning.plist: dropped {side1} because its value is not a dictionary."
+            ));
+            continue;
+        };
+        let pairs = pairs
+            .into_iter()
+            .map(|(side2, value)| (second.get(&side2).cloned().unwrap_or(side2), value))
+            .collect::<Dictionary>();
+        let side1 = first.get(&side1).cloned().unwrap_or(side1);
+        ret.insert(side1, Value::Dictionary(pairs));
+    }
+    ret
+}
+
+fn rename_groups(
+    groups: &mut Dictionary,
+    names: &[String],
+    prefix: &str,
+) -> HashMap<String, String> {
+    let mut ret = HashMap::default();
+    for old in names {
+        if old.starts_with(prefix) {
+            continue;
+        }
+        let mut new = format!("{prefix}{old}");
+        let mut counter = 1;
+        while groups.contains_key(&new) {
+            new = format!("{prefix}{old}{counter}");
+            counter += 1;
+        }
+        if let Some(members) = groups.get(old).cloned() {
+            groups.insert(new.clone(), members);
+        }
+        ret.insert(old.clone(), new);
+    }
+    ret
+}
+
+fn convert_glyphs(
+    input: &Path,
+    output: &Path,
+    lossy: &mut Vec<String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    std::fs::create_dir_all(output)?;
+    if !input.is_dir() {
+        return Ok(());
+    }
+    for entry in std::fs::read_dir(input)? {
+        let path = entry?.path();
+        let Some(file_name) = path.file_name() else {
+            continue;
+        };
+        let out_path = output.join(file_name);
+        if path.is_dir() {
+            copy_directory(&path, &out_path)?;
+            continue;
+        }
+        if path.extension().map(|ext| ext != "glif").unwrap_or(true) {
+            std::fs::copy(&path, &out_path)?;
+            continue;
+        }
+        let source = std::fs::read_to_string(&path)
+            .map_err(|err| format!("Couldn't read {}: {err}", path.display()))?;
+        let converted = convert_glif(&source)
+            .map_err(|err| format!("Couldn't convert {}: {err}", path.display()))?;
+        glif::Glif::from_str(&converted)
+            .map_err(|err| format!("Converted {} is not valid: {err}", path.display()))?;
+        if source.contains(LIB_ROBOFONT_IMAGE) {
+            lossy.push(format!(
+                "{}: RoboFont image reference in the glyph lib was not converted to an image element.",
+                file_name.to_string_lossy()
+            ));
+        }
+        std::fs::write(&out_path, converted)?;
+    }
+    Ok(())
+}
+
+/// Converts a format 1 `.glif` to format 2.
+///
+/// Format 1 stores anchors as contours with a single named `move` point; these are moved out of
+/// the outline into `<anchor>` elements. Everything else is kept as is.
+fn convert_glif(source: &str) -> Result<String, Box<dyn std::error::Error>> {
+    use quick_xml::events::{BytesStart, BytesText, Event};
+
+    let mut reader = quick_xml::Reader::from_str(source);
+    let mut writer = quick_xml::Writer::new(Vec::new());
+    let mut contour: Option<Vec<Event<'static>>> = None;
+    let mut anchors: Vec<BytesStart<'static>> = vec![];
+    loop {
+        let event = reader.read_event()?;
+        if let Some(events) = contour.as_mut() {
+            let is_end = matches!(&event, Event::End(e) if e.name().as_ref() == b"contour");
+            events.push(event.into_owned());
+            if is_end {
+                let events = contour.take().unwrap_or_default();
+                if let Some(anchor) = as_anchor(&events) {
+                    anchors.push(anchor);
+                } else {
+                    for event in events {
+                        writer.write_event(event)?;
+                    }
+                }
+            }
+            continue;
+        }
+        match event {
+            Event::Eof => break,
+            Event::Start(e) if e.name().as_ref() == b"glyph" => {
+                writer.write_event(Event::Start(glyph_format_2(&e)))?;
+            }
+            Event::Empty(e) if e.name().as_ref() == b"glyph" => {
+                writer.write_event(Event::Empty(glyph_format_2(&e)))?;
+            }
+            Event::Start(e) if e.name().as_ref() == b"contour" => {
+                contour = Some(vec![Event::Start(e.into_owned())]);
+            }
+            Event::End(e) if e.name().as_ref() == b"outline" => {
+                writer.write_event(Event::End(e))?;
+                for anchor in anchors.drain(..) {
+                    writer.write_event(Event::Text(BytesText::new("\n\t")))?;
+                    writer.write_event(Event::Empty(anchor))?;
+                }
+            }
+            other => writer.write_event(other)?,
+        }
+    }
+    if contour.is_some() {
+        return Err("Unterminated <contour> element.".into());
+    }
+    Ok(String::from_utf8(writer.into_inner())?)
+}
+
+fn glyph_format_2(
+    glyph: &quick_xml::events::BytesStart<'_>,
+) -> quick_xml::events::BytesStart<'static> {
+    let mut ret = quick_xml::events::BytesStart::new("glyph");
+    for attr in glyph.attributes().flatten() {
+        if attr.key.as_ref() != b"format" {
+            ret.push_attribute(attr);
+        }
+    }
+    ret.push_attribute(("format", "2"));
+    ret
+}
+
+/// Returns an `<anchor>` element if `events` is a format 1 anchor contour.
+fn as_anchor(
+    events: &[quick_xml::events::Event<'_>],
+) -> Option<quick_xml::events::BytesStart<'static>> {
+    use quick_xml::events::Event;
+
+    let mut points = events.iter().filter_map(|e| match e {
+        Event::Start(p) | Event::Empty(p) if p.name().as_ref() == b"point" => Some(p),
+        _ => None,
+    });
+    let point = points.next()?;
+    if points.next().is_some() {
+        return None;
+    }
+    let attr = |key: &str| -> Option<String> {
+        Some(
+            point
+                .try_get_attribute(key)
+                .ok()??
+                .unescape_value()
+                .ok()?
+                .into_owned(),
+        )
+    };
+    if attr("type")? != "move" {
+        return None;
+    }
+    let mut anchor = quick_xml::events::BytesStart::new("anchor");
+    anchor.push_attribute(("x", attr("x").unwrap_or_else(|| "0".into()).as_str()));
+    anchor.push_attribute(("y", attr("y").unwrap_or_else(|| "0".into()).as_str()));
+    anchor.push_attribute(("name", attr("name")?.as_str()));
+    Some(anchor)
+}
+
+/// Copies PNG images; UFOv3 only allows PNG files in `images`.
+fn convert_images(
+    input: &Path,
+    output: &Path,
+    lossy: &mut Vec<String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    std::fs::create_dir_all(output)?;
+    for entry in std::fs::read_dir(input)? {
+        let path = entry?.path();
+        let Some(file_name) = path.file_name() else {
+            continue;
+        };
+        let is_png = path.is_file()
+            && std::fs::read(&path)
+                .map(|bytes| bytes.starts_with(PNG_SIGNATURE))
+                .unwrap_or(false);
+        if is_png {
+            std::fs::copy(&path, output.join(file_name))?;
+        } else {
+            lossy.push(format!(
+                "images: skipped {} because it is not a PNG file.",
+                file_name.to_string_lossy()
+            ));
+        }
+    }
+    Ok(())
+}
+
+pub fn import_action_cb(window: gtk::Window) {
+    use crate::prelude::*;
+
+    let dialog = gtk::FileChooserNative::new(
+        Some("Select UFOv2 input path"),
+        Some(&window),
+        gtk::FileChooserAction::SelectFolder,
+        None,
+        None,
+    );
+    return_if_not_ok_or_accept!(dialog.run());
+    dialog.hide();
+    let Some(input_dir) = dialog.filename() else {
+        return;
+    };
+    drop(dialog);
+    let dialog2 = gtk::FileChooserNative::new(
+        Some("Select UFOv3 output path"),
+        Some(&window),
+        gtk::FileChooserAction::SelectFolder,
+        None,
+        None,
+    );
+
+    return_if_not_ok_or_accept!(dialog2.run());
+    dialog2.hide();
+
+    let Some(output_dir) = dialog2.filename() else {
+        return;
+    };
+    drop(dialog2);
+    match import(UFO2ToUFO3Options::new(input_dir, output_dir)) {
+        Ok((instance, lossy)) => {
+            if !lossy.is_empty() {
+                let dialog = crate::utils::widgets::new_simple_info_dialog(
+                    Some("Warning: some data could not be converted to UFOv3 losslessly"),
+                    &lossy
+                        .iter()
+                        .map(|msg| format!("• {}", glib::markup_escape_text(msg)))
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                    Some(&format!(
+                        "Project saved at {}",
+                        glib::markup_escape_text(&instance.full_path.display().to_string())
+                    )),
+                    &window,
+                );
+                dialog.run();
+                dialog.emit_close();
+            }
+            window.emit_by_name::<()>("open-project", &[&instance.full_path.display().to_string()]);
+        }
+        Err(err) => {
+            let dialog = crate::utils::widgets::new_simple_error_dialog(
+                Some("Error: could not perform conversion to UFOv3"),
+                &err.to_string(),
+                None,
+                &window,
+            );
+            dialog.run();
+            dialog.emit_close();
+        }
+    }
+}
+
+#[test]
+fn test_ufo2_to_ufo3() {
+    let tmp = std::env::temp_dir().join(format!(
+        "gerb-test-ufo2-{}",
+        crate::ufo::make_random_identifier()
+    ));
+    let input = tmp.join("Test.ufo");
+    std::fs::create_dir_all(input.join("glyphs")).unwrap();
+    let write = |name: &str, contents: &str| std::fs::write(input.join(name), contents).unwrap();
+    let plist = |body: &str| {
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+{body}
+</plist>"#
+        )
+    };
+    write(
+        "metainfo.plist",
+        &plist("<dict><key>creator</key><string>test</string><key>formatVersion</key><integer>2</integer></dict>"),
+    );
+    write(
+        "fontinfo.plist",
+        &plist("<dict><key>familyName</key><string>Test</string><key>designer</key><string>Someone</string><key>openTypeHheaAscender</key><real>800.5</real></dict>"),
+    );
+    write(
+        "groups.plist",
+        &plist("<dict><key>@MMK_L_O</key><array><string>O</string></array><key>V_</key><array><string>V</string></array></dict>"),
+    );
+    write(
+        "kerning.plist",
+        &plist("<dict><key>@MMK_L_O</key><dict><key>V_</key><integer>-40</integer></dict></dict>"),
+    );
+    write(
+        "glyphs/contents.plist",
+        &plist("<dict><key>A</key><string>A_.glif</string></dict>"),
+    );
+    write(
+        "glyphs/A_.glif",
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<glyph name="A" format="1">
+	<advance width="600"/>
+	<unicode hex="0041"/>
+	<outline>
+		<contour>
+			<point x="300" y="700" type="move" name="top"/>
+		</contour>
+		<contour>
+			<point x="0" y="0" type="line"/>
+			<point x="300" y="700" type="line"/>
+			<point x="600" y="0" type="line"/>
+		</contour>
+	</outline>
+</glyph>
+"#,
+    );
+    write("notes.txt", "");
+
+    let output = tmp.join("out");
+    let (instance, lossy) = import(UFO2ToUFO3Options::new(input, output.clone())).unwrap();
+    assert_eq!(instance.full_path, output);
+    assert_eq!(instance.family_name, "Test");
+    assert_eq!(lossy.len(), 2, "{lossy:?}");
+
+    assert_eq!(
+        MetaInfo::from_path(&output.join("metainfo.plist"))
+            .unwrap()
+            .format_version,
+        3
+    );
+    assert!(output.join("layercontents.plist").exists());
+
+    let fontinfo = read_dictionary(&output.join("fontinfo.plist"))
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        fontinfo
+            .get("openTypeNameDesigner")
+            .and_then(Value::as_string),
+        Some("Someone")
+    );
+    assert!(!fontinfo.contains_key("designer"));
+    assert_eq!(
+        fontinfo
+            .get("openTypeHheaAscender")
+            .and_then(Value::as_signed_integer),
+        Some(801)
+    );
+
+    let groups = read_dictionary(&output.join("groups.plist"))
+        .unwrap()
+        .unwrap();
+    assert!(groups.contains_key("public.kern1.@MMK_L_O"));
+    assert!(groups.contains_key("public.kern2.V_"));
+    let kerning = read_dictionary(&output.join("kerning.plist"))
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        kerning
+            .get("public.kern1.@MMK_L_O")
+            .and_then(Value::as_dictionary)
+            .and_then(|d| d.get("public.kern2.V_"))
+            .and_then(Value::as_signed_integer),
+        Some(-40)
+    );
+
+    let (glif, _) = glif::Glif::from_path(&output.join("glyphs").join("A_.glif")).unwrap();
+    assert_eq!(glif.format.as_deref(), Some("2"));
+    assert_eq!(glif.anchors.len(), 1);
+    assert_eq!(glif.anchors[0].name, "top");
+    assert_eq!((glif.anchors[0].x, glif.anchors[0].y), (300.0, 700.0));
+    assert_eq!(glif.outline.unwrap().contours.len(), 1);
+    std::fs::remove_dir_all(&tmp).unwrap();
+}
@@ -347,6 +347,11 @@ impl FontInfo {
         Ok(())
     }
 
+    /// Writes fontinfo.plist to `destination` without marking it as saved.
+    pub fn save_copy(&self, destination: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        self.source.borrow().save(destination)
+    }
+
     pub fn path(&self) -> &Path {
         self.path.get().unwrap()
     }
@@ -366,6 +371,8 @@ impl_modified!(FontInfo);
 
 mod layer {
     use super::*;
+    use crate::ufo::glif;
+    use std::sync::mpsc;
 
     #[derive(Debug)]
     pub struct LayerInner {
@@ -376,6 +383,16 @@ mod layer {
         pub dir_name: RefCell<String>,
         pub contents_plist: RefCell<ufo::Contents>,
         pub glyphs: RefCell<IndexMap<String, Rc<RefCell<Glyph>>>>,
+        loaded: Cell<bool>,
+        parsed_rx: RefCell<Option<mpsc::Receiver<(String, glif::ParseResult)>>>,
+        /// Set while [`Layer::update_metric_dependents`] applies metric keys, so that the
+        /// glyphs it modifies don't start another update.
+        syncing_metrics: Cell<bool>,
+        /// Names the metric keys of each glyph that has any refer to. Built on first use once
+        /// the layer is loaded and kept up to date as glyphs are modified.
+        metric_references: RefCell<Option<std::collections::HashMap<String, Vec<String>>>>,
+        /// Where updates of metric dependents are recorded, see [`Layer::set_undo_database`].
+        undo_db: RefCell<Option<UndoDatabase>>,
     }
 
     impl Default for LayerInner {
@@ -388,6 +405,11 @@ mod layer {
                 dir_name: RefCell::new(String::new()),
                 contents_plist: RefCell::new(ufo::Contents::default()),
                 glyphs: RefCell::new(IndexMap::default()),
+                loaded: Cell::new(true),
+                parsed_rx: RefCell::new(None),
+                syncing_metrics: Cell::new(false),
+                metric_references: RefCell::new(None),
+                undo_db: RefCell::new(None),
             }
         }
     }
@@ -416,6 +438,13 @@ mod layer {
                         ),
                         def_param!(str Layer::NAME),
                         def_param!(str Layer::DIR_NAME),
+                        ParamSpecBoolean::new(
+                            Layer::LOADED,
+                            Layer::LOADED,
+                            "Whether all glyph outlines of this layer have been parsed.",
+                            true,
+                            glib::ParamFlags::READABLE,
+                        ),
                     ]
                 });
             PROPERTIES.as_ref()
@@ -426,6 +455,7 @@ mod layer {
                 Layer::MODIFIED => self.modified.get().to_value(),
                 Layer::NAME => self.name.borrow().to_value(),
                 Layer::DIR_NAME => self.dir_name.borrow().to_value(),
+                Layer::LOADED => self.loaded.get().to_value(),
                 _ => unimplemented!("{}", pspec.name()),
             }
         }
@@ -478,6 +508,10 @@ mod layer {
         pub const MODIFIED: &'static str = "modified";
         pub const NAME: &'static str = "name";
         pub const DIR_NAME: &'static str = "dir-name";
+        pub const LOADED: &'static str = "loaded";
+
+        /// How many glyphs [`Layer::load_in_background`] parses per main loop iteration.
+        const LOAD_BATCH_SIZE: usize = 32;
 
         pub fn new() -> Self {
             let ret: Self = glib::Object::new::<Self>(&[]).unwrap();
@@ -512,7 +546,21 @@ mod layer {
             path.pop();
             let glyphs = Glyph::from_ufo(path.clone(), &contents)?;
             for g in glyphs.values() {
-                self.link(&g.borrow().metadata);
+                let metadata = &g.borrow().metadata;
+                metadata.set_property(GlyphMetadata::LAYER, Some(self.clone()));
+                self.link(metadata);
+                self.watch_name(metadata);
+                self.watch_metrics(metadata);
+            }
+            self.loaded.set(glyphs.is_empty());
+            if !glyphs.is_empty() {
+                /* Start parsing on worker threads right away, glyphs are built out of the results
+                 * on the main thread by `load_in_background` or `load_all`. */
+                let jobs = glyphs
+                    .iter()
+                    .map(|(name, g)| (name.clone(), path.join(g.borrow().filename().as_str())))
+                    .collect::<Vec<(String, PathBuf)>>();
+                *self.parsed_rx.borrow_mut() = Some(glif::parse_in_thread_pool(jobs));
             }
             *self.glyphs.borrow_mut() = glyphs;
             *self.contents_plist.borrow_mut() = contents;
@@ -552,6 +600,26 @@ mod layer {
             Ok(())
         }
 
+        /// Writes the layer into `ufo_dir`, which should hold a copy of the project, without
+        /// marking anything as saved.
+        pub fn save_copy(&self, ufo_dir: &Path) -> Result<(), Box<dyn std::error::Error>> {
+            let prefix = ufo_dir.join(&*self.dir_name.borrow());
+            std::fs::create_dir_all(&prefix)?;
+            self.contents_plist
+                .borrow()
+                .clone()
+                .save(Some(&prefix.join("contents.plist")), true)?;
+            for g in self
+                .glyphs
+                .borrow()
+                .values()
+                .filter(|g| g.borrow().modified())
+            {
+                g.borrow().save(&prefix)?;
+            }
+            Ok(())
+        }
+
         pub fn new_glyph(
             &self,
             name: String,
@@ -569,12 +637,350 @@ mod layer {
                 .borrow()
                 .metadata
                 .set_property(GlyphMetadata::LAYER, Some(self.clone()));
+            self.watch_name(&glyph.borrow().metadata);
+            self.watch_metrics(&glyph.borrow().metadata);
             self.set_property(Self::MODIFIED, true);
             contents.insert(name.clone(), glyph.borrow().metadata.filename().to_string());
             self.glyphs.borrow_mut().insert(name, glyph);
             Ok(())
         }
 
+        /// Builds the glyphs that haven't been accessed yet out of the `.glif` files parsed by
+        /// the worker threads [`Layer::init_from_path`] started, in small batches from the main
+        /// loop's idle handler so that opening a project doesn't wait on every file.
+        /// [`Layer::LOADED`] is notified when all glyphs are loaded.
+        pub fn load_in_background(&self) {
+            glib::idle_add_local(
+                clone!(@weak self as layer => @default-return glib::Continue(false), move || {
+                    let mut errors = vec![];
+                    let more = layer.receive_parsed(false, &mut errors);
+                    for err in errors {
+                        eprintln!("{err}");
+                    }
+                    if more {
+                        return glib::Continue(true);
+                    }
+                    layer.loaded.set(true);
+                    layer.notify(Self::LOADED);
+                    glib::Continue(false)
+                }),
+            );
+        }
+
+        /// Loads every glyph that hasn't been accessed yet, blocking until done.
+        pub fn load_all(&self) -> Result<(), Box<dyn std::error::Error>> {
+            let mut errors = vec![];
+            self.receive_parsed(true, &mut errors);
+            let glyphs = self.glyphs.borrow().values().cloned().collect::<Vec<_>>();
+            for g in glyphs {
+                /* Anything the worker threads didn't deliver is parsed here. */
+                if let Err(err) = Glyph::load(&g) {
+                    errors.push(err.to_string());
+                }
+            }
+            if !self.loaded.get() {
+                self.loaded.set(true);
+                self.notify(Self::LOADED);
+            }
+            if !errors.is_empty() {
+                return Err(errors.join("\n").into());
+            }
+            Ok(())
+        }
+
+        /// Builds glyphs out of parse results from the worker threads. If `blocking` is false, at
+        /// most [`Layer::LOAD_BATCH_SIZE`] results that are already available are processed.
+        /// Returns whether there are more results to receive.
+        fn receive_parsed(&self, blocking: bool, errors: &mut Vec<String>) -> bool {
+            use std::sync::mpsc::TryRecvError;
+
+            let Some(rx) = self.parsed_rx.take() else {
+                return false;
+            };
+            let mut count = 0;
+            loop {
+                let msg = if blocking {
+                    rx.recv().map_err(|_| TryRecvError::Disconnected)
+                } else if count == Self::LOAD_BATCH_SIZE {
+                    Err(TryRecvError::Empty)
+                } else {
+                    rx.try_recv()
+                };
+                match msg {
+                    Ok((name, result)) => {
+                        count += 1;
+                        let glyph = self.glyphs.borrow().get(&name).cloned();
+                        let Some(glyph) = glyph else {
+                            continue;
+                        };
+                        match result {
+                            Ok((glif, source)) => Glyph::load_parsed(&glyph, glif, source),
+                            Err(_) => {
+                                /* Retry on this thread, which records the error on the glyph. */
+                                if let Err(err) = Glyph::load(&glyph) {
+                                    errors.push(err.to_string());
+                                }
+                            }
+                        }
+                    }
+                    Err(TryRecvError::Empty) => {
+                        *self.parsed_rx.borrow_mut() = Some(rx);
+                        return true;
+                    }
+                    Err(TryRecvError::Disconnected) => return false,
+                }
+            }
+        }
+
+        /// Links the components of every loaded glyph to their base glyphs by name, and returns a
+        /// description of each problem found: components whose base glyph doesn't exist and
+        /// cycles of components, which are drawn as placeholders.
+        pub fn resolve_components(&self) -> Vec<String> {
+            let mut problems = vec![];
+            let glyphs = self.glyphs.borrow();
+            for (name, glyph) in glyphs.iter() {
+                let mut glyph = glyph.borrow_mut();
+                if !glyph.is_loaded() {
+                    continue;
+                }
+                for c in glyph.components.iter_mut() {
+                    if let Some(base) = glyphs.get(&c.base_name) {
+                        c.base = Rc::downgrade(base);
+                    } else {
+                        c.base = std::rc::Weak::new();
+                        problems.push(format!(
+                            "Glyph `{name}` has a component with missing base glyph `{}`.",
+                            c.base_name
+                        ));
+                    }
+                }
+            }
+            for cycle in Glyph::component_cycles(&glyphs) {
+                problems.push(format!("Components form a cycle: {}.", cycle.join(" → ")));
+            }
+            problems
+        }
+
+        /// Keeps the glyph map and contents.plist keyed by the glyph's current name.
+        fn watch_name(&self, metadata: &GlyphMetadata) {
+            metadata.connect_notify_local(
+                Some(GlyphMetadata::NAME),
+                clone!(@weak self as layer => move |metadata, _| {
+                    layer.glyph_renamed(metadata);
+                }),
+            );
+        }
+
+        /// Updates the glyphs whose metric keys refer to a glyph when it is modified.
+        fn watch_metrics(&self, metadata: &GlyphMetadata) {
+            metadata.connect_notify_local(
+                Some(GlyphMetadata::MODIFIED),
+                clone!(@weak self as layer => move |metadata, _| {
+                    if !metadata.modified() || layer.syncing_metrics.get() {
+                        return;
+                    }
+                    /* Undoing or redoing a change also undoes or redoes its dependents. */
+                    let replaying = layer
+                        .undo_db
+                        .borrow()
+                        .as_ref()
+                        .map_or(false, UndoDatabase::is_replaying);
+                    let name = metadata.name().clone();
+                    /* Whoever modified the glyph may still be borrowing it. */
+                    glib::idle_add_local(
+                        clone!(@weak layer => @default-return glib::Continue(false), move || {
+                            layer.reindex_metric_references(&name);
+                            if replaying {
+                                return glib::Continue(false);
+                            }
+                            let action = layer.update_metric_dependents(&name);
+                            if let (Some(action), Some(undo_db)) =
+                                (action, layer.undo_db.borrow().as_ref())
+                            {
+                                undo_db.amend(action);
+                            }
+                            glib::Continue(false)
+                        }),
+                    );
+                }),
+            );
+        }
+
+        /// Sets where changes the layer makes on its own, like updating metric dependents, are
+        /// recorded. They are added to the event of the change that caused them.
+        pub fn set_undo_database(&self, undo_db: UndoDatabase) {
+            *self.undo_db.borrow_mut() = Some(undo_db);
+        }
+
+        /// Applies the metric keys of the glyphs that refer to the glyph `name`, then of the
+        /// glyphs that refer to those, and so on. Keys that refer to each other without
+        /// settling on a value are given up on after a few rounds. Returns the changes, already
+        /// applied, as a single action for the caller to add to the change that caused them
+        /// with [`UndoDatabase::amend`]. Glyphs that
+        /// aren't parsed yet are left out; the collection shows them as out of sync.
+        pub fn update_metric_dependents(&self, name: &str) -> Option<Action> {
+            if self.syncing_metrics.get() {
+                return None;
+            }
+            self.syncing_metrics.set(true);
+            let glyphs = self.glyphs.borrow().clone();
+            let mut budget = 4 * glyphs.len();
+            let mut actions = vec![];
+            let mut queue = std::collections::VecDeque::from([name.to_string()]);
+            'queue: while let Some(source) = queue.pop_front() {
+                for dependent in self.metric_dependents(&source) {
+                    let Some(glyph) = glyphs.get(&dependent) else {
+                        continue;
+                    };
+                    match Glyph::sync_metrics_action(glyph, &glyphs) {
+                        Ok(Some(mut action)) => {
+                            if budget == 0 {
+                                eprintln!("Metric keys referring to `{name}` don't converge.");
+                                break 'queue;
+                            }
+                            budget -= 1;
+                            (action.redo)();
+                            actions.push(action);
+                            if !queue.contains(&dependent) {
+                                queue.push_back(dependent);
+                            }
+                        }
+                        Ok(None) => {}
+                        Err(err) => eprintln!("{err}"),
+                    }
+                }
+            }
+            self.syncing_metrics.set(false);
+            if actions.is_empty() {
+                return None;
+            }
+            Some(Action::group(
+                EventStamp {
+                    t: std::any::TypeId::of::<Glyph>(),
+                    property: "metric-keys",
+                    id: name.as_bytes().into(),
+                },
+                actions,
+            ))
+        }
+
+        /// Names of the parsed glyphs whose metric keys refer to the glyph `source`.
+        fn metric_dependents(&self, source: &str) -> Vec<String> {
+            let dependents = |references: &std::collections::HashMap<String, Vec<String>>| {
+                references
+                    .iter()
+                    .filter(|(_, r)| r.iter().any(|r| r == source))
+                    .map(|(name, _)| name.clone())
+                    .collect()
+            };
+            if let Some(references) = self.metric_references.borrow().as_ref() {
+                return dependents(references);
+            }
+            let references = self
+                .glyphs
+                .borrow()
+                .iter()
+                .filter(|(_, g)| g.borrow().is_loaded())
+                .filter_map(|(name, g)| {
+                    let r = g.borrow().metric_key_references();
+                    (!r.is_empty()).then(|| (name.clone(), r))
+                })
+                .collect();
+            let ret = dependents(&references);
+            /* Glyphs parsed later wouldn't be in it. */
+            if self.loaded.get() {
+                *self.metric_references.borrow_mut() = Some(references);
+            }
+            ret
+        }
+
+        /// Updates the references of the glyph `name` after it was modified, in case its
+        /// metric keys changed.
+        fn reindex_metric_references(&self, name: &str) {
+            let mut references = self.metric_references.borrow_mut();
+            let Some(references) = references.as_mut() else {
+                return;
+            };
+            let r = self
+                .glyphs
+                .borrow()
+                .get(name)
+                .map(|g| g.borrow().metric_key_references())
+                .unwrap_or_default();
+            if r.is_empty() {
+                references.remove(name);
+            } else {
+                references.insert(name.to_string(), r);
+            }
+        }
+
+        /// Re-keys a glyph after its [`GlyphMetadata::NAME`] changed and makes the components
+        /// that referred to its old name refer to the new one. Renaming to an empty name or
+        /// the name of another glyph is reverted.
+        fn glyph_renamed(&self, metadata: &GlyphMetadata) {
+            let new_name = metadata.name().to_string();
+            let old_name = self
+                .glyphs
+                .borrow()
+                .iter()
+                .find(|(_, g)| g.borrow().metadata == *metadata)
+                .map(|(name, _)| name.clone());
+            let Some(old_name) = old_name.filter(|n| *n != new_name) else {
+                return;
+            };
+            if new_name.is_empty() || self.glyphs.borrow().contains_key(&new_name) {
+                eprintln!("Cannot rename glyph `{old_name}` to `{new_name}`.");
+                metadata.set_property(GlyphMetadata::NAME, old_name);
+                return;
+            }
+            {
+                let mut glyphs = self.glyphs.borrow_mut();
+                *glyphs = std::mem::take(&mut *glyphs)
+                    .into_iter()
+                    .map(|(k, v)| {
+                        if k == old_name {
+                            (new_name.clone(), v)
+                        } else {
+                            (k, v)
+                        }
+                    })
+                    .collect();
+            }
+            self.contents_plist
+                .borrow_mut()
+                .rename(&old_name, new_name.clone());
+            self.metric_references.borrow_mut().take();
+            let glyphs = self.glyphs.borrow().values().cloned().collect::<Vec<_>>();
+            let mut modified = vec![];
+            for g in glyphs {
+                /* Glyphs that haven't been parsed yet refer to the old name in their files. */
+                if let Err(err) = Glyph::load(&g) {
+                    eprintln!("{err}");
+                }
+                let mut changed = false;
+                for c in g
+                    .borrow_mut()
+                    .components
+                    .iter_mut()
+                    .filter(|c| c.base_name == old_name)
+                {
+                    c.base_name = new_name.clone();
+                    changed = true;
+                }
+                if changed {
+                    modified.push(g);
+                }
+            }
+            for g in modified {
+                let metadata = g.borrow().metadata.clone();
+                metadata.set_property(GlyphMetadata::MODIFIED, true);
+            }
+            self.set_property(Self::MODIFIED, true);
+            for problem in self.resolve_components() {
+                eprintln!("{problem}");
+            }
+        }
+
         pub fn path(&'_ self) -> FieldRef<'_, PathBuf> {
             self.path.borrow().into()
         }
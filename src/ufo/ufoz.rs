@@ -0,0 +1,260 @@
+/*
+ * gerb
+ *
+ * Copyright 2022 - Manos Pitsidianakis
+ *
+ * This file is part of gerb.
+ *
+ * gerb is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * gerb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with gerb. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! UFOZ, a ZIP archive with a single UFO directory at its root.
+//!
+//! Archives are extracted to a temporary directory and the project is edited there; saving writes
+//! the directory back into the archive.
+
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+
+pub const EXTENSION: &str = "ufoz";
+
+/// MIME type of UFOZ archives, e.g. for the Recent Files database.
+pub const MIME_TYPE: &str = "application/zip";
+
+/// ZIP local file header signature.
+const MAGIC: [u8; 4] = *b"PK\x03\x04";
+
+/// Returns `true` if `path` is a regular file that looks like a UFOZ archive, either by its
+/// extension or by its ZIP signature.
+pub fn is_ufoz(path: &Path) -> bool {
+    if !path.is_file() {
+        return false;
+    }
+    if path
+        .extension()
+        .map(|ext| ext.eq_ignore_ascii_case(EXTENSION))
+        .unwrap_or(false)
+    {
+        return true;
+    }
+    let mut magic = [0; 4];
+    File::open(path)
+        .and_then(|mut f| f.read_exact(&mut magic))
+        .map(|()| magic == MAGIC)
+        .unwrap_or(false)
+}
+
+/// Extracts `archive` into a new temporary directory and returns the path of the UFO directory
+/// inside it.
+pub fn extract(archive: &Path) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let file = File::open(archive)
+        .map_err(|err| format!("Could not open {}: {err}", archive.display()))?;
+    let mut zip = zip::ZipArchive::new(file)
+        .map_err(|err| format!("{} is not a valid ZIP archive: {err}", archive.display()))?;
+    let root_name = root_directory(&mut zip)?;
+    let destination =
+        std::env::temp_dir().join(format!("gerb-{}", crate::ufo::make_random_identifier()));
+    std::fs::create_dir_all(&destination)?;
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i)?;
+        /* `enclosed_name` rejects absolute paths and `..` components. */
+        let Some(name) = entry.enclosed_name().map(Path::to_path_buf) else {
+            return Err(format!("Archive contains invalid path {:?}.", entry.name()).into());
+        };
+        let out_path = destination.join(name);
+        if entry.is_dir() {
+            std::fs::create_dir_all(&out_path)?;
+            continue;
+        }
+        if let Some(parent) = out_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut out = File::create(&out_path)?;
+        std::io::copy(&mut entry, &mut out)?;
+    }
+    Ok(destination.join(root_name))
+}
+
+/// Validates that all entries of the archive are under the same top-level directory and returns
+/// its name.
+fn root_directory<R: Read + std::io::Seek>(
+    zip: &mut zip::ZipArchive<R>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let mut root: Option<String> = None;
+    for name in zip.file_names() {
+        let Some(Component::Normal(first)) = Path::new(name).components().next() else {
+            return Err(format!("Archive contains invalid path {name:?}.").into());
+        };
+        let first = first.to_string_lossy();
+        match root {
+            None => {
+                if first.as_ref() == name {
+                    return Err(format!("Archive should contain a single UFO directory at its root, but file {name:?} was found.").into());
+                }
+                root = Some(first.to_string());
+            }
+            Some(ref r) if r.as_str() != first.as_ref() => {
+                return Err(format!(
+                    "Archive should contain a single UFO directory at its root, found both {r:?} and {first:?}."
+                )
+                .into());
+            }
+            Some(_) => {}
+        }
+    }
+    let root = root.ok_or("Archive is empty.")?;
+    if zip.by_name(&format!("{root}/metainfo.plist")).is_err() {
+        return Err(format!("UFO directory {root:?} in archive has no metainfo.plist.").into());
+    }
+    Ok(root)
+}
+
+/// Writes the UFO directory `ufo_dir` to a UFOZ archive at `archive`, replacing it if it exists.
+pub fn write(ufo_dir: &Path, archive: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let mut root_name = ufo_dir
+        .file_name()
+        .ok_or_else(|| format!("Invalid UFO directory path {}", ufo_dir.display()))?
+        .to_string_lossy()
+        .to_string();
+    if Path::new(&root_name)
+        .extension()
+        .map(|ext| ext != "ufo")
+        .unwrap_or(true)
+    {
+        root_name.push_str(".ufo");
+    }
+    /* Write to a temporary file first so that a failure doesn't destroy the existing archive. */
+    let tmp_path = archive.with_extension(format!("{EXTENSION}.tmp"));
+    let result = write_archive(&tmp_path, ufo_dir, &root_name);
+    if let Err(err) = result {
+        _ = std::fs::remove_file(&tmp_path);
+        return Err(format!("Could not write {}: {err}", archive.display()).into());
+    }
+    std::fs::rename(&tmp_path, archive)?;
+    Ok(())
+}
+
+fn write_archive(
+    archive: &Path,
+    ufo_dir: &Path,
+    root_name: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut zip = zip::ZipWriter::new(File::create(archive)?);
+    let options =
+        zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    zip.add_directory(root_name, options)?;
+    write_directory(&mut zip, ufo_dir, root_name, options)?;
+    zip.finish()?.flush()?;
+    Ok(())
+}
+
+fn write_directory(
+    zip: &mut zip::ZipWriter<File>,
+    dir: &Path,
+    prefix: &str,
+    options: zip::write::FileOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut entries = std::fs::read_dir(dir)?.collect::<Result<Vec<_>, _>>()?;
+    entries.sort_by_key(std::fs::DirEntry::file_name);
+    for entry in entries {
+        let name = format!("{prefix}/{}", entry.file_name().to_string_lossy());
+        let path = entry.path();
+        if path.is_dir() {
+            zip.add_directory(name.as_str(), options)?;
+            write_directory(zip, &path, &name, options)?;
+        } else {
+            zip.start_file(name.as_str(), options)?;
+            std::io::copy(&mut File::open(&path)?, zip)?;
+        }
+    }
+    Ok(())
+}
+
+pub fn export_action_cb(window: gtk::Window, project: crate::prelude::Project) {
+    use crate::prelude::*;
+
+    let filter = gtk::FileFilter::new();
+    filter.add_pattern("*.ufoz");
+    filter.set_name(Some("Zipped UFO (.ufoz)"));
+    let filechooser = gtk::FileChooserNative::builder()
+        .accept_label("Export")
+        .create_folders(true)
+        .do_overwrite_confirmation(true)
+        .title("Select output path")
+        .action(gtk::FileChooserAction::Save)
+        .transient_for(&window)
+        .build();
+    filechooser.add_filter(&filter);
+    if let Some(f) = project.property::<Option<String>>(Project::FILENAME_STEM) {
+        filechooser.set_current_name(&format!("{f}.{EXTENSION}"));
+    }
+
+    return_if_not_ok_or_accept!(filechooser.run());
+
+    let Some(mut path) = filechooser.filename() else {
+        return;
+    };
+    filechooser.hide();
+    if path.extension().is_none() {
+        path.set_extension(EXTENSION);
+    }
+    if let Err(err) = project.export_ufoz(&path) {
+        let dialog = crate::utils::widgets::new_simple_error_dialog(
+            Some("Error: could not export .ufoz archive"),
+            &err.to_string(),
+            None,
+            &window,
+        );
+        dialog.run();
+        dialog.emit_close();
+    }
+}
+
+#[test]
+fn test_ufoz_roundtrip() {
+    let tmp = std::env::temp_dir().join(format!(
+        "gerb-test-ufoz-{}",
+        crate::ufo::make_random_identifier()
+    ));
+    let ufo_dir = tmp.join("Test.ufo");
+    std::fs::create_dir_all(ufo_dir.join("glyphs")).unwrap();
+    std::fs::write(ufo_dir.join("metainfo.plist"), "metainfo").unwrap();
+    std::fs::write(ufo_dir.join("glyphs").join("contents.plist"), "contents").unwrap();
+    let archive = tmp.join("Test.ufoz");
+    write(&ufo_dir, &archive).unwrap();
+    assert!(is_ufoz(&archive));
+    assert!(!is_ufoz(&ufo_dir));
+
+    let extracted = extract(&archive).unwrap();
+    assert_eq!(extracted.file_name().unwrap(), "Test.ufo");
+    assert_eq!(
+        std::fs::read_to_string(extracted.join("metainfo.plist")).unwrap(),
+        "metainfo"
+    );
+    assert_eq!(
+        std::fs::read_to_string(extracted.join("glyphs").join("contents.plist")).unwrap(),
+        "contents"
+    );
+    std::fs::remove_dir_all(extracted.parent().unwrap()).unwrap();
+
+    /* Archives without a single UFO directory at their root are rejected. */
+    let mut zip = zip::ZipWriter::new(File::create(&archive).unwrap());
+    zip.start_file("metainfo.plist", zip::write::FileOptions::default())
+        .unwrap();
+    zip.write_all(b"metainfo").unwrap();
+    zip.finish().unwrap();
+    assert!(extract(&archive).is_err());
+    std::fs::remove_dir_all(&tmp).unwrap();
+}
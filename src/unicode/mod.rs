@@ -377,3 +377,637 @@ pub mod blocks {
     (('\u{100000}', '\u{10FFFF}'), "Supplementary Private Use Area-B", 65536, 65534, "Unknown"),
 ];
 }
+
+pub mod decompositions {
+    //! Canonical decompositions of precomposed letters into a base character and a combining
+    //! mark, and the glyph names and anchors conventionally used for those marks.
+
+    /// Fully decomposes `c` into its base character and combining marks, in order, e.g. `ǖ`
+    /// into `u`, `U+0308` and `U+0304`. Returns `None` if `c` has no canonical decomposition in
+    /// [`CANONICAL_DECOMPOSITIONS`].
+    pub fn decompose(c: char) -> Option<(char, Vec<char>)> {
+        let idx = CANONICAL_DECOMPOSITIONS
+            .binary_search_by_key(&c, |&(c, _, _)| c)
+            .ok()?;
+        let (_, base, mark) = CANONICAL_DECOMPOSITIONS[idx];
+        let (base, mut marks) = decompose(base).unwrap_or((base, vec![]));
+        marks.push(mark);
+        Some((base, marks))
+    }
+
+    /// The glyph name and the base anchor name for combining mark `c`, e.g. `acutecomb` and
+    /// `top` for `U+0301`.
+    pub fn mark_info(c: char) -> Option<(&'static str, &'static str)> {
+        COMBINING_MARKS
+            .binary_search_by_key(&c, |&(c, _, _)| c)
+            .ok()
+            .map(|idx| (COMBINING_MARKS[idx].1, COMBINING_MARKS[idx].2))
+    }
+
+    /// Combining mark, glyph name, anchor name. Sorted by codepoint.
+    pub const COMBINING_MARKS: &[(char, &str, &str)] = &[
+        ('\u{0300}', "gravecomb", "top"),
+        ('\u{0301}', "acutecomb", "top"),
+        ('\u{0302}', "circumflexcomb", "top"),
+        ('\u{0303}', "tildecomb", "top"),
+        ('\u{0304}', "macroncomb", "top"),
+        ('\u{0306}', "brevecomb", "top"),
+        ('\u{0307}', "dotaccentcomb", "top"),
+        ('\u{0308}', "dieresiscomb", "top"),
+        ('\u{0309}', "hookabovecomb", "top"),
+        ('\u{030A}', "ringcomb", "top"),
+        ('\u{030B}', "hungarumlautcomb", "top"),
+        ('\u{030C}', "caroncomb", "top"),
+        ('\u{030F}', "dblgravecomb", "top"),
+        ('\u{0311}', "breveinvertedcomb", "top"),
+        ('\u{031B}', "horncomb", "horn"),
+        ('\u{0323}', "dotbelowcomb", "bottom"),
+        ('\u{0324}', "dieresisbelowcomb", "bottom"),
+        ('\u{0325}', "ringbelowcomb", "bottom"),
+        ('\u{0326}', "commaaccentcomb", "bottom"),
+        ('\u{0327}', "cedillacomb", "bottom"),
+        ('\u{0328}', "ogonekcomb", "ogonek"),
+        ('\u{032D}', "circumflexbelowcomb", "bottom"),
+        ('\u{032E}', "brevebelowcomb", "bottom"),
+        ('\u{0330}', "tildebelowcomb", "bottom"),
+        ('\u{0331}', "macronbelowcomb", "bottom"),
+    ];
+
+    /* Generated from UnicodeData.txt for the Latin, Greek, Cyrillic and Latin Extended Additional
+     * blocks, keeping decompositions into exactly a base and a nonspacing mark. */
+    /// Precomposed character, base character, combining mark. Sorted by precomposed character.
+    pub const CANONICAL_DECOMPOSITIONS: &[(char, char, char)] = &[
+        ('\u{00C0}', '\u{0041}', '\u{0300}'),
+        ('\u{00C1}', '\u{0041}', '\u{0301}'),
+        ('\u{00C2}', '\u{0041}', '\u{0302}'),
+        ('\u{00C3}', '\u{0041}', '\u{0303}'),
+        ('\u{00C4}', '\u{0041}', '\u{0308}'),
+        ('\u{00C5}', '\u{0041}', '\u{030A}'),
+        ('\u{00C7}', '\u{0043}', '\u{0327}'),
+        ('\u{00C8}', '\u{0045}', '\u{0300}'),
+        ('\u{00C9}', '\u{0045}', '\u{0301}'),
+        ('\u{00CA}', '\u{0045}', '\u{0302}'),
+        ('\u{00CB}', '\u{0045}', '\u{0308}'),
+        ('\u{00CC}', '\u{0049}', '\u{0300}'),
+        ('\u{00CD}', '\u{0049}', '\u{0301}'),
+        ('\u{00CE}', '\u{0049}', '\u{0302}'),
+        ('\u{00CF}', '\u{0049}', '\u{0308}'),
+        ('\u{00D1}', '\u{004E}', '\u{0303}'),
+        ('\u{00D2}', '\u{004F}', '\u{0300}'),
+        ('\u{00D3}', '\u{004F}', '\u{0301}'),
+        ('\u{00D4}', '\u{004F}', '\u{0302}'),
+        ('\u{00D5}', '\u{004F}', '\u{0303}'),
+        ('\u{00D6}', '\u{004F}', '\u{0308}'),
+        ('\u{00D9}', '\u{0055}', '\u{0300}'),
+        ('\u{00DA}', '\u{0055}', '\u{0301}'),
+        ('\u{00DB}', '\u{0055}', '\u{0302}'),
+        ('\u{00DC}', '\u{0055}', '\u{0308}'),
+        ('\u{00DD}', '\u{0059}', '\u{0301}'),
+        ('\u{00E0}', '\u{0061}', '\u{0300}'),
+        ('\u{00E1}', '\u{0061}', '\u{0301}'),
+        ('\u{00E2}', '\u{0061}', '\u{0302}'),
+        ('\u{00E3}', '\u{0061}', '\u{0303}'),
+        ('\u{00E4}', '\u{0061}', '\u{0308}'),
+        ('\u{00E5}', '\u{0061}', '\u{030A}'),
+        ('\u{00E7}', '\u{0063}', '\u{0327}'),
+        ('\u{00E8}', '\u{0065}', '\u{0300}'),
+        ('\u{00E9}', '\u{0065}', '\u{0301}'),
+        ('\u{00EA}', '\u{0065}', '\u{0302}'),
+        ('\u{00EB}', '\u{0065}', '\u{0308}'),
+        ('\u{00EC}', '\u{0069}', '\u{0300}'),
+        ('\u{00ED}', '\u{0069}', '\u{0301}'),
+        ('\u{00EE}', '\u{0069}', '\u{0302}'),
+        ('\u{00EF}', '\u{0069}', '\u{0308}'),
+        ('\u{00F1}', '\u{006E}', '\u{0303}'),
+        ('\u{00F2}', '\u{006F}', '\u{0300}'),
+        ('\u{00F3}', '\u{006F}', '\u{0301}'),
+        ('\u{00F4}', '\u{006F}', '\u{0302}'),
+        ('\u{00F5}', '\u{006F}', '\u{0303}'),
+        ('\u{00F6}', '\u{006F}', '\u{0308}'),
+        ('\u{00F9}', '\u{0075}', '\u{0300}'),
+        ('\u{00FA}', '\u{0075}', '\u{0301}'),
+        ('\u{00FB}', '\u{0075}', '\u{0302}'),
+        ('\u{00FC}', '\u{0075}', '\u{0308}'),
+        ('\u{00FD}', '\u{0079}', '\u{0301}'),
+        ('\u{00FF}', '\u{0079}', '\u{0308}'),
+        ('\u{0100}', '\u{0041}', '\u{0304}'),
+        ('\u{0101}', '\u{0061}', '\u{0304}'),
+        ('\u{0102}', '\u{0041}', '\u{0306}'),
+        ('\u{0103}', '\u{0061}', '\u{0306}'),
+        ('\u{0104}', '\u{0041}', '\u{0328}'),
+        ('\u{0105}', '\u{0061}', '\u{0328}'),
+        ('\u{0106}', '\u{0043}', '\u{0301}'),
+        ('\u{0107}', '\u{0063}', '\u{0301}'),
+        ('\u{0108}', '\u{0043}', '\u{0302}'),
+        ('\u{0109}', '\u{0063}', '\u{0302}'),
+        ('\u{010A}', '\u{0043}', '\u{0307}'),
+        ('\u{010B}', '\u{0063}', '\u{0307}'),
+        ('\u{010C}', '\u{0043}', '\u{030C}'),
+        ('\u{010D}', '\u{0063}', '\u{030C}'),
+        ('\u{010E}', '\u{0044}', '\u{030C}'),
+        ('\u{010F}', '\u{0064}', '\u{030C}'),
+        ('\u{0112}', '\u{0045}', '\u{0304}'),
+        ('\u{0113}', '\u{0065}', '\u{0304}'),
+        ('\u{0114}', '\u{0045}', '\u{0306}'),
+        ('\u{0115}', '\u{0065}', '\u{0306}'),
+        ('\u{0116}', '\u{0045}', '\u{0307}'),
+        ('\u{0117}', '\u{0065}', '\u{0307}'),
+        ('\u{0118}', '\u{0045}', '\u{0328}'),
+        ('\u{0119}', '\u{0065}', '\u{0328}'),
+        ('\u{011A}', '\u{0045}', '\u{030C}'),
+        ('\u{011B}', '\u{0065}', '\u{030C}'),
+        ('\u{011C}', '\u{0047}', '\u{0302}'),
+        ('\u{011D}', '\u{0067}', '\u{0302}'),
+        ('\u{011E}', '\u{0047}', '\u{0306}'),
+        ('\u{011F}', '\u{0067}', '\u{0306}'),
+        ('\u{0120}', '\u{0047}', '\u{0307}'),
+        ('\u{0121}', '\u{0067}', '\u{0307}'),
+        ('\u{0122}', '\u{0047}', '\u{0327}'),
+        ('\u{0123}', '\u{0067}', '\u{0327}'),
+        ('\u{0124}', '\u{0048}', '\u{0302}'),
+        ('\u{0125}', '\u{0068}', '\u{0302}'),
+        ('\u{0128}', '\u{0049}', '\u{0303}'),
+        ('\u{0129}', '\u{0069}', '\u{0303}'),
+        ('\u{012A}', '\u{0049}', '\u{0304}'),
+        ('\u{012B}', '\u{0069}', '\u{0304}'),
+        ('\u{012C}', '\u{0049}', '\u{0306}'),
+        ('\u{012D}', '\u{0069}', '\u{0306}'),
+        ('\u{012E}', '\u{0049}', '\u{0328}'),
+        ('\u{012F}', '\u{0069}', '\u{0328}'),
+        ('\u{0130}', '\u{0049}', '\u{0307}'),
+        ('\u{0134}', '\u{004A}', '\u{0302}'),
+        ('\u{0135}', '\u{006A}', '\u{0302}'),
+        ('\u{0136}', '\u{004B}', '\u{0327}'),
+        ('\u{0137}', '\u{006B}', '\u{0327}'),
+        ('\u{0139}', '\u{004C}', '\u{0301}'),
+        ('\u{013A}', '\u{006C}', '\u{0301}'),
+        ('\u{013B}', '\u{004C}', '\u{0327}'),
+        ('\u{013C}', '\u{006C}', '\u{0327}'),
+        ('\u{013D}', '\u{004C}', '\u{030C}'),
+        ('\u{013E}', '\u{006C}', '\u{030C}'),
+        ('\u{0143}', '\u{004E}', '\u{0301}'),
+        ('\u{0144}', '\u{006E}', '\u{0301}'),
+        ('\u{0145}', '\u{004E}', '\u{0327}'),
+        ('\u{0146}', '\u{006E}', '\u{0327}'),
+        ('\u{0147}', '\u{004E}', '\u{030C}'),
+        ('\u{0148}', '\u{006E}', '\u{030C}'),
+        ('\u{014C}', '\u{004F}', '\u{0304}'),
+        ('\u{014D}', '\u{006F}', '\u{0304}'),
+        ('\u{014E}', '\u{004F}', '\u{0306}'),
+        ('\u{014F}', '\u{006F}', '\u{0306}'),
+        ('\u{0150}', '\u{004F}', '\u{030B}'),
+        ('\u{0151}', '\u{006F}', '\u{030B}'),
+        ('\u{0154}', '\u{0052}', '\u{0301}'),
+        ('\u{0155}', '\u{0072}', '\u{0301}'),
+        ('\u{0156}', '\u{0052}', '\u{0327}'),
+        ('\u{0157}', '\u{0072}', '\u{0327}'),
+        ('\u{0158}', '\u{0052}', '\u{030C}'),
+        ('\u{0159}', '\u{0072}', '\u{030C}'),
+        ('\u{015A}', '\u{0053}', '\u{0301}'),
+        ('\u{015B}', '\u{0073}', '\u{0301}'),
+        ('\u{015C}', '\u{0053}', '\u{0302}'),
+        ('\u{015D}', '\u{0073}', '\u{0302}'),
+        ('\u{015E}', '\u{0053}', '\u{0327}'),
+        ('\u{015F}', '\u{0073}', '\u{0327}'),
+        ('\u{0160}', '\u{0053}', '\u{030C}'),
+        ('\u{0161}', '\u{0073}', '\u{030C}'),
+        ('\u{0162}', '\u{0054}', '\u{0327}'),
+        ('\u{0163}', '\u{0074}', '\u{0327}'),
+        ('\u{0164}', '\u{0054}', '\u{030C}'),
+        ('\u{0165}', '\u{0074}', '\u{030C}'),
+        ('\u{0168}', '\u{0055}', '\u{0303}'),
+        ('\u{0169}', '\u{0075}', '\u{0303}'),
+        ('\u{016A}', '\u{0055}', '\u{0304}'),
+        ('\u{016B}', '\u{0075}', '\u{0304}'),
+        ('\u{016C}', '\u{0055}', '\u{0306}'),
+        ('\u{016D}', '\u{0075}', '\u{0306}'),
+        ('\u{016E}', '\u{0055}', '\u{030A}'),
+        ('\u{016F}', '\u{0075}', '\u{030A}'),
+        ('\u{0170}', '\u{0055}', '\u{030B}'),
+        ('\u{0171}', '\u{0075}', '\u{030B}'),
+        ('\u{0172}', '\u{0055}', '\u{0328}'),
+        ('\u{0173}', '\u{0075}', '\u{0328}'),
+        ('\u{0174}', '\u{0057}', '\u{0302}'),
+        ('\u{0175}', '\u{0077}', '\u{0302}'),
+        ('\u{0176}', '\u{0059}', '\u{0302}'),
+        ('\u{0177}', '\u{0079}', '\u{0302}'),
+        ('\u{0178}', '\u{0059}', '\u{0308}'),
+        ('\u{0179}', '\u{005A}', '\u{0301}'),
+        ('\u{017A}', '\u{007A}', '\u{0301}'),
+        ('\u{017B}', '\u{005A}', '\u{0307}'),
+        ('\u{017C}', '\u{007A}', '\u{0307}'),
+        ('\u{017D}', '\u{005A}', '\u{030C}'),
+        ('\u{017E}', '\u{007A}', '\u{030C}'),
+        ('\u{01A0}', '\u{004F}', '\u{031B}'),
+        ('\u{01A1}', '\u{006F}', '\u{031B}'),
+        ('\u{01AF}', '\u{0055}', '\u{031B}'),
+        ('\u{01B0}', '\u{0075}', '\u{031B}'),
+        ('\u{01CD}', '\u{0041}', '\u{030C}'),
+        ('\u{01CE}', '\u{0061}', '\u{030C}'),
+        ('\u{01CF}', '\u{0049}', '\u{030C}'),
+        ('\u{01D0}', '\u{0069}', '\u{030C}'),
+        ('\u{01D1}', '\u{004F}', '\u{030C}'),
+        ('\u{01D2}', '\u{006F}', '\u{030C}'),
+        ('\u{01D3}', '\u{0055}', '\u{030C}'),
+        ('\u{01D4}', '\u{0075}', '\u{030C}'),
+        ('\u{01D5}', '\u{00DC}', '\u{0304}'),
+        ('\u{01D6}', '\u{00FC}', '\u{0304}'),
+        ('\u{01D7}', '\u{00DC}', '\u{0301}'),
+        ('\u{01D8}', '\u{00FC}', '\u{0301}'),
+        ('\u{01D9}', '\u{00DC}', '\u{030C}'),
+        ('\u{01DA}', '\u{00FC}', '\u{030C}'),
+        ('\u{01DB}', '\u{00DC}', '\u{0300}'),
+        ('\u{01DC}', '\u{00FC}', '\u{0300}'),
+        ('\u{01DE}', '\u{00C4}', '\u{0304}'),
+        ('\u{01DF}', '\u{00E4}', '\u{0304}'),
+        ('\u{01E0}', '\u{0226}', '\u{0304}'),
+        ('\u{01E1}', '\u{0227}', '\u{0304}'),
+        ('\u{01E2}', '\u{00C6}', '\u{0304}'),
+        ('\u{01E3}', '\u{00E6}', '\u{0304}'),
+        ('\u{01E6}', '\u{0047}', '\u{030C}'),
+        ('\u{01E7}', '\u{0067}', '\u{030C}'),
+        ('\u{01E8}', '\u{004B}', '\u{030C}'),
+        ('\u{01E9}', '\u{006B}', '\u{030C}'),
+        ('\u{01EA}', '\u{004F}', '\u{0328}'),
+        ('\u{01EB}', '\u{006F}', '\u{0328}'),
+        ('\u{01EC}', '\u{01EA}', '\u{0304}'),
+        ('\u{01ED}', '\u{01EB}', '\u{0304}'),
+        ('\u{01EE}', '\u{01B7}', '\u{030C}'),
+        ('\u{01EF}', '\u{0292}', '\u{030C}'),
+        ('\u{01F0}', '\u{006A}', '\u{030C}'),
+        ('\u{01F4}', '\u{0047}', '\u{0301}'),
+        ('\u{01F5}', '\u{0067}', '\u{0301}'),
+        ('\u{01F8}', '\u{004E}', '\u{0300}'),
+        ('\u{01F9}', '\u{006E}', '\u{0300}'),
+        ('\u{01FA}', '\u{00C5}', '\u{0301}'),
+        ('\u{01FB}', '\u{00E5}', '\u{0301}'),
+        ('\u{01FC}', '\u{00C6}', '\u{0301}'),
+        ('\u{01FD}', '\u{00E6}', '\u{0301}'),
+        ('\u{01FE}', '\u{00D8}', '\u{0301}'),
+        ('\u{01FF}', '\u{00F8}', '\u{0301}'),
+        ('\u{0200}', '\u{0041}', '\u{030F}'),
+        ('\u{0201}', '\u{0061}', '\u{030F}'),
+        ('\u{0202}', '\u{0041}', '\u{0311}'),
+        ('\u{0203}', '\u{0061}', '\u{0311}'),
+        ('\u{0204}', '\u{0045}', '\u{030F}'),
+        ('\u{0205}', '\u{0065}', '\u{030F}'),
+        ('\u{0206}', '\u{0045}', '\u{0311}'),
+        ('\u{0207}', '\u{0065}', '\u{0311}'),
+        ('\u{0208}', '\u{0049}', '\u{030F}'),
+        ('\u{0209}', '\u{0069}', '\u{030F}'),
+        ('\u{020A}', '\u{0049}', '\u{0311}'),
+        ('\u{020B}', '\u{0069}', '\u{0311}'),
+        ('\u{020C}', '\u{004F}', '\u{030F}'),
+        ('\u{020D}', '\u{006F}', '\u{030F}'),
+        ('\u{020E}', '\u{004F}', '\u{0311}'),
+        ('\u{020F}', '\u{006F}', '\u{0311}'),
+        ('\u{0210}', '\u{0052}', '\u{030F}'),
+        ('\u{0211}', '\u{0072}', '\u{030F}'),
+        ('\u{0212}', '\u{0052}', '\u{0311}'),
+        ('\u{0213}', '\u{0072}', '\u{0311}'),
+        ('\u{0214}', '\u{0055}', '\u{030F}'),
+        ('\u{0215}', '\u{0075}', '\u{030F}'),
+        ('\u{0216}', '\u{0055}', '\u{0311}'),
+        ('\u{0217}', '\u{0075}', '\u{0311}'),
+        ('\u{0218}', '\u{0053}', '\u{0326}'),
+        ('\u{0219}', '\u{0073}', '\u{0326}'),
+        ('\u{021A}', '\u{0054}', '\u{0326}'),
+        ('\u{021B}', '\u{0074}', '\u{0326}'),
+        ('\u{021E}', '\u{0048}', '\u{030C}'),
+        ('\u{021F}', '\u{0068}', '\u{030C}'),
+        ('\u{0226}', '\u{0041}', '\u{0307}'),
+        ('\u{0227}', '\u{0061}', '\u{0307}'),
+        ('\u{0228}', '\u{0045}', '\u{0327}'),
+        ('\u{0229}', '\u{0065}', '\u{0327}'),
+        ('\u{022A}', '\u{00D6}', '\u{0304}'),
+        ('\u{022B}', '\u{00F6}', '\u{0304}'),
+        ('\u{022C}', '\u{00D5}', '\u{0304}'),
+        ('\u{022D}', '\u{00F5}', '\u{0304}'),
+        ('\u{022E}', '\u{004F}', '\u{0307}'),
+        ('\u{022F}', '\u{006F}', '\u{0307}'),
+        ('\u{0230}', '\u{022E}', '\u{0304}'),
+        ('\u{0231}', '\u{022F}', '\u{0304}'),
+        ('\u{0232}', '\u{0059}', '\u{0304}'),
+        ('\u{0233}', '\u{0079}', '\u{0304}'),
+        ('\u{0385}', '\u{00A8}', '\u{0301}'),
+        ('\u{0386}', '\u{0391}', '\u{0301}'),
+        ('\u{0388}', '\u{0395}', '\u{0301}'),
+        ('\u{0389}', '\u{0397}', '\u{0301}'),
+        ('\u{038A}', '\u{0399}', '\u{0301}'),
+        ('\u{038C}', '\u{039F}', '\u{0301}'),
+        ('\u{038E}', '\u{03A5}', '\u{0301}'),
+        ('\u{038F}', '\u{03A9}', '\u{0301}'),
+        ('\u{0390}', '\u{03CA}', '\u{0301}'),
+        ('\u{03AA}', '\u{0399}', '\u{0308}'),
+        ('\u{03AB}', '\u{03A5}', '\u{0308}'),
+        ('\u{03AC}', '\u{03B1}', '\u{0301}'),
+        ('\u{03AD}', '\u{03B5}', '\u{0301}'),
+        ('\u{03AE}', '\u{03B7}', '\u{0301}'),
+        ('\u{03AF}', '\u{03B9}', '\u{0301}'),
+        ('\u{03B0}', '\u{03CB}', '\u{0301}'),
+        ('\u{03CA}', '\u{03B9}', '\u{0308}'),
+        ('\u{03CB}', '\u{03C5}', '\u{0308}'),
+        ('\u{03CC}', '\u{03BF}', '\u{0301}'),
+        ('\u{03CD}', '\u{03C5}', '\u{0301}'),
+        ('\u{03CE}', '\u{03C9}', '\u{0301}'),
+        ('\u{03D3}', '\u{03D2}', '\u{0301}'),
+        ('\u{03D4}', '\u{03D2}', '\u{0308}'),
+        ('\u{0400}', '\u{0415}', '\u{0300}'),
+        ('\u{0401}', '\u{0415}', '\u{0308}'),
+        ('\u{0403}', '\u{0413}', '\u{0301}'),
+        ('\u{0407}', '\u{0406}', '\u{0308}'),
+        ('\u{040C}', '\u{041A}', '\u{0301}'),
+        ('\u{040D}', '\u{0418}', '\u{0300}'),
+        ('\u{040E}', '\u{0423}', '\u{0306}'),
+        ('\u{0419}', '\u{0418}', '\u{0306}'),
+        ('\u{0439}', '\u{0438}', '\u{0306}'),
+        ('\u{0450}', '\u{0435}', '\u{0300}'),
+        ('\u{0451}', '\u{0435}', '\u{0308}'),
+        ('\u{0453}', '\u{0433}', '\u{0301}'),
+        ('\u{0457}', '\u{0456}', '\u{0308}'),
+        ('\u{045C}', '\u{043A}', '\u{0301}'),
+        ('\u{045D}', '\u{0438}', '\u{0300}'),
+        ('\u{045E}', '\u{0443}', '\u{0306}'),
+        ('\u{0476}', '\u{0474}', '\u{030F}'),
+        ('\u{0477}', '\u{0475}', '\u{030F}'),
+        ('\u{04C1}', '\u{0416}', '\u{0306}'),
+        ('\u{04C2}', '\u{0436}', '\u{0306}'),
+        ('\u{04D0}', '\u{0410}', '\u{0306}'),
+        ('\u{04D1}', '\u{0430}', '\u{0306}'),
+        ('\u{04D2}', '\u{0410}', '\u{0308}'),
+        ('\u{04D3}', '\u{0430}', '\u{0308}'),
+        ('\u{04D6}', '\u{0415}', '\u{0306}'),
+        ('\u{04D7}', '\u{0435}', '\u{0306}'),
+        ('\u{04DA}', '\u{04D8}', '\u{0308}'),
+        ('\u{04DB}', '\u{04D9}', '\u{0308}'),
+        ('\u{04DC}', '\u{0416}', '\u{0308}'),
+        ('\u{04DD}', '\u{0436}', '\u{0308}'),
+        ('\u{04DE}', '\u{0417}', '\u{0308}'),
+        ('\u{04DF}', '\u{0437}', '\u{0308}'),
+        ('\u{04E2}', '\u{0418}', '\u{0304}'),
+        ('\u{04E3}', '\u{0438}', '\u{0304}'),
+        ('\u{04E4}', '\u{0418}', '\u{0308}'),
+        ('\u{04E5}', '\u{0438}', '\u{0308}'),
+        ('\u{04E6}', '\u{041E}', '\u{0308}'),
+        ('\u{04E7}', '\u{043E}', '\u{0308}'),
+        ('\u{04EA}', '\u{04E8}', '\u{0308}'),
+        ('\u{04EB}', '\u{04E9}', '\u{0308}'),
+        ('\u{04EC}', '\u{042D}', '\u{0308}'),
+        ('\u{04ED}', '\u{044D}', '\u{0308}'),
+        ('\u{04EE}', '\u{0423}', '\u{0304}'),
+        ('\u{04EF}', '\u{0443}', '\u{0304}'),
+        ('\u{04F0}', '\u{0423}', '\u{0308}'),
+        ('\u{04F1}', '\u{0443}', '\u{0308}'),
+        ('\u{04F2}', '\u{0423}', '\u{030B}'),
+        ('\u{04F3}', '\u{0443}', '\u{030B}'),
+        ('\u{04F4}', '\u{0427}', '\u{0308}'),
+        ('\u{04F5}', '\u{0447}', '\u{0308}'),
+        ('\u{04F8}', '\u{042B}', '\u{0308}'),
+        ('\u{04F9}', '\u{044B}', '\u{0308}'),
+        ('\u{1E00}', '\u{0041}', '\u{0325}'),
+        ('\u{1E01}', '\u{0061}', '\u{0325}'),
+        ('\u{1E02}', '\u{0042}', '\u{0307}'),
+        ('\u{1E03}', '\u{0062}', '\u{0307}'),
+        ('\u{1E04}', '\u{0042}', '\u{0323}'),
+        ('\u{1E05}', '\u{0062}', '\u{0323}'),
+        ('\u{1E06}', '\u{0042}', '\u{0331}'),
+        ('\u{1E07}', '\u{0062}', '\u{0331}'),
+        ('\u{1E08}', '\u{00C7}', '\u{0301}'),
+        ('\u{1E09}', '\u{00E7}', '\u{0301}'),
+        ('\u{1E0A}', '\u{0044}', '\u{0307}'),
+        ('\u{1E0B}', '\u{0064}', '\u{0307}'),
+        ('\u{1E0C}', '\u{0044}', '\u{0323}'),
+        ('\u{1E0D}', '\u{0064}', '\u{0323}'),
+        ('\u{1E0E}', '\u{0044}', '\u{0331}'),
+        ('\u{1E0F}', '\u{0064}', '\u{0331}'),
+        ('\u{1E10}', '\u{0044}', '\u{0327}'),
+        ('\u{1E11}', '\u{0064}', '\u{0327}'),
+        ('\u{1E12}', '\u{0044}', '\u{032D}'),
+        ('\u{1E13}', '\u{0064}', '\u{032D}'),
+        ('\u{1E14}', '\u{0112}', '\u{0300}'),
+        ('\u{1E15}', '\u{0113}', '\u{0300}'),
+        ('\u{1E16}', '\u{0112}', '\u{0301}'),
+        ('\u{1E17}', '\u{0113}', '\u{0301}'),
+        ('\u{1E18}', '\u{0045}', '\u{032D}'),
+        ('\u{1E19}', '\u{0065}', '\u{032D}'),
+        ('\u{1E1A}', '\u{0045}', '\u{0330}'),
+        ('\u{1E1B}', '\u{0065}', '\u{0330}'),
+        ('\u{1E1C}', '\u{0228}', '\u{0306}'),
+        ('\u{1E1D}', '\u{0229}', '\u{0306}'),
+        ('\u{1E1E}', '\u{0046}', '\u{0307}'),
+        ('\u{1E1F}', '\u{0066}', '\u{0307}'),
+        ('\u{1E20}', '\u{0047}', '\u{0304}'),
+        ('\u{1E21}', '\u{0067}', '\u{0304}'),
+        ('\u{1E22}', '\u{0048}', '\u{0307}'),
+        ('\u{1E23}', '\u{0068}', '\u{0307}'),
+        ('\u{1E24}', '\u{0048}', '\u{0323}'),
+        ('\u{1E25}', '\u{0068}', '\u{0323}'),
+        ('\u{1E26}', '\u{0048}', '\u{0308}'),
+        ('\u{1E27}', '\u{0068}', '\u{0308}'),
+        ('\u{1E28}', '\u{0048}', '\u{0327}'),
+        ('\u{1E29}', '\u{0068}', '\u{0327}'),
+        ('\u{1E2A}', '\u{0048}', '\u{032E}'),
+        ('\u{1E2B}', '\u{0068}', '\u{032E}'),
+        ('\u{1E2C}', '\u{0049}', '\u{0330}'),
+        ('\u{1E2D}', '\u{0069}', '\u{0330}'),
+        ('\u{1E2E}', '\u{00CF}', '\u{0301}'),
+        ('\u{1E2F}', '\u{00EF}', '\u{0301}'),
+        ('\u{1E30}', '\u{004B}', '\u{0301}'),
+        ('\u{1E31}', '\u{006B}', '\u{0301}'),
+        ('\u{1E32}', '\u{004B}', '\u{0323}'),
+        ('\u{1E33}', '\u{006B}', '\u{0323}'),
+        ('\u{1E34}', '\u{004B}', '\u{0331}'),
+        ('\u{1E35}', '\u{006B}', '\u{0331}'),
+        ('\u{1E36}', '\u{004C}', '\u{0323}'),
+        ('\u{1E37}', '\u{006C}', '\u{0323}'),
+        ('\u{1E38}', '\u{1E36}', '\u{0304}'),
+        ('\u{1E39}', '\u{1E37}', '\u{0304}'),
+        ('\u{1E3A}', '\u{004C}', '\u{0331}'),
+        ('\u{1E3B}', '\u{006C}', '\u{0331}'),
+        ('\u{1E3C}', '\u{004C}', '\u{032D}'),
+        ('\u{1E3D}', '\u{006C}', '\u{032D}'),
+        ('\u{1E3E}', '\u{004D}', '\u{0301}'),
+        ('\u{1E3F}', '\u{006D}', '\u{0301}'),
+        ('\u{1E40}', '\u{004D}', '\u{0307}'),
+        ('\u{1E41}', '\u{006D}', '\u{0307}'),
+        ('\u{1E42}', '\u{004D}', '\u{0323}'),
+        ('\u{1E43}', '\u{006D}', '\u{0323}'),
+        ('\u{1E44}', '\u{004E}', '\u{0307}'),
+        ('\u{1E45}', '\u{006E}', '\u{0307}'),
+        ('\u{1E46}', '\u{004E}', '\u{0323}'),
+        ('\u{1E47}', '\u{006E}', '\u{0323}'),
+        ('\u{1E48}', '\u{004E}', '\u{0331}'),
+        ('\u{1E49}', '\u{006E}', '\u{0331}'),
+        ('\u{1E4A}', '\u{004E}', '\u{032D}'),
+        ('\u{1E4B}', '\u{006E}', '\u{032D}'),
+        ('\u{1E4C}', '\u{00D5}', '\u{0301}'),
+        ('\u{1E4D}', '\u{00F5}', '\u{0301}'),
+        ('\u{1E4E}', '\u{00D5}', '\u{0308}'),
+        ('\u{1E4F}', '\u{00F5}', '\u{0308}'),
+        ('\u{1E50}', '\u{014C}', '\u{0300}'),
+        ('\u{1E51}', '\u{014D}', '\u{0300}'),
+        ('\u{1E52}', '\u{014C}', '\u{0301}'),
+        ('\u{1E53}', '\u{014D}', '\u{0301}'),
+        ('\u{1E54}', '\u{0050}', '\u{0301}'),
+        ('\u{1E55}', '\u{0070}', '\u{0301}'),
+        ('\u{1E56}', '\u{0050}', '\u{0307}'),
+        ('\u{1E57}', '\u{0070}', '\u{0307}'),
+        ('\u{1E58}', '\u{0052}', '\u{0307}'),
+        ('\u{1E59}', '\u{0072}', '\u{0307}'),
+        ('\u{1E5A}', '\u{0052}', '\u{0323}'),
+        ('\u{1E5B}', '\u{0072}', '\u{0323}'),
+        ('\u{1E5C}', '\u{1E5A}', '\u{0304}'),
+        ('\u{1E5D}', '\u{1E5B}', '\u{0304}'),
+        ('\u{1E5E}', '\u{0052}', '\u{0331}'),
+        ('\u{1E5F}', '\u{0072}', '\u{0331}'),
+        ('\u{1E60}', '\u{0053}', '\u{0307}'),
+        ('\u{1E61}', '\u{0073}', '\u{0307}'),
+        ('\u{1E62}', '\u{0053}', '\u{0323}'),
+        ('\u{1E63}', '\u{0073}', '\u{0323}'),
+        ('\u{1E64}', '\u{015A}', '\u{0307}'),
+        ('\u{1E65}', '\u{015B}', '\u{0307}'),
+        ('\u{1E66}', '\u{0160}', '\u{0307}'),
+        ('\u{1E67}', '\u{0161}', '\u{0307}'),
+        ('\u{1E68}', '\u{1E62}', '\u{0307}'),
+        ('\u{1E69}', '\u{1E63}', '\u{0307}'),
+        ('\u{1E6A}', '\u{0054}', '\u{0307}'),
+        ('\u{1E6B}', '\u{0074}', '\u{0307}'),
+        ('\u{1E6C}', '\u{0054}', '\u{0323}'),
+        ('\u{1E6D}', '\u{0074}', '\u{0323}'),
+        ('\u{1E6E}', '\u{0054}', '\u{0331}'),
+        ('\u{1E6F}', '\u{0074}', '\u{0331}'),
+        ('\u{1E70}', '\u{0054}', '\u{032D}'),
+        ('\u{1E71}', '\u{0074}', '\u{032D}'),
+        ('\u{1E72}', '\u{0055}', '\u{0324}'),
+        ('\u{1E73}', '\u{0075}', '\u{0324}'),
+        ('\u{1E74}', '\u{0055}', '\u{0330}'),
+        ('\u{1E75}', '\u{0075}', '\u{0330}'),
+        ('\u{1E76}', '\u{0055}', '\u{032D}'),
+        ('\u{1E77}', '\u{0075}', '\u{032D}'),
+        ('\u{1E78}', '\u{0168}', '\u{0301}'),
+        ('\u{1E79}', '\u{0169}', '\u{0301}'),
+        ('\u{1E7A}', '\u{016A}', '\u{0308}'),
+        ('\u{1E7B}', '\u{016B}', '\u{0308}'),
+        ('\u{1E7C}', '\u{0056}', '\u{0303}'),
+        ('\u{1E7D}', '\u{0076}', '\u{0303}'),
+        ('\u{1E7E}', '\u{0056}', '\u{0323}'),
+        ('\u{1E7F}', '\u{0076}', '\u{0323}'),
+        ('\u{1E80}', '\u{0057}', '\u{0300}'),
+        ('\u{1E81}', '\u{0077}', '\u{0300}'),
+        ('\u{1E82}', '\u{0057}', '\u{0301}'),
+        ('\u{1E83}', '\u{0077}', '\u{0301}'),
+        ('\u{1E84}', '\u{0057}', '\u{0308}'),
+        ('\u{1E85}', '\u{0077}', '\u{0308}'),
+        ('\u{1E86}', '\u{0057}', '\u{0307}'),
+        ('\u{1E87}', '\u{0077}', '\u{0307}'),
+        ('\u{1E88}', '\u{0057}', '\u{0323}'),
+        ('\u{1E89}', '\u{0077}', '\u{0323}'),
+        ('\u{1E8A}', '\u{0058}', '\u{0307}'),
+        ('\u{1E8B}', '\u{0078}', '\u{0307}'),
+        ('\u{1E8C}', '\u{0058}', '\u{0308}'),
+        ('\u{1E8D}', '\u{0078}', '\u{0308}'),
+        ('\u{1E8E}', '\u{0059}', '\u{0307}'),
+        ('\u{1E8F}', '\u{0079}', '\u{0307}'),
+        ('\u{1E90}', '\u{005A}', '\u{0302}'),
+        ('\u{1E91}', '\u{007A}', '\u{0302}'),
+        ('\u{1E92}', '\u{005A}', '\u{0323}'),
+        ('\u{1E93}', '\u{007A}', '\u{0323}'),
+        ('\u{1E94}', '\u{005A}', '\u{0331}'),
+        ('\u{1E95}', '\u{007A}', '\u{0331}'),
+        ('\u{1E96}', '\u{0068}', '\u{0331}'),
+        ('\u{1E97}', '\u{0074}', '\u{0308}'),
+        ('\u{1E98}', '\u{0077}', '\u{030A}'),
+        ('\u{1E99}', '\u{0079}', '\u{030A}'),
+        ('\u{1E9B}', '\u{017F}', '\u{0307}'),
+        ('\u{1EA0}', '\u{0041}', '\u{0323}'),
+        ('\u{1EA1}', '\u{0061}', '\u{0323}'),
+        ('\u{1EA2}', '\u{0041}', '\u{0309}'),
+        ('\u{1EA3}', '\u{0061}', '\u{0309}'),
+        ('\u{1EA4}', '\u{00C2}', '\u{0301}'),
+        ('\u{1EA5}', '\u{00E2}', '\u{0301}'),
+        ('\u{1EA6}', '\u{00C2}', '\u{0300}'),
+        ('\u{1EA7}', '\u{00E2}', '\u{0300}'),
+        ('\u{1EA8}', '\u{00C2}', '\u{0309}'),
+        ('\u{1EA9}', '\u{00E2}', '\u{0309}'),
+        ('\u{1EAA}', '\u{00C2}', '\u{0303}'),
+        ('\u{1EAB}', '\u{00E2}', '\u{0303}'),
+        ('\u{1EAC}', '\u{1EA0}', '\u{0302}'),
+        ('\u{1EAD}', '\u{1EA1}', '\u{0302}'),
+        ('\u{1EAE}', '\u{0102}', '\u{0301}'),
+        ('\u{1EAF}', '\u{0103}', '\u{0301}'),
+        ('\u{1EB0}', '\u{0102}', '\u{0300}'),
+        ('\u{1EB1}', '\u{0103}', '\u{0300}'),
+        ('\u{1EB2}', '\u{0102}', '\u{0309}'),
+        ('\u{1EB3}', '\u{0103}', '\u{0309}'),
+        ('\u{1EB4}', '\u{0102}', '\u{0303}'),
+        ('\u{1EB5}', '\u{0103}', '\u{0303}'),
+        ('\u{1EB6}', '\u{1EA0}', '\u{0306}'),
+        ('\u{1EB7}', '\u{1EA1}', '\u{0306}'),
+        ('\u{1EB8}', '\u{0045}', '\u{0323}'),
+        ('\u{1EB9}', '\u{0065}', '\u{0323}'),
+        ('\u{1EBA}', '\u{0045}', '\u{0309}'),
+        ('\u{1EBB}', '\u{0065}', '\u{0309}'),
+        ('\u{1EBC}', '\u{0045}', '\u{0303}'),
+        ('\u{1EBD}', '\u{0065}', '\u{0303}'),
+        ('\u{1EBE}', '\u{00CA}', '\u{0301}'),
+        ('\u{1EBF}', '\u{00EA}', '\u{0301}'),
+        ('\u{1EC0}', '\u{00CA}', '\u{0300}'),
+        ('\u{1EC1}', '\u{00EA}', '\u{0300}'),
+        ('\u{1EC2}', '\u{00CA}', '\u{0309}'),
+        ('\u{1EC3}', '\u{00EA}', '\u{0309}'),
+        ('\u{1EC4}', '\u{00CA}', '\u{0303}'),
+        ('\u{1EC5}', '\u{00EA}', '\u{0303}'),
+        ('\u{1EC6}', '\u{1EB8}', '\u{0302}'),
+        ('\u{1EC7}', '\u{1EB9}', '\u{0302}'),
+        ('\u{1EC8}', '\u{0049}', '\u{0309}'),
+        ('\u{1EC9}', '\u{0069}', '\u{0309}'),
+        ('\u{1ECA}', '\u{0049}', '\u{0323}'),
+        ('\u{1ECB}', '\u{0069}', '\u{0323}'),
+        ('\u{1ECC}', '\u{004F}', '\u{0323}'),
+        ('\u{1ECD}', '\u{006F}', '\u{0323}'),
+        ('\u{1ECE}', '\u{004F}', '\u{0309}'),
+        ('\u{1ECF}', '\u{006F}', '\u{0309}'),
+        ('\u{1ED0}', '\u{00D4}', '\u{0301}'),
+        ('\u{1ED1}', '\u{00F4}', '\u{0301}'),
+        ('\u{1ED2}', '\u{00D4}', '\u{0300}'),
+        ('\u{1ED3}', '\u{00F4}', '\u{0300}'),
+        ('\u{1ED4}', '\u{00D4}', '\u{0309}'),
+        ('\u{1ED5}', '\u{00F4}', '\u{0309}'),
+        ('\u{1ED6}', '\u{00D4}', '\u{0303}'),
+        ('\u{1ED7}', '\u{00F4}', '\u{0303}'),
+        ('\u{1ED8}', '\u{1ECC}', '\u{0302}'),
+        ('\u{1ED9}', '\u{1ECD}', '\u{0302}'),
+        ('\u{1EDA}', '\u{01A0}', '\u{0301}'),
+        ('\u{1EDB}', '\u{01A1}', '\u{0301}'),
+        ('\u{1EDC}', '\u{01A0}', '\u{0300}'),
+        ('\u{1EDD}', '\u{01A1}', '\u{0300}'),
+        ('\u{1EDE}', '\u{01A0}', '\u{0309}'),
+        ('\u{1EDF}', '\u{01A1}', '\u{0309}'),
+        ('\u{1EE0}', '\u{01A0}', '\u{0303}'),
+        ('\u{1EE1}', '\u{01A1}', '\u{0303}'),
+        ('\u{1EE2}', '\u{01A0}', '\u{0323}'),
+        ('\u{1EE3}', '\u{01A1}', '\u{0323}'),
+        ('\u{1EE4}', '\u{0055}', '\u{0323}'),
+        ('\u{1EE5}', '\u{0075}', '\u{0323}'),
+        ('\u{1EE6}', '\u{0055}', '\u{0309}'),
+        ('\u{1EE7}', '\u{0075}', '\u{0309}'),
+        ('\u{1EE8}', '\u{01AF}', '\u{0301}'),
+        ('\u{1EE9}', '\u{01B0}', '\u{0301}'),
+        ('\u{1EEA}', '\u{01AF}', '\u{0300}'),
+        ('\u{1EEB}', '\u{01B0}', '\u{0300}'),
+        ('\u{1EEC}', '\u{01AF}', '\u{0309}'),
+        ('\u{1EED}', '\u{01B0}', '\u{0309}'),
+        ('\u{1EEE}', '\u{01AF}', '\u{0303}'),
+        ('\u{1EEF}', '\u{01B0}', '\u{0303}'),
+        ('\u{1EF0}', '\u{01AF}', '\u{0323}'),
+        ('\u{1EF1}', '\u{01B0}', '\u{0323}'),
+        ('\u{1EF2}', '\u{0059}', '\u{0300}'),
+        ('\u{1EF3}', '\u{0079}', '\u{0300}'),
+        ('\u{1EF4}', '\u{0059}', '\u{0323}'),
+        ('\u{1EF5}', '\u{0079}', '\u{0323}'),
+        ('\u{1EF6}', '\u{0059}', '\u{0309}'),
+        ('\u{1EF7}', '\u{0079}', '\u{0309}'),
+        ('\u{1EF8}', '\u{0059}', '\u{0303}'),
+        ('\u{1EF9}', '\u{0079}', '\u{0303}'),
+    ];
+}
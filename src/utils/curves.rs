@@ -21,6 +21,8 @@
 
 use crate::prelude::*;
 
+pub mod math;
+
 glib::wrapper! {
     pub struct Bezier(ObjectSubclass<BezierInner>);
 }
@@ -330,131 +332,98 @@ impl Bezier {
                 .into();
             return ret;
         }
-        todo!()
-
-        /*
         // higher order curves: use de Casteljau's computation
-        const dCpts = JSON.parse(JSON.stringify(points));
-        while (dCpts.length > 1) {
-          for (let i = 0; i < dCpts.length - 1; i++) {
-            dCpts[i] = {
-              x: dCpts[i].x + (dCpts[i + 1].x - dCpts[i].x) * t,
-              y: dCpts[i].y + (dCpts[i + 1].y - dCpts[i].y) * t,
-            };
-            if (typeof dCpts[i].z !== "undefined") {
-              dCpts[i] = dCpts[i].z + (dCpts[i + 1].z - dCpts[i].z) * t;
-            }
-          }
-          dCpts.splice(dCpts.length - 1, 1);
-        }
-        dCpts[0].t = t;
-        return dCpts[0];
-            */
+        math::evaluate(&positions, t)
     }
 
+    /// Derivative of the curve at `t`, i.e. the (unnormalized) tangent vector.
     pub fn tangent(&self, t: f64) -> Point {
-        let points = self.points();
-        // shortcuts
-        if t == 0.0 {
-            return points[0].position;
-        }
+        math::evaluate(&math::derivative(&self.positions()), t)
+    }
 
-        let order = self.degree().unwrap();
+    /// Positions of the control points.
+    pub fn positions(&self) -> Vec<Point> {
+        self.points().iter().map(|cp| cp.position).collect()
+    }
 
-        if t == 1.0 {
-            return points[order].position;
+    /// Split the curve at `t` into two curves of the same degree. The first keeps this curve's
+    /// incoming continuity and the second its outgoing continuity.
+    pub fn split(&self, t: f64) -> (Self, Self) {
+        let (left, right) = math::split(&self.positions(), t);
+        let (left, right) = (Self::new(left), Self::new(right));
+        for c in [&left, &right] {
+            c.set_property(Self::SMOOTH, self.property::<bool>(Self::SMOOTH));
         }
+        left.set_property(
+            Self::CONTINUITY_IN,
+            self.property::<Option<Continuity>>(Self::CONTINUITY_IN),
+        );
+        right.set_property(
+            Self::CONTINUITY_OUT,
+            self.property::<Option<Continuity>>(Self::CONTINUITY_OUT),
+        );
+        (left, right)
+    }
 
-        if order == 0 {
-            return points[0].position;
-        }
+    /// Parameter values strictly between the end points where the tangent is horizontal or
+    /// vertical, sorted.
+    pub fn extrema(&self) -> Vec<f64> {
+        math::extrema(&self.positions())
+    }
 
-        // linear
-        if order == 1 {
-            return points[1].position - points[0].position;
-        }
+    /// Tight bounding box of the curve (not of its control points) as `(min, max)` corners.
+    pub fn bounding_box(&self) -> (Point, Point) {
+        math::bounding_box(&self.positions())
+    }
 
-        let mt = 1.0 - t;
+    /// Parameter value and position of the point on the curve closest to `point`.
+    pub fn nearest(&self, point: Point) -> (f64, Point) {
+        math::nearest(&self.positions(), point)
+    }
 
-        if order == 2 {
-            let d: [Point; 2] = [
-                (
-                    2.0 * (points[1].x - points[0].x),
-                    2.0 * (points[1].y - points[0].y),
-                )
-                    .into(),
-                (
-                    2.0 * (points[2].x - points[1].x),
-                    2.0 * (points[2].y - points[1].y),
-                )
-                    .into(),
-            ];
-
-            return (
-                mt.mul_add(d[0].x, t * d[1].x),
-                mt.mul_add(d[0].y, t * d[1].y),
-            )
-                .into();
-        } else if order == 3 {
-            let a = mt * mt;
-            let b = 2.0 * mt * t;
-            let c = t * t;
-            let d: [Point; 3] = [
-                (
-                    3.0 * (points[1].x - points[0].x),
-                    3.0 * (points[1].y - points[0].y),
-                )
-                    .into(),
-                (
-                    3.0 * (points[2].x - points[1].x),
-                    3.0 * (points[2].y - points[1].y),
-                )
-                    .into(),
-                (
-                    3.0 * (points[3].x - points[2].x),
-                    3.0 * (points[3].y - points[2].y),
-                )
-                    .into(),
-            ];
-
-            return (
-                c.mul_add(d[2].x, a.mul_add(d[0].x, b * d[1].x)),
-                c.mul_add(d[2].y, a.mul_add(d[0].y, b * d[1].y)),
-            )
-                .into();
-        }
-        todo!()
+    /// Intersections with the line segment from `a` to `b`, as pairs of this curve's parameter
+    /// and the segment's parameter.
+    pub fn line_intersections(&self, a: Point, b: Point) -> Vec<(f64, f64)> {
+        math::line_intersections(&self.positions(), a, b)
     }
 
-    pub fn approx_length(&self) -> f64 {
-        let lut = self.get_lut(None);
-        let mut ret = 0.0;
-        for pair in lut.windows(2).skip(1) {
-            let p1 = pair[0];
-            let p2 = pair[1];
-            ret += p1.distance(p2);
-        }
-        ret
+    /// Intersections with another curve, as pairs of this curve's parameter and `other`'s.
+    pub fn intersections(&self, other: &Self) -> Vec<(f64, f64)> {
+        math::curve_intersections(&self.positions(), &other.positions(), 1e-3)
+    }
+
+    /// Signed area swept by the curve with respect to the origin, see [`math::signed_area`].
+    pub fn signed_area(&self) -> f64 {
+        math::signed_area(&self.positions())
+    }
+
+    /// Arc length of the whole curve.
+    pub fn length(&self) -> f64 {
+        math::length(&self.positions(), 0.0, 1.0)
+    }
+
+    /// Arc length from the start of the curve up to `t`.
+    pub fn length_at(&self, t: f64) -> f64 {
+        math::length(&self.positions(), 0.0, t)
+    }
+
+    /// Parameter value at which the arc length from the start of the curve is `length`.
+    pub fn t_at_length(&self, length: f64) -> f64 {
+        math::t_at_length(&self.positions(), length)
+    }
+
+    /// Signed curvature at `t`, positive when turning counter-clockwise.
+    pub fn curvature(&self, t: f64) -> f64 {
+        math::curvature(&self.positions(), t)
     }
 
-    // [ref:needs_unit_test]
     pub fn on_curve_query(&self, point: Point, error: Option<f64>) -> bool {
         let error = error.unwrap_or(15.0);
-        let lut = self.get_lut(None);
-        let mut hits = vec![];
-        let mut c;
-        let mut t = 0.0;
-        for i in 0..lut.len() {
-            c = lut[i];
-            if super::distance_between_two_points(c, point) < error {
-                hits.push(c);
-                t += i as f64 / lut.len() as f64;
-            }
-        }
-        if hits.is_empty() {
+        if self.degree().is_none() {
             return false;
         }
-        (t / hits.len() as f64) != 0.0
+        let (_, nearest) = self.nearest(point);
+        nearest.distance(point) < error
     }
 
     pub fn clean_up(&self) {
@@ -566,3 +535,38 @@ impl Bezier {
         self.imp().lut.borrow_mut().clear();
     }
 }
+
+#[test]
+fn test_bezier_queries() {
+    let quartic = Bezier::new(vec![
+        (0.0, 0.0).into(),
+        (0.0, 4.0).into(),
+        (4.0, 4.0).into(),
+        (4.0, 0.0).into(),
+        (8.0, 0.0).into(),
+    ]);
+    assert_eq!(quartic.compute(0.5), (3.0, 2.5).into());
+    assert_eq!(quartic.tangent(0.0), (0.0, 16.0).into());
+    assert_eq!(quartic.tangent(1.0), (16.0, 0.0).into());
+
+    let cubic = Bezier::new(vec![
+        (0.0, 0.0).into(),
+        (0.0, 100.0).into(),
+        (100.0, 100.0).into(),
+        (100.0, 0.0).into(),
+    ]);
+    assert_eq!(cubic.compute(0.5), (50.0, 75.0).into());
+    let (left, right) = cubic.split(0.5);
+    assert_eq!(left.points().last().unwrap().position, (50.0, 75.0).into());
+    assert_eq!(right.points()[0].position, (50.0, 75.0).into());
+    assert!((left.length() + right.length() - cubic.length()).abs() < 1e-9);
+    assert!((cubic.length_at(cubic.t_at_length(42.0)) - 42.0).abs() < 1e-6);
+    let extrema = cubic.extrema();
+    assert_eq!(extrema.len(), 1);
+    assert!((extrema[0] - 0.5).abs() < 1e-9);
+
+    assert!(cubic.on_curve_query((50.0, 80.0).into(), None));
+    assert!(cubic.on_curve_query((0.0, 0.0).into(), None));
+    assert!(!cubic.on_curve_query((50.0, 0.0).into(), None));
+    assert!(!Bezier::default().on_curve_query((0.0, 0.0).into(), None));
+}
@@ -0,0 +1,1280 @@
+/*
+ * gerb
+ *
+ * Copyright 2022 - Manos Pitsidianakis
+ *
+ * This file is part of gerb.
+ *
+ * gerb is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * gerb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with gerb. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Exact Bézier curve routines on control point slices of any degree.
+//!
+//! [`Bezier`](super::Bezier) methods are thin wrappers over these; tools that work on temporary
+//! curves (fitting, previews) can use them directly without allocating curve objects.
+
+use crate::utils::points::Point;
+
+/// Parameter values closer than this are considered equal.
+const T_EPSILON: f64 = 1e-9;
+
+fn lerp(a: Point, b: Point, t: f64) -> Point {
+    a + t * (b - a)
+}
+
+pub fn cross(a: Point, b: Point) -> f64 {
+    a.x.mul_add(b.y, -a.y * b.x)
+}
+
+/// Point of curve at `t` using de Casteljau's algorithm.
+pub fn evaluate(points: &[Point], t: f64) -> Point {
+    let mut tmp = points.to_vec();
+    while tmp.len() > 1 {
+        for i in 0..(tmp.len() - 1) {
+            tmp[i] = lerp(tmp[i], tmp[i + 1], t);
+        }
+        tmp.pop();
+    }
+    tmp.first().copied().unwrap_or_default()
+}
+
+/// Control points of the derivative (hodograph) of a curve. Its degree is one less than the
+/// curve's, and it is empty for constant curves.
+pub fn derivative(points: &[Point]) -> Vec<Point> {
+    let n = points.len().saturating_sub(1) as f64;
+    points.windows(2).map(|w| n * (w[1] - w[0])).collect()
+}
+
+/// Split curve at `t` into two curves of the same degree.
+pub fn split(points: &[Point], t: f64) -> (Vec<Point>, Vec<Point>) {
+    let mut left = Vec::with_capacity(points.len());
+    let mut right = Vec::with_capacity(points.len());
+    let mut tmp = points.to_vec();
+    while !tmp.is_empty() {
+        left.push(tmp[0]);
+        right.push(tmp[tmp.len() - 1]);
+        for i in 0..(tmp.len() - 1) {
+            tmp[i] = lerp(tmp[i], tmp[i + 1], t);
+        }
+        tmp.pop();
+    }
+    right.reverse();
+    (left, right)
+}
+
+/// The part of the curve between `t0` and `t1`, reparameterized to `[0, 1]`.
+pub fn segment(points: &[Point], t0: f64, t1: f64) -> Vec<Point> {
+    if t1 <= t0 {
+        return vec![evaluate(points, t0); points.len()];
+    }
+    let (_, right) = split(points, t0);
+    if t1 >= 1.0 {
+        return right;
+    }
+    let (left, _) = split(&right, (t1 - t0) / (1.0 - t0));
+    left
+}
+
+fn evaluate_1d(coeffs: &[f64], t: f64) -> f64 {
+    let mut tmp = coeffs.to_vec();
+    while tmp.len() > 1 {
+        for i in 0..(tmp.len() - 1) {
+            tmp[i] = (tmp[i + 1] - tmp[i]).mul_add(t, tmp[i]);
+        }
+        tmp.pop();
+    }
+    tmp.first().copied().unwrap_or_default()
+}
+
+fn derivative_1d(coeffs: &[f64]) -> Vec<f64> {
+    let n = coeffs.len().saturating_sub(1) as f64;
+    coeffs.windows(2).map(|w| n * (w[1] - w[0])).collect()
+}
+
+/// Real roots of `coeffs[0] + coeffs[1] t + coeffs[2] t² + coeffs[3] t³`, up to degree 3.
+fn solve_polynomial(coeffs: &[f64]) -> Vec<f64> {
+    const EPSILON: f64 = 1e-12;
+
+    let degree = coeffs
+        .iter()
+        .rposition(|c| c.abs() > EPSILON)
+        .unwrap_or_default();
+    match degree {
+        0 => vec![],
+        1 => vec![-coeffs[0] / coeffs[1]],
+        2 => {
+            let (a, b, c) = (coeffs[2], coeffs[1], coeffs[0]);
+            let discriminant = b.mul_add(b, -4.0 * a * c);
+            if discriminant < -EPSILON {
+                vec![]
+            } else if discriminant.abs() <= EPSILON {
+                vec![-b / (2.0 * a)]
+            } else {
+                let sqrt = discriminant.sqrt();
+                /* Avoid catastrophic cancellation by not subtracting nearly equal values. */
+                let q = -0.5 * b.signum().mul_add(sqrt, b);
+                if q == 0.0 {
+                    vec![sqrt / (2.0 * a), -sqrt / (2.0 * a)]
+                } else {
+                    vec![q / a, c / q]
+                }
+            }
+        }
+        _ => {
+            /* Cardano's method on the depressed cubic. */
+            let a = coeffs[2] / coeffs[3];
+            let b = coeffs[1] / coeffs[3];
+            let c = coeffs[0] / coeffs[3];
+            let p = a.mul_add(-a, 3.0 * b) / 3.0;
+            let q = (2.0 * a * a).mul_add(a, (-9.0 * a).mul_add(b, 27.0 * c)) / 27.0;
+            let discriminant = (q * q).mul_add(0.25, p * p * p / 27.0);
+            let shift = a / 3.0;
+            if discriminant < -EPSILON {
+                let r = (-p * p * p / 27.0).sqrt();
+                let phi = (-q / (2.0 * r)).clamp(-1.0, 1.0).acos();
+                let m = 2.0 * r.cbrt();
+                let tau = 2.0 * std::f64::consts::PI;
+                vec![
+                    m.mul_add((phi / 3.0).cos(), -shift),
+                    m.mul_add(((phi + tau) / 3.0).cos(), -shift),
+                    m.mul_add((2.0f64.mul_add(tau, phi) / 3.0).cos(), -shift),
+                ]
+            } else if discriminant.abs() <= EPSILON {
+                let u = (-q / 2.0).cbrt();
+                vec![2.0f64.mul_add(u, -shift), -u - shift]
+            } else {
+                let sqrt = discriminant.sqrt();
+                let u = (-q / 2.0 + sqrt).cbrt();
+                let v = (q / 2.0 + sqrt).cbrt();
+                vec![u - v - shift]
+            }
+        }
+    }
+}
+
+/// Roots in `[0, 1]` of the one-dimensional Bézier polynomial with control values `coeffs`,
+/// sorted and without duplicates. A polynomial that is identically zero has no roots.
+pub fn roots(coeffs: &[f64]) -> Vec<f64> {
+    const SAMPLES: usize = 256;
+
+    let scale = coeffs.iter().fold(0.0_f64, |acc, c| acc.max(c.abs()));
+    if scale == 0.0 || !scale.is_finite() {
+        return vec![];
+    }
+    let coeffs = coeffs.iter().map(|c| c / scale).collect::<Vec<f64>>();
+    let mut ret = match coeffs.as_slice() {
+        [] | [_] => vec![],
+        &[b0, b1] => solve_polynomial(&[b0, b1 - b0]),
+        &[b0, b1, b2] => solve_polynomial(&[b0, 2.0 * (b1 - b0), 2.0f64.mul_add(-b1, b0) + b2]),
+        &[b0, b1, b2, b3] => solve_polynomial(&[
+            b0,
+            3.0 * (b1 - b0),
+            3.0 * (2.0f64.mul_add(-b1, b0) + b2),
+            3.0f64.mul_add(b1 - b2, b3 - b0),
+        ]),
+        _ => {
+            /* No closed form: bracket sign changes and bisect. */
+            let mut ret = vec![];
+            let mut prev = (0.0, evaluate_1d(&coeffs, 0.0));
+            for i in 1..=SAMPLES {
+                let t = i as f64 / SAMPLES as f64;
+                let value = evaluate_1d(&coeffs, t);
+                if prev.1 == 0.0 {
+                    ret.push(prev.0);
+                } else if prev.1.signum() != value.signum() && value != 0.0 {
+                    let (mut lo, mut hi) = (prev.0, t);
+                    for _ in 0..64 {
+                        let mid = (lo + hi) / 2.0;
+                        if evaluate_1d(&coeffs, mid).signum() == prev.1.signum() {
+                            lo = mid;
+                        } else {
+                            hi = mid;
+                        }
+                    }
+                    ret.push((lo + hi) / 2.0);
+                }
+                prev = (t, value);
+            }
+            if prev.1 == 0.0 {
+                ret.push(1.0);
+            }
+            ret
+        }
+    };
+
+    /* Polish closed form roots with a few Newton iterations. */
+    let d = derivative_1d(&coeffs);
+    for t in ret.iter_mut() {
+        for _ in 0..4 {
+            let slope = evaluate_1d(&d, *t);
+            if slope == 0.0 || !slope.is_finite() {
+                break;
+            }
+            let next = *t - evaluate_1d(&coeffs, *t) / slope;
+            if !next.is_finite() {
+                break;
+            }
+            *t = next;
+        }
+    }
+    ret.retain(|t| t.is_finite() && (-T_EPSILON..=1.0 + T_EPSILON).contains(t));
+    for t in ret.iter_mut() {
+        *t = t.clamp(0.0, 1.0);
+    }
+    ret.sort_by(f64::total_cmp);
+    ret.dedup_by(|a, b| (*a - *b).abs() < T_EPSILON);
+    ret
+}
+
+/// Parameter values strictly between the end points where the curve has a horizontal or vertical
+/// tangent, sorted.
+pub fn extrema(points: &[Point]) -> Vec<f64> {
+    let d = derivative(points);
+    let mut ret = roots(&d.iter().map(|p| p.x).collect::<Vec<f64>>());
+    ret.extend(roots(&d.iter().map(|p| p.y).collect::<Vec<f64>>()));
+    ret.retain(|t| *t > T_EPSILON && *t < 1.0 - T_EPSILON);
+    ret.sort_by(f64::total_cmp);
+    ret.dedup_by(|a, b| (*a - *b).abs() < T_EPSILON);
+    ret
+}
+
+/// Tight axis aligned bounding box of the curve as `(min, max)` corners.
+pub fn bounding_box(points: &[Point]) -> (Point, Point) {
+    let (Some(first), Some(last)) = (points.first(), points.last()) else {
+        return (Point::default(), Point::default());
+    };
+    let mut min = *first;
+    let mut max = *first;
+    for p in std::iter::once(*last).chain(extrema(points).into_iter().map(|t| evaluate(points, t)))
+    {
+        min.x = min.x.min(p.x);
+        min.y = min.y.min(p.y);
+        max.x = max.x.max(p.x);
+        max.y = max.y.max(p.y);
+    }
+    (min, max)
+}
+
+/// Bounding box of the control points, which contains the curve. Cheaper than
+/// [`bounding_box`].
+fn hull_box(points: &[Point]) -> (Point, Point) {
+    let mut min = points.first().copied().unwrap_or_default();
+    let mut max = min;
+    for p in points {
+        min.x = min.x.min(p.x);
+        min.y = min.y.min(p.y);
+        max.x = max.x.max(p.x);
+        max.y = max.y.max(p.y);
+    }
+    (min, max)
+}
+
+/// Parameter value and position of the point on the curve closest to `point`.
+pub fn nearest(points: &[Point], point: Point) -> (f64, Point) {
+    const SAMPLES: usize = 64;
+
+    if points.len() < 2 {
+        return (0.0, points.first().copied().unwrap_or_default());
+    }
+    let d1 = derivative(points);
+    let d2 = derivative(&d1);
+    let distances = (0..=SAMPLES)
+        .map(|i| evaluate(points, i as f64 / SAMPLES as f64).distance(point))
+        .collect::<Vec<f64>>();
+    let mut best = (0.0, points[0], f64::INFINITY);
+    for i in 0..=SAMPLES {
+        let is_local_minimum = (i == 0 || distances[i] <= distances[i - 1])
+            && (i == SAMPLES || distances[i] <= distances[i + 1]);
+        if !is_local_minimum {
+            continue;
+        }
+        /* Newton iterations on (B(t) - P) · B'(t) = 0, kept inside the sample's neighbourhood. */
+        let lo = i.saturating_sub(1) as f64 / SAMPLES as f64;
+        let hi = (i + 1).min(SAMPLES) as f64 / SAMPLES as f64;
+        let mut t = i as f64 / SAMPLES as f64;
+        for _ in 0..16 {
+            let diff = evaluate(points, t) - point;
+            let first = evaluate(&d1, t);
+            let numerator = diff.dot(first);
+            let denominator = first.dot(first) + diff.dot(evaluate(&d2, t));
+            if denominator <= 0.0 || !denominator.is_finite() {
+                break;
+            }
+            let next = (t - numerator / denominator).clamp(lo, hi);
+            if (next - t).abs() < T_EPSILON {
+                t = next;
+                break;
+            }
+            t = next;
+        }
+        let position = evaluate(points, t);
+        let distance = position.distance(point);
+        let (t, position, distance) = if distance <= distances[i] {
+            (t, position, distance)
+        } else {
+            let t = i as f64 / SAMPLES as f64;
+            (t, evaluate(points, t), distances[i])
+        };
+        if distance < best.2 {
+            best = (t, position, distance);
+        }
+    }
+    (best.0, best.1)
+}
+
+/// Intersections of the curve with the line segment from `a` to `b`, as pairs of the curve's
+/// parameter and the segment's parameter (`0.0` at `a`, `1.0` at `b`), sorted by the former.
+pub fn line_intersections(points: &[Point], a: Point, b: Point) -> Vec<(f64, f64)> {
+    const EPSILON: f64 = 1e-7;
+
+    let direction = b - a;
+    let length_sq = direction.dot(direction);
+    if length_sq == 0.0 {
+        return vec![];
+    }
+    /* Signed distances of the control points from the line are the control values of the
+     * curve's signed distance polynomial. */
+    let coeffs = points
+        .iter()
+        .map(|p| cross(direction, *p - a))
+        .collect::<Vec<f64>>();
+    roots(&coeffs)
+        .into_iter()
+        .filter_map(|t| {
+            let s = (evaluate(points, t) - a).dot(direction) / length_sq;
+            (-EPSILON..=1.0 + EPSILON)
+                .contains(&s)
+                .then_some((t, s.clamp(0.0, 1.0)))
+        })
+        .collect()
+}
+
+/// Intersections of two curves, as pairs of their parameters sorted by the first curve's.
+///
+/// `tolerance` is the size in curve units under which overlapping parts are considered to
+/// intersect. Coincident curves have no discrete intersections and return nothing.
+pub fn curve_intersections(a: &[Point], b: &[Point], tolerance: f64) -> Vec<(f64, f64)> {
+    const MAX_PAIRS: usize = 4096;
+
+    fn overlap((amin, amax): (Point, Point), (bmin, bmax): (Point, Point)) -> bool {
+        amin.x <= bmax.x && bmin.x <= amax.x && amin.y <= bmax.y && bmin.y <= amax.y
+    }
+
+    fn size((min, max): (Point, Point)) -> f64 {
+        (max.x - min.x).max(max.y - min.y)
+    }
+
+    if a.len() < 2 || b.len() < 2 {
+        return vec![];
+    }
+    let tolerance = tolerance.max(f64::EPSILON);
+    let mut found = vec![];
+    let mut pending = vec![((0.0, 1.0), (0.0, 1.0))];
+    while !pending.is_empty() {
+        let mut next = vec![];
+        for ((a0, a1), (b0, b1)) in pending {
+            let box_a = hull_box(&segment(a, a0, a1));
+            let box_b = hull_box(&segment(b, b0, b1));
+            if !overlap(box_a, box_b) {
+                continue;
+            }
+            let small_a = size(box_a) < tolerance;
+            let small_b = size(box_b) < tolerance;
+            if small_a && small_b {
+                found.push(((a0 + a1) / 2.0, (b0 + b1) / 2.0));
+                continue;
+            }
+            let a_ranges = if small_a {
+                vec![(a0, a1)]
+            } else {
+                let mid = (a0 + a1) / 2.0;
+                vec![(a0, mid), (mid, a1)]
+            };
+            let b_ranges = if small_b {
+                vec![(b0, b1)]
+            } else {
+                let mid = (b0 + b1) / 2.0;
+                vec![(b0, mid), (mid, b1)]
+            };
+            for ra in &a_ranges {
+                for rb in &b_ranges {
+                    next.push((*ra, *rb));
+                }
+            }
+        }
+        if next.len() > MAX_PAIRS {
+            return vec![];
+        }
+        pending = next;
+    }
+
+    /* Refine with Newton iterations on A(s) - B(t) = 0. */
+    let da = derivative(a);
+    let db = derivative(b);
+    for (s, t) in found.iter_mut() {
+        let (mut cs, mut ct) = (*s, *t);
+        let initial = evaluate(a, cs).distance(evaluate(b, ct));
+        for _ in 0..8 {
+            let f = evaluate(a, cs) - evaluate(b, ct);
+            let ta = evaluate(&da, cs);
+            let tb = evaluate(&db, ct);
+            let det = cross(ta, tb);
+            if det.abs() < f64::EPSILON {
+                break;
+            }
+            cs = (cs - cross(f, tb) / det).clamp(0.0, 1.0);
+            ct = (ct + cross(ta, f) / det).clamp(0.0, 1.0);
+        }
+        if evaluate(a, cs).distance(evaluate(b, ct)) <= initial {
+            *s = cs;
+            *t = ct;
+        }
+    }
+    found.sort_by(|x, y| x.0.total_cmp(&y.0));
+    let mut ret: Vec<(f64, f64)> = vec![];
+    for (s, t) in found {
+        let (p, q) = (evaluate(a, s), evaluate(b, t));
+        if ret.iter().any(|(rs, rt)| {
+            p.distance(evaluate(a, *rs)) < 2.0 * tolerance
+                && q.distance(evaluate(b, *rt)) < 2.0 * tolerance
+        }) {
+            continue;
+        }
+        ret.push((s, t));
+    }
+    ret
+}
+
+/// Gauss-Legendre quadrature abscissae and weights on `[-1, 1]`.
+static GAUSS_LEGENDRE: once_cell::sync::Lazy<Vec<(f64, f64)>> = once_cell::sync::Lazy::new(|| {
+    const N: usize = 16;
+
+    (1..=N)
+        .map(|i| {
+            let n = N as f64;
+            let mut x = (std::f64::consts::PI * (i as f64 - 0.25) / (n + 0.5)).cos();
+            let mut derivative = 1.0;
+            for _ in 0..100 {
+                /* Legendre polynomial P_n(x) by its recurrence. */
+                let (mut p0, mut p1) = (1.0, x);
+                for k in 2..=N {
+                    let k = k as f64;
+                    let p2 = (2.0f64.mul_add(k, -1.0) * x).mul_add(p1, -(k - 1.0) * p0) / k;
+                    p0 = p1;
+                    p1 = p2;
+                }
+                derivative = n * x.mul_add(p1, -p0) / x.mul_add(x, -1.0);
+                let dx = p1 / derivative;
+                x -= dx;
+                if dx.abs() < 1e-15 {
+                    break;
+                }
+            }
+            (x, 2.0 / (x.mul_add(-x, 1.0) * derivative * derivative))
+        })
+        .collect()
+});
+
+/// Arc length of the curve between parameters `t0` and `t1`.
+pub fn length(points: &[Point], t0: f64, t1: f64) -> f64 {
+    const INTERVALS: usize = 8;
+
+    if points.len() < 2 || t1 <= t0 {
+        return 0.0;
+    }
+    let d = derivative(points);
+    let step = (t1 - t0) / INTERVALS as f64;
+    let mut ret = 0.0;
+    for i in 0..INTERVALS {
+        let center = step.mul_add(i as f64 + 0.5, t0);
+        for (x, w) in GAUSS_LEGENDRE.iter() {
+            ret = w.mul_add(evaluate(&d, (step / 2.0).mul_add(*x, center)).norm(), ret);
+        }
+    }
+    ret * step / 2.0
+}
+
+/// Inverse of [`length`]: the parameter at which the arc length from the start of the curve is
+/// `distance`.
+pub fn t_at_length(points: &[Point], distance: f64) -> f64 {
+    let total = length(points, 0.0, 1.0);
+    if distance <= 0.0 || total == 0.0 {
+        return 0.0;
+    }
+    if distance >= total {
+        return 1.0;
+    }
+    let d = derivative(points);
+    let (mut lo, mut hi) = (0.0, 1.0);
+    let mut t = distance / total;
+    for _ in 0..64 {
+        let error = length(points, 0.0, t) - distance;
+        if error.abs() < 1e-9 * total.max(1.0) {
+            break;
+        }
+        if error > 0.0 {
+            hi = t;
+        } else {
+            lo = t;
+        }
+        let speed = evaluate(&d, t).norm();
+        let next = t - error / speed;
+        t = if speed > 0.0 && next > lo && next < hi {
+            next
+        } else {
+            (lo + hi) / 2.0
+        };
+    }
+    t
+}
+
+/// Signed curvature at `t`; positive when the curve turns counter-clockwise. It is `0.0` where
+/// the curve's velocity vanishes.
+pub fn curvature(points: &[Point], t: f64) -> f64 {
+    let d1 = derivative(points);
+    let d2 = derivative(&d1);
+    let first = evaluate(&d1, t);
+    let second = evaluate(&d2, t);
+    let speed_sq = first.dot(first);
+    if speed_sq <= f64::EPSILON {
+        return 0.0;
+    }
+    cross(first, second) / (speed_sq * speed_sq.sqrt())
+}
+
+/// Signed area swept by the curve with respect to the origin, `½∫ P(t) × P'(t) dt`. Summed over
+/// the curves of a closed contour it gives the contour's area, positive when the contour runs
+/// counter-clockwise.
+pub fn signed_area(points: &[Point]) -> f64 {
+    if points.len() < 2 {
+        return 0.0;
+    }
+    let d = derivative(points);
+    /* The integrand is a polynomial of degree 2n - 1, so the quadrature is exact. */
+    let ret = GAUSS_LEGENDRE.iter().fold(0.0, |acc, (x, w)| {
+        let t = x.mul_add(0.5, 0.5);
+        w.mul_add(cross(evaluate(points, t), evaluate(&d, t)), acc)
+    });
+    ret / 4.0
+}
+
+/// Fit a chain of cubic curves to `points` so that none of them is further than `tolerance`
+/// from it, with Schneider's algorithm ("An Algorithm for Automatically Fitting Digitized
+/// Curves", Graphics Gems, 1990). The chain starts and ends at the first and last point.
+/// `start_tangent` and `end_tangent` are the directions pointing into the chain at either end;
+/// they are estimated from the points when `None`. Joins inside the chain are smooth.
+pub fn fit_cubics(
+    points: &[Point],
+    start_tangent: Option<Point>,
+    end_tangent: Option<Point>,
+    tolerance: f64,
+) -> Vec<[Point; 4]> {
+    let mut points = points.to_vec();
+    points.dedup_by(|a, b| a.distance(*b) < T_EPSILON);
+    let (Some(&first), Some(&last)) = (points.first(), points.last()) else {
+        return vec![];
+    };
+    if points.len() < 2 {
+        return vec![];
+    }
+    let start_tangent = start_tangent
+        .map(|t| t.unit())
+        .filter(|t| t.norm() > 0.0)
+        .unwrap_or_else(|| (points[1] - first).unit());
+    let end_tangent = end_tangent
+        .map(|t| t.unit())
+        .filter(|t| t.norm() > 0.0)
+        .unwrap_or_else(|| (points[points.len() - 2] - last).unit());
+    let mut ret = vec![];
+    fit_cubics_recursive(
+        &points,
+        start_tangent,
+        end_tangent,
+        tolerance.max(T_EPSILON),
+        &mut ret,
+    );
+    ret
+}
+
+fn fit_cubics_recursive(
+    points: &[Point],
+    start_tangent: Point,
+    end_tangent: Point,
+    tolerance: f64,
+    ret: &mut Vec<[Point; 4]>,
+) {
+    const MAX_ITERATIONS: usize = 8;
+
+    let (first, last) = (points[0], points[points.len() - 1]);
+    if points.len() == 2 {
+        let d = first.distance(last) / 3.0;
+        ret.push([
+            first,
+            first + d * start_tangent,
+            last + d * end_tangent,
+            last,
+        ]);
+        return;
+    }
+    /* Chord length parameterization. */
+    let mut params = Vec::with_capacity(points.len());
+    let mut acc = 0.0;
+    params.push(0.0);
+    for w in points.windows(2) {
+        acc += w[0].distance(w[1]);
+        params.push(acc);
+    }
+    for u in params.iter_mut() {
+        *u /= acc;
+    }
+    let mut split_at = points.len() / 2;
+    let mut prev_error = f64::INFINITY;
+    /* Improve the parameterization for as long as it helps. */
+    for _ in 0..MAX_ITERATIONS {
+        let curve = fit_cubic_to_params(points, &params, start_tangent, end_tangent);
+        let (error, index) = max_fit_error(points, &params, &curve);
+        split_at = index;
+        if error <= tolerance {
+            ret.push(curve);
+            return;
+        }
+        if error >= prev_error {
+            break;
+        }
+        prev_error = error;
+        reparameterize(points, &mut params, &curve);
+    }
+    let mut center = (points[split_at - 1] - points[split_at + 1]).unit();
+    if center.norm() == 0.0 {
+        center = (points[split_at - 1] - points[split_at]).unit();
+    }
+    fit_cubics_recursive(&points[..=split_at], start_tangent, center, tolerance, ret);
+    fit_cubics_recursive(
+        &points[split_at..],
+        -1.0 * center,
+        end_tangent,
+        tolerance,
+        ret,
+    );
+}
+
+/// Least squares fit of a cubic with fixed end points and end tangent directions.
+fn fit_cubic_to_params(
+    points: &[Point],
+    params: &[f64],
+    start_tangent: Point,
+    end_tangent: Point,
+) -> [Point; 4] {
+    let (first, last) = (points[0], points[points.len() - 1]);
+    let (mut c00, mut c01, mut c11, mut x0, mut x1) = (0.0, 0.0, 0.0, 0.0, 0.0);
+    for (&p, &u) in points.iter().zip(params) {
+        let v = 1.0 - u;
+        let (b0, b1, b2, b3) = (v * v * v, 3.0 * u * v * v, 3.0 * u * u * v, u * u * u);
+        let a0 = b1 * start_tangent;
+        let a1 = b2 * end_tangent;
+        c00 += a0.dot(a0);
+        c01 += a0.dot(a1);
+        c11 += a1.dot(a1);
+        let tmp = p - ((b0 + b1) * first + (b2 + b3) * last);
+        x0 += a0.dot(tmp);
+        x1 += a1.dot(tmp);
+    }
+    let det = c00.mul_add(c11, -c01 * c01);
+    let segment_length = first.distance(last);
+    let epsilon = 1e-6 * segment_length;
+    let (alpha_start, alpha_end) = if det.abs() > f64::EPSILON {
+        (
+            x0.mul_add(c11, -x1 * c01) / det,
+            c00.mul_add(x1, -c01 * x0) / det,
+        )
+    } else {
+        (0.0, 0.0)
+    };
+    let (alpha_start, alpha_end) = if alpha_start < epsilon || alpha_end < epsilon {
+        (segment_length / 3.0, segment_length / 3.0)
+    } else {
+        (alpha_start, alpha_end)
+    };
+    [
+        first,
+        first + alpha_start * start_tangent,
+        last + alpha_end * end_tangent,
+        last,
+    ]
+}
+
+/// Largest distance between `points` and `curve` at their parameters, and the index of the
+/// point where it occurs (never the first or last point).
+fn max_fit_error(points: &[Point], params: &[f64], curve: &[Point]) -> (f64, usize) {
+    let mut ret = (0.0, points.len() / 2);
+    for i in 1..points.len() - 1 {
+        let d = evaluate(curve, params[i]).distance(points[i]);
+        if d > ret.0 {
+            ret = (d, i);
+        }
+    }
+    ret
+}
+
+/// Improve the parameters of `points` on `curve` with a Newton-Raphson step each.
+fn reparameterize(points: &[Point], params: &mut [f64], curve: &[Point]) {
+    let d1 = derivative(curve);
+    let d2 = derivative(&d1);
+    for (&p, u) in points.iter().zip(params.iter_mut()) {
+        let diff = evaluate(curve, *u) - p;
+        let first = evaluate(&d1, *u);
+        let numerator = diff.dot(first);
+        let denominator = first.dot(first) + diff.dot(evaluate(&d2, *u));
+        if denominator.abs() > f64::EPSILON {
+            *u = (*u - numerator / denominator).clamp(0.0, 1.0);
+        }
+    }
+}
+
+/// Knot vector for a cubic B-spline with `count` control points. Uniform knots are evenly
+/// spaced, so the curve doesn't reach the first and last control points; clamped knots repeat
+/// at the ends so that it starts and ends on them.
+pub fn bspline_knots(count: usize, clamped: bool) -> Vec<f64> {
+    const DEGREE: usize = 3;
+
+    if clamped {
+        let last = count.saturating_sub(DEGREE) as f64;
+        (0..count + DEGREE + 1)
+            .map(|i| (i.saturating_sub(DEGREE) as f64).min(last))
+            .collect()
+    } else {
+        (0..count + DEGREE + 1).map(|i| i as f64).collect()
+    }
+}
+
+/// Convert a cubic B-spline with `control` points and `knots` (`control.len() + 4` of them,
+/// non-decreasing) into the chain of cubic Bézier curves that traces the same curve, by
+/// inserting knots (Boehm's algorithm) until every knot in the domain has multiplicity three.
+pub fn bspline_to_cubics(control: &[Point], knots: &[f64]) -> Vec<[Point; 4]> {
+    const DEGREE: usize = 3;
+
+    if control.len() <= DEGREE || knots.len() != control.len() + DEGREE + 1 {
+        return vec![];
+    }
+    let mut control = control.to_vec();
+    let mut knots = knots.to_vec();
+    let (lo, hi) = (knots[DEGREE], knots[control.len()]);
+    let mut values = knots
+        .iter()
+        .copied()
+        .filter(|u| (lo..=hi).contains(u))
+        .collect::<Vec<f64>>();
+    values.dedup();
+    for u in values {
+        let multiplicity = knots.iter().filter(|&&k| k == u).count();
+        for _ in multiplicity..DEGREE {
+            /* Span containing `u`; at the end of the domain use the last span. */
+            let k = knots
+                .iter()
+                .rposition(|&t| t <= u)
+                .unwrap()
+                .clamp(DEGREE, control.len() - 1);
+            let mut new_control = Vec::with_capacity(control.len() + 1);
+            new_control.extend_from_slice(&control[..=k - DEGREE]);
+            for i in (k - DEGREE + 1)..=k {
+                let denominator = knots[i + DEGREE] - knots[i];
+                let a = if denominator == 0.0 {
+                    0.0
+                } else {
+                    (u - knots[i]) / denominator
+                };
+                new_control.push(lerp(control[i - 1], control[i], a));
+            }
+            new_control.extend_from_slice(&control[k..]);
+            control = new_control;
+            knots.insert(k + 1, u);
+        }
+    }
+    (DEGREE..control.len())
+        .filter(|&k| knots[k] < knots[k + 1] && knots[k] >= lo && knots[k + 1] <= hi)
+        .map(|k| [control[k - 3], control[k - 2], control[k - 1], control[k]])
+        .collect()
+}
+
+/// Solve the linear system `a · x = b` by Gaussian elimination with partial pivoting. Returns
+/// `None` if the matrix is singular.
+fn solve_linear(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> Option<Vec<f64>> {
+    let n = b.len();
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[pivot][col].abs() < f64::EPSILON {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        for row in (col + 1)..n {
+            let factor = a[row][col] / a[col][col];
+            if factor == 0.0 {
+                continue;
+            }
+            for k in col..n {
+                a[row][k] -= factor * a[col][k];
+            }
+            b[row] -= factor * b[col];
+        }
+    }
+    let mut x = vec![0.0; n];
+    for row in (0..n).rev() {
+        let sum = ((row + 1)..n).fold(b[row], |acc, k| a[row][k].mul_add(-x[k], acc));
+        x[row] = sum / a[row][row];
+    }
+    Some(x)
+}
+
+/// Hobby's velocity function for tension 1: the length of a control arm relative to a third
+/// of the chord, given the angles the arms make with it.
+fn hobby_velocity(theta: f64, phi: f64) -> f64 {
+    let (st, ct) = theta.sin_cos();
+    let (sp, cp) = phi.sin_cos();
+    let sqrt5 = 5.0f64.sqrt();
+    let numerator =
+        (std::f64::consts::SQRT_2 * (st - sp / 16.0) * (sp - st / 16.0)).mul_add(ct - cp, 2.0);
+    let denominator = (0.5 * (3.0 - sqrt5)).mul_add(cp, (0.5 * (sqrt5 - 1.0)).mul_add(ct, 1.0));
+    (numerator / denominator).clamp(0.0, 4.0)
+}
+
+/// Hobby's curves through `points` with tension 1: a chain of cubics whose mock curvature is
+/// continuous at every point, as in METAFONT's `..` paths. Open paths have curl 1 at their
+/// ends; closed paths also have a curve from the last point back to the first.
+pub fn hobby(points: &[Point], closed: bool) -> Vec<[Point; 4]> {
+    let mut points = points.to_vec();
+    points.dedup_by(|a, b| a.distance(*b) < T_EPSILON);
+    if closed && points.len() > 1 && points[0].distance(points[points.len() - 1]) < T_EPSILON {
+        points.pop();
+    }
+    let n = points.len();
+    if n < 2 || (closed && n < 3) {
+        return vec![];
+    }
+    let segments = if closed { n } else { n - 1 };
+    let chord = |k: usize| points[(k + 1) % n] - points[k];
+    let d = (0..segments).map(|k| chord(k).norm()).collect::<Vec<f64>>();
+    /* Turning angle at each point, zero at the ends of open paths. */
+    let psi = (0..n)
+        .map(|k| {
+            if !closed && (k == 0 || k == n - 1) {
+                return 0.0;
+            }
+            let (prev, next) = (chord((k + n - 1) % n), chord(k));
+            cross(prev, next).atan2(prev.dot(next))
+        })
+        .collect::<Vec<f64>>();
+    /* Unknowns are the angles θ between each point's outgoing direction and its chord. The
+     * incoming angles are φ_k = -θ_k - ψ_k. For an open path, θ at the last point stands for
+     * -φ there. */
+    let mut a = vec![vec![0.0; n]; n];
+    let mut b = vec![0.0; n];
+    for k in 0..n {
+        if !closed && k == 0 {
+            /* Curl 1 at the start: θ_0 = φ_1. */
+            a[0][0] = 1.0;
+            a[0][1] = 1.0;
+            b[0] = -psi[1];
+            continue;
+        }
+        if !closed && k == n - 1 {
+            /* Curl 1 at the end: φ_n = θ_{n-1}. */
+            a[k][k - 1] = 1.0;
+            a[k][k] = 1.0;
+            continue;
+        }
+        let (prev, next) = ((k + n - 1) % n, (k + 1) % n);
+        let (d_prev, d_next) = (d[prev], d[k]);
+        a[k][prev] += 1.0 / d_prev;
+        a[k][k] += 2.0 / d_prev + 2.0 / d_next;
+        a[k][next] += 1.0 / d_next;
+        b[k] = -2.0 * psi[k] / d_prev - psi[next] / d_next;
+    }
+    let Some(theta) = solve_linear(a, b) else {
+        return vec![];
+    };
+    let rotate = |v: Point, angle: f64| {
+        let (s, c) = angle.sin_cos();
+        Point::from((v.x.mul_add(c, -v.y * s), v.x.mul_add(s, v.y * c)))
+    };
+    (0..segments)
+        .map(|k| {
+            let next = (k + 1) % n;
+            let t = theta[k];
+            let p = if !closed && next == n - 1 {
+                -theta[next]
+            } else {
+                -theta[next] - psi[next]
+            };
+            let v = chord(k);
+            [
+                points[k],
+                points[k] + (hobby_velocity(t, p) / 3.0) * rotate(v, t),
+                points[next] - (hobby_velocity(p, t) / 3.0) * rotate(v, -p),
+                points[next],
+            ]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+fn assert_close(a: f64, b: f64, epsilon: f64) {
+    assert!((a - b).abs() < epsilon, "{a} != {b}");
+}
+
+#[cfg(test)]
+fn assert_point_close(a: Point, b: Point, epsilon: f64) {
+    assert!(a.distance(b) < epsilon, "{a:?} != {b:?}");
+}
+
+#[cfg(test)]
+fn pts(points: &[(f64, f64)]) -> Vec<Point> {
+    points.iter().copied().map(Point::from).collect()
+}
+
+#[test]
+fn test_evaluate_split() {
+    let cubic = pts(&[(0.0, 0.0), (0.0, 100.0), (100.0, 100.0), (100.0, 0.0)]);
+    assert_point_close(evaluate(&cubic, 0.5), (50.0, 75.0).into(), 1e-9);
+    let (left, right) = split(&cubic, 0.3);
+    assert_eq!(left.len(), 4);
+    assert_eq!(right.len(), 4);
+    assert_point_close(left[0], cubic[0], 1e-9);
+    assert_point_close(right[3], cubic[3], 1e-9);
+    assert_point_close(left[3], right[0], 1e-9);
+    for i in 0..=10 {
+        let t = f64::from(i) / 10.0;
+        assert_point_close(evaluate(&left, t), evaluate(&cubic, 0.3 * t), 1e-9);
+        assert_point_close(
+            evaluate(&right, t),
+            evaluate(&cubic, 0.7f64.mul_add(t, 0.3)),
+            1e-9,
+        );
+    }
+    let middle = segment(&cubic, 0.25, 0.75);
+    assert_point_close(middle[0], evaluate(&cubic, 0.25), 1e-9);
+    assert_point_close(middle[3], evaluate(&cubic, 0.75), 1e-9);
+    assert_point_close(evaluate(&middle, 0.5), evaluate(&cubic, 0.5), 1e-9);
+
+    /* Higher degrees are supported as well. */
+    let quartic = pts(&[(0.0, 0.0), (0.0, 4.0), (4.0, 4.0), (4.0, 0.0), (8.0, 0.0)]);
+    let (left, right) = split(&quartic, 0.5);
+    assert_point_close(left[4], evaluate(&quartic, 0.5), 1e-9);
+    assert_point_close(evaluate(&right, 0.5), evaluate(&quartic, 0.75), 1e-9);
+    assert_point_close(
+        evaluate(&derivative(&quartic), 0.0),
+        (0.0, 16.0).into(),
+        1e-9,
+    );
+}
+
+#[test]
+fn test_roots() {
+    /* (t - 0.25)(t - 0.5)(t - 0.75) in Bernstein form. */
+    let coeffs = [-3.0 / 32.0, 13.0 / 96.0, -13.0 / 96.0, 3.0 / 32.0];
+    let r = roots(&coeffs);
+    assert_eq!(r.len(), 3, "{r:?}");
+    assert_close(r[0], 0.25, 1e-9);
+    assert_close(r[1], 0.5, 1e-9);
+    assert_close(r[2], 0.75, 1e-9);
+    assert_eq!(roots(&[1.0, 2.0, 3.0]), Vec::<f64>::new());
+    assert_eq!(roots(&[0.0, 0.0]), Vec::<f64>::new());
+    let r = roots(&[-1.0, 1.0]);
+    assert_eq!(r.len(), 1);
+    assert_close(r[0], 0.5, 1e-12);
+    /* Degree 4 uses bisection. */
+    let r = roots(&[1.0, -1.0, -1.0, -1.0, 1.0]);
+    assert_eq!(r.len(), 2, "{r:?}");
+    assert_close(evaluate_1d(&[1.0, -1.0, -1.0, -1.0, 1.0], r[0]), 0.0, 1e-9);
+}
+
+#[test]
+fn test_extrema_bounding_box() {
+    let cubic = pts(&[(0.0, 0.0), (0.0, 100.0), (100.0, 100.0), (100.0, 0.0)]);
+    let t = extrema(&cubic);
+    assert_eq!(t.len(), 1, "{t:?}");
+    assert_close(t[0], 0.5, 1e-9);
+    let (min, max) = bounding_box(&cubic);
+    assert_point_close(min, (0.0, 0.0).into(), 1e-9);
+    assert_point_close(max, (100.0, 75.0).into(), 1e-9);
+
+    let s_curve = pts(&[(0.0, 0.0), (100.0, 0.0), (-100.0, 100.0), (0.0, 100.0)]);
+    let (min, max) = bounding_box(&s_curve);
+    for i in 0..=1000 {
+        let p = evaluate(&s_curve, f64::from(i) / 1000.0);
+        assert!(p.x >= min.x - 1e-9 && p.x <= max.x + 1e-9);
+        assert!(p.y >= min.y - 1e-9 && p.y <= max.y + 1e-9);
+    }
+    assert!(min.x < -20.0 && max.x > 20.0);
+    for t in extrema(&s_curve) {
+        let d = evaluate(&derivative(&s_curve), t);
+        assert!(d.x.abs() < 1e-6 || d.y.abs() < 1e-6);
+    }
+}
+
+#[test]
+fn test_nearest() {
+    let cubic = pts(&[(0.0, 0.0), (0.0, 100.0), (100.0, 100.0), (100.0, 0.0)]);
+    let (t, p) = nearest(&cubic, (50.0, 200.0).into());
+    assert_close(t, 0.5, 1e-6);
+    assert_point_close(p, (50.0, 75.0).into(), 1e-6);
+    for i in 0..=20 {
+        let t = f64::from(i) / 20.0;
+        let on_curve = evaluate(&cubic, t);
+        let (found, _) = nearest(&cubic, on_curve);
+        assert_point_close(evaluate(&cubic, found), on_curve, 1e-6);
+    }
+    let (t, p) = nearest(&cubic, (-10.0, -10.0).into());
+    assert_close(t, 0.0, 1e-9);
+    assert_point_close(p, cubic[0], 1e-9);
+}
+
+#[test]
+fn test_intersections() {
+    let cubic = pts(&[(0.0, 0.0), (0.0, 100.0), (100.0, 100.0), (100.0, 0.0)]);
+    let hits = line_intersections(&cubic, (-10.0, 50.0).into(), (110.0, 50.0).into());
+    assert_eq!(hits.len(), 2, "{hits:?}");
+    for (t, s) in &hits {
+        assert_close(evaluate(&cubic, *t).y, 50.0, 1e-6);
+        assert_point_close(
+            evaluate(&cubic, *t),
+            lerp((-10.0, 50.0).into(), (110.0, 50.0).into(), *s),
+            1e-6,
+        );
+    }
+    assert!(hits[0].0 < hits[1].0);
+    /* Segment too short to reach the curve. */
+    assert!(line_intersections(&cubic, (40.0, 50.0).into(), (60.0, 50.0).into()).is_empty());
+
+    let other = pts(&[(0.0, 80.0), (30.0, -20.0), (70.0, -20.0), (100.0, 80.0)]);
+    let hits = curve_intersections(&cubic, &other, 1e-3);
+    assert_eq!(hits.len(), 2, "{hits:?}");
+    for (s, t) in hits {
+        assert_point_close(evaluate(&cubic, s), evaluate(&other, t), 1e-6);
+    }
+    let far = pts(&[(200.0, 0.0), (300.0, 100.0)]);
+    assert!(curve_intersections(&cubic, &far, 1e-3).is_empty());
+    /* Coincident curves */
+    assert!(curve_intersections(&cubic, &cubic, 1e-3).is_empty());
+}
+
+#[test]
+fn test_length() {
+    let line = pts(&[(0.0, 0.0), (30.0, 40.0)]);
+    assert_close(length(&line, 0.0, 1.0), 50.0, 1e-9);
+    assert_close(t_at_length(&line, 25.0), 0.5, 1e-9);
+
+    /* Quarter circle approximation; its length is close to π/2 · r. */
+    let k = 0.552_284_749_830_793_4;
+    let arc = pts(&[
+        (100.0, 0.0),
+        (100.0, 100.0 * k),
+        (100.0 * k, 100.0),
+        (0.0, 100.0),
+    ]);
+    let total = length(&arc, 0.0, 1.0);
+    assert_close(total, std::f64::consts::FRAC_PI_2 * 100.0, 0.05);
+    let polyline: f64 = (0..10_000)
+        .map(|i| {
+            evaluate(&arc, f64::from(i) / 10_000.0)
+                .distance(evaluate(&arc, f64::from(i + 1) / 10_000.0))
+        })
+        .sum();
+    assert_close(total, polyline, 1e-4);
+    for fraction in [0.1, 0.25, 0.5, 0.9] {
+        let t = t_at_length(&arc, total * fraction);
+        assert_close(length(&arc, 0.0, t), total * fraction, 1e-6);
+    }
+    assert_close(t_at_length(&arc, -1.0), 0.0, 1e-12);
+    assert_close(t_at_length(&arc, total + 1.0), 1.0, 1e-12);
+}
+
+#[test]
+fn test_curvature() {
+    let line = pts(&[(0.0, 0.0), (10.0, 10.0), (20.0, 20.0), (30.0, 30.0)]);
+    assert_close(curvature(&line, 0.5), 0.0, 1e-12);
+    let k = 0.552_284_749_830_793_4;
+    let arc = pts(&[
+        (100.0, 0.0),
+        (100.0, 100.0 * k),
+        (100.0 * k, 100.0),
+        (0.0, 100.0),
+    ]);
+    /* Counter-clockwise circle of radius 100. */
+    for i in 0..=10 {
+        assert_close(curvature(&arc, f64::from(i) / 10.0), 0.01, 5e-4);
+    }
+    let reversed = arc.iter().rev().copied().collect::<Vec<Point>>();
+    assert_close(curvature(&reversed, 0.5), -0.01, 5e-4);
+}
+
+#[test]
+fn test_signed_area() {
+    /* A counter-clockwise unit square made of lines. */
+    let square = [
+        pts(&[(0.0, 0.0), (1.0, 0.0)]),
+        pts(&[(1.0, 0.0), (1.0, 1.0)]),
+        pts(&[(1.0, 1.0), (0.0, 1.0)]),
+        pts(&[(0.0, 1.0), (0.0, 0.0)]),
+    ];
+    assert_close(square.iter().map(|c| signed_area(c)).sum(), 1.0, 1e-12);
+    assert_close(
+        square
+            .iter()
+            .rev()
+            .map(|c| signed_area(&c.iter().rev().copied().collect::<Vec<_>>()))
+            .sum(),
+        -1.0,
+        1e-12,
+    );
+    /* A cubic arch closed by its chord, with control points at height h over a base of
+     * width w, encloses 3/5 · w · h. */
+    let arch = pts(&[(100.0, 0.0), (100.0, 100.0), (0.0, 100.0), (0.0, 0.0)]);
+    let chord = pts(&[(0.0, 0.0), (100.0, 0.0)]);
+    assert_close(signed_area(&arch) + signed_area(&chord), 6000.0, 1e-9);
+}
+
+#[test]
+fn test_fit_cubics() {
+    /* Samples of a single cubic are fitted back with one curve. */
+    let cubic = pts(&[(0.0, 0.0), (0.0, 100.0), (100.0, 100.0), (100.0, 0.0)]);
+    let samples = (0..=32)
+        .map(|i| evaluate(&cubic, f64::from(i) / 32.0))
+        .collect::<Vec<_>>();
+    let fitted = fit_cubics(
+        &samples,
+        Some((0.0, 1.0).into()),
+        Some((0.0, 1.0).into()),
+        0.5,
+    );
+    assert_eq!(fitted.len(), 1);
+    assert_point_close(fitted[0][0], cubic[0], 1e-9);
+    assert_point_close(fitted[0][3], cubic[3], 1e-9);
+    for p in &samples {
+        assert!(nearest(&fitted[0], *p).1.distance(*p) < 0.5);
+    }
+
+    /* A half circle needs more than one cubic for a tight tolerance, and the joins are
+     * smooth. */
+    let arc = (0..=64)
+        .map(|i| {
+            let a = std::f64::consts::PI * f64::from(i) / 64.0;
+            Point::from((100.0 * a.cos(), 100.0 * a.sin()))
+        })
+        .collect::<Vec<_>>();
+    let fitted = fit_cubics(&arc, Some((0.0, 1.0).into()), Some((0.0, 1.0).into()), 0.1);
+    assert!(fitted.len() > 1);
+    for w in fitted.windows(2) {
+        assert_point_close(w[0][3], w[1][0], 1e-9);
+        assert_close(cross(w[0][3] - w[0][2], w[1][1] - w[1][0]), 0.0, 1e-6);
+    }
+    for p in &arc {
+        let d = fitted
+            .iter()
+            .map(|c| nearest(c, *p).1.distance(*p))
+            .fold(f64::INFINITY, f64::min);
+        assert!(d < 0.1 + 1e-6, "{d}");
+    }
+    assert!(fit_cubics(&pts(&[(1.0, 1.0)]), None, None, 1.0).is_empty());
+}
+
+#[cfg(test)]
+fn de_boor(control: &[Point], knots: &[f64], u: f64) -> Point {
+    let k = knots
+        .iter()
+        .rposition(|&t| t <= u)
+        .unwrap()
+        .clamp(3, control.len() - 1);
+    let mut d = control[k - 3..=k].to_vec();
+    for r in 1..=3 {
+        for j in (r..=3).rev() {
+            let i = j + k - 3;
+            let a = (u - knots[i]) / (knots[i + 4 - r] - knots[i]);
+            d[j] = lerp(d[j - 1], d[j], a);
+        }
+    }
+    d[3]
+}
+
+#[test]
+fn test_bspline_to_cubics() {
+    let control = pts(&[
+        (0.0, 0.0),
+        (10.0, 40.0),
+        (50.0, 60.0),
+        (80.0, 10.0),
+        (120.0, 30.0),
+        (150.0, 90.0),
+    ]);
+    for clamped in [false, true] {
+        let knots = bspline_knots(control.len(), clamped);
+        assert_eq!(knots.len(), control.len() + 4);
+        let cubics = bspline_to_cubics(&control, &knots);
+        assert_eq!(cubics.len(), control.len() - 3);
+        for w in cubics.windows(2) {
+            assert_point_close(w[0][3], w[1][0], 1e-9);
+        }
+        let (lo, hi) = (knots[3], knots[control.len()]);
+        for (i, cubic) in cubics.iter().enumerate() {
+            for j in 0..=8 {
+                let t = f64::from(j) / 8.0;
+                let u = lo + (hi - lo) * (i as f64 + t) / cubics.len() as f64;
+                assert_point_close(evaluate(cubic, t), de_boor(&control, &knots, u), 1e-9);
+            }
+        }
+        if clamped {
+            assert_point_close(cubics[0][0], control[0], 1e-9);
+            assert_point_close(cubics[cubics.len() - 1][3], control[5], 1e-9);
+        } else {
+            /* The well known closed form for uniform cubic B-splines. */
+            let expected = (1.0 / 6.0) * (control[0] + 4.0 * control[1] + control[2]);
+            assert_point_close(cubics[0][0], expected, 1e-9);
+        }
+    }
+    assert!(bspline_to_cubics(&control[..3], &bspline_knots(3, true)).is_empty());
+}
+
+#[test]
+fn test_hobby() {
+    /* Four points on a circle give the familiar 0.5523 control arm length. */
+    let square = pts(&[(1.0, 0.0), (0.0, 1.0), (-1.0, 0.0), (0.0, -1.0)]);
+    let cubics = hobby(&square, true);
+    assert_eq!(cubics.len(), 4);
+    for cubic in &cubics {
+        assert_close(cubic[0].distance(cubic[1]), 0.5523, 1e-3);
+        assert_close(cubic[2].distance(cubic[3]), 0.5523, 1e-3);
+        assert_close(evaluate(cubic, 0.5).norm(), 1.0, 1e-3);
+    }
+    assert_point_close(cubics[3][3], cubics[0][0], 1e-12);
+    /* Tangents are continuous. */
+    for (prev, next) in cubics.iter().zip(cubics.iter().cycle().skip(1)) {
+        assert_close(cross(prev[3] - prev[2], next[1] - next[0]), 0.0, 1e-9);
+    }
+
+    /* Collinear points give a straight line. */
+    let line = pts(&[(0.0, 0.0), (1.0, 0.0), (3.0, 0.0)]);
+    let cubics = hobby(&line, false);
+    assert_eq!(cubics.len(), 2);
+    for cubic in &cubics {
+        for p in cubic {
+            assert_close(p.y, 0.0, 1e-12);
+        }
+    }
+    /* A symmetric open arch. */
+    let arch = pts(&[(-1.0, 0.0), (0.0, 1.0), (1.0, 0.0)]);
+    let cubics = hobby(&arch, false);
+    assert_close(cubics[0][2].y, 1.0, 1e-9);
+    assert_close(cubics[0][1].x + 1.0, 1.0 - cubics[1][2].x, 1e-9);
+    assert!(hobby(&arch[..1], false).is_empty());
+}
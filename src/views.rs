@@ -22,7 +22,9 @@
 pub mod canvas;
 pub mod collection;
 pub mod overlay;
+pub mod text_preview;
 
 pub use canvas::{Canvas, Transformation, UnitPoint, ViewPoint};
 pub use collection::*;
 pub use overlay::Overlay;
+pub use text_preview::TextPreview;
@@ -26,8 +26,11 @@ use gtk::cairo::{Context, FontSlant, FontWeight};
 use once_cell::unsync::OnceCell;
 use std::collections::HashMap;
 
-use crate::glyphs::{Glyph, GlyphDrawingOptions, GlyphKind};
+use crate::glyphs::{
+    Glyph, GlyphDrawingOptions, GlyphKind, Metric, Metrics, Recipe, Spacing, SpacingOp,
+};
 use crate::prelude::*;
+use crate::ufo::objects::Layer;
 use crate::unicode::blocks::*;
 
 const GLYPH_BOX_WIDTH: f64 = 110.0;
@@ -314,6 +317,34 @@ impl ObjectImpl for CollectionInner {
                         }
                     }
                 })
+            )
+            .add_button_cb(
+                "Build composite glyphs",
+                clone!(@weak obj => move |_| {
+                    obj.build_composites_dialog();
+                })
+            )
+            .add_button_cb(
+                "Update metrics from metric keys",
+                clone!(@weak obj => move |_| {
+                    obj.sync_metric_keys();
+                })
+            )
+            .add_button_cb(
+                "Select all shown glyphs",
+                clone!(@weak obj => move |_| {
+                    for glyph_box in obj.shown_glyph_boxes() {
+                        glyph_box.set_property(GlyphBox::SELECTED, true);
+                    }
+                })
+            )
+            .add_button_cb(
+                "Clear selection",
+                clone!(@weak obj => move |_| {
+                    for glyph_box in obj.imp().widgets.borrow().iter() {
+                        glyph_box.set_property(GlyphBox::SELECTED, false);
+                    }
+                })
             );
             context_menu.popup(0);
         }));
@@ -451,6 +482,19 @@ impl ObjectImpl for CollectionInner {
         tool_palette.add(&show_filter_pop);
         tool_palette.set_item_homogeneous(&show_filter_pop, false);
 
+        let spacing_button = gtk::ToolButton::builder()
+            .label("Spacing...")
+            .tooltip_text("Set or adjust the metrics of the selected glyphs, or of all shown glyphs if none are selected. Ctrl or Shift click a glyph to select it.")
+            .valign(gtk::Align::Center)
+            .halign(gtk::Align::Start)
+            .visible(true)
+            .build();
+        spacing_button.connect_clicked(clone!(@weak obj => move |_| {
+            obj.batch_spacing_dialog();
+        }));
+        tool_palette.add(&spacing_button);
+        tool_palette.set_item_homogeneous(&spacing_button, false);
+
         tool_palette
             .style_context()
             .add_class("glyphs_area_toolbar");
@@ -615,6 +659,32 @@ impl Collection {
                 widgets.push(glyph_box);
             }
         }
+        flow_box.set_sort_func(Some(Box::new(
+            |a: &gtk::FlowBoxChild, b: &gtk::FlowBoxChild| match (
+                a.child().and_then(|w| w.downcast::<GlyphBox>().ok()),
+                b.child().and_then(|w| w.downcast::<GlyphBox>().ok()),
+            ) {
+                (Some(a), Some(b)) => {
+                    let a = a.imp().glyph.get().unwrap().borrow();
+                    let b = b.imp().glyph.get().unwrap().borrow();
+                    match a.cmp(&b) {
+                        std::cmp::Ordering::Less => -1,
+                        std::cmp::Ordering::Equal => 0,
+                        std::cmp::Ordering::Greater => 1,
+                    }
+                }
+                _ => 0,
+            },
+        )));
+        project.default_layer.connect_notify_local(
+            Some(Layer::LOADED),
+            clone!(@weak ret => move |_, _| {
+                /* Glyph kinds and unicode blocks are only known once the glyphs are parsed. */
+                ret.imp().flow_box.invalidate_sort();
+                ret.update_tree_store();
+                ret.update_flow_box();
+            }),
+        );
         ret.imp().app.set(app).unwrap();
         ret.imp().project.set(project).unwrap();
         *ret.imp().widgets.borrow_mut() = widgets;
@@ -623,6 +693,475 @@ impl Collection {
         ret
     }
 
+    /// Applies the metric keys of every glyph whose metrics are out of sync, as a single undo
+    /// step. Keys that can't be evaluated are reported in an error dialog.
+    fn sync_metric_keys(&self) {
+        let layer = self.project().default_layer.clone();
+        if let Err(err) = layer.load_all() {
+            eprintln!("{err}");
+        }
+        let glyphs = layer.glyphs().clone();
+        let mut actions = vec![];
+        let mut errors = vec![];
+        for glyph in glyphs.values() {
+            match Glyph::sync_metrics_action(glyph, &glyphs) {
+                Ok(Some(mut action)) => {
+                    (action.redo)();
+                    actions.push(action);
+                }
+                Ok(None) => {}
+                Err(err) => errors.push(err),
+            }
+        }
+        if !actions.is_empty() {
+            self.app().undo_db.borrow().event(Action::group(
+                EventStamp {
+                    t: std::any::TypeId::of::<Glyph>(),
+                    property: "metric-keys",
+                    id: Box::new([]),
+                },
+                actions,
+            ));
+        }
+        if !errors.is_empty() {
+            let dialog = crate::utils::widgets::new_simple_error_dialog(
+                Some("Some metric keys could not be evaluated"),
+                &errors.join("\n"),
+                None,
+                self.app().window.upcast_ref(),
+            );
+            dialog.run();
+            dialog.emit_close();
+        }
+        self.queue_draw();
+    }
+
+    /// Dialog to build composite glyphs out of recipes like `Aacute = A + acutecomb@top`, one
+    /// per line. Recipes can be filled in from the Unicode canonical decompositions of the
+    /// glyphs the default layer already has. The built glyphs are a single undo step.
+    fn build_composites_dialog(&self) {
+        let layer = self.project().default_layer.clone();
+        let dialog = gtk::Dialog::builder()
+            .attached_to(&self.app().window)
+            .application(self.app())
+            .border_width(10)
+            .destroy_with_parent(true)
+            .modal(true)
+            .title("Build composite glyphs")
+            .default_width(450)
+            .default_height(400)
+            .build();
+        dialog.add_button("Build", gtk::ResponseType::Accept);
+        dialog.add_button("Cancel", gtk::ResponseType::Close);
+        let b = dialog.content_area();
+        b.pack_start(
+            &gtk::Label::builder()
+                .label("Insert one recipe per line, e.g. Aacute = A + acutecomb@top. Marks are aligned by their _top anchor to the top anchor of the base or of the previous mark. An optional codepoint can follow, e.g. Aacute = A + acutecomb@top | 00C1")
+                .visible(true)
+                .wrap(true)
+                .halign(gtk::Align::Start)
+                .build(),
+            false,
+            false,
+            5,
+        );
+        let buffer = gtk::TextBuffer::new(gtk::TextTagTable::NONE);
+        let text_view = gtk::TextView::builder()
+            .visible(true)
+            .monospace(true)
+            .buffer(&buffer)
+            .build();
+        b.pack_start(
+            &gtk::ScrolledWindow::builder()
+                .child(&text_view)
+                .expand(true)
+                .visible(true)
+                .build(),
+            true,
+            true,
+            0,
+        );
+        let from_unicode = gtk::Button::builder()
+            .label("Add recipes from Unicode decompositions")
+            .visible(true)
+            .halign(gtk::Align::Start)
+            .build();
+        from_unicode.connect_clicked(clone!(@weak layer, @weak buffer => move |_| {
+            /* Codepoints and anchors are only known once the glyphs are parsed. */
+            if let Err(err) = layer.load_all() {
+                eprintln!("{err}");
+            }
+            let recipes = Recipe::from_unicode(&layer.glyphs());
+            let mut end = buffer.end_iter();
+            for r in recipes {
+                buffer.insert(&mut end, &format!("{r}\n"));
+            }
+        }));
+        b.pack_start(&from_unicode, false, false, 5);
+        loop {
+            match dialog.run() {
+                gtk::ResponseType::Accept => {
+                    let (start, end) = buffer.bounds();
+                    let text = buffer
+                        .text(&start, &end, false)
+                        .map(|gstr| gstr.to_string())
+                        .unwrap_or_default();
+                    let recipes = match Recipe::parse_list(&text) {
+                        Ok(v) => v,
+                        Err(err) => {
+                            let error = crate::utils::widgets::new_simple_error_dialog(
+                                Some("Error: Invalid recipe."),
+                                &err,
+                                None,
+                                self.app().window.upcast_ref(),
+                            );
+                            error.run();
+                            error.emit_close();
+                            continue;
+                        }
+                    };
+                    let mut actions = vec![];
+                    let mut errors = vec![];
+                    for r in recipes {
+                        match r.apply(&layer) {
+                            Ok((mut action, new_glyph)) => {
+                                (action.redo)();
+                                actions.push(action);
+                                if let Some(metadata) = new_glyph {
+                                    self.emit_by_name::<()>(Collection::NEW_GLYPH, &[&metadata]);
+                                }
+                            }
+                            Err(err) => errors.push(err),
+                        }
+                    }
+                    if !actions.is_empty() {
+                        self.app().undo_db.borrow().event(Action::group(
+                            EventStamp {
+                                t: std::any::TypeId::of::<Glyph>(),
+                                property: "composites",
+                                id: Box::new([]),
+                            },
+                            actions,
+                        ));
+                    }
+                    self.imp().flow_box.queue_draw();
+                    dialog.emit_close();
+                    if !errors.is_empty() {
+                        let error = crate::utils::widgets::new_simple_error_dialog(
+                            Some("Error: Some glyphs could not be built."),
+                            &errors.join("\n"),
+                            None,
+                            self.app().window.upcast_ref(),
+                        );
+                        error.run();
+                        error.emit_close();
+                    }
+                    break;
+                }
+                _ => {
+                    dialog.emit_close();
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Glyph boxes not hidden by the current filter.
+    fn shown_glyph_boxes(&self) -> Vec<GlyphBox> {
+        self.imp()
+            .widgets
+            .borrow()
+            .iter()
+            .filter(|w| w.parent().map_or(false, |p| p.is_child_visible()))
+            .cloned()
+            .collect()
+    }
+
+    /// Dialog to set or adjust the sidebearings and advance widths of the selected glyphs, or
+    /// of all shown glyphs if none are selected. The new metrics are previewed in a table and
+    /// applied as a single undo step.
+    fn batch_spacing_dialog(&self) {
+        let layer = self.project().default_layer.clone();
+        if let Err(err) = layer.load_all() {
+            eprintln!("{err}");
+        }
+        let mut glyph_boxes = self
+            .imp()
+            .widgets
+            .borrow()
+            .iter()
+            .filter(|w| w.imp().selected.get())
+            .cloned()
+            .collect::<Vec<GlyphBox>>();
+        let description = if glyph_boxes.is_empty() {
+            glyph_boxes = self.shown_glyph_boxes();
+            format!("Applies to all {} shown glyphs.", glyph_boxes.len())
+        } else {
+            format!("Applies to {} selected glyphs.", glyph_boxes.len())
+        };
+        let glyphs = glyph_boxes
+            .iter()
+            .map(|w| w.imp().glyph.get().unwrap().clone())
+            /* Glyphs whose files couldn't be parsed have no outline to space. */
+            .filter(|g| g.borrow().is_loaded())
+            .collect::<Vec<Rc<RefCell<Glyph>>>>();
+        if glyphs.is_empty() {
+            return;
+        }
+        let dialog = gtk::Dialog::builder()
+            .attached_to(&self.app().window)
+            .application(self.app())
+            .border_width(10)
+            .destroy_with_parent(true)
+            .modal(true)
+            .title("Spacing")
+            .default_width(550)
+            .default_height(500)
+            .build();
+        let apply_button = dialog.add_button("Apply", gtk::ResponseType::Accept);
+        dialog.add_button("Cancel", gtk::ResponseType::Close);
+        let b = dialog.content_area();
+        b.pack_start(
+            &gtk::Label::builder()
+                .label(&format!("{description} Sidebearings are changed first, then the advance width by changing the right sidebearing. Glyphs without an outline only get their advance width changed."))
+                .visible(true)
+                .wrap(true)
+                .halign(gtk::Align::Start)
+                .build(),
+            false,
+            false,
+            5,
+        );
+        let grid = gtk::Grid::builder()
+            .visible(true)
+            .row_spacing(5)
+            .column_spacing(5)
+            .build();
+        let rows = ["Left sidebearing", "Right sidebearing", "Advance width"]
+            .into_iter()
+            .enumerate()
+            .map(|(i, label)| {
+                let mode = gtk::ComboBoxText::builder().visible(true).build();
+                for (id, text) in [
+                    ("keep", "Keep"),
+                    ("set", "Set to"),
+                    ("add", "Add"),
+                    ("percent", "Percentage of current"),
+                    ("copy", "Copy from source glyph"),
+                ] {
+                    mode.append(Some(id), text);
+                }
+                mode.set_active_id(Some("keep"));
+                let value = gtk::SpinButton::with_range(-10000.0, 10000.0, 1.0);
+                value.set_digits(1);
+                value.set_visible(true);
+                value.set_sensitive(false);
+                grid.attach(
+                    &gtk::Label::builder()
+                        .label(label)
+                        .visible(true)
+                        .halign(gtk::Align::Start)
+                        .build(),
+                    0,
+                    i as i32,
+                    1,
+                    1,
+                );
+                grid.attach(&mode, 1, i as i32, 1, 1);
+                grid.attach(&value, 2, i as i32, 1, 1);
+                (mode, value)
+            })
+            .collect::<Vec<(gtk::ComboBoxText, gtk::SpinButton)>>();
+        let source_entry = gtk::Entry::builder()
+            .visible(true)
+            .placeholder_text("Glyph name")
+            .build();
+        grid.attach(
+            &gtk::Label::builder()
+                .label("Source glyph")
+                .visible(true)
+                .halign(gtk::Align::Start)
+                .build(),
+            0,
+            3,
+            1,
+            1,
+        );
+        grid.attach(&source_entry, 1, 3, 2, 1);
+        let center = gtk::CheckButton::builder()
+            .label("Center within advance width")
+            .visible(true)
+            .build();
+        grid.attach(&center, 0, 4, 3, 1);
+        b.pack_start(&grid, false, false, 5);
+        let error_label = gtk::Label::builder()
+            .visible(false)
+            .wrap(true)
+            .halign(gtk::Align::Start)
+            .build();
+        b.pack_start(&error_label, false, false, 5);
+
+        let store = gtk::ListStore::new(&[
+            String::static_type(),
+            String::static_type(),
+            String::static_type(),
+            String::static_type(),
+        ]);
+        let tree = gtk::TreeView::builder()
+            .visible(true)
+            .model(&store)
+            .headers_visible(true)
+            .build();
+        tree.set_grid_lines(gtk::TreeViewGridLines::Both);
+        for (i, title) in ["Glyph", "LSB", "RSB", "Width"].into_iter().enumerate() {
+            let column = gtk::TreeViewColumn::new();
+            column.set_title(title);
+            let cell = gtk::CellRendererText::new();
+
+            column.pack_start(&cell, true);
+            column.add_attribute(&cell, "text", i as i32);
+            tree.append_column(&column);
+        }
+        b.pack_start(
+            &gtk::ScrolledWindow::builder()
+                .child(&tree)
+                .expand(true)
+                .visible(true)
+                .build(),
+            true,
+            true,
+            0,
+        );
+
+        let compute = Rc::new(
+            clone!(@strong rows, @weak center, @weak source_entry, @weak layer, @strong glyphs => @default-return Err(String::new()), move || {
+                let op = |(mode, value): &(gtk::ComboBoxText, gtk::SpinButton)| {
+                    let v = value.value();
+                    match mode.active_id().as_deref() {
+                        Some("set") => SpacingOp::Set(v),
+                        Some("add") => SpacingOp::Add(v),
+                        Some("percent") => SpacingOp::Percent(v),
+                        Some("copy") => SpacingOp::Copy,
+                        _ => SpacingOp::Keep,
+                    }
+                };
+                let spacing = Spacing {
+                    lsb: op(&rows[0]),
+                    rsb: op(&rows[1]),
+                    width: op(&rows[2]),
+                    center: center.is_active(),
+                };
+                let source_name = source_entry.text();
+                let source = if source_name.is_empty() {
+                    None
+                } else if let Some(source) = layer.glyphs().get(source_name.as_str()) {
+                    Some(source.borrow().metrics())
+                } else {
+                    return Err(format!("There's no glyph named {source_name}."));
+                };
+                if source.is_none()
+                    && [spacing.lsb, spacing.rsb, spacing.width].contains(&SpacingOp::Copy)
+                {
+                    return Err("Enter a source glyph to copy metrics from.".to_string());
+                }
+                Ok(glyphs
+                    .iter()
+                    .map(|g| {
+                        let current = g.borrow().metrics();
+                        (current, spacing.apply(current, source))
+                    })
+                    .collect::<Vec<(Metrics, Metrics)>>())
+            }),
+        );
+        let update = Rc::new(
+            clone!(@strong compute, @weak store, @weak error_label, @weak apply_button, @strong glyphs => move || {
+                store.clear();
+                let targets = match compute() {
+                    Ok(v) => v,
+                    Err(err) => {
+                        error_label.set_text(&err);
+                        error_label.set_visible(true);
+                        apply_button.set_sensitive(false);
+                        return;
+                    }
+                };
+                error_label.set_visible(false);
+                apply_button.set_sensitive(true);
+                let fmt = |old: Option<f64>, new: Option<f64>| match (old, new) {
+                    (Some(old), Some(new)) if (old - new).abs() > 0.001 => {
+                        format!("{old:.1} → {new:.1}")
+                    }
+                    (Some(old), _) => format!("{old:.1}"),
+                    _ => "-".to_string(),
+                };
+                for (glyph, (old, new)) in glyphs.iter().zip(targets) {
+                    store.insert_with_values(
+                        None,
+                        &[
+                            (0, &glyph.borrow().name()),
+                            (1, &fmt(old.lsb, new.lsb)),
+                            (2, &fmt(old.rsb, new.rsb)),
+                            (3, &fmt(Some(old.width), Some(new.width))),
+                        ],
+                    );
+                }
+            }),
+        );
+        for (mode, value) in rows.iter() {
+            mode.connect_changed(clone!(@strong update, @weak value => move |mode| {
+                value.set_sensitive(matches!(mode.active_id().as_deref(), Some("set" | "add" | "percent")));
+                if mode.active_id().as_deref() == Some("percent") && value.value().abs() < 0.001 {
+                    value.set_value(100.0);
+                }
+                update();
+            }));
+            value.connect_value_changed(clone!(@strong update => move |_| {
+                update();
+            }));
+        }
+        source_entry.connect_changed(clone!(@strong update => move |_| {
+            update();
+        }));
+        center.connect_toggled(clone!(@strong update => move |_| {
+            update();
+        }));
+        update();
+
+        loop {
+            match dialog.run() {
+                gtk::ResponseType::Accept => {
+                    let Ok(targets) = compute() else {
+                        continue;
+                    };
+                    let mut actions = vec![];
+                    for (glyph, (_, new)) in glyphs.iter().zip(targets) {
+                        if let Some(mut action) = Glyph::set_metrics_action(glyph, new) {
+                            (action.redo)();
+                            actions.push(action);
+                        }
+                    }
+                    if !actions.is_empty() {
+                        self.app().undo_db.borrow().event(Action::group(
+                            EventStamp {
+                                t: std::any::TypeId::of::<Glyph>(),
+                                property: "spacing",
+                                id: Box::new([]),
+                            },
+                            actions,
+                        ));
+                    }
+                    self.queue_draw();
+                    dialog.emit_close();
+                    break;
+                }
+                _ => {
+                    dialog.emit_close();
+                    break;
+                }
+            }
+        }
+    }
+
     fn update_tree_store(&self) {
         let tree_store = self.imp().tree_store.get().unwrap();
         let mut show_blocks = self.imp().show_blocks.borrow_mut();
@@ -704,6 +1243,9 @@ impl Collection {
                     c.queue_draw();
                     let show_blocks = show_blocks.borrow();
                     let filter_input = filter_input.borrow();
+                    if hide_empty {
+                        _ = Glyph::load(c.imp().glyph.get().unwrap());
+                    }
                     let glyph = c.imp().glyph.get().unwrap().borrow();
                     if hide_empty && glyph.is_empty() {
                         return false;
@@ -752,6 +1294,7 @@ pub struct GlyphBoxInner {
     pub project: OnceCell<Project>,
     pub glyph: OnceCell<Rc<RefCell<Glyph>>>,
     pub focused: Cell<bool>,
+    pub selected: Cell<bool>,
     modified: Cell<bool>,
     mark_color: Cell<Color>,
     pub zoom_factor: Cell<f64>,
@@ -803,10 +1346,83 @@ impl ObjectImpl for GlyphBoxInner {
                                         w.present();
                                     }),
                                 )
+                                .add_button_cb(
+                                    "Add extreme points",
+                                    clone!(@weak obj => move |_| {
+                                        let app = obj.imp().app.get().unwrap();
+                                        let glyph = obj.imp().glyph.get().unwrap();
+                                        if let Some(mut action) = Glyph::add_extrema_action(glyph, None) {
+                                            (action.redo)();
+                                            app.undo_db.borrow().event(action);
+                                        }
+                                        obj.queue_draw();
+                                    }),
+                                )
+                                .add_button_cb(
+                                    "Correct path direction (PostScript)",
+                                    clone!(@weak obj => move |_| {
+                                        let app = obj.imp().app.get().unwrap();
+                                        let glyph = obj.imp().glyph.get().unwrap();
+                                        if let Some(mut action) = Glyph::correct_direction_action(glyph, DirectionConvention::PostScript) {
+                                            (action.redo)();
+                                            app.undo_db.borrow().event(action);
+                                        }
+                                        obj.queue_draw();
+                                    }),
+                                )
+                                .add_button_cb(
+                                    "Correct path direction (TrueType)",
+                                    clone!(@weak obj => move |_| {
+                                        let app = obj.imp().app.get().unwrap();
+                                        let glyph = obj.imp().glyph.get().unwrap();
+                                        if let Some(mut action) = Glyph::correct_direction_action(glyph, DirectionConvention::TrueType) {
+                                            (action.redo)();
+                                            app.undo_db.borrow().event(action);
+                                        }
+                                        obj.queue_draw();
+                                    }),
+                                )
+                                .add_button_cb(
+                                    "Update metrics from metric keys",
+                                    clone!(@weak obj => move |_| {
+                                        let app = obj.imp().app.get().unwrap();
+                                        let glyph = obj.imp().glyph.get().unwrap();
+                                        let layer = glyph.borrow().metadata.layer().clone();
+                                        let Some(layer) = layer else {
+                                            return;
+                                        };
+                                        let glyphs = layer.glyphs().clone();
+                                        match Glyph::sync_metrics_action(glyph, &glyphs) {
+                                            Ok(Some(mut action)) => {
+                                                (action.redo)();
+                                                app.undo_db.borrow().event(action);
+                                            }
+                                            Ok(None) => {}
+                                            Err(err) => {
+                                                let dialog = crate::utils::widgets::new_simple_error_dialog(
+                                                    Some("Metric keys could not be evaluated"),
+                                                    &err,
+                                                    None,
+                                                    app.window.upcast_ref(),
+                                                );
+                                                dialog.run();
+                                                dialog.emit_close();
+                                            }
+                                        }
+                                        obj.queue_draw();
+                                    }),
+                                )
                                 .add_button("Delete glyph")
                                 .add_button("Export SVG");
                             context_menu.popup(event.time());
                         }
+                        gtk::gdk::BUTTON_PRIMARY
+                            if event
+                                .state()
+                                .intersects(gtk::gdk::ModifierType::CONTROL_MASK | gtk::gdk::ModifierType::SHIFT_MASK) =>
+                        {
+                            obj.set_property(GlyphBox::SELECTED, !obj.imp().selected.get());
+                        }
                         gtk::gdk::BUTTON_PRIMARY => {
                             obj.emit_open_glyph_edit();
                         }
@@ -822,7 +1438,7 @@ impl ObjectImpl for GlyphBoxInner {
         self.drawing_area.connect_query_tooltip(
             clone!(@weak obj => @default-return false, move |_self, _x: i32, _y: i32, _by_keyboard: bool, tooltip| {
                 let glyph = obj.imp().glyph.get().unwrap().borrow();
-                if let GlyphKind::Char(c) = glyph.kinds().0 {
+                let mut text = if let GlyphKind::Char(c) = glyph.kinds().0 {
                     let block_name = if let Some(idx) = c.char_block() {
                         UNICODE_BLOCKS[idx].1
                     } else {
@@ -830,10 +1446,19 @@ impl ObjectImpl for GlyphBoxInner {
                     };
                     let unicode = format!("U+{:04X}", c as u32);
 
-                    tooltip.set_text(Some(&format!("Name: {}\nUnicode: {}\nBlock: {}", glyph.name(), unicode, block_name)));
+                    format!("Name: {}\nUnicode: {}\nBlock: {}", glyph.name(), unicode, block_name)
                 } else {
-                    tooltip.set_text(Some(&format!("Name: {}\nComponent", glyph.name())));
+                    format!("Name: {}\nComponent", glyph.name())
+                };
+                let out_of_sync = obj.out_of_sync_metrics();
+                if !out_of_sync.is_empty() {
+                    let labels = out_of_sync.iter().map(|m| m.label()).collect::<Vec<_>>();
+                    text.push_str(&format!("\nOut of sync with metric keys: {}", labels.join(", ")));
+                }
+                if let Some(err) = glyph.load_error() {
+                    text.push_str(&format!("\nCould not load: {err}"));
                 }
+                tooltip.set_text(Some(&text));
                 true
             }));
         self.drawing_area.connect_draw(clone!(@weak obj => @default-return Inhibit(false), move |viewport: &gtk::DrawingArea, mut ctx: &Context| {
@@ -846,6 +1471,8 @@ impl ObjectImpl for GlyphBoxInner {
             let units_per_em = obj.imp().project.get().unwrap().fontinfo().property(FontInfo::UNITS_PER_EM);
 
             let (x, y) = (0.01, 0.01);
+            /* Failures are shown in the tooltip. */
+            _ = Glyph::load(obj.imp().glyph.get().unwrap());
             let glyph = obj.imp().glyph.get().unwrap().borrow();
             let label = match glyph.kinds().0 {
                 GlyphKind::Char(c) => c.to_string(),
@@ -861,8 +1488,14 @@ impl ObjectImpl for GlyphBoxInner {
                 cr.set_source_color(colors.theme_base_color);
             }
             cr.fill_preserve().expect("Invalid cairo surface state");
-            cr.set_source_rgba(0.0, 0.0, 0.0, 0.5);
+            if obj.imp().selected.get() {
+                cr.set_line_width(4.0);
+                cr.set_source_color(colors.theme_selected_bg_color);
+            } else {
+                cr.set_source_rgba(0.0, 0.0, 0.0, 0.5);
+            }
             cr.stroke_preserve().expect("Invalid cairo surface state");
+            cr.set_line_width(1.5);
             cr.clip();
             cr.new_path();
             let mark_color = obj.imp().mark_color.get();
@@ -926,6 +1559,15 @@ impl ObjectImpl for GlyphBoxInner {
                 glyph.draw(cr.push(), options);
             }
 
+            /* Mark glyphs whose metrics don't match their metric keys. */
+
+            if !obj.out_of_sync_metrics().is_empty() {
+                let cr1 = cr.push();
+                cr1.set_source_color(Color::from_hex("#fb8c00")); // [ref:hardcoded_color_value]
+                cr1.arc(point.x + 10.0, point.y + 10.0, 4.0, 0.0, 2.0 * std::f64::consts::PI);
+                cr1.fill().unwrap();
+            }
+
             /* Draw glyph label */
 
             cr.set_line_width(2.0);
@@ -1026,6 +1668,13 @@ impl ObjectImpl for GlyphBoxInner {
                         false,
                         ParamFlags::READABLE,
                     ),
+                    ParamSpecBoolean::new(
+                        GlyphBox::SELECTED,
+                        GlyphBox::SELECTED,
+                        GlyphBox::SELECTED,
+                        false,
+                        ParamFlags::READWRITE,
+                    ),
                     glib::ParamSpecBoxed::new(
                         GlyphMetadata::MARK_COLOR,
                         GlyphMetadata::MARK_COLOR,
@@ -1050,6 +1699,7 @@ impl ObjectImpl for GlyphBoxInner {
             GlyphBox::SHOW_DETAILS => self.show_details.get().to_value(),
             GlyphBox::ZOOM_FACTOR => self.zoom_factor.get().to_value(),
             GlyphBox::FOCUSED => self.focused.get().to_value(),
+            GlyphBox::SELECTED => self.selected.get().to_value(),
             GlyphMetadata::MARK_COLOR => self.mark_color.get().to_value(),
             GlyphMetadata::MODIFIED => self.modified.get().to_value(),
             _ => unimplemented!("{}", pspec.name()),
@@ -1061,6 +1711,10 @@ impl ObjectImpl for GlyphBoxInner {
             GlyphBox::SHOW_DETAILS => self.show_details.set(value.get().unwrap()),
             GlyphBox::ZOOM_FACTOR => self.zoom_factor.set(value.get().unwrap()),
             GlyphBox::FOCUSED => self.focused.set(value.get().unwrap()),
+            GlyphBox::SELECTED => {
+                self.selected.set(value.get().unwrap());
+                self.drawing_area.queue_draw();
+            }
             GlyphMetadata::MARK_COLOR => {
                 self.mark_color.set(value.get().unwrap());
             }
@@ -1081,9 +1735,27 @@ impl GlyphBox {
     pub const SHOW_DETAILS: &'static str = "show-details";
     pub const ZOOM_FACTOR: &'static str = Collection::ZOOM_FACTOR;
     pub const FOCUSED: &'static str = "focused";
+    pub const SELECTED: &'static str = "selected";
     pub const MODIFIED: &'static str = GlyphMetadata::MODIFIED;
     pub const MARK_COLOR: &'static str = GlyphMetadata::MARK_COLOR;
 
+    /// Metrics of the glyph that don't match its metric keys.
+    fn out_of_sync_metrics(&self) -> Vec<Metric> {
+        let glyph = self.imp().glyph.get().unwrap();
+        let layer = glyph.borrow().metadata.layer().clone();
+        let Some(layer) = layer else {
+            return vec![];
+        };
+        let glyph = glyph.borrow();
+        if Metric::ALL
+            .into_iter()
+            .all(|m| glyph.metric_key(m).is_none())
+        {
+            return vec![];
+        }
+        glyph.out_of_sync_metrics(&layer.glyphs())
+    }
+
     fn emit_open_glyph_edit(&self) {
         self.imp()
             .app
@@ -1111,6 +1783,12 @@ impl GlyphBox {
                 .flags(glib::BindingFlags::SYNC_CREATE)
                 .build();
             ret.link(metadata);
+            metadata.connect_notify_local(
+                Some(GlyphMetadata::LOADED),
+                clone!(@weak ret => move |_, _| {
+                    ret.imp().drawing_area.queue_draw();
+                }),
+            );
         }
         ret.imp().glyph.set(glyph).unwrap();
         ret
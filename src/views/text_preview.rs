@@ -0,0 +1,512 @@
+/*
+ * gerb
+ *
+ * Copyright 2022 - Manos Pitsidianakis
+ *
+ * This file is part of gerb.
+ *
+ * gerb is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * gerb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with gerb. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Text preview to check spacing: a line of text laid out with the advance widths and the
+//! kerning of the font. Clicking a glyph edits it below the line, which follows the changes.
+
+use glib::{clone, ParamFlags, ParamSpec, ParamSpecBoolean, ParamSpecString, Value};
+use gtk::cairo::{Context, FontSlant, FontWeight};
+use once_cell::unsync::OnceCell;
+
+use crate::glyphs::{layout_text, GlyphDrawingOptions, PlacedGlyph};
+use crate::prelude::*;
+use crate::ufo::objects::Layer;
+
+const MARGIN: f64 = 25.0;
+
+#[derive(Debug, Default)]
+pub struct TextPreviewInner {
+    app: OnceCell<Application>,
+    project: OnceCell<Project>,
+    strings: OnceCell<gtk::ComboBoxText>,
+    font_size: OnceCell<gtk::SpinButton>,
+    kerning: OnceCell<gtk::CheckButton>,
+    drawing_area: gtk::DrawingArea,
+    /// Holds the line above the editor of the clicked glyph.
+    paned: OnceCell<gtk::Paned>,
+    editor: RefCell<Option<Editor>>,
+    /// Glyphs of the current text, as of the last draw.
+    placed: RefCell<Vec<PlacedGlyph>>,
+    hovered: Cell<Option<usize>>,
+    title: RefCell<String>,
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for TextPreviewInner {
+    const NAME: &'static str = "TextPreview";
+    type Type = TextPreview;
+    type ParentType = gtk::EventBox;
+}
+
+impl ObjectImpl for TextPreviewInner {
+    fn constructed(&self, obj: &Self::Type) {
+        self.parent_constructed(obj);
+        *self.title.borrow_mut() = "Text preview".to_string();
+        obj.set_visible(true);
+        obj.set_expand(true);
+        obj.set_can_focus(true);
+
+        let strings = gtk::ComboBoxText::with_entry();
+        strings.set_visible(true);
+        strings.set_hexpand(true);
+        if let Some(entry) = strings
+            .child()
+            .and_then(|c| c.downcast::<gtk::Entry>().ok())
+        {
+            entry.set_placeholder_text(Some("Type text, use /glyphname for glyphs by name"));
+        }
+        strings.connect_changed(clone!(@weak obj => move |_| {
+            obj.imp().hovered.set(None);
+            obj.imp().drawing_area.queue_draw();
+        }));
+
+        let save_string = gtk::Button::builder()
+            .image(&gtk::Image::from_icon_name(
+                Some("list-add-symbolic"),
+                gtk::IconSize::Button,
+            ))
+            .tooltip_text("Save this text in the list of strings")
+            .visible(true)
+            .build();
+        save_string.connect_clicked(clone!(@weak obj => move |_| {
+            obj.edit_saved_strings(true);
+        }));
+        let remove_string = gtk::Button::builder()
+            .image(&gtk::Image::from_icon_name(
+                Some("list-remove-symbolic"),
+                gtk::IconSize::Button,
+            ))
+            .tooltip_text("Remove this text from the list of strings")
+            .visible(true)
+            .build();
+        remove_string.connect_clicked(clone!(@weak obj => move |_| {
+            obj.edit_saved_strings(false);
+        }));
+
+        let font_size = gtk::SpinButton::with_range(8.0, 1000.0, 4.0);
+        font_size.set_value(96.0);
+        font_size.set_visible(true);
+        font_size.set_tooltip_text(Some("Font size in pixels"));
+        font_size.connect_value_changed(clone!(@weak obj => move |_| {
+            obj.imp().drawing_area.queue_draw();
+        }));
+
+        let kerning = gtk::CheckButton::builder()
+            .label("Kerning")
+            .active(true)
+            .visible(true)
+            .build();
+        kerning.connect_toggled(clone!(@weak obj => move |_| {
+            obj.imp().drawing_area.queue_draw();
+        }));
+
+        let toolbar = gtk::Box::builder()
+            .orientation(gtk::Orientation::Horizontal)
+            .spacing(5)
+            .margin(5)
+            .visible(true)
+            .build();
+        toolbar.pack_start(&strings, true, true, 0);
+        toolbar.pack_start(&save_string, false, false, 0);
+        toolbar.pack_start(&remove_string, false, false, 0);
+        toolbar.pack_start(&font_size, false, false, 0);
+        toolbar.pack_start(&kerning, false, false, 0);
+
+        self.drawing_area.set_visible(true);
+        self.drawing_area.set_can_focus(true);
+        self.drawing_area.set_has_tooltip(true);
+        self.drawing_area.add_events(
+            gtk::gdk::EventMask::BUTTON_PRESS_MASK
+                | gtk::gdk::EventMask::POINTER_MOTION_MASK
+                | gtk::gdk::EventMask::LEAVE_NOTIFY_MASK,
+        );
+        self.drawing_area.connect_draw(
+            clone!(@weak obj => @default-return Inhibit(false), move |viewport: &gtk::DrawingArea, mut ctx: &Context| {
+                obj.draw(viewport, ctx.push());
+                Inhibit(false)
+            }),
+        );
+        self.drawing_area.connect_motion_notify_event(
+            clone!(@weak obj => @default-return Inhibit(false), move |viewport, event| {
+                let hovered = obj.glyph_at(event.position().0);
+                if hovered != obj.imp().hovered.get() {
+                    obj.imp().hovered.set(hovered);
+                    viewport.queue_draw();
+                }
+                Inhibit(false)
+            }),
+        );
+        self.drawing_area.connect_leave_notify_event(
+            clone!(@weak obj => @default-return Inhibit(false), move |viewport, _event| {
+                obj.imp().hovered.set(None);
+                viewport.queue_draw();
+                Inhibit(false)
+            }),
+        );
+        self.drawing_area.connect_button_press_event(
+            clone!(@weak obj => @default-return Inhibit(false), move |_viewport, event| {
+                if event.button() != gtk::gdk::BUTTON_PRIMARY {
+                    return Inhibit(false);
+                }
+                let glyph = obj
+                    .glyph_at(event.position().0)
+                    .and_then(|i| obj.imp().placed.borrow()[i].glyph.clone());
+                if let Some(glyph) = glyph {
+                    obj.edit_glyph(glyph);
+                }
+                Inhibit(true)
+            }),
+        );
+        self.drawing_area.connect_query_tooltip(
+            clone!(@weak obj => @default-return false, move |_self, x: i32, _y: i32, _by_keyboard: bool, tooltip| {
+                let Some(i) = obj.glyph_at(f64::from(x)) else {
+                    return false;
+                };
+                let placed = obj.imp().placed.borrow();
+                let p = &placed[i];
+                let mut text = match p.glyph {
+                    Some(ref glyph) => {
+                        let mut text = format!("{}\nAdvance: {}", p.item, p.width);
+                        if let Some((lsb, rsb)) = glyph.borrow().sidebearings() {
+                            text.push_str(&format!("\nLSB: {lsb}\nRSB: {rsb}"));
+                        }
+                        text.push_str("\n\nClick to edit.");
+                        text
+                    }
+                    None => format!("{}\nNo such glyph.", p.item),
+                };
+                if p.kerning != 0.0 {
+                    text.push_str(&format!("\nKerning with previous glyph: {}", p.kerning));
+                }
+                tooltip.set_text(Some(&text));
+                true
+            }),
+        );
+        /* Glyphs may have been edited while the preview was in another tab. */
+        self.drawing_area.connect_map(|viewport| {
+            viewport.queue_draw();
+        });
+
+        let scrolled_window = gtk::ScrolledWindow::builder()
+            .expand(true)
+            .visible(true)
+            .child(&self.drawing_area)
+            .build();
+
+        let vbox = gtk::Box::builder()
+            .orientation(gtk::Orientation::Vertical)
+            .expand(true)
+            .visible(true)
+            .build();
+        let paned = gtk::Paned::builder()
+            .orientation(gtk::Orientation::Vertical)
+            .expand(true)
+            .visible(true)
+            .build();
+        paned.pack1(&scrolled_window, true, false);
+        vbox.pack_start(&toolbar, false, false, 0);
+        vbox.pack_start(&paned, true, true, 0);
+        obj.add(&vbox);
+        self.paned.set(paned).unwrap();
+
+        self.strings.set(strings).unwrap();
+        self.font_size.set(font_size).unwrap();
+        self.kerning.set(kerning).unwrap();
+    }
+
+    fn properties() -> &'static [ParamSpec] {
+        static PROPERTIES: once_cell::sync::Lazy<Vec<ParamSpec>> =
+            once_cell::sync::Lazy::new(|| {
+                vec![
+                    ParamSpecString::new(
+                        TextPreview::TITLE,
+                        TextPreview::TITLE,
+                        TextPreview::TITLE,
+                        Some("Text preview"),
+                        ParamFlags::READWRITE,
+                    ),
+                    ParamSpecBoolean::new(
+                        TextPreview::CLOSEABLE,
+                        TextPreview::CLOSEABLE,
+                        TextPreview::CLOSEABLE,
+                        true,
+                        ParamFlags::READABLE,
+                    ),
+                ]
+            });
+        PROPERTIES.as_ref()
+    }
+
+    fn property(&self, _obj: &Self::Type, _id: usize, pspec: &ParamSpec) -> Value {
+        match pspec.name() {
+            TextPreview::TITLE => self.title.borrow().to_value(),
+            TextPreview::CLOSEABLE => true.to_value(),
+            _ => unimplemented!("{}", pspec.name()),
+        }
+    }
+
+    fn set_property(&self, _obj: &Self::Type, _id: usize, value: &Value, pspec: &ParamSpec) {
+        match pspec.name() {
+            TextPreview::TITLE => {
+                *self.title.borrow_mut() = value.get().unwrap();
+            }
+            _ => unimplemented!("{}", pspec.name()),
+        }
+    }
+}
+
+impl WidgetImpl for TextPreviewInner {}
+impl ContainerImpl for TextPreviewInner {}
+impl BinImpl for TextPreviewInner {}
+impl EventBoxImpl for TextPreviewInner {}
+
+impl TextPreviewInner {
+    pub fn app(&self) -> &Application {
+        self.app.get().unwrap()
+    }
+
+    pub fn project(&self) -> &Project {
+        self.project.get().unwrap()
+    }
+}
+
+impl std::ops::Deref for TextPreview {
+    type Target = TextPreviewInner;
+
+    fn deref(&self) -> &Self::Target {
+        self.imp()
+    }
+}
+
+glib::wrapper! {
+    pub struct TextPreview(ObjectSubclass<TextPreviewInner>)
+        @extends gtk::Widget, gtk::Container, gtk::Bin, gtk::EventBox;
+}
+
+impl TextPreview {
+    pub const TITLE: &'static str = Workspace::TITLE;
+    pub const CLOSEABLE: &'static str = Workspace::CLOSEABLE;
+
+    pub fn new(app: Application, project: Project) -> Self {
+        let ret: Self = glib::Object::new(&[]).expect("Failed to create TextPreview");
+        let settings = app.runtime.settings.clone();
+        settings.connect_notify_local(
+            Some(Settings::TEXT_PREVIEW_STRINGS),
+            clone!(@weak ret => move |settings, _| {
+                ret.update_saved_strings(settings);
+            }),
+        );
+        ret.update_saved_strings(&settings);
+        if let Some(first) = settings.text_preview_strings.borrow().first() {
+            if let Some(entry) = ret.entry() {
+                entry.set_text(first);
+            }
+        }
+        ret.imp().app.set(app).unwrap();
+        ret.imp().project.set(project).unwrap();
+        ret
+    }
+
+    fn entry(&self) -> Option<gtk::Entry> {
+        self.strings
+            .get()
+            .unwrap()
+            .child()
+            .and_then(|c| c.downcast::<gtk::Entry>().ok())
+    }
+
+    fn text(&self) -> String {
+        self.entry()
+            .map(|e| e.text().to_string())
+            .unwrap_or_default()
+    }
+
+    /// Fills the drop down list with the strings saved in the settings.
+    fn update_saved_strings(&self, settings: &Settings) {
+        let strings = self.strings.get().unwrap();
+        strings.remove_all();
+        for s in settings.text_preview_strings.borrow().iter() {
+            strings.append_text(s);
+        }
+    }
+
+    /// Adds the current text to the saved strings, or removes it.
+    fn edit_saved_strings(&self, add: bool) {
+        let text = self.text();
+        if text.is_empty() {
+            return;
+        }
+        let settings = &self.app().runtime.settings;
+        let mut saved = settings.text_preview_strings.borrow().clone();
+        if add && !saved.contains(&text) {
+            saved.push(text);
+        } else if !add {
+            saved.retain(|s| s != &text);
+        } else {
+            return;
+        }
+        settings.set_property(Settings::TEXT_PREVIEW_STRINGS, saved.join("\n"));
+    }
+
+    /// Edit `glyph` below the line, replacing the glyph edited before.
+    fn edit_glyph(&self, glyph: Rc<RefCell<Glyph>>) {
+        if !self.app().window.load_glyph(&glyph) {
+            return;
+        }
+        let paned = self.paned.get().unwrap();
+        if let Some(previous) = self.editor.borrow_mut().take() {
+            paned.remove(&previous);
+        }
+        let editor = Editor::new(self.app().clone(), glyph);
+        /* The editor redraws whenever the glyph changes, and so does the line. */
+        editor.connect_draw(
+            clone!(@weak self as obj => @default-return Inhibit(false), move |_, _| {
+                obj.imp().drawing_area.queue_draw();
+                Inhibit(false)
+            }),
+        );
+        paned.pack2(&editor, true, false);
+        *self.editor.borrow_mut() = Some(editor);
+        self.drawing_area.queue_draw();
+    }
+
+    fn scale(&self) -> f64 {
+        let units_per_em = self.project().fontinfo().units_per_em.get();
+        self.font_size.get().unwrap().value() / units_per_em
+    }
+
+    /// Index of the glyph at horizontal position `x` of the drawing area.
+    fn glyph_at(&self, x: f64) -> Option<usize> {
+        let scale = self.scale();
+        let x = (x - MARGIN) / scale;
+        self.placed
+            .borrow()
+            .iter()
+            .position(|p| (p.x..(p.x + p.width)).contains(&x))
+    }
+
+    fn draw(&self, viewport: &gtk::DrawingArea, mut cr: ContextRef) {
+        let app = self.app();
+        let colors = app.colors();
+        let project = self.project();
+        let (units_per_em, ascender, descender) = {
+            let fontinfo = project.fontinfo();
+            (
+                fontinfo.units_per_em.get(),
+                fontinfo.ascender.get(),
+                fontinfo.descender.get(),
+            )
+        };
+        let scale = self.scale();
+        let kerning = |first: &str, second: &str| project.kerning_pair(first, second);
+        let layer = &project.default_layer;
+        if !layer.property::<bool>(Layer::LOADED) {
+            /* Codepoints and advance widths are only known once the glyphs are parsed. */
+            if let Err(err) = layer.load_all() {
+                eprintln!("{err}");
+            }
+        }
+        let edited = self.editor.borrow().as_ref().map(|e| e.glyph());
+        let placed = {
+            let glyphs = layer.glyphs();
+            layout_text(
+                &self.text(),
+                &glyphs,
+                self.kerning
+                    .get()
+                    .unwrap()
+                    .is_active()
+                    .then_some(&kerning as &dyn Fn(&str, &str) -> f64),
+                units_per_em / 2.0,
+            )
+        };
+        let total_width = placed.last().map_or(0.0, |p| p.x + p.width);
+        let baseline = MARGIN + ascender * scale;
+        let height = (ascender - descender) * scale + 2.0 * MARGIN + 30.0;
+        viewport.set_size_request((total_width * scale + 2.0 * MARGIN) as i32, height as i32);
+
+        cr.set_source_color(colors.theme_base_color);
+        cr.paint().expect("Invalid cairo surface state");
+        cr.select_font_face("Sans", FontSlant::Normal, FontWeight::Normal);
+        cr.set_font_size(10.0);
+        let hovered = self.hovered.get();
+        for (i, p) in placed.iter().enumerate() {
+            let x = MARGIN + p.x * scale;
+            let is_edited = matches!((&p.glyph, &edited), (Some(g), Some(e)) if Rc::ptr_eq(g, e));
+            if hovered == Some(i) || is_edited {
+                let cr1 = cr.push();
+                cr1.set_source_color(
+                    colors
+                        .theme_selected_bg_color
+                        .with_alpha_f64(if hovered == Some(i) { 0.3 } else { 0.15 }),
+                );
+                cr1.rectangle(
+                    x,
+                    baseline - ascender * scale,
+                    p.width * scale,
+                    (ascender - descender) * scale,
+                );
+                cr1.fill().unwrap();
+            }
+            if let Some(ref glyph) = p.glyph {
+                let mut matrix = gtk::cairo::Matrix::identity();
+                matrix.translate(x, baseline);
+                matrix.scale(scale, -scale);
+                let options = GlyphDrawingOptions {
+                    outline: (Color::new_alpha(0, 0, 0, 0), 1.5).into(),
+                    inner_fill: Some((colors.theme_text_color, 1.5).into()),
+                    highlight: None,
+                    matrix,
+                    units_per_em,
+                    ..Default::default()
+                };
+                glyph.borrow().draw(cr.push(), options);
+            } else {
+                /* Missing glyphs are drawn as a crossed box. */
+                let cr1 = cr.push();
+                cr1.set_source_color(colors.insensitive_fg_color);
+                cr1.set_line_width(1.0);
+                cr1.rectangle(
+                    x + 2.0,
+                    baseline - ascender * scale * 0.7,
+                    (p.width * scale - 4.0).max(1.0),
+                    ascender * scale * 0.7,
+                );
+                cr1.stroke_preserve().unwrap();
+                cr1.move_to(x + 2.0, baseline);
+                cr1.line_to(x + p.width * scale - 2.0, baseline - ascender * scale * 0.7);
+                cr1.stroke().unwrap();
+            }
+            if p.kerning != 0.0 {
+                let cr1 = cr.push();
+                cr1.set_source_color(Color::from_hex("#e53935")); // [ref:hardcoded_color_value]
+                cr1.move_to(x, baseline - descender * scale + 28.0);
+                cr1.show_text(&format!("{}", p.kerning)).unwrap();
+            }
+        }
+        if let Some(p) = hovered.and_then(|i| placed.get(i)) {
+            cr.set_source_color(colors.theme_text_color);
+            cr.move_to(MARGIN + p.x * scale, baseline - descender * scale + 14.0);
+            cr.show_text(&p.item.to_string()).unwrap();
+        }
+        *self.placed.borrow_mut() = placed;
+    }
+}
@@ -23,6 +23,7 @@ mod workspace;
 pub use workspace::*;
 
 use crate::prelude::*;
+use crate::ufo::objects::Layer;
 use gtk::glib::subclass::Signal;
 
 #[derive(Debug, Default)]
@@ -306,7 +307,12 @@ impl WindowInner {
     }
 
     pub fn load_project(&self, project: Project) {
-        if let Ok(uri) = glib::filename_to_uri(&*project.path.borrow(), None) {
+        /* The directory of an archive is temporary, so the archive itself is remembered. */
+        let (path, mime_type) = match project.archive_path.borrow().clone() {
+            Some(archive) => (archive, crate::ufo::ufoz::MIME_TYPE),
+            None => (project.path.borrow().clone(), "inode/directory"),
+        };
+        if let Ok(uri) = glib::filename_to_uri(&path, None) {
             /* add directory to user's Recent Files database */
 
             // Get gtk's default manager or create new
@@ -314,7 +320,7 @@ impl WindowInner {
             let recent_data = gtk::RecentData {
                 display_name: None,
                 description: None,
-                mime_type: "inode/directory".to_string(),
+                mime_type: mime_type.to_string(),
                 app_name: crate::APPLICATION_NAME.to_string(),
                 app_exec: std::fs::read_link("/proc/self/exe")
                     .unwrap_or_default()
@@ -354,13 +360,40 @@ impl WindowInner {
             ),
         );
         {
-            *self.application().runtime.project.borrow_mut() = project.clone();
+            let previous = std::mem::replace(
+                &mut *self.application().runtime.project.borrow_mut(),
+                project.clone(),
+            );
+            previous.close();
         }
         self.notebook.foreach(|tab| {
             self.notebook.remove(tab);
         });
 
-        let collection = Collection::new(self.application(), project);
+        let collection = Collection::new(self.application(), project.clone());
+        for layer in project.all_layers.borrow().iter() {
+            layer.set_undo_database(self.application().undo_db.borrow().clone());
+            layer.connect_notify_local(
+                Some(Layer::LOADED),
+                clone!(@weak self.statusbar as statusbar => move |layer, _| {
+                    let problems = layer.resolve_components();
+                    for problem in problems.iter() {
+                        eprintln!("{problem}");
+                    }
+                    if let Some(first) = problems.first() {
+                        statusbar.push(
+                            statusbar.context_id("main"),
+                            &format!(
+                                "Layer {}: {} component problem(s). {first}",
+                                layer.property::<String>(Layer::NAME),
+                                problems.len()
+                            ),
+                        );
+                    }
+                }),
+            );
+            layer.load_in_background();
+        }
         add_tab(
             &self.notebook,
             Workspace::new(collection.upcast_ref::<gtk::Widget>()).upcast_ref::<gtk::Widget>(),
@@ -371,7 +404,27 @@ impl WindowInner {
         self.notebook.queue_draw();
     }
 
+    /// Parses `glyph` if it hasn't been yet. Returns `false` after showing an error dialog if
+    /// its file can't be read, since editing it would show an empty glyph.
+    pub fn load_glyph(&self, glyph: &Rc<RefCell<crate::glyphs::Glyph>>) -> bool {
+        let Err(err) = crate::glyphs::Glyph::load(glyph) else {
+            return true;
+        };
+        let dialog = crate::utils::widgets::new_simple_error_dialog(
+            Some("Error: Could not load glyph."),
+            &err.to_string(),
+            None,
+            self.upcast_ref(),
+        );
+        dialog.run();
+        dialog.emit_close();
+        false
+    }
+
     pub fn edit_glyph(&self, glyph: &Rc<RefCell<crate::glyphs::Glyph>>) {
+        if !self.load_glyph(glyph) {
+            return;
+        }
         let edit_view = Editor::new(self.application(), glyph.clone());
         add_tab(
             &self.notebook,
@@ -381,10 +434,25 @@ impl WindowInner {
         );
     }
 
+    pub fn open_text_preview(&self) {
+        let project = self.application().runtime.project.borrow().clone();
+        let text_preview = TextPreview::new(self.application(), project);
+        add_tab(
+            &self.notebook,
+            Workspace::new(text_preview.upcast_ref::<gtk::Widget>()).upcast_ref::<gtk::Widget>(),
+            true,
+            true,
+        );
+    }
+
     pub fn unload_project(&self) {
         self.headerbar.set_subtitle(None);
         self.notebook.queue_draw();
-        *self.application().runtime.project.borrow_mut() = Project::new();
+        let previous = std::mem::replace(
+            &mut *self.application().runtime.project.borrow_mut(),
+            Project::new(),
+        );
+        previous.close();
     }
 
     pub fn application(&self) -> Application {
@@ -468,7 +536,7 @@ impl WindowInner {
             .filter(|i| {
                 i.last_application().map(|a| a == "gerb").unwrap_or(false)
                     && i.mime_type()
-                        .map(|a| a == "inode/directory")
+                        .map(|a| a == "inode/directory" || a == crate::ufo::ufoz::MIME_TYPE)
                         .unwrap_or(false)
                     && i.uri_display()
                         .map(|a| Path::new(&a).exists())
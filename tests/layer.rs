@@ -0,0 +1,156 @@
+/*
+ * gerb
+ *
+ * Copyright 2022 - Manos Pitsidianakis
+ *
+ * This file is part of gerb.
+ *
+ * gerb is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * gerb is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with gerb. If not, see <http://www.gnu.org/licenses/>.
+*/
+
+mod utils;
+use utils::*;
+
+#[test]
+fn test_layer_lazy_glif_parsing() {
+    use gerb::prelude::*;
+    use gerb::ufo::objects::Layer;
+
+    glib_test_wrapper(|| {
+        let root = std::env::temp_dir().join("gerb-test-layer-lazy-glif-parsing.ufo");
+        write_synthetic_ufo(&root, 3).unwrap();
+
+        let layer = Layer::new();
+        layer
+            .init_from_path(
+                "public.default".to_string(),
+                "glyphs".to_string(),
+                root.clone(),
+                false,
+            )
+            .unwrap();
+        assert!(!layer.property::<bool>(Layer::LOADED));
+        let glyphs = layer
+            .glyphs()
+            .values()
+            .cloned()
+            .collect::<Vec<Rc<RefCell<Glyph>>>>();
+        assert_eq!(glyphs.len(), 3);
+        assert!(glyphs.iter().all(|g| !g.borrow().is_loaded()));
+        assert!(glyphs.iter().all(|g| g.borrow().contours.is_empty()));
+
+        /* First access parses only the requested glyph. */
+        Glyph::load(&glyphs[1]).unwrap();
+        assert!(glyphs[1].borrow().is_loaded());
+        assert!(glyphs[1]
+            .borrow()
+            .metadata
+            .property::<bool>(GlyphMetadata::LOADED));
+        assert_eq!(glyphs[1].borrow().contours.len(), 1);
+        assert_eq!(glyphs[1].borrow().width(), Some(500.0));
+        assert!(!glyphs[0].borrow().is_loaded());
+        assert!(!glyphs[2].borrow().is_loaded());
+
+        layer.load_all().unwrap();
+        assert!(layer.property::<bool>(Layer::LOADED));
+        assert!(glyphs.iter().all(|g| g.borrow().contours.len() == 1));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    });
+}
+
+#[test]
+fn test_layer_malformed_glif_is_not_overwritten() {
+    use gerb::prelude::*;
+    use gerb::ufo::{objects::Layer, LayerContents};
+
+    glib_test_wrapper(|| {
+        let root = std::env::temp_dir().join("gerb-test-layer-malformed-glif.ufo");
+        write_synthetic_ufo(&root, 3).unwrap();
+        let malformed_path = root.join("glyphs").join("g1.glif");
+        let malformed = b"<glyph name=\"g1\" format=\"2\">\n<outline>\n";
+        std::fs::write(&malformed_path, malformed).unwrap();
+
+        let layer = Layer::new();
+        layer
+            .init_from_path(
+                "public.default".to_string(),
+                "glyphs".to_string(),
+                root.clone(),
+                false,
+            )
+            .unwrap();
+        assert!(layer.load_all().is_err());
+        let glyph = layer.glyphs().get("g1").cloned().unwrap();
+        assert!(!glyph.borrow().is_loaded());
+        assert!(glyph.borrow().load_error().is_some());
+        /* The error is returned again instead of an empty outline. */
+        assert!(Glyph::load(&glyph).is_err());
+        assert!(!glyph.borrow().is_loaded());
+        assert!(layer
+            .glyphs()
+            .values()
+            .filter(|g| g.borrow().name() != "g1")
+            .all(|g| g.borrow().is_loaded()));
+
+        /* Saving leaves the file as it is. */
+        glyph
+            .borrow()
+            .metadata
+            .set_property(GlyphMetadata::MODIFIED, true);
+        layer.save(&mut LayerContents::default()).unwrap();
+        assert_eq!(std::fs::read(&malformed_path).unwrap(), malformed);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    });
+}
+
+#[test]
+#[ignore = "benchmark, run with `cargo test --release --test layer -- --ignored --nocapture`"]
+fn bench_layer_load_20k_glyphs() {
+    use gerb::ufo::objects::Layer;
+    use std::time::Instant;
+
+    const GLYPH_COUNT: usize = 20_000;
+
+    glib_test_wrapper(|| {
+        let root = std::env::temp_dir().join("gerb-bench-layer-load-20k-glyphs.ufo");
+        write_synthetic_ufo(&root, GLYPH_COUNT).unwrap();
+
+        let start = Instant::now();
+        let layer = Layer::new();
+        layer
+            .init_from_path(
+                "public.default".to_string(),
+                "glyphs".to_string(),
+                root.clone(),
+                false,
+            )
+            .unwrap();
+        let init = start.elapsed();
+        layer.load_all().unwrap();
+        let total = start.elapsed();
+        println!(
+            "{GLYPH_COUNT} glyphs: init_from_path {init:?}, fully loaded {total:?} ({:?} per glyph)",
+            total / u32::try_from(GLYPH_COUNT).unwrap()
+        );
+        assert_eq!(layer.glyphs().len(), GLYPH_COUNT);
+        assert!(layer
+            .glyphs()
+            .values()
+            .all(|g| g.borrow().is_loaded() && g.borrow().contours.len() == 1));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    });
+}
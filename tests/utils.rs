@@ -77,3 +77,65 @@ where
         .expect("Failed to receive result from thread pool")
         .unwrap_or_else(|e| std::panic::resume_unwind(e))
 }
+
+#[allow(dead_code)]
+/// Writes a UFO 3 font with `glyph_count` synthetic glyphs to `root`, overwriting anything that
+/// was there. Each glyph is a closed square with a private use area codepoint.
+pub fn write_synthetic_ufo(root: &std::path::Path, glyph_count: usize) -> std::io::Result<()> {
+    use std::fmt::Write;
+
+    const PLIST_HEADER: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+"#;
+
+    if root.exists() {
+        std::fs::remove_dir_all(root)?;
+    }
+    let glyphs_dir = root.join("glyphs");
+    std::fs::create_dir_all(&glyphs_dir)?;
+    std::fs::write(
+        root.join("metainfo.plist"),
+        format!("{PLIST_HEADER}<dict>\n<key>creator</key>\n<string>io.github.epilys.gerb</string>\n<key>formatVersion</key>\n<integer>3</integer>\n</dict>\n</plist>\n"),
+    )?;
+    std::fs::write(
+        root.join("fontinfo.plist"),
+        format!("{PLIST_HEADER}<dict>\n<key>familyName</key>\n<string>Synthetic</string>\n<key>unitsPerEm</key>\n<integer>1000</integer>\n</dict>\n</plist>\n"),
+    )?;
+    std::fs::write(
+        root.join("layercontents.plist"),
+        format!("{PLIST_HEADER}<array>\n<array>\n<string>public.default</string>\n<string>glyphs</string>\n</array>\n</array>\n</plist>\n"),
+    )?;
+    let mut contents = format!("{PLIST_HEADER}<dict>\n");
+    for i in 0..glyph_count {
+        let name = format!("g{i}");
+        let filename = format!("{name}.glif");
+        let _ = write!(
+            contents,
+            "<key>{name}</key>\n<string>{filename}</string>\n"
+        );
+        std::fs::write(
+            glyphs_dir.join(&filename),
+            format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+<glyph name="{name}" format="2">
+  <advance width="500"/>
+  <unicode hex="{codepoint:X}"/>
+  <outline>
+    <contour>
+      <point x="50" y="0" type="line"/>
+      <point x="450" y="0" type="line"/>
+      <point x="450" y="400" type="line"/>
+      <point x="50" y="400" type="line"/>
+    </contour>
+  </outline>
+</glyph>
+"#,
+                codepoint = 0xF0000 + i
+            ),
+        )?;
+    }
+    contents.push_str("</dict>\n</plist>\n");
+    std::fs::write(glyphs_dir.join("contents.plist"), contents)?;
+    Ok(())
+}